@@ -0,0 +1,120 @@
+//! Replays a past block on its historical state; e.g., to debug one of its transactions.
+//!
+//! Usage: `cargo run --example replay_block -- <block context config>`.
+//!
+//! An embedder plugs in an archive (e.g., a node database or RPC client) by implementing
+//! `HistoricalStateReader`, and fetches the header and transactions of the block. Later
+//! transactions of a block depend on the earlier ones, so a transaction is replayed after those
+//! preceding it in its block.
+
+use std::env;
+use std::path::Path;
+
+use blockifier::block_context::BlockContext;
+use blockifier::block_execution::pre_process_block;
+use blockifier::execution::contract_class::ContractClass;
+use blockifier::state::cached_state::CachedState;
+use blockifier::state::errors::StateError;
+use blockifier::state::historical_state::{HistoricalState, HistoricalStateReader};
+use blockifier::state::state_api::StateResult;
+use blockifier::transaction::objects::{TransactionExecutionInfo, TransactionExecutionResult};
+use blockifier::transaction::transaction_execution::Transaction;
+use blockifier::transaction::transactions::ExecutableTransaction;
+use starknet_api::block::{BlockHash, BlockHeader, BlockNumber};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+/// A stand-in for an archive, in which every block has the empty state.
+struct EmptyArchive;
+
+impl HistoricalStateReader for EmptyArchive {
+    fn get_storage_at(
+        &self,
+        _block_number: BlockNumber,
+        _contract_address: ContractAddress,
+        _key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        Ok(StarkFelt::default())
+    }
+
+    fn get_nonce_at(
+        &self,
+        _block_number: BlockNumber,
+        _contract_address: ContractAddress,
+    ) -> StateResult<Nonce> {
+        Ok(Nonce::default())
+    }
+
+    fn get_class_hash_at(
+        &self,
+        _block_number: BlockNumber,
+        _contract_address: ContractAddress,
+    ) -> StateResult<ClassHash> {
+        Ok(ClassHash::default())
+    }
+
+    fn get_compiled_contract_class(
+        &self,
+        _block_number: BlockNumber,
+        class_hash: ClassHash,
+    ) -> StateResult<ContractClass> {
+        Err(StateError::UndeclaredClassHash(class_hash))
+    }
+
+    fn get_compiled_class_hash(
+        &self,
+        _block_number: BlockNumber,
+        _class_hash: ClassHash,
+    ) -> StateResult<CompiledClassHash> {
+        Ok(CompiledClassHash::default())
+    }
+}
+
+/// Executes the transactions of the block on the state it was executed on. The old block is the
+/// one `STORED_BLOCK_HASH_BUFFER` blocks earlier, if any, whose hash the sequencer wrote at the
+/// start of the block.
+fn replay_block<R: HistoricalStateReader>(
+    archive: R,
+    chain_block_context: BlockContext,
+    block_header: &BlockHeader,
+    old_block_number_and_hash: Option<(BlockNumber, BlockHash)>,
+    transactions: Vec<Transaction>,
+) -> StateResult<Vec<TransactionExecutionResult<TransactionExecutionInfo>>> {
+    let block_context = chain_block_context.with_block_header(block_header);
+    let mut state = CachedState::from(HistoricalState::new(archive, block_header.block_number));
+    pre_process_block(&mut state, old_block_number_and_hash)?;
+
+    Ok(transactions
+        .into_iter()
+        .map(|transaction| transaction.execute(&mut state, &block_context, true, true))
+        .collect())
+}
+
+fn main() {
+    let Some(config_path) = env::args().nth(1) else {
+        eprintln!("Usage: replay_block <block context config>");
+        return;
+    };
+    let chain_block_context = BlockContext::try_from(Path::new(&config_path))
+        .unwrap_or_else(|error| panic!("Failed to load the block context: {error}"));
+
+    // Fetched from the archive in practice.
+    let block_header = BlockHeader { block_number: BlockNumber(20), ..Default::default() };
+    let transactions = vec![];
+
+    let execution_results = replay_block(
+        EmptyArchive,
+        chain_block_context,
+        &block_header,
+        Some((BlockNumber(10), BlockHash::default())),
+        transactions,
+    )
+    .expect("Failed to pre-process the block.");
+    for (index, execution_result) in execution_results.iter().enumerate() {
+        match execution_result {
+            Ok(tx_execution_info) => println!("Transaction {index}: {tx_execution_info:?}"),
+            Err(error) => println!("Transaction {index} failed: {error}"),
+        }
+    }
+}
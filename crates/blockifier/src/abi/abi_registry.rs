@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+use starknet_api::core::{ClassHash, EntryPointSelector};
+use starknet_api::hash::StarkFelt;
+
+use crate::abi::abi_utils::selector_from_name;
+
+#[cfg(test)]
+#[path = "abi_registry_test.rs"]
+mod test;
+
+// ABI entry types that define an entry point, in both Cairo 0 and Sierra ABIs.
+const ENTRY_POINT_ABI_TYPES: [&str; 3] = ["function", "constructor", "l1_handler"];
+
+/// Translates selectors and class hashes into human-readable names, for debugging purposes (e.g.,
+/// rendering call trees and error messages).
+#[derive(Clone, Debug, Default)]
+pub struct AbiRegistry {
+    selector_names: HashMap<EntryPointSelector, String>,
+    class_names: HashMap<ClassHash, String>,
+}
+
+impl AbiRegistry {
+    pub fn register_entry_point(&mut self, entry_point_name: &str) {
+        self.selector_names.insert(selector_from_name(entry_point_name), entry_point_name.into());
+    }
+
+    pub fn register_class(&mut self, class_hash: ClassHash, class_name: &str) {
+        self.class_names.insert(class_hash, class_name.into());
+    }
+
+    /// Registers the entry points of the given ABI; either a Cairo 0 ABI, or a Sierra ABI, whose
+    /// interfaces are traversed as well.
+    pub fn register_abi(&mut self, abi: &Value) {
+        let Some(entries) = abi.as_array() else {
+            return;
+        };
+
+        for entry in entries {
+            if let Some(items) = entry.get("items") {
+                self.register_abi(items);
+            }
+            let entry_type = entry.get("type").and_then(Value::as_str).unwrap_or_default();
+            let Some(name) = entry.get("name").and_then(Value::as_str) else {
+                continue;
+            };
+            if ENTRY_POINT_ABI_TYPES.contains(&entry_type) {
+                self.register_entry_point(name);
+            }
+        }
+    }
+
+    /// Registers a class by name, together with the entry points of its ABI (see `register_abi`).
+    /// The raw class may be a (Cairo 0 or Sierra) contract class, or an ABI.
+    pub fn register_raw_class(
+        &mut self,
+        class_hash: ClassHash,
+        class_name: &str,
+        raw_class: &str,
+    ) -> serde_json::Result<()> {
+        let raw_class: Value = serde_json::from_str(raw_class)?;
+        let abi = match raw_class.get("abi") {
+            // In some representations (e.g., of Sierra classes), the ABI is serialized as a string.
+            Some(Value::String(raw_abi)) => serde_json::from_str(raw_abi)?,
+            Some(abi) => abi.clone(),
+            None => raw_class,
+        };
+
+        self.register_class(class_hash, class_name);
+        self.register_abi(&abi);
+        Ok(())
+    }
+
+    pub fn selector_name(&self, selector: &EntryPointSelector) -> Option<&str> {
+        self.selector_names.get(selector).map(String::as_str)
+    }
+
+    pub fn class_name(&self, class_hash: &ClassHash) -> Option<&str> {
+        self.class_names.get(class_hash).map(String::as_str)
+    }
+
+    /// Appends the registered names to the selectors and class hashes mentioned in the given
+    /// (e.g., error) message, in hex (with or without leading zeros).
+    pub fn annotate(&self, message: &str) -> String {
+        let mut annotated_message = String::with_capacity(message.len());
+        let mut rest = message;
+        while let Some(hex_start) = rest.find("0x") {
+            let hex_length = 2 + rest[hex_start + 2..]
+                .find(|c: char| !c.is_ascii_hexdigit())
+                .unwrap_or(rest.len() - hex_start - 2);
+            let (prefix, suffix) = rest.split_at(hex_start + hex_length);
+            annotated_message.push_str(prefix);
+            let hex = &prefix[hex_start..];
+            // Hex substrings of words (e.g., the `0x1` in `a0x1`) are not felts.
+            let is_word_start = !message[..message.len() - rest.len() + hex_start]
+                .ends_with(|c: char| c.is_ascii_alphanumeric());
+            if let (true, Some(name)) = (is_word_start, self.felt_name(hex)) {
+                annotated_message.push_str(&format!(" ({name})"));
+            }
+            rest = suffix;
+        }
+        annotated_message.push_str(rest);
+
+        annotated_message
+    }
+
+    fn felt_name(&self, hex: &str) -> Option<&str> {
+        let felt = StarkFelt::try_from(hex).ok()?;
+        self.selector_name(&EntryPointSelector(felt)).or_else(|| self.class_name(&ClassHash(felt)))
+    }
+}
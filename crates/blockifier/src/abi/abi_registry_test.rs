@@ -0,0 +1,64 @@
+use serde_json::json;
+
+use crate::abi::abi_registry::AbiRegistry;
+use crate::abi::abi_utils::selector_from_name;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::CairoVersion;
+use crate::utils::format_selector;
+
+#[test]
+fn test_register_cairo0_class() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let class_hash = test_contract.get_class_hash();
+    let mut abi_registry = AbiRegistry::default();
+    abi_registry
+        .register_raw_class(class_hash, "TestContract", &test_contract.get_raw_class())
+        .unwrap();
+
+    assert_eq!(abi_registry.class_name(&class_hash), Some("TestContract"));
+    let selector = selector_from_name("test_storage_read_write");
+    assert_eq!(abi_registry.selector_name(&selector), Some("test_storage_read_write"));
+    assert_eq!(abi_registry.selector_name(&selector_from_name("no_such_function")), None);
+}
+
+#[test]
+fn test_register_sierra_abi() {
+    let abi = json!([
+        {"type": "impl", "name": "MyImpl", "interface_name": "IMyContract"},
+        {
+            "type": "interface",
+            "name": "IMyContract",
+            "items": [{"type": "function", "name": "transfer", "inputs": [], "outputs": []}]
+        },
+        {"type": "constructor", "name": "constructor", "inputs": []},
+        {"type": "event", "name": "Transfer", "kind": "struct", "members": []}
+    ]);
+    let mut abi_registry = AbiRegistry::default();
+    abi_registry.register_abi(&abi);
+
+    for name in ["transfer", "constructor"] {
+        assert_eq!(abi_registry.selector_name(&selector_from_name(name)), Some(name));
+    }
+    for name in ["MyImpl", "IMyContract", "Transfer"] {
+        assert_eq!(abi_registry.selector_name(&selector_from_name(name)), None);
+    }
+}
+
+#[test]
+fn test_annotate() {
+    let mut abi_registry = AbiRegistry::default();
+    abi_registry.register_entry_point("foo");
+    let selector = selector_from_name("foo");
+
+    let message = format!("Entry point {selector:?} not found.");
+    assert_eq!(
+        abi_registry.annotate(&message),
+        format!("Entry point EntryPointSelector(StarkFelt(\"{} (foo)\")) not found.", selector.0)
+    );
+
+    let message = format!("Entry point {} not found; a{}.", format_selector(&selector), selector.0);
+    assert_eq!(
+        abi_registry.annotate(&message),
+        format!("Entry point {} (foo) not found; a{}.", format_selector(&selector), selector.0)
+    );
+}
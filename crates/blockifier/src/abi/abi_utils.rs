@@ -23,6 +23,17 @@ pub fn starknet_keccak(data: &[u8]) -> Felt252 {
     Felt252::from_bytes_be(&result)
 }
 
+/// Returns the eth-keccak of the given felts, each encoded as a 32-byte big-endian word; i.e., the
+/// Solidity `keccak256(abi.encodePacked(...))` of the corresponding `uint256` values.
+pub fn keccak256_of_felts(felts: impl IntoIterator<Item = StarkFelt>) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    for felt in felts {
+        hasher.update(felt.bytes());
+    }
+
+    hasher.finalize().into()
+}
+
 /// Returns an entry point selector, given its name.
 pub fn selector_from_name(entry_point_name: &str) -> EntryPointSelector {
     static DEFAULT_ENTRY_POINTS: [&str; 2] =
@@ -6,6 +6,9 @@ pub const DEFAULT_ENTRY_POINT_NAME: &str = "__default__";
 pub const DEFAULT_ENTRY_POINT_SELECTOR: u64 = 0;
 pub const DEFAULT_L1_ENTRY_POINT_NAME: &str = "__l1_default__";
 
+// The Sierra contract class version, which is not part of the Starknet API class.
+pub const SIERRA_CONTRACT_CLASS_VERSION: &str = "0.1.0";
+
 // The version is considered 0 for L1-Handler transaction hash calculation purposes.
 pub const L1_HANDLER_VERSION: TransactionVersion = TransactionVersion(StarkFelt::ZERO);
 
@@ -14,6 +17,10 @@ pub const L1_TO_L2_MSG_HEADER_SIZE: usize = 5;
 pub const L2_TO_L1_MSG_HEADER_SIZE: usize = 3;
 pub const CLASS_UPDATE_SIZE: usize = 1;
 
+// The block info exposed to `validate` is rounded down to multiples of these values.
+pub const VALIDATE_BLOCK_NUMBER_ROUNDING: u64 = 100;
+pub const VALIDATE_TIMESTAMP_ROUNDING: u64 = 3600;
+
 // Starknet solidity contract-related constants.
 pub const N_DEFAULT_TOPICS: usize = 1; // Events have one default topic.
 
@@ -33,6 +40,14 @@ pub const MAX_VALIDATE_STEPS_PER_TX: usize = 1_000_000;
 pub const MAX_STEPS_PER_TX: usize = 4_000_000;
 pub const GAS_USAGE: &str = "l1_gas_usage";
 pub const N_STEPS_RESOURCE: &str = "n_steps";
+pub const N_MEMORY_HOLES_RESOURCE: &str = "n_memory_holes";
+
+// Declared contract class limits.
+pub const MAX_SIERRA_PROGRAM_LENGTH: usize = 81_920;
+pub const MAX_BYTECODE_LENGTH: usize = 81_920;
+pub const MAX_ENTRY_POINTS: usize = 1_000;
+pub const MAX_ABI_LENGTH: usize = 1_000_000;
+pub const MAX_ARTIFACT_SIZE: usize = 4_089_446;
 
 // Casm hash calculation-related constants.
 pub const CAIRO0_ENTRY_POINT_STRUCT_SIZE: usize = 2;
@@ -1,12 +1,33 @@
 use std::collections::HashMap;
+#[cfg(feature = "fs")]
+use std::fs;
+#[cfg(feature = "fs")]
+use std::path::Path;
 use std::sync::Arc;
 
-use starknet_api::block::{BlockNumber, BlockTimestamp};
+use serde::{Deserialize, Serialize};
+use starknet_api::block::{BlockHeader, BlockNumber, BlockTimestamp};
 use starknet_api::core::{ChainId, ContractAddress};
+use thiserror::Error;
 
+use crate::chain_id::validate_chain_id;
+use crate::execution::common_hints::{ExecutionMode, UnknownHintPolicy};
+use crate::execution::hint_extension::HintExtension;
+use crate::execution::syscalls::custom_syscalls::CustomSyscalls;
+use crate::fee::fee_model::{FeeModel, StarknetFeeModel};
+use crate::fee::fee_transfer_delegate::FeeTransferDelegate;
+use crate::fee::vm_resource_costs::{VmResourceFeeCosts, VmResourceKey};
+use crate::metrics::MetricsSink;
 use crate::transaction::objects::FeeType;
+use crate::versioned_constants::{VersionedConstants, VersionedConstantsError};
 
-#[derive(Clone, Debug)]
+#[cfg(test)]
+#[path = "block_context_test.rs"]
+pub mod test;
+
+/// A block context may be loaded from a JSON or TOML config file, in which the execution extensions
+/// are not specified.
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct BlockContext {
     pub chain_id: ChainId,
     pub block_number: BlockNumber,
@@ -15,22 +36,204 @@ pub struct BlockContext {
     // Fee-related.
     pub sequencer_address: ContractAddress,
     pub fee_token_addresses: FeeTokenAddresses,
-    pub vm_resource_fee_cost: Arc<HashMap<String, f64>>,
+    pub vm_resource_fee_cost: Arc<VmResourceFeeCosts>,
     pub gas_prices: GasPrices,
+    // If true, fee transfers do not credit the sequencer, so that they do not conflict with one
+    // another; see `fee::sequencer_fee::DeferredSequencerFees`.
+    #[serde(default)]
+    pub concurrency_mode: bool,
+    // If true, the tip of V3 transactions is added to the L1 gas price they pay; otherwise (e.g.,
+    // on chains without a fee market), tips are ignored.
+    #[serde(default)]
+    pub charge_tips: bool,
 
     // Limits.
     pub invoke_tx_max_n_steps: u32,
     pub validate_max_n_steps: u32,
     pub max_recursion_depth: usize,
+    // The maximal offset by which a transaction nonce may be ahead of the account nonce; a
+    // non-zero value is meant for simulating future-nonce transactions (e.g., in a mempool).
+    #[serde(default)]
+    pub max_nonce_gap: u64,
+
+    // Hints of old Cairo 0 classes (e.g., of former versions of the common library), accepted as
+    // the supported hints they are mapped to (by code); other unsupported hints fail.
+    #[serde(default)]
+    pub deprecated_hint_aliases: Arc<HashMap<String, String>>,
+    // If not set, unsupported hints are rejected in validation, and reported (by code) otherwise.
+    #[serde(default)]
+    pub unknown_hint_policy: Option<UnknownHintPolicy>,
+    // If true, the Sierra classes given to declare transactions are kept along with the compiled
+    // classes in the state (e.g., for serving `starknet_getClass`); otherwise, they are dropped to
+    // save memory.
+    #[serde(default)]
+    pub retain_sierra_classes: bool,
+    // If true (for debugging), each transaction is executed twice on the same state, and fails if
+    // the two executions differ; e.g., due to hash map iteration order. The execution extensions
+    // below are invoked on both executions, and should tolerate the repetition.
+    #[serde(default)]
+    pub determinism_audit: bool,
+    // If true, the return data of each call is verified to lie within the written part of a
+    // segment other than the program, execution and syscall segments (or any temporary segment),
+    // rejecting calls whose program hands off memory it does not own.
+    #[serde(default)]
+    pub verify_retdata_segments: bool,
+    // If true, the outputs of programs that use the output builtin are collected (see
+    // `CallExecution::program_output`); calls run as usual otherwise.
+    #[serde(default)]
+    pub collect_program_outputs: bool,
+
+    // Versioned constants.
+    #[serde(default)]
+    pub versioned_constants: VersionedConstants,
+
+    // Execution extensions.
+    #[serde(skip)]
+    pub hint_extension: Option<Arc<dyn HintExtension>>,
+    #[serde(skip)]
+    pub custom_syscalls: Arc<CustomSyscalls>,
+    // If set, fee balances are read from the delegate rather than the fee token contracts, and fee
+    // transfers are returned to the embedder rather than executed.
+    #[serde(skip)]
+    pub fee_transfer_delegate: Option<Arc<dyn FeeTransferDelegate>>,
+    // If not set, fees are computed by `StarknetFeeModel`.
+    #[serde(skip)]
+    pub fee_model: Option<Arc<dyn FeeModel>>,
+    #[serde(skip)]
+    pub metrics_sink: Option<Arc<dyn MetricsSink>>,
 }
 
 impl BlockContext {
     pub fn fee_token_address(&self, fee_type: &FeeType) -> ContractAddress {
         self.fee_token_addresses.get_by_fee_type(fee_type)
     }
+
+    pub fn unknown_hint_policy(&self, execution_mode: ExecutionMode) -> UnknownHintPolicy {
+        self.unknown_hint_policy.unwrap_or(match execution_mode {
+            ExecutionMode::Validate => UnknownHintPolicy::Reject,
+            ExecutionMode::Execute => UnknownHintPolicy::ReportHintCode,
+        })
+    }
+
+    /// Returns the context of the given (e.g., historical) block on the same chain: the block
+    /// number, timestamp, sequencer and L1 gas prices are taken from the header, and the rest from
+    /// this context.
+    pub fn with_block_header(self, block_header: &BlockHeader) -> Self {
+        Self {
+            block_number: block_header.block_number,
+            block_timestamp: block_header.timestamp,
+            sequencer_address: block_header.sequencer,
+            gas_prices: GasPrices {
+                eth_l1_gas_price: block_header.eth_l1_gas_price.0,
+                strk_l1_gas_price: block_header.strk_l1_gas_price.0,
+                ..self.gas_prices
+            },
+            ..self
+        }
+    }
+
+    pub fn fee_model(&self) -> &dyn FeeModel {
+        self.fee_model.as_deref().unwrap_or(&StarknetFeeModel)
+    }
+
+    pub fn try_from_json_string(raw_json: &str) -> BlockContextConfigResult<Self> {
+        let block_context: Self = serde_json::from_str(raw_json)?;
+        block_context.validate()?;
+        Ok(block_context)
+    }
+
+    pub fn try_from_toml_string(raw_toml: &str) -> BlockContextConfigResult<Self> {
+        // Goes through JSON, whose numbers (unlike TOML's) are deserializable into `u128`.
+        let json_value: serde_json::Value = toml::from_str(raw_toml)?;
+        let block_context: Self = serde_json::from_value(json_value)?;
+        block_context.validate()?;
+        Ok(block_context)
+    }
+
+    /// Verifies the values that deserialization alone does not; errors name the offending field by
+    /// its path in the config.
+    pub fn validate(&self) -> BlockContextConfigResult<()> {
+        let invalid_field = |path: &str, reason: &str| BlockContextConfigError::InvalidField {
+            path: path.to_string(),
+            reason: reason.to_string(),
+        };
+
+        if let Err(error) = validate_chain_id(&self.chain_id) {
+            return Err(invalid_field("chain_id", error.to_string().trim_end_matches('.')));
+        }
+        for (path, gas_price) in [
+            ("gas_prices.eth_l1_gas_price", self.gas_prices.eth_l1_gas_price),
+            ("gas_prices.strk_l1_gas_price", self.gas_prices.strk_l1_gas_price),
+        ] {
+            if gas_price == 0 {
+                return Err(invalid_field(path, "must be positive"));
+            }
+        }
+        if self.vm_resource_fee_cost.get(VmResourceKey::NSteps).is_none() {
+            return Err(invalid_field("vm_resource_fee_cost", "must contain n_steps"));
+        }
+        for (path, limit) in [
+            ("invoke_tx_max_n_steps", self.invoke_tx_max_n_steps as usize),
+            ("validate_max_n_steps", self.validate_max_n_steps as usize),
+            ("max_recursion_depth", self.max_recursion_depth),
+        ] {
+            if limit == 0 {
+                return Err(invalid_field(path, "must be positive"));
+            }
+        }
+        let tx_versions = &self.versioned_constants.tx_versions;
+        for (path, version_range) in [
+            ("versioned_constants.tx_versions.declare", tx_versions.declare),
+            ("versioned_constants.tx_versions.deploy_account", tx_versions.deploy_account),
+            ("versioned_constants.tx_versions.invoke", tx_versions.invoke),
+        ] {
+            if version_range.min > version_range.max {
+                return Err(invalid_field(path, "min must not exceed max"));
+            }
+        }
+        self.versioned_constants.validate().map_err(|error| match error {
+            VersionedConstantsError::SyscallGasCostTooLow { syscall, .. } => invalid_field(
+                &format!("versioned_constants.gas_costs.{syscall}"),
+                "must be at least the base syscall gas cost",
+            ),
+            VersionedConstantsError::IoError(_) | VersionedConstantsError::JsonParseError(_) => {
+                BlockContextConfigError::VersionedConstantsError(error)
+            }
+        })
+    }
 }
 
-#[derive(Clone, Debug)]
+/// Loads a JSON config, or a TOML config if the file extension is `toml`.
+#[cfg(feature = "fs")]
+impl TryFrom<&Path> for BlockContext {
+    type Error = BlockContextConfigError;
+
+    fn try_from(path: &Path) -> BlockContextConfigResult<Self> {
+        let raw_config = fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::try_from_toml_string(&raw_config),
+            _ => Self::try_from_json_string(&raw_config),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum BlockContextConfigError {
+    #[error("Invalid value of `{path}`: {reason}.")]
+    InvalidField { path: String, reason: String },
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("Failed to parse JSON: {0}.")]
+    JsonParseError(#[from] serde_json::Error),
+    #[error("Failed to parse TOML: {0}.")]
+    TomlParseError(#[from] toml::de::Error),
+    #[error(transparent)]
+    VersionedConstantsError(#[from] VersionedConstantsError),
+}
+
+pub type BlockContextConfigResult<T> = Result<T, BlockContextConfigError>;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct FeeTokenAddresses {
     pub strk_fee_token_address: ContractAddress,
     pub eth_fee_token_address: ContractAddress,
@@ -45,10 +248,13 @@ impl FeeTokenAddresses {
     }
 }
 
-#[derive(Clone, Debug)]
+/// The L2 gas prices are only charged in `ResourceGasMode::SeparateL2Gas`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct GasPrices {
     pub eth_l1_gas_price: u128,  // In wei.
     pub strk_l1_gas_price: u128, // In fri.
+    pub eth_l2_gas_price: u128,  // In wei.
+    pub strk_l2_gas_price: u128, // In fri.
 }
 
 impl GasPrices {
@@ -58,4 +264,11 @@ impl GasPrices {
             FeeType::Eth => self.eth_l1_gas_price,
         }
     }
+
+    pub fn get_l2_gas_price_by_fee_type(&self, fee_type: &FeeType) -> u128 {
+        match fee_type {
+            FeeType::Strk => self.strk_l2_gas_price,
+            FeeType::Eth => self.eth_l2_gas_price,
+        }
+    }
 }
@@ -0,0 +1,123 @@
+use assert_matches::assert_matches;
+use starknet_api::block::{BlockHeader, BlockNumber, BlockTimestamp, GasPrice};
+use starknet_api::core::{ContractAddress, PatriciaKey};
+use starknet_api::hash::StarkHash;
+use starknet_api::{contract_address, patricia_key};
+
+use crate::block_context::{BlockContext, BlockContextConfigError};
+use crate::fee::vm_resource_costs::{Ratio, VmResourceKey};
+use crate::versioned_constants::VersionedConstants;
+
+const RAW_TOML_CONFIG: &str = r#"
+chain_id = "SN_GOERLI"
+block_number = 2001
+block_timestamp = 1072023
+sequencer_address = "0x1000"
+invoke_tx_max_n_steps = 1000000
+validate_max_n_steps = 1000000
+max_recursion_depth = 50
+
+[fee_token_addresses]
+strk_fee_token_address = "0x1001"
+eth_fee_token_address = "0x1002"
+
+[vm_resource_fee_cost]
+n_steps = 0.0025
+pedersen_builtin = 0.08
+range_check_builtin = 0.04
+
+[gas_prices]
+eth_l1_gas_price = 100000000000
+strk_l1_gas_price = 100000000000
+eth_l2_gas_price = 80000
+strk_l2_gas_price = 80000
+"#;
+
+#[test]
+fn test_json_round_trip() {
+    let block_context = BlockContext::create_for_account_testing();
+    let json_value = serde_json::to_value(&block_context).unwrap();
+    let loaded_block_context = BlockContext::try_from_json_string(&json_value.to_string()).unwrap();
+    assert_eq!(serde_json::to_value(&loaded_block_context).unwrap(), json_value);
+}
+
+#[test]
+fn test_toml_config() {
+    let block_context = BlockContext::try_from_toml_string(RAW_TOML_CONFIG).unwrap();
+    assert_eq!(block_context.chain_id.0, "SN_GOERLI");
+    assert_eq!(block_context.gas_prices.eth_l1_gas_price, 100_000_000_000);
+    assert_eq!(block_context.vm_resource_fee_cost.get(VmResourceKey::Pedersen), Ratio::new(8, 100));
+    // Omitted fields are defaulted.
+    assert_eq!(block_context.max_nonce_gap, 0);
+    assert!(block_context.deprecated_hint_aliases.is_empty());
+    assert_eq!(block_context.unknown_hint_policy, None);
+    assert!(!block_context.retain_sierra_classes);
+    assert!(!block_context.charge_tips);
+    assert_eq!(block_context.versioned_constants, VersionedConstants::default());
+}
+
+#[test]
+fn test_invalid_config() {
+    let invalid_config =
+        RAW_TOML_CONFIG.replace("strk_l1_gas_price = 100000000000", "strk_l1_gas_price = 0");
+    assert_matches!(
+        BlockContext::try_from_toml_string(&invalid_config),
+        Err(BlockContextConfigError::InvalidField { path, .. })
+        if path == "gas_prices.strk_l1_gas_price"
+    );
+
+    let mut json_value = serde_json::to_value(BlockContext::create_for_account_testing()).unwrap();
+    json_value["versioned_constants"]["tx_versions"]["invoke"]["min"] = 4.into();
+    assert_matches!(
+        BlockContext::try_from_json_string(&json_value.to_string()),
+        Err(BlockContextConfigError::InvalidField { path, .. })
+        if path == "versioned_constants.tx_versions.invoke"
+    );
+
+    let mut json_value = serde_json::to_value(BlockContext::create_for_account_testing()).unwrap();
+    json_value["versioned_constants"]["gas_costs"]["storage_read"] = 0.into();
+    assert_matches!(
+        BlockContext::try_from_json_string(&json_value.to_string()),
+        Err(BlockContextConfigError::InvalidField { path, .. })
+        if path == "versioned_constants.gas_costs.storage_read"
+    );
+
+    let invalid_config = RAW_TOML_CONFIG.replace("SN_GOERLI", "SN GOERLI");
+    assert_matches!(
+        BlockContext::try_from_toml_string(&invalid_config),
+        Err(BlockContextConfigError::InvalidField { path, .. }) if path == "chain_id"
+    );
+
+    let invalid_config = RAW_TOML_CONFIG.replace("n_steps = 0.0025", "n_cycles = 0.0025");
+    assert_matches!(
+        BlockContext::try_from_toml_string(&invalid_config),
+        Err(BlockContextConfigError::JsonParseError(error))
+        if error.to_string().contains("n_cycles")
+    );
+}
+
+#[test]
+fn test_with_block_header() {
+    let block_header = BlockHeader {
+        block_number: BlockNumber(500000),
+        timestamp: BlockTimestamp(1700000000),
+        sequencer: contract_address!("0x1234"),
+        eth_l1_gas_price: GasPrice(30_000_000_000),
+        strk_l1_gas_price: GasPrice(40_000_000_000),
+        ..Default::default()
+    };
+    let block_context = BlockContext::create_for_testing();
+    let historical_block_context = block_context.clone().with_block_header(&block_header);
+
+    assert_eq!(historical_block_context.block_number, block_header.block_number);
+    assert_eq!(historical_block_context.block_timestamp, block_header.timestamp);
+    assert_eq!(historical_block_context.sequencer_address, block_header.sequencer);
+    assert_eq!(historical_block_context.gas_prices.eth_l1_gas_price, 30_000_000_000);
+    assert_eq!(historical_block_context.gas_prices.strk_l1_gas_price, 40_000_000_000);
+    // The chain configuration is kept.
+    assert_eq!(historical_block_context.chain_id, block_context.chain_id);
+    assert_eq!(
+        historical_block_context.gas_prices.eth_l2_gas_price,
+        block_context.gas_prices.eth_l2_gas_price
+    );
+}
@@ -0,0 +1,157 @@
+use std::iter::Sum;
+use std::ops::{Add, AddAssign};
+
+use starknet_api::deprecated_contract_class::EntryPointType;
+use thiserror::Error;
+
+use crate::metrics::{MetricsSink, BOUNCER_ACCUMULATED_WEIGHT, BOUNCER_MAX_WEIGHT};
+use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::transaction_types::TransactionType;
+
+#[cfg(test)]
+#[path = "bouncer_test.rs"]
+pub mod test;
+
+#[derive(Debug, Error)]
+pub enum BouncerError {
+    #[error(
+        "Transaction exceeds the block cap of {resource}: the block would contain {total_count} \
+         (cap: {max_count})."
+    )]
+    BlockCapExceeded { resource: &'static str, total_count: usize, max_count: usize },
+}
+
+pub type BouncerResult<T> = Result<T, BouncerError>;
+
+/// The block resources that are not captured by step counting, but translate to large data
+/// availability and commitment costs.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BouncerWeights {
+    pub n_declared_classes: usize,
+    pub n_deployed_contracts: usize,
+}
+
+impl Add for BouncerWeights {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl AddAssign for BouncerWeights {
+    fn add_assign(&mut self, other: Self) {
+        self.n_declared_classes += other.n_declared_classes;
+        self.n_deployed_contracts += other.n_deployed_contracts;
+    }
+}
+
+impl Sum for BouncerWeights {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+impl BouncerWeights {
+    /// Returns the weights of an executed transaction of the given type; deployments are counted
+    /// by the executed constructors (of both `DeployAccount` transactions and `deploy` syscalls).
+    pub fn new(tx_type: TransactionType, tx_execution_info: &TransactionExecutionInfo) -> Self {
+        let n_deployed_contracts = tx_execution_info
+            .execute_call_info
+            .iter()
+            .flat_map(|call_info| call_info.into_iter())
+            .filter(|call_info| call_info.call.entry_point_type == EntryPointType::Constructor)
+            .count();
+
+        Self {
+            n_declared_classes: usize::from(tx_type == TransactionType::Declare),
+            n_deployed_contracts,
+        }
+    }
+}
+
+/// Per-block caps on the bouncer weights; unbounded by default.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BouncerConfig {
+    pub max_declared_classes: usize,
+    pub max_deployed_contracts: usize,
+}
+
+impl Default for BouncerConfig {
+    fn default() -> Self {
+        Self { max_declared_classes: usize::MAX, max_deployed_contracts: usize::MAX }
+    }
+}
+
+/// Accumulates the weights of the transactions of a block, and rejects transactions that would
+/// exceed the block caps.
+#[derive(Clone, Debug, Default)]
+pub struct Bouncer {
+    pub config: BouncerConfig,
+    accumulated_weights: BouncerWeights,
+}
+
+impl Bouncer {
+    pub fn new(config: BouncerConfig) -> Self {
+        Self { config, accumulated_weights: BouncerWeights::default() }
+    }
+
+    pub fn accumulated_weights(&self) -> BouncerWeights {
+        self.accumulated_weights
+    }
+
+    /// Verifies that adding the given weights to the block does not exceed the block caps.
+    pub fn check(&self, tx_weights: &BouncerWeights) -> BouncerResult<()> {
+        for (resource, accumulated_count, tx_count, max_count) in [
+            (
+                "declared classes",
+                self.accumulated_weights.n_declared_classes,
+                tx_weights.n_declared_classes,
+                self.config.max_declared_classes,
+            ),
+            (
+                "deployed contracts",
+                self.accumulated_weights.n_deployed_contracts,
+                tx_weights.n_deployed_contracts,
+                self.config.max_deployed_contracts,
+            ),
+        ] {
+            let total_count = accumulated_count.saturating_add(tx_count);
+            if total_count > max_count {
+                return Err(BouncerError::BlockCapExceeded { resource, total_count, max_count });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reports the accumulated weights of the block and their caps, labeled by resource.
+    pub fn report(&self, metrics_sink: &dyn MetricsSink) {
+        for (resource, accumulated_count, max_count) in [
+            (
+                "declared_classes",
+                self.accumulated_weights.n_declared_classes,
+                self.config.max_declared_classes,
+            ),
+            (
+                "deployed_contracts",
+                self.accumulated_weights.n_deployed_contracts,
+                self.config.max_deployed_contracts,
+            ),
+        ] {
+            let labels = [("resource", resource)];
+            metrics_sink.set_gauge(BOUNCER_ACCUMULATED_WEIGHT, &labels, accumulated_count as f64);
+            metrics_sink.set_gauge(BOUNCER_MAX_WEIGHT, &labels, max_count as f64);
+        }
+    }
+
+    /// Adds the given weights to the block, if they do not exceed the block caps; otherwise, the
+    /// block is left unchanged.
+    pub fn try_update(&mut self, tx_weights: &BouncerWeights) -> BouncerResult<()> {
+        self.check(tx_weights)?;
+        self.accumulated_weights += *tx_weights;
+
+        Ok(())
+    }
+}
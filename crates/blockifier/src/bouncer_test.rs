@@ -0,0 +1,73 @@
+use assert_matches::assert_matches;
+use starknet_api::deprecated_contract_class::EntryPointType;
+
+use crate::bouncer::{Bouncer, BouncerConfig, BouncerError, BouncerWeights};
+use crate::execution::call_info::CallInfo;
+use crate::execution::entry_point::CallEntryPoint;
+use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::transaction_types::TransactionType;
+
+#[test]
+fn test_bouncer_weights() {
+    let constructor_call = || CallInfo {
+        call: CallEntryPoint {
+            entry_point_type: EntryPointType::Constructor,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    // An invoke deploying two contracts.
+    let tx_execution_info = TransactionExecutionInfo {
+        execute_call_info: Some(CallInfo {
+            inner_calls: vec![constructor_call(), CallInfo::default(), constructor_call()],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        BouncerWeights::new(TransactionType::InvokeFunction, &tx_execution_info),
+        BouncerWeights { n_declared_classes: 0, n_deployed_contracts: 2 }
+    );
+    assert_eq!(
+        BouncerWeights::new(TransactionType::Declare, &TransactionExecutionInfo::default()),
+        BouncerWeights { n_declared_classes: 1, n_deployed_contracts: 0 }
+    );
+}
+
+#[test]
+fn test_bouncer_caps() {
+    let mut bouncer =
+        Bouncer::new(BouncerConfig { max_declared_classes: 1, max_deployed_contracts: 3 });
+    let declare_weights = BouncerWeights { n_declared_classes: 1, n_deployed_contracts: 0 };
+    let deploy_weights = BouncerWeights { n_declared_classes: 0, n_deployed_contracts: 2 };
+
+    bouncer.try_update(&declare_weights).unwrap();
+    bouncer.try_update(&deploy_weights).unwrap();
+    assert_matches!(
+        bouncer.try_update(&declare_weights).unwrap_err(),
+        BouncerError::BlockCapExceeded {
+            resource: "declared classes",
+            total_count: 2,
+            max_count: 1
+        }
+    );
+    assert_matches!(
+        bouncer.try_update(&deploy_weights).unwrap_err(),
+        BouncerError::BlockCapExceeded {
+            resource: "deployed contracts",
+            total_count: 4,
+            max_count: 3
+        }
+    );
+
+    // Rejected transactions do not affect the block.
+    assert_eq!(
+        bouncer.accumulated_weights(),
+        BouncerWeights { n_declared_classes: 1, n_deployed_contracts: 2 }
+    );
+    assert_eq!(bouncer.accumulated_weights(), [declare_weights, deploy_weights].into_iter().sum());
+    assert!(Bouncer::default()
+        .check(&BouncerWeights { n_declared_classes: usize::MAX, n_deployed_contracts: usize::MAX })
+        .is_ok());
+}
@@ -0,0 +1,80 @@
+use starknet_api::core::ChainId;
+use starknet_api::hash::StarkFelt;
+use thiserror::Error;
+
+#[cfg(test)]
+#[path = "chain_id_test.rs"]
+pub mod test;
+
+pub const MAINNET_CHAIN_ID: &str = "SN_MAIN";
+pub const SEPOLIA_CHAIN_ID: &str = "SN_SEPOLIA";
+
+/// The maximal length of a chain ID; chain IDs are encoded as Cairo short strings, i.e., big-endian
+/// ASCII in a single felt.
+pub const MAX_CHAIN_ID_LENGTH: usize = 31;
+
+#[derive(Debug, Error)]
+pub enum ChainIdError {
+    #[error("Chain ID must not be empty.")]
+    EmptyChainId,
+    #[error("Chain ID {0:?} must consist of printable, non-space ASCII characters.")]
+    NonAsciiChainId(String),
+    #[error("Chain ID {0:?} is longer than {MAX_CHAIN_ID_LENGTH} characters.")]
+    ChainIdTooLong(String),
+    #[error("{0} is not the encoding of a chain ID.")]
+    InvalidChainIdEncoding(StarkFelt),
+}
+
+pub type ChainIdResult<T> = Result<T, ChainIdError>;
+
+/// Returns the chain ID of the given name: `mainnet` or `sepolia` (case-insensitive) for the public
+/// Starknet chains, or the name itself (e.g., of an appchain) otherwise.
+pub fn chain_id_from_name(name: &str) -> ChainIdResult<ChainId> {
+    let chain_id = match name.to_ascii_lowercase().as_str() {
+        "mainnet" => ChainId(MAINNET_CHAIN_ID.to_string()),
+        "sepolia" => ChainId(SEPOLIA_CHAIN_ID.to_string()),
+        _ => ChainId(name.to_string()),
+    };
+    validate_chain_id(&chain_id)?;
+
+    Ok(chain_id)
+}
+
+/// Verifies the chain ID can be encoded as a felt; see `chain_id_as_felt`.
+pub fn validate_chain_id(chain_id: &ChainId) -> ChainIdResult<()> {
+    let name = &chain_id.0;
+    if name.is_empty() {
+        return Err(ChainIdError::EmptyChainId);
+    }
+    if !name.bytes().all(|byte| byte.is_ascii_graphic()) {
+        return Err(ChainIdError::NonAsciiChainId(name.clone()));
+    }
+    if name.len() > MAX_CHAIN_ID_LENGTH {
+        return Err(ChainIdError::ChainIdTooLong(name.clone()));
+    }
+
+    Ok(())
+}
+
+/// Returns the felt encoding of the chain ID, as given to the transaction hash and to the
+/// `get_tx_info` syscall; e.g., `SN_MAIN` is encoded as `0x534e5f4d41494e`.
+pub fn chain_id_as_felt(chain_id: &ChainId) -> ChainIdResult<StarkFelt> {
+    validate_chain_id(chain_id)?;
+
+    let name = chain_id.0.as_bytes();
+    let mut bytes = [0_u8; 32];
+    bytes[32 - name.len()..].copy_from_slice(name);
+    Ok(StarkFelt::new(bytes).expect("A short string is a valid felt."))
+}
+
+/// Returns the chain ID encoded by the felt; the inverse of `chain_id_as_felt`.
+pub fn chain_id_from_felt(felt: StarkFelt) -> ChainIdResult<ChainId> {
+    let bytes = felt.bytes();
+    let first_nonzero_index = bytes.iter().position(|&byte| byte != 0).unwrap_or(bytes.len());
+    let chain_id = String::from_utf8(bytes[first_nonzero_index..].to_vec())
+        .map(ChainId)
+        .map_err(|_| ChainIdError::InvalidChainIdEncoding(felt))?;
+    validate_chain_id(&chain_id).map_err(|_| ChainIdError::InvalidChainIdEncoding(felt))?;
+
+    Ok(chain_id)
+}
@@ -0,0 +1,44 @@
+use assert_matches::assert_matches;
+use rstest::rstest;
+use starknet_api::core::ChainId;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+
+use crate::chain_id::{
+    chain_id_as_felt, chain_id_from_felt, chain_id_from_name, ChainIdError, MAINNET_CHAIN_ID,
+    SEPOLIA_CHAIN_ID,
+};
+
+#[rstest]
+#[case("mainnet", MAINNET_CHAIN_ID, "0x534e5f4d41494e")]
+#[case("Sepolia", SEPOLIA_CHAIN_ID, "0x534e5f5345504f4c4941")]
+#[case("SN_MAIN", MAINNET_CHAIN_ID, "0x534e5f4d41494e")]
+#[case("MY_APPCHAIN", "MY_APPCHAIN", "0x4d595f415050434841494e")]
+fn test_chain_id_encoding(
+    #[case] name: &str,
+    #[case] expected_chain_id: &str,
+    #[case] expected_felt: &str,
+) {
+    let chain_id = chain_id_from_name(name).unwrap();
+    assert_eq!(chain_id, ChainId(expected_chain_id.to_string()));
+
+    let felt = chain_id_as_felt(&chain_id).unwrap();
+    assert_eq!(felt, stark_felt!(expected_felt));
+    assert_eq!(chain_id_from_felt(felt).unwrap(), chain_id);
+}
+
+#[test]
+fn test_invalid_chain_id() {
+    assert_matches!(chain_id_from_name(""), Err(ChainIdError::EmptyChainId));
+    assert_matches!(chain_id_from_name("MY APPCHAIN"), Err(ChainIdError::NonAsciiChainId(_)));
+    assert_matches!(chain_id_from_name("MY_APPCHAIN_ÉTÉ"), Err(ChainIdError::NonAsciiChainId(_)));
+    assert_matches!(
+        chain_id_as_felt(&ChainId("MY_APPCHAIN_WITH_A_VERY_LONG_NAME".to_string())),
+        Err(ChainIdError::ChainIdTooLong(_))
+    );
+
+    // Felts that are not short strings.
+    for felt in [stark_felt!(0_u8), stark_felt!("0x534e00"), stark_felt!("0xff")] {
+        assert_matches!(chain_id_from_felt(felt), Err(ChainIdError::InvalidChainIdEncoding(_)));
+    }
+}
@@ -9,4 +9,5 @@ pub mod entry_point_execution;
 pub mod errors;
 pub mod execution_utils;
 pub mod hint_code;
+pub mod hint_extension;
 pub mod syscalls;
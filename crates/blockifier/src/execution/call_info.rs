@@ -1,19 +1,72 @@
 use std::collections::HashSet;
+use std::fmt;
 
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
-use starknet_api::core::{ClassHash, EthAddress};
+use serde::{Deserialize, Serialize};
+use starknet_api::core::{ClassHash, ContractAddress, EthAddress};
 use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey;
-use starknet_api::transaction::{EventContent, L2ToL1Payload};
+use starknet_api::transaction::{Calldata, EventContent, L2ToL1Payload};
 
-use crate::execution::entry_point::CallEntryPoint;
+use crate::abi::abi_registry::AbiRegistry;
+use crate::abi::abi_utils::keccak256_of_felts;
+use crate::execution::deprecated_syscalls::hint_processor::SyscallCounter;
+use crate::execution::entry_point::{CallEntryPoint, CallType};
 use crate::state::cached_state::StorageEntry;
 use crate::transaction::errors::TransactionExecutionError;
 use crate::transaction::objects::TransactionExecutionResult;
+use crate::utils::{felt_to_u128, format_address, format_felt, format_felts, usize_to_felt};
 
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg(test)]
+#[path = "call_info_test.rs"]
+pub mod test;
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Retdata(pub Vec<StarkFelt>);
 
+impl Retdata {
+    /// Returns the returned value, if the retdata consists of exactly one felt.
+    pub fn as_felt(&self) -> Option<StarkFelt> {
+        match self.0.as_slice() {
+            [value] => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the returned value, if the retdata consists of exactly one felt that fits in a u128.
+    pub fn as_u128(&self) -> Option<u128> {
+        felt_to_u128(self.as_felt()?).ok()
+    }
+
+    /// Returns the returned value, if the retdata consists of exactly one felt that is 0 or 1.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.as_u128()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl From<Vec<StarkFelt>> for Retdata {
+    fn from(retdata: Vec<StarkFelt>) -> Self {
+        Self(retdata)
+    }
+}
+
+impl From<Calldata> for Retdata {
+    fn from(calldata: Calldata) -> Self {
+        Self(calldata.0.to_vec())
+    }
+}
+
+/// Allows comparing the returned values to the calldata of a call; e.g., of an echo function.
+impl PartialEq<Calldata> for Retdata {
+    fn eq(&self, calldata: &Calldata) -> bool {
+        self.0 == *calldata.0
+    }
+}
+
 #[macro_export]
 macro_rules! retdata {
     ( $( $x:expr ),* ) => {
@@ -22,21 +75,35 @@ macro_rules! retdata {
 }
 
 #[cfg_attr(test, derive(Clone))]
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct OrderedEvent {
     pub order: usize,
     pub event: EventContent,
 }
 
 #[cfg_attr(test, derive(Clone))]
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct MessageToL1 {
     pub to_address: EthAddress,
     pub payload: L2ToL1Payload,
 }
 
+impl MessageToL1 {
+    /// Returns the hash of the message sent by the given contract, as computed by the Starknet core
+    /// contract on L1 upon consumption:
+    /// `keccak256(from_address, to_address, payload_size, payload)`.
+    pub fn message_hash(&self, from_address: ContractAddress) -> [u8; 32] {
+        let mut to_address = [0_u8; 32];
+        to_address[12..].copy_from_slice(self.to_address.0.as_bytes());
+        let to_address = StarkFelt::new(to_address).expect("An Ethereum address fits in a felt.");
+
+        let header = [*from_address.0.key(), to_address, usize_to_felt(self.payload.0.len())];
+        keccak256_of_felts(header.into_iter().chain(self.payload.0.iter().copied()))
+    }
+}
+
 #[cfg_attr(test, derive(Clone))]
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct OrderedL2ToL1Message {
     pub order: usize,
     pub message: MessageToL1,
@@ -44,17 +111,21 @@ pub struct OrderedL2ToL1Message {
 
 /// Represents the effects of executing a single entry point.
 #[cfg_attr(test, derive(Clone))]
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct CallExecution {
     pub retdata: Retdata,
     pub events: Vec<OrderedEvent>,
     pub l2_to_l1_messages: Vec<OrderedL2ToL1Message>,
     pub failed: bool,
     pub gas_consumed: u64,
+    /// The contents of the output builtin segment; collected only if
+    /// `BlockContext::collect_program_outputs` is set.
+    #[serde(default)]
+    pub program_output: Vec<StarkFelt>,
 }
 
 /// Represents the full effects of executing an entry point, including the inner calls it invoked.
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct CallInfo {
     pub call: CallEntryPoint,
     pub execution: CallExecution,
@@ -63,10 +134,26 @@ pub struct CallInfo {
 
     // Additional information gathered during execution.
     pub storage_read_values: Vec<StarkFelt>,
+    /// The storage keys of `storage_read_values`, in the same order.
+    #[serde(default)]
+    pub storage_read_keys: Vec<StorageKey>,
     pub accessed_storage_keys: HashSet<StorageKey>,
+    /// The number of invocations of each syscall by this call (not including inner calls).
+    pub syscall_counter: SyscallCounter,
 }
 
 impl CallInfo {
+    /// Returns the number of invocations of each syscall during this call execution, including
+    /// inner calls.
+    pub fn summarize_syscalls(&self) -> SyscallCounter {
+        let mut syscall_counter = SyscallCounter::new();
+        for call_info in self {
+            merge_syscall_counters(&mut syscall_counter, &call_info.syscall_counter);
+        }
+
+        syscall_counter
+    }
+
     /// Returns the set of class hashes that were executed during this call execution.
     // TODO: Add unit test for this method
     pub fn get_executed_class_hashes(&self) -> HashSet<ClassHash> {
@@ -81,6 +168,18 @@ impl CallInfo {
         class_hashes
     }
 
+    /// Returns the hashes of the L2-to-L1 messages sent during this call execution, in the order of
+    /// the call tree traversal.
+    pub fn get_l2_to_l1_message_hashes(&self) -> Vec<[u8; 32]> {
+        self.into_iter()
+            .flat_map(|call_info| {
+                call_info.execution.l2_to_l1_messages.iter().map(|ordered_message| {
+                    ordered_message.message.message_hash(call_info.call.storage_address)
+                })
+            })
+            .collect()
+    }
+
     /// Returns the set of storage entries visited during this call execution.
     // TODO: Add unit test for this method
     pub fn get_visited_storage_entries(&self) -> HashSet<StorageEntry> {
@@ -134,6 +233,88 @@ impl CallInfo {
     }
 }
 
+impl CallInfo {
+    /// Renders the call tree rooted at this call: a line per call, followed by its retdata,
+    /// events, messages and failure, with inner calls indented below it.
+    /// Selectors and class hashes are resolved by `abi_registry`, if given.
+    pub fn fmt_tree(
+        &self,
+        f: &mut dyn fmt::Write,
+        abi_registry: Option<&AbiRegistry>,
+    ) -> fmt::Result {
+        let mut lines = Vec::new();
+        self.push_tree_lines(&mut lines, abi_registry, 0);
+        write!(f, "{}", lines.join("\n"))
+    }
+
+    pub(crate) fn push_tree_lines(
+        &self,
+        lines: &mut Vec<String>,
+        abi_registry: Option<&AbiRegistry>,
+        depth: usize,
+    ) {
+        let indent = "  ".repeat(depth);
+        let call = &self.call;
+        let selector = call.entry_point_selector;
+        let entry_point = abi_registry
+            .and_then(|registry| registry.selector_name(&selector))
+            .map_or_else(|| format_felt(&selector.0), str::to_string);
+        let call_kind = match call.call_type {
+            CallType::Call => "call",
+            CallType::Delegate => "library call",
+        };
+        let class_hash = match call.class_hash {
+            Some(class_hash) => abi_registry
+                .and_then(|registry| registry.class_name(&class_hash))
+                .map_or_else(|| format_felt(&class_hash.0), str::to_string),
+            None => "unknown".to_string(),
+        };
+        lines.push(format!(
+            "{indent}{call_kind} {entry_point} on {address} (class: {class_hash}, calldata: \
+             {calldata})",
+            address = format_address(&call.storage_address),
+            calldata = format_felts(&call.calldata.0),
+        ));
+
+        let execution = &self.execution;
+        lines.push(format!("{indent}  retdata: {}", format_felts(&execution.retdata.0)));
+        for OrderedEvent { order, event } in &execution.events {
+            lines.push(format!(
+                "{indent}  event #{order}: keys: {keys}, data: {data}",
+                keys = format_felts(&event.keys.iter().map(|key| key.0).collect::<Vec<_>>()),
+                data = format_felts(&event.data.0),
+            ));
+        }
+        for OrderedL2ToL1Message { order, message } in &execution.l2_to_l1_messages {
+            lines.push(format!(
+                "{indent}  message #{order} to {to_address}: {payload}",
+                to_address = format_args!("{:#x}", message.to_address.0),
+                payload = format_felts(&message.payload.0),
+            ));
+        }
+        if execution.failed {
+            lines.push(format!("{indent}  FAILED"));
+        }
+
+        for inner_call in &self.inner_calls {
+            inner_call.push_tree_lines(lines, abi_registry, depth + 1);
+        }
+    }
+}
+
+impl fmt::Display for CallInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_tree(f, None)
+    }
+}
+
+/// Adds the counts of the given syscall counter to the target counter.
+pub fn merge_syscall_counters(target: &mut SyscallCounter, other: &SyscallCounter) {
+    for (selector, count) in other {
+        *target.entry(*selector).or_default() += count;
+    }
+}
+
 pub struct CallInfoIter<'a> {
     call_infos: Vec<&'a CallInfo>,
 }
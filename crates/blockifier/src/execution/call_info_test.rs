@@ -0,0 +1,144 @@
+use starknet_api::core::{ClassHash, ContractAddress, EthAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::{Calldata, EventContent, EventData, EventKey, Fee, L2ToL1Payload};
+use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
+
+use super::{CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message, Retdata};
+use crate::abi::abi_registry::AbiRegistry;
+use crate::abi::abi_utils::selector_from_name;
+use crate::execution::entry_point::{CallEntryPoint, CallType};
+use crate::retdata;
+use crate::test_utils::to_hex_string;
+use crate::transaction::objects::TransactionExecutionInfo;
+
+fn call_tree() -> CallInfo {
+    let inner_call = CallInfo {
+        call: CallEntryPoint {
+            class_hash: Some(class_hash!("0x20")),
+            entry_point_selector: selector_from_name("inner"),
+            storage_address: contract_address!("0x1"),
+            call_type: CallType::Delegate,
+            ..Default::default()
+        },
+        execution: CallExecution {
+            events: vec![OrderedEvent {
+                order: 0,
+                event: EventContent {
+                    keys: vec![EventKey(stark_felt!(3_u8))],
+                    data: EventData(vec![stark_felt!(4_u8)]),
+                },
+            }],
+            failed: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    CallInfo {
+        call: CallEntryPoint {
+            class_hash: Some(class_hash!("0x10")),
+            entry_point_selector: selector_from_name("outer"),
+            calldata: calldata![stark_felt!(1_u8), stark_felt!(2_u8)],
+            storage_address: contract_address!("0x1"),
+            ..Default::default()
+        },
+        execution: CallExecution {
+            retdata: retdata![stark_felt!(5_u8)],
+            l2_to_l1_messages: vec![OrderedL2ToL1Message {
+                order: 0,
+                message: MessageToL1 {
+                    to_address: EthAddress::try_from(stark_felt!(6_u8)).unwrap(),
+                    payload: L2ToL1Payload(vec![stark_felt!(7_u8)]),
+                },
+            }],
+            ..Default::default()
+        },
+        inner_calls: vec![inner_call],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_fmt_tree() {
+    let call_info = call_tree();
+    let mut abi_registry = AbiRegistry::default();
+    abi_registry.register_entry_point("outer");
+    abi_registry.register_entry_point("inner");
+    abi_registry.register_class(class_hash!("0x10"), "Outer");
+    let mut rendered = String::new();
+    call_info.fmt_tree(&mut rendered, Some(&abi_registry)).unwrap();
+
+    assert_eq!(
+        rendered,
+        "call outer on 0x1 (class: Outer, calldata: [0x1, 0x2])
+  retdata: [0x5]
+  message #0 to 0x0000000000000000000000000000000000000006: [0x7]
+  library call inner on 0x1 (class: 0x20, calldata: [])
+    retdata: []
+    event #0: keys: [0x3], data: [0x4]
+    FAILED"
+    );
+
+    // Without names, selectors are shown as is.
+    let outer_selector =
+        format!("{:#x}", starknet_crypto::FieldElement::from(selector_from_name("outer").0));
+    assert!(call_info.to_string().starts_with(&format!("call {outer_selector} on 0x1")));
+}
+
+#[test]
+fn test_fmt_execution_info_tree() {
+    let execution_info = TransactionExecutionInfo {
+        execute_call_info: Some(call_tree()),
+        actual_fee: Fee(8),
+        revert_error: Some("Out of gas.".to_string()),
+        ..Default::default()
+    };
+    let rendered = execution_info.to_string();
+    let lines: Vec<&str> = rendered.lines().collect();
+
+    assert_eq!(lines[0], "execute:");
+    assert!(lines[1].starts_with("  call "));
+    assert_eq!(lines[lines.len() - 2..], ["actual fee: 8", "reverted: Out of gas."]);
+}
+
+#[test]
+fn test_l2_to_l1_message_hash() {
+    let message = MessageToL1 {
+        to_address: EthAddress::try_from(stark_felt!(0xabcd_u16)).unwrap(),
+        payload: L2ToL1Payload(vec![stark_felt!(12_u8), stark_felt!(34_u8)]),
+    };
+    assert_eq!(
+        to_hex_string(&message.message_hash(contract_address!("0x1234"))),
+        "3476e1432352891d89d984e09f64b95879377f1a88455bc0543d02c61312ba99"
+    );
+
+    // The sender of a message is the contract whose storage the sending call runs on.
+    let message_hashes: Vec<String> =
+        call_tree().get_l2_to_l1_message_hashes().iter().map(|hash| to_hex_string(hash)).collect();
+    assert_eq!(
+        message_hashes,
+        ["f56fa028911c8e5e62a07c5257c71830fc927840fbe455bea3e9dcef4ad767e8"]
+    );
+}
+
+#[test]
+fn test_retdata_conversions() {
+    let felt_true = retdata![StarkFelt::ONE];
+    assert_eq!(felt_true.as_felt(), Some(StarkFelt::ONE));
+    assert_eq!(felt_true.as_u128(), Some(1));
+    assert_eq!(felt_true.as_bool(), Some(true));
+    assert_eq!(retdata![StarkFelt::ZERO].as_bool(), Some(false));
+
+    let large_value = retdata![stark_felt!(u128::MAX)];
+    assert_eq!(large_value.as_u128(), Some(u128::MAX));
+    assert_eq!(large_value.as_bool(), None);
+    assert_eq!(retdata![stark_felt!("0x100000000000000000000000000000000")].as_u128(), None);
+
+    // Only single-valued retdata is converted.
+    assert_eq!(retdata![].as_felt(), None);
+    assert_eq!(retdata![StarkFelt::ONE, StarkFelt::ONE].as_bool(), None);
+
+    let calldata = calldata![stark_felt!(1_u8), stark_felt!(2_u8)];
+    assert_eq!(Retdata::from(calldata.clone()), calldata);
+    assert_ne!(retdata![stark_felt!(1_u8)], calldata);
+}
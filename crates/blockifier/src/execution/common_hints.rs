@@ -18,7 +18,11 @@ use cairo_vm::vm::runners::cairo_runner::RunResources;
 use cairo_vm::vm::vm_core::VirtualMachine;
 use num_bigint::BigUint;
 use num_traits::{Num, One, Zero};
+use serde::{Deserialize, Serialize};
+use starknet_api::core::ContractAddress;
 
+use crate::block_context::BlockContext;
+use crate::execution::deprecated_syscalls::DeprecatedSyscallSelector;
 use crate::execution::hint_code::{
     NORMALIZE_ADDRESS_SET_IS_250_HINT, NORMALIZE_ADDRESS_SET_IS_SMALL_HINT,
 };
@@ -33,8 +37,90 @@ pub enum ExecutionMode {
     Validate,
 }
 
+/// Syscalls that may not be invoked in validate mode, as their result depends on the block the
+/// transaction is included in.
+const SYSCALLS_FORBIDDEN_IN_VALIDATE: &[DeprecatedSyscallSelector] =
+    &[DeprecatedSyscallSelector::GetBlockHash, DeprecatedSyscallSelector::GetSequencerAddress];
+
+/// The block info exposed to an entry point.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BlockInfoView {
+    pub block_number: u64,
+    pub block_timestamp: u64,
+    pub sequencer_address: ContractAddress,
+}
+
+/// The mode-specific rules of the syscalls; shared by the Cairo 0 and Cairo 1 syscall handlers.
+impl ExecutionMode {
+    pub fn is_syscall_allowed(self, selector: DeprecatedSyscallSelector) -> bool {
+        match self {
+            Self::Execute => true,
+            Self::Validate => !SYSCALLS_FORBIDDEN_IN_VALIDATE.contains(&selector),
+        }
+    }
+
+    /// Returns whether the given contract may call the given contract in this mode: validation
+    /// may not depend on the state of other contracts.
+    pub fn is_inner_call_allowed(
+        self,
+        caller_address: ContractAddress,
+        callee_address: ContractAddress,
+    ) -> bool {
+        match self {
+            Self::Execute => true,
+            Self::Validate => caller_address == callee_address,
+        }
+    }
+
+    /// Returns the block info visible in this mode: validation must not depend on the exact block
+    /// the transaction is included in, so the block number and timestamp are rounded (see
+    /// `ValidateRounding`), and the sequencer address is hidden.
+    pub fn block_info(self, block_context: &BlockContext) -> BlockInfoView {
+        let block_number = block_context.block_number.0;
+        let block_timestamp = block_context.block_timestamp.0;
+        match self {
+            Self::Execute => BlockInfoView {
+                block_number,
+                block_timestamp,
+                sequencer_address: block_context.sequencer_address,
+            },
+            Self::Validate => {
+                let rounding = &block_context.versioned_constants.validate_rounding;
+                BlockInfoView {
+                    block_number: rounding.round_block_number(block_number),
+                    block_timestamp: rounding.round_timestamp(block_timestamp),
+                    sequencer_address: ContractAddress::default(),
+                }
+            }
+        }
+    }
+}
+
 pub type HintExecutionResult = Result<(), HintError>;
 
+/// The behavior on hints the hint processors do not support (e.g., hints of old Cairo 0 classes,
+/// or Cairo 1 test functions); see `BlockContext::unknown_hint_policy`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum UnknownHintPolicy {
+    /// Fails with a generic error, which does not echo the hint.
+    Reject,
+    /// Fails with an error that captures the raw hint code, for diagnosing why a class cannot be
+    /// executed.
+    ReportHintCode,
+}
+
+impl UnknownHintPolicy {
+    /// Returns the error of an unsupported hint, failed for the given reason.
+    pub fn error(self, reason: &str, hint_code: &str) -> HintError {
+        match self {
+            Self::Reject => HintError::CustomHint(reason.into()),
+            Self::ReportHintCode => {
+                HintError::CustomHint(format!("{reason} Hint code:\n{hint_code}").into())
+            }
+        }
+    }
+}
+
 /// Must comply with the API of a hint function, as defined by the `HintProcessor`.
 pub fn normalize_address_set_is_small(
     vm: &mut VirtualMachine,
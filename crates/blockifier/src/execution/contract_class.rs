@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::ops::Deref;
 use std::sync::Arc;
 
@@ -6,6 +7,11 @@ use cairo_felt::Felt252;
 use cairo_lang_casm;
 use cairo_lang_casm::hints::Hint;
 use cairo_lang_starknet::casm_contract_class::{CasmContractClass, CasmContractEntryPoint};
+use cairo_lang_starknet::contract_class::{
+    ContractClass as CairoLangContractClass, ContractEntryPoint as CairoLangContractEntryPoint,
+    ContractEntryPoints as CairoLangContractEntryPoints,
+};
+use cairo_lang_utils::bigint::BigUintAsHex;
 use cairo_vm::serde::deserialize_program::{
     ApTracking, FlowTrackingData, HintParams, ReferenceManager,
 };
@@ -14,23 +20,34 @@ use cairo_vm::types::program::Program;
 use cairo_vm::types::relocatable::MaybeRelocatable;
 use cairo_vm::vm::runners::builtin_runner::{HASH_BUILTIN_NAME, POSEIDON_BUILTIN_NAME};
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
-use serde::de::Error as DeserializationError;
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{Error as DeserializationError, MapAccess, Visitor};
 use serde::{Deserialize, Deserializer};
 use starknet_api::core::EntryPointSelector;
 use starknet_api::deprecated_contract_class::{
-    ContractClass as DeprecatedContractClass, EntryPoint, EntryPointOffset, EntryPointType,
-    Program as DeprecatedProgram,
+    ContractClass as DeprecatedContractClass, ContractClassAbiEntry, EntryPoint, EntryPointOffset,
+    EntryPointType, Program as DeprecatedProgram,
+};
+use starknet_api::serde_utils::deserialize_optional_contract_class_abi_entry_vector;
+use starknet_api::state::{
+    ContractClass as SierraContractClass, EntryPointType as SierraEntryPointType,
 };
 
 use crate::abi::abi_utils::selector_from_name;
-use crate::abi::constants::{self, CONSTRUCTOR_ENTRY_POINT_NAME};
+use crate::abi::constants::{self, CONSTRUCTOR_ENTRY_POINT_NAME, SIERRA_CONTRACT_CLASS_VERSION};
 use crate::execution::entry_point::CallEntryPoint;
-use crate::execution::errors::PreExecutionError;
+use crate::execution::errors::{ContractClassError, PreExecutionError};
 use crate::execution::execution_utils::{felt_to_stark_felt, sn_api_to_cairo_vm_program};
+use crate::utils::{decode_base64, felt_to_biguint, gunzip, gzip};
+
+#[cfg(test)]
+#[path = "contract_class_test.rs"]
+pub mod test;
+
 /// Represents a runnable Starknet contract class (meaning, the program is runnable by the VM).
 /// We wrap the actual class in an Arc to avoid cloning the program when cloning the class.
-// Note: when deserializing from a SN API class JSON string, the ABI field is ignored
-// by serde, since it is not required for execution.
+// Note: the ABI is not required for execution; it is retained for Cairo 0 classes only, for
+// embedders that serve it, or resolve entry point names.
 #[derive(Clone, Debug, Eq, PartialEq, derive_more::From)]
 pub enum ContractClass {
     V0(ContractClassV0),
@@ -38,6 +55,36 @@ pub enum ContractClass {
 }
 
 impl ContractClass {
+    /// Loads a compiled class from its JSON: a Cairo 0 class (which has a `program`), or a CASM
+    /// class. Unlike the test utilities, requires no filesystem; classes may come from database
+    /// blobs or network payloads.
+    pub fn from_json_str(raw_contract_class: &str) -> Result<Self, ContractClassError> {
+        Self::from_json_value(serde_json::from_str(raw_contract_class).map_err(ProgramError::from)?)
+    }
+
+    pub fn from_bytes(raw_contract_class: &[u8]) -> Result<Self, ContractClassError> {
+        Self::from_json_value(
+            serde_json::from_slice(raw_contract_class).map_err(ProgramError::from)?,
+        )
+    }
+
+    pub fn from_reader<R: std::io::Read>(reader: R) -> Result<Self, ContractClassError> {
+        Self::from_json_value(serde_json::from_reader(reader).map_err(ProgramError::from)?)
+    }
+
+    fn from_json_value(raw_contract_class: serde_json::Value) -> Result<Self, ContractClassError> {
+        if raw_contract_class.get("program").is_some() {
+            let mut contract_class: ContractClassV0Inner =
+                serde_json::from_value(raw_contract_class).map_err(ProgramError::from)?;
+            sort_entry_points(&mut contract_class.entry_points_by_type)?;
+            return Ok(ContractClassV0(Arc::new(contract_class)).into());
+        }
+
+        let casm_contract_class: CasmContractClass =
+            serde_json::from_value(raw_contract_class).map_err(ProgramError::from)?;
+        Ok(ContractClassV1::try_from(casm_contract_class)?.into())
+    }
+
     pub fn constructor_selector(&self) -> Option<EntryPointSelector> {
         match self {
             ContractClass::V0(class) => class.constructor_selector(),
@@ -45,16 +92,46 @@ impl ContractClass {
         }
     }
 
+    pub fn bytecode_length(&self) -> usize {
+        match self {
+            ContractClass::V0(class) => class.bytecode_length(),
+            ContractClass::V1(class) => class.bytecode_length(),
+        }
+    }
+
+    pub fn n_entry_points(&self) -> usize {
+        match self {
+            ContractClass::V0(class) => class.n_entry_points(),
+            ContractClass::V1(class) => class.n_entry_points(),
+        }
+    }
+
+    /// Returns the approximate memory footprint of the class, for memory budgets: the bytecode
+    /// and the entry points. Hints, identifiers and retained artifacts are not accounted for.
+    pub fn approximate_size_in_bytes(&self) -> usize {
+        self.bytecode_length() * std::mem::size_of::<MaybeRelocatable>()
+            + self.n_entry_points() * std::mem::size_of::<EntryPointV1>()
+    }
+
     pub fn estimate_casm_hash_computation_resources(&self) -> VmExecutionResources {
         match self {
             ContractClass::V0(class) => class.estimate_casm_hash_computation_resources(),
             ContractClass::V1(class) => class.estimate_casm_hash_computation_resources(),
         }
     }
+
+    /// Returns the Sierra class this class was compiled from, if it was retained on declaration
+    /// (see `BlockContext::retain_sierra_classes`).
+    pub fn sierra_class(&self) -> Option<&SierraContractClass> {
+        match self {
+            ContractClass::V0(_) => None,
+            ContractClass::V1(class) => class.sierra_class.as_ref(),
+        }
+    }
 }
 
 // V0.
-#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct ContractClassV0(pub Arc<ContractClassV0Inner>);
 impl Deref for ContractClassV0 {
     type Target = ContractClassV0Inner;
@@ -81,6 +158,25 @@ impl ContractClassV0 {
         self.program.data_len()
     }
 
+    /// Returns the ABI of the class, if the artifact contains a valid one.
+    pub fn abi(&self) -> Option<&[ContractClassAbiEntry]> {
+        self.abi.as_deref()
+    }
+
+    /// Returns the name of the function of the given selector, as declared in the ABI.
+    pub fn entry_point_name(&self, selector: EntryPointSelector) -> Option<&str> {
+        self.abi()?.iter().find_map(|entry| match entry {
+            ContractClassAbiEntry::Function(function)
+            | ContractClassAbiEntry::Constructor(function)
+            | ContractClassAbiEntry::L1Handler(function)
+                if selector_from_name(&function.name) == selector =>
+            {
+                Some(function.name.as_str())
+            }
+            _ => None,
+        })
+    }
+
     fn estimate_casm_hash_computation_resources(&self) -> VmExecutionResources {
         let hashed_data_size = (constants::CAIRO0_ENTRY_POINT_STRUCT_SIZE * self.n_entry_points())
             + self.n_builtins()
@@ -99,8 +195,21 @@ impl ContractClassV0 {
         }
     }
 
-    pub fn try_from_json_string(raw_contract_class: &str) -> Result<ContractClassV0, ProgramError> {
-        let contract_class: ContractClassV0Inner = serde_json::from_str(raw_contract_class)?;
+    pub fn try_from_json_string(
+        raw_contract_class: &str,
+    ) -> Result<ContractClassV0, ContractClassError> {
+        let mut contract_class: ContractClassV0Inner =
+            serde_json::from_str(raw_contract_class).map_err(ProgramError::from)?;
+        sort_entry_points(&mut contract_class.entry_points_by_type)?;
+        Ok(ContractClassV0(Arc::new(contract_class)))
+    }
+}
+
+impl<'de> Deserialize<'de> for ContractClassV0 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut contract_class = ContractClassV0Inner::deserialize(deserializer)?;
+        sort_entry_points(&mut contract_class.entry_points_by_type)
+            .map_err(|err| DeserializationError::custom(err.to_string()))?;
         Ok(ContractClassV0(Arc::new(contract_class)))
     }
 }
@@ -109,16 +218,65 @@ impl ContractClassV0 {
 pub struct ContractClassV0Inner {
     #[serde(deserialize_with = "deserialize_program")]
     pub program: Program,
+    /// Sorted by selector within each type, which is relied upon by the entry point lookup.
     pub entry_points_by_type: HashMap<EntryPointType, Vec<EntryPoint>>,
+    /// As in the artifact; `None` if missing, or if it cannot be parsed (Starknet does not verify
+    /// the ABI).
+    #[serde(default, deserialize_with = "deserialize_optional_contract_class_abi_entry_vector")]
+    pub abi: Option<Vec<ContractClassAbiEntry>>,
+}
+
+impl ContractClassV0 {
+    /// Compresses the program, which makes up the bulk of a class.
+    pub fn compress(&self) -> Result<CompressedContractClassV0, ProgramError> {
+        let compressed_program = gzip(&self.program.serialize()?)?;
+        let class_without_program = ContractClassV0(Arc::new(ContractClassV0Inner {
+            program: Program::default(),
+            entry_points_by_type: self.entry_points_by_type.clone(),
+            abi: self.abi.clone(),
+        }));
+
+        Ok(CompressedContractClassV0 { class_without_program, compressed_program })
+    }
+}
+
+/// A Cairo 0 class with a compressed program, for keeping large class sets in memory.
+// Note: Cairo 1 programs are not compressed, since they do not round-trip through the program
+// serialization of the VM (which drops the main entry point).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CompressedContractClassV0 {
+    class_without_program: ContractClassV0,
+    compressed_program: Vec<u8>,
+}
+
+impl CompressedContractClassV0 {
+    pub fn decompress(&self) -> Result<ContractClassV0, ProgramError> {
+        let program = Program::deserialize(&gunzip(&self.compressed_program)?, None)?;
+        Ok(ContractClassV0(Arc::new(ContractClassV0Inner {
+            program,
+            entry_points_by_type: self.class_without_program.entry_points_by_type.clone(),
+            abi: self.class_without_program.abi.clone(),
+        })))
+    }
+
+    /// Returns the approximate memory footprint of the class (see
+    /// `ContractClass::approximate_size_in_bytes`).
+    pub fn approximate_size_in_bytes(&self) -> usize {
+        self.compressed_program.len()
+            + self.class_without_program.n_entry_points() * std::mem::size_of::<EntryPointV1>()
+    }
 }
 
 impl TryFrom<DeprecatedContractClass> for ContractClassV0 {
-    type Error = ProgramError;
+    type Error = ContractClassError;
 
     fn try_from(class: DeprecatedContractClass) -> Result<Self, Self::Error> {
+        let mut entry_points_by_type = class.entry_points_by_type;
+        sort_entry_points(&mut entry_points_by_type)?;
         Ok(Self(Arc::new(ContractClassV0Inner {
             program: sn_api_to_cairo_vm_program(class.program)?,
-            entry_points_by_type: class.entry_points_by_type,
+            entry_points_by_type,
+            abi: class.abi,
         })))
     }
 }
@@ -139,34 +297,30 @@ impl ContractClassV1 {
         Some(self.0.entry_points_by_type[&EntryPointType::Constructor].first()?.selector)
     }
 
+    fn n_entry_points(&self) -> usize {
+        self.entry_points_by_type.values().map(|vec| vec.len()).sum()
+    }
+
     pub fn bytecode_length(&self) -> usize {
         self.program.data_len()
     }
 
+    /// Returns a copy of this class, with the Sierra class it was compiled from attached.
+    pub fn with_sierra_class(&self, sierra_class: SierraContractClass) -> Self {
+        Self(Arc::new(ContractClassV1Inner {
+            sierra_class: Some(sierra_class),
+            ..self.0.as_ref().clone()
+        }))
+    }
+
     pub fn get_entry_point(
         &self,
         call: &CallEntryPoint,
     ) -> Result<EntryPointV1, PreExecutionError> {
-        if call.entry_point_type == EntryPointType::Constructor
-            && call.entry_point_selector != selector_from_name(CONSTRUCTOR_ENTRY_POINT_NAME)
-        {
-            return Err(PreExecutionError::InvalidConstructorEntryPointName);
-        }
-
-        let entry_points_of_same_type = &self.0.entry_points_by_type[&call.entry_point_type];
-        let filtered_entry_points: Vec<_> = entry_points_of_same_type
-            .iter()
-            .filter(|ep| ep.selector == call.entry_point_selector)
-            .collect();
-
-        match &filtered_entry_points[..] {
-            [] => Err(PreExecutionError::EntryPointNotFound(call.entry_point_selector)),
-            [entry_point] => Ok((*entry_point).clone()),
-            _ => Err(PreExecutionError::DuplicatedEntryPointSelector {
-                selector: call.entry_point_selector,
-                typ: call.entry_point_type,
-            }),
-        }
+        // Cairo 1 classes have no default entry points; an entry point named `__default__` is
+        // called by its own selector only.
+        let entry_point = resolve_entry_point(&self.0.entry_points_by_type, call, None)?;
+        Ok(entry_point.clone())
     }
 
     /// Returns the estimated VM resources required for computing Casm hash.
@@ -187,8 +341,11 @@ impl ContractClassV1 {
         }
     }
 
-    pub fn try_from_json_string(raw_contract_class: &str) -> Result<ContractClassV1, ProgramError> {
-        let casm_contract_class: CasmContractClass = serde_json::from_str(raw_contract_class)?;
+    pub fn try_from_json_string(
+        raw_contract_class: &str,
+    ) -> Result<ContractClassV1, ContractClassError> {
+        let casm_contract_class: CasmContractClass =
+            serde_json::from_str(raw_contract_class).map_err(ProgramError::from)?;
         let contract_class: ContractClassV1 = casm_contract_class.try_into()?;
 
         Ok(contract_class)
@@ -198,8 +355,11 @@ impl ContractClassV1 {
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct ContractClassV1Inner {
     pub program: Program,
+    /// Sorted by selector within each type, which is relied upon by the entry point lookup.
     pub entry_points_by_type: HashMap<EntryPointType, Vec<EntryPointV1>>,
     pub hints: HashMap<String, Hint>,
+    /// Not required for execution; retained only for serving the class.
+    pub sierra_class: Option<SierraContractClass>,
 }
 
 #[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
@@ -216,7 +376,7 @@ impl EntryPointV1 {
 }
 
 impl TryFrom<CasmContractClass> for ContractClassV1 {
-    type Error = ProgramError;
+    type Error = ContractClassError;
 
     fn try_from(class: CasmContractClass) -> Result<Self, Self::Error> {
         let data: Vec<MaybeRelocatable> = class
@@ -237,7 +397,8 @@ impl TryFrom<CasmContractClass> for ContractClassV1 {
         let mut string_to_hint: HashMap<String, Hint> = HashMap::new();
         for (_, hint_list) in class.hints.iter() {
             for hint in hint_list.iter() {
-                string_to_hint.insert(serde_json::to_string(hint)?, hint.clone());
+                string_to_hint
+                    .insert(serde_json::to_string(hint).map_err(ProgramError::from)?, hint.clone());
             }
         }
 
@@ -272,26 +433,198 @@ impl TryFrom<CasmContractClass> for ContractClassV1 {
             EntryPointType::L1Handler,
             convert_entry_points_v1(class.entry_points_by_type.l1_handler)?,
         );
+        sort_entry_points(&mut entry_points_by_type)?;
 
         Ok(Self(Arc::new(ContractClassV1Inner {
             program,
             entry_points_by_type,
             hints: string_to_hint,
+            sierra_class: None,
         })))
     }
 }
 
+/// Compiles a Sierra class to CASM; the Sierra class is retained (see `sierra_class`).
+impl TryFrom<SierraContractClass> for ContractClassV1 {
+    type Error = ContractClassError;
+
+    fn try_from(sierra_class: SierraContractClass) -> Result<Self, Self::Error> {
+        let entry_points = |typ: SierraEntryPointType| -> Vec<CairoLangContractEntryPoint> {
+            sierra_class
+                .entry_point_by_type
+                .get(&typ)
+                .into_iter()
+                .flatten()
+                .map(|entry_point| CairoLangContractEntryPoint {
+                    selector: felt_to_biguint(entry_point.selector.0),
+                    function_idx: entry_point.function_idx.0,
+                })
+                .collect()
+        };
+        let cairo_lang_contract_class = CairoLangContractClass {
+            sierra_program: sierra_class
+                .sierra_program
+                .iter()
+                .map(|felt| BigUintAsHex { value: felt_to_biguint(*felt) })
+                .collect(),
+            sierra_program_debug_info: None,
+            contract_class_version: SIERRA_CONTRACT_CLASS_VERSION.to_string(),
+            entry_points_by_type: CairoLangContractEntryPoints {
+                external: entry_points(SierraEntryPointType::External),
+                l1_handler: entry_points(SierraEntryPointType::L1Handler),
+                constructor: entry_points(SierraEntryPointType::Constructor),
+            },
+            // Not required for compilation.
+            abi: None,
+        };
+
+        let casm_contract_class =
+            CasmContractClass::from_contract_class(cairo_lang_contract_class, false)?;
+        Ok(ContractClassV1::try_from(casm_contract_class)?.with_sierra_class(sierra_class))
+    }
+}
+
+impl TryFrom<SierraContractClass> for ContractClass {
+    type Error = ContractClassError;
+
+    fn try_from(sierra_class: SierraContractClass) -> Result<Self, Self::Error> {
+        Ok(ContractClassV1::try_from(sierra_class)?.into())
+    }
+}
+
+// Utilities.
+
+/// Sorts the entry points of each type by selector, so that they can be looked up by binary
+/// search; fails if a selector appears more than once within a type, or if there is more than one
+/// constructor, rather than failing only when such an entry point is called.
+fn sort_entry_points<EP: HasSelector>(
+    entry_points_by_type: &mut HashMap<EntryPointType, Vec<EP>>,
+) -> Result<(), ContractClassError> {
+    for (typ, entry_points) in entry_points_by_type.iter_mut() {
+        entry_points.sort_by_key(HasSelector::selector);
+        if let Some(duplicate) =
+            entry_points.windows(2).find(|pair| pair[0].selector() == pair[1].selector())
+        {
+            return Err(ContractClassError::DuplicatedEntryPointSelector {
+                selector: duplicate[0].selector(),
+                typ: *typ,
+            });
+        }
+        if *typ == EntryPointType::Constructor && entry_points.len() > 1 {
+            return Err(ContractClassError::MultipleConstructors(entry_points.len()));
+        }
+    }
+
+    Ok(())
+}
+
+/// An entry point that can be looked up by its selector.
+pub trait HasSelector {
+    fn selector(&self) -> EntryPointSelector;
+}
+
+impl HasSelector for EntryPoint {
+    fn selector(&self) -> EntryPointSelector {
+        self.selector
+    }
+}
+
+impl HasSelector for EntryPointV1 {
+    fn selector(&self) -> EntryPointSelector {
+        self.selector
+    }
+}
+
+/// Returns the entry point with the given selector, out of entry points sorted by selector.
+pub fn find_entry_point<EP: HasSelector>(
+    entry_points: &[EP],
+    selector: EntryPointSelector,
+) -> Option<&EP> {
+    let index = entry_points.binary_search_by_key(&selector, HasSelector::selector).ok()?;
+    Some(&entry_points[index])
+}
+
+/// Returns the entry point the call resolves to, by the lookup rules of its type:
+/// * Constructor: a class has at most one constructor, which must be named `constructor`.
+/// * External: a missing selector falls back to the default entry point, if defined.
+/// * L1 handler: a missing selector falls back to the default L1 handler, if defined; otherwise the
+///   message cannot be handled.
+///
+/// Default entry points are looked up by the given selector, if classes of the given kind have any
+/// (only Cairo 0 classes do). The selector of a call resolved to a default entry point remains the
+/// original one.
+pub fn resolve_entry_point<'a, EP: HasSelector>(
+    entry_points_by_type: &'a HashMap<EntryPointType, Vec<EP>>,
+    call: &CallEntryPoint,
+    default_selector: Option<EntryPointSelector>,
+) -> Result<&'a EP, PreExecutionError> {
+    let entry_points_of_same_type =
+        entry_points_by_type.get(&call.entry_point_type).map(Vec::as_slice).unwrap_or_default();
+    let find_with_fallback = || {
+        find_entry_point(entry_points_of_same_type, call.entry_point_selector).or_else(|| {
+            default_selector
+                .and_then(|selector| find_entry_point(entry_points_of_same_type, selector))
+        })
+    };
+
+    match call.entry_point_type {
+        EntryPointType::Constructor => {
+            if call.entry_point_selector != selector_from_name(CONSTRUCTOR_ENTRY_POINT_NAME) {
+                return Err(PreExecutionError::InvalidConstructorEntryPointName);
+            }
+            find_entry_point(entry_points_of_same_type, call.entry_point_selector)
+                .ok_or_else(|| PreExecutionError::constructor_not_found(call))
+        }
+        EntryPointType::External => {
+            find_with_fallback().ok_or_else(|| PreExecutionError::entry_point_not_found(call))
+        }
+        EntryPointType::L1Handler => {
+            find_with_fallback().ok_or_else(|| PreExecutionError::l1_handler_not_found(call))
+        }
+    }
+}
+
 // V0 utilities.
 
-/// Converts the program type from SN API into a Cairo VM-compatible type.
+/// Deserializes a Cairo 0 program, given either as a JSON object, or (as served by the gateway)
+/// as a base64-encoded, gzipped JSON string.
 pub fn deserialize_program<'de, D: Deserializer<'de>>(
     deserializer: D,
 ) -> Result<Program, D::Error> {
-    let deprecated_program = DeprecatedProgram::deserialize(deserializer)?;
+    let deprecated_program = deserializer.deserialize_any(DeprecatedProgramVisitor)?;
     sn_api_to_cairo_vm_program(deprecated_program)
         .map_err(|err| DeserializationError::custom(err.to_string()))
 }
 
+struct DeprecatedProgramVisitor;
+
+impl<'de> Visitor<'de> for DeprecatedProgramVisitor {
+    type Value = DeprecatedProgram;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a program, or a base64-encoded gzipped program")
+    }
+
+    fn visit_str<E: DeserializationError>(
+        self,
+        compressed_program: &str,
+    ) -> Result<Self::Value, E> {
+        decompress_program(compressed_program).map_err(|err| E::custom(err.to_string()))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        DeprecatedProgram::deserialize(MapAccessDeserializer::new(map))
+    }
+}
+
+/// Decodes a program in the gateway format: base64-encoded, gzipped JSON.
+pub fn decompress_program(compressed_program: &str) -> Result<DeprecatedProgram, ProgramError> {
+    let gzipped_program = decode_base64(compressed_program).ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, "Program is not valid base64.")
+    })?;
+    Ok(serde_json::from_slice(&gunzip(&gzipped_program)?)?)
+}
+
 // V1 utilities.
 
 // TODO(spapini): Share with cairo-lang-runner.
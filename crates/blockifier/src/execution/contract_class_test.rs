@@ -0,0 +1,171 @@
+use rstest::rstest;
+use starknet_api::core::{ClassHash, EntryPointSelector};
+use starknet_api::deprecated_contract_class::{ContractClassAbiEntry, EntryPointType};
+use starknet_api::state::ContractClass as SierraContractClass;
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::execution::contract_class::{
+    find_entry_point, ContractClass, ContractClassV0, ContractClassV1, HasSelector,
+};
+use crate::execution::entry_point::CallEntryPoint;
+use crate::execution::errors::{ContractClassError, PreExecutionError};
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::{
+    get_raw_contract_class, CairoVersion, TEST_CONTRACT_CAIRO0_PATH, TEST_CONTRACT_CAIRO1_PATH,
+};
+use crate::utils::{encode_base64, gzip};
+
+/// Returns the raw test contract class, with its first external entry point defined twice.
+fn raw_class_with_duplicated_selector(cairo_version: CairoVersion) -> String {
+    let contract_path = match cairo_version {
+        CairoVersion::Cairo0 => TEST_CONTRACT_CAIRO0_PATH,
+        CairoVersion::Cairo1 => TEST_CONTRACT_CAIRO1_PATH,
+    };
+    let mut raw_class: serde_json::Value =
+        serde_json::from_str(&get_raw_contract_class(contract_path)).unwrap();
+    let external_entry_points =
+        raw_class["entry_points_by_type"]["EXTERNAL"].as_array_mut().unwrap();
+    external_entry_points.push(external_entry_points[0].clone());
+
+    raw_class.to_string()
+}
+
+#[rstest]
+fn test_duplicated_selector_rejected_on_load(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let raw_class = raw_class_with_duplicated_selector(cairo_version);
+    let result = match cairo_version {
+        CairoVersion::Cairo0 => ContractClassV0::try_from_json_string(&raw_class).map(|_| ()),
+        CairoVersion::Cairo1 => ContractClassV1::try_from_json_string(&raw_class).map(|_| ()),
+    };
+
+    assert!(matches!(
+        result,
+        Err(ContractClassError::DuplicatedEntryPointSelector { typ: EntryPointType::External, .. })
+    ));
+}
+
+#[rstest]
+fn test_multiple_constructors_rejected_on_load(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let contract_path = match cairo_version {
+        CairoVersion::Cairo0 => TEST_CONTRACT_CAIRO0_PATH,
+        CairoVersion::Cairo1 => TEST_CONTRACT_CAIRO1_PATH,
+    };
+    let mut raw_class: serde_json::Value =
+        serde_json::from_str(&get_raw_contract_class(contract_path)).unwrap();
+    let entry_points = &mut raw_class["entry_points_by_type"];
+    let external_entry_points = entry_points["EXTERNAL"].as_array().unwrap().clone();
+    entry_points["CONSTRUCTOR"] = serde_json::Value::from(external_entry_points[..2].to_vec());
+    let raw_class = raw_class.to_string();
+
+    let result = match cairo_version {
+        CairoVersion::Cairo0 => ContractClassV0::try_from_json_string(&raw_class).map(|_| ()),
+        CairoVersion::Cairo1 => ContractClassV1::try_from_json_string(&raw_class).map(|_| ()),
+    };
+    assert!(matches!(result, Err(ContractClassError::MultipleConstructors(2))));
+}
+
+#[test]
+fn test_entry_point_lookup_rules() {
+    let contract_class = ContractClassV1::from_file(TEST_CONTRACT_CAIRO1_PATH);
+    let missing_selector = EntryPointSelector::default();
+    let call = |entry_point_type, entry_point_selector| CallEntryPoint {
+        entry_point_type,
+        entry_point_selector,
+        class_hash: Some(ClassHash::default()),
+        ..Default::default()
+    };
+
+    assert!(matches!(
+        contract_class.get_entry_point(&call(EntryPointType::External, missing_selector)),
+        Err(PreExecutionError::EntryPointNotFound { .. })
+    ));
+    assert!(matches!(
+        contract_class.get_entry_point(&call(EntryPointType::L1Handler, missing_selector)),
+        Err(PreExecutionError::L1HandlerNotFound { selector, .. }) if selector == missing_selector
+    ));
+    assert!(matches!(
+        contract_class.get_entry_point(&call(EntryPointType::Constructor, missing_selector)),
+        Err(PreExecutionError::InvalidConstructorEntryPointName)
+    ));
+}
+
+#[test]
+fn test_entry_point_lookup() {
+    let contract_class = ContractClassV1::from_file(TEST_CONTRACT_CAIRO1_PATH);
+    let entry_points = &contract_class.entry_points_by_type[&EntryPointType::External];
+    assert!(entry_points.windows(2).all(|pair| pair[0].selector() < pair[1].selector()));
+
+    for entry_point in entry_points {
+        assert_eq!(find_entry_point(entry_points, entry_point.selector), Some(entry_point));
+    }
+    assert_eq!(find_entry_point(entry_points, EntryPointSelector::default()), None);
+}
+
+#[test]
+fn test_cairo0_abi() {
+    let contract_class = ContractClassV0::from_file(TEST_CONTRACT_CAIRO0_PATH);
+    let abi = contract_class.abi().unwrap();
+    assert!(abi.iter().any(|entry| matches!(
+        entry,
+        ContractClassAbiEntry::Function(function) if function.name == "return_result"
+    )));
+    assert_eq!(
+        contract_class.entry_point_name(selector_from_name("return_result")),
+        Some("return_result")
+    );
+    assert_eq!(contract_class.entry_point_name(selector_from_name("no_such_function")), None);
+
+    // An invalid ABI is dropped, rather than failing the load.
+    let mut raw_class: serde_json::Value =
+        serde_json::from_str(&get_raw_contract_class(TEST_CONTRACT_CAIRO0_PATH)).unwrap();
+    raw_class["abi"] = serde_json::Value::String("Junk".into());
+    let contract_class = ContractClassV0::try_from_json_string(&raw_class.to_string()).unwrap();
+    assert_eq!(contract_class.abi(), None);
+}
+
+#[rstest]
+fn test_load_from_json(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let raw_class = test_contract.get_raw_class();
+    let expected_class = test_contract.get_class();
+
+    assert_eq!(ContractClass::from_json_str(&raw_class).unwrap(), expected_class);
+    assert_eq!(ContractClass::from_bytes(raw_class.as_bytes()).unwrap(), expected_class);
+    assert_eq!(ContractClass::from_reader(raw_class.as_bytes()).unwrap(), expected_class);
+
+    let raw_class = raw_class_with_duplicated_selector(cairo_version);
+    assert!(matches!(
+        ContractClass::from_json_str(&raw_class),
+        Err(ContractClassError::DuplicatedEntryPointSelector { typ: EntryPointType::External, .. })
+    ));
+}
+
+#[test]
+fn test_invalid_sierra_class_rejected() {
+    let sierra_class = SierraContractClass::default();
+    assert!(matches!(
+        ContractClass::try_from(sierra_class),
+        Err(ContractClassError::SierraCompilationError(_))
+    ));
+}
+
+#[test]
+fn test_load_compressed_cairo0_program() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let mut raw_class: serde_json::Value =
+        serde_json::from_str(&test_contract.get_raw_class()).unwrap();
+    let raw_program = raw_class["program"].to_string();
+    raw_class["program"] = encode_base64(&gzip(raw_program.as_bytes()).unwrap()).into();
+
+    let contract_class = ContractClassV0::try_from_json_string(&raw_class.to_string()).unwrap();
+    assert_eq!(ContractClass::from(contract_class), test_contract.get_class());
+
+    raw_class["program"] = "not base64".into();
+    assert!(ContractClassV0::try_from_json_string(&raw_class.to_string()).is_err());
+}
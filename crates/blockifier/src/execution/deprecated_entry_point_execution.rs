@@ -5,13 +5,11 @@ use cairo_vm::vm::runners::cairo_runner::{
 };
 use cairo_vm::vm::vm_core::VirtualMachine;
 use starknet_api::core::EntryPointSelector;
-use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkHash;
 
-use crate::abi::abi_utils::selector_from_name;
-use crate::abi::constants::{CONSTRUCTOR_ENTRY_POINT_NAME, DEFAULT_ENTRY_POINT_SELECTOR};
+use crate::abi::constants::DEFAULT_ENTRY_POINT_SELECTOR;
 use crate::execution::call_info::{CallExecution, CallInfo};
-use crate::execution::contract_class::ContractClassV0;
+use crate::execution::contract_class::{resolve_entry_point, ContractClassV0};
 use crate::execution::deprecated_syscalls::hint_processor::DeprecatedSyscallHintProcessor;
 use crate::execution::entry_point::{
     CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult, ExecutionResources,
@@ -20,7 +18,8 @@ use crate::execution::errors::{
     PostExecutionError, PreExecutionError, VirtualMachineExecutionError,
 };
 use crate::execution::execution_utils::{
-    read_execution_retdata, stark_felt_to_felt, Args, ReadOnlySegments,
+    read_execution_retdata, read_program_output, register_visited_pcs, stark_felt_to_felt,
+    verify_retdata_segment, Args, ReadOnlySegments,
 };
 use crate::state::state_api::State;
 
@@ -83,11 +82,13 @@ pub fn initialize_execution_context<'a>(
     // Resolve initial PC from EP indicator.
     let entry_point_pc = resolve_entry_point_pc(call, &contract_class)?;
 
-    // Instantiate Cairo runner.
+    // Instantiate Cairo runner. The runner shares the (reference-counted) program data of the
+    // cached class, while its memory is created per call; runners and VMs are therefore not
+    // pooled, as neither can be reset once used.
     let proof_mode = false;
     let mut runner = CairoRunner::new(&contract_class.program, "starknet", proof_mode)?;
 
-    let trace_enabled = false;
+    let trace_enabled = state.tracks_visited_pcs();
     let mut vm = VirtualMachine::new(trace_enabled);
 
     runner.initialize_builtins(&mut vm)?;
@@ -111,47 +112,11 @@ pub fn resolve_entry_point_pc(
     call: &CallEntryPoint,
     contract_class: &ContractClassV0,
 ) -> Result<usize, PreExecutionError> {
-    if call.entry_point_type == EntryPointType::Constructor
-        && call.entry_point_selector != selector_from_name(CONSTRUCTOR_ENTRY_POINT_NAME)
-    {
-        return Err(PreExecutionError::InvalidConstructorEntryPointName);
-    }
-
-    let entry_points_of_same_type = &contract_class.entry_points_by_type[&call.entry_point_type];
-    let filtered_entry_points: Vec<_> = entry_points_of_same_type
-        .iter()
-        .filter(|ep| ep.selector == call.entry_point_selector)
-        .collect();
-
-    // Returns the default entrypoint if the given selector is missing.
-    if filtered_entry_points.is_empty() {
-        match entry_points_of_same_type.first() {
-            Some(entry_point) => {
-                if entry_point.selector
-                    == EntryPointSelector(StarkHash::from(DEFAULT_ENTRY_POINT_SELECTOR))
-                {
-                    return Ok(entry_point.offset.0);
-                } else {
-                    return Err(PreExecutionError::EntryPointNotFound(call.entry_point_selector));
-                }
-            }
-            None => {
-                return Err(PreExecutionError::NoEntryPointOfTypeFound(call.entry_point_type));
-            }
-        }
-    }
-
-    if filtered_entry_points.len() > 1 {
-        return Err(PreExecutionError::DuplicatedEntryPointSelector {
-            selector: call.entry_point_selector,
-            typ: call.entry_point_type,
-        });
-    }
-
-    // Filtered entry points contain exactly one element.
-    let entry_point = filtered_entry_points
-        .first()
-        .expect("The number of entry points with the given selector is exactly one.");
+    // The default entry points of Cairo 0 (both the external one and the L1 handler) have the zero
+    // selector, and are passed the original one as an argument.
+    let default_selector = EntryPointSelector(StarkHash::from(DEFAULT_ENTRY_POINT_SELECTOR));
+    let entry_point =
+        resolve_entry_point(&contract_class.entry_points_by_type, call, Some(default_selector))?;
     Ok(entry_point.offset.0)
 }
 
@@ -232,8 +197,26 @@ pub fn finalize_execution(
     // Validate run.
     let [retdata_size, retdata_ptr]: [MaybeRelocatable; 2] =
         vm.get_return_values(2)?.try_into().expect("Return values must be of size 2.");
+    if syscall_handler.context.block_context.verify_retdata_segments {
+        let program_base = runner
+            .program_base
+            .expect("The program_base field should be initialized after running the entry point.");
+        let reserved_segment_indices = [
+            program_base.segment_index,
+            initial_fp.segment_index,
+            syscall_handler.syscall_ptr.segment_index,
+        ];
+        verify_retdata_segment(&vm, &retdata_ptr, retdata_size.clone(), &reserved_segment_indices)?;
+    }
     let implicit_args_end_ptr = (vm.get_ap() - 2)?;
     validate_run(&mut vm, &runner, &syscall_handler, implicit_args, implicit_args_end_ptr)?;
+    let class_hash = call.class_hash.expect("The class hash must be set before execution.");
+    register_visited_pcs(
+        &mut vm,
+        syscall_handler.state,
+        class_hash,
+        runner.get_program().data_len(),
+    )?;
 
     // Take into account the VM execution resources of the current call, without inner calls.
     // Has to happen after marking holes in segments as accessed.
@@ -244,6 +227,11 @@ pub fn finalize_execution(
     syscall_handler.resources.vm_resources += &vm_resources_without_inner_calls;
 
     let full_call_vm_resources = &syscall_handler.resources.vm_resources - &previous_vm_resources;
+    let program_output = if syscall_handler.context.block_context.collect_program_outputs {
+        read_program_output(&vm)?
+    } else {
+        vec![]
+    };
     Ok(CallInfo {
         call,
         execution: CallExecution {
@@ -252,11 +240,14 @@ pub fn finalize_execution(
             l2_to_l1_messages: syscall_handler.l2_to_l1_messages,
             failed: false,
             gas_consumed: 0,
+            program_output,
         },
         vm_resources: full_call_vm_resources.filter_unused_builtins(),
         inner_calls: syscall_handler.inner_calls,
         storage_read_values: syscall_handler.read_values,
+        storage_read_keys: syscall_handler.read_keys,
         accessed_storage_keys: syscall_handler.accessed_keys,
+        syscall_counter: syscall_handler.syscall_counter,
     })
 }
 
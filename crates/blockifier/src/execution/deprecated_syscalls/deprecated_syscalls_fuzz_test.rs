@@ -0,0 +1,47 @@
+use std::any::type_name;
+
+use crate::execution::deprecated_syscalls::hint_processor::{read_call_params, read_calldata};
+use crate::execution::deprecated_syscalls::{
+    CallContractRequest, DeployRequest, EmitEventRequest, EmptyRequest, LibraryCallRequest,
+    ReplaceClassRequest, SendMessageToL1Request, StorageReadRequest, StorageWriteRequest,
+    SyscallRequest,
+};
+use crate::execution::execution_utils::read_execution_retdata;
+use crate::execution::syscalls::syscalls_fuzz_test::fuzz;
+
+fn fuzz_request<T: SyscallRequest>() {
+    fuzz(type_name::<T>(), |vm, ptr| T::read(vm, ptr).is_ok());
+}
+
+#[test]
+fn test_fuzz_syscall_requests() {
+    fuzz_request::<CallContractRequest>();
+    fuzz_request::<DeployRequest>();
+    fuzz_request::<EmitEventRequest>();
+    fuzz_request::<EmptyRequest>();
+    fuzz_request::<LibraryCallRequest>();
+    fuzz_request::<ReplaceClassRequest>();
+    fuzz_request::<SendMessageToL1Request>();
+    fuzz_request::<StorageReadRequest>();
+    fuzz_request::<StorageWriteRequest>();
+}
+
+#[test]
+fn test_fuzz_calldata() {
+    fuzz("calldata", |vm, ptr| read_calldata(vm, ptr).is_ok());
+    fuzz("call params", |vm, ptr| read_call_params(vm, ptr).is_ok());
+}
+
+#[test]
+fn test_fuzz_retdata() {
+    // The return values of a Cairo 0 entry point are given as a (size, pointer) pair.
+    fuzz("retdata", |vm, ptr| {
+        let retdata_ptr_address = (*ptr + 1_usize).unwrap();
+        let (Some(retdata_size), Some(retdata_ptr)) =
+            (vm.get_maybe(ptr), vm.get_maybe(&retdata_ptr_address))
+        else {
+            return false;
+        };
+        read_execution_retdata(vm, retdata_size, &retdata_ptr).is_ok()
+    });
+}
@@ -18,8 +18,10 @@ use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_f
 use test_case::test_case;
 
 use crate::abi::abi_utils::selector_from_name;
+use crate::abi::constants;
 use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
 use crate::execution::common_hints::ExecutionMode;
+use crate::execution::deprecated_syscalls::DeprecatedSyscallSelector;
 use crate::execution::entry_point::{CallEntryPoint, CallType};
 use crate::execution::errors::{EntryPointExecutionError, VirtualMachineExecutionError};
 use crate::execution::execution_utils::felt_to_stark_felt;
@@ -132,12 +134,18 @@ fn test_nested_library_call() {
     };
     let storage_entry_point_vm_resources =
         VmExecutionResources { n_steps: 42, ..Default::default() };
+    let storage_syscall_counter = HashMap::from([
+        (DeprecatedSyscallSelector::StorageRead, 1),
+        (DeprecatedSyscallSelector::StorageWrite, 1),
+    ]);
     let nested_storage_call_info = CallInfo {
         call: nested_storage_entry_point,
         execution: CallExecution::from_retdata(retdata![stark_felt!(value + 1)]),
         vm_resources: storage_entry_point_vm_resources.clone(),
         storage_read_values: vec![stark_felt!(0_u8), stark_felt!(value + 1)],
+        storage_read_keys: vec![StorageKey(patricia_key!(key + 1)); 2],
         accessed_storage_keys: HashSet::from([StorageKey(patricia_key!(key + 1))]),
+        syscall_counter: storage_syscall_counter.clone(),
         ..Default::default()
     };
     let mut library_call_vm_resources = VmExecutionResources {
@@ -151,6 +159,7 @@ fn test_nested_library_call() {
         execution: CallExecution::from_retdata(retdata![stark_felt!(value + 1)]),
         vm_resources: library_call_vm_resources.clone(),
         inner_calls: vec![nested_storage_call_info],
+        syscall_counter: HashMap::from([(DeprecatedSyscallSelector::LibraryCall, 1)]),
         ..Default::default()
     };
     let storage_call_info = CallInfo {
@@ -158,7 +167,9 @@ fn test_nested_library_call() {
         execution: CallExecution::from_retdata(retdata![stark_felt!(value)]),
         vm_resources: storage_entry_point_vm_resources.clone(),
         storage_read_values: vec![stark_felt!(0_u8), stark_felt!(value)],
+        storage_read_keys: vec![StorageKey(patricia_key!(key)); 2],
         accessed_storage_keys: HashSet::from([StorageKey(patricia_key!(key))]),
+        syscall_counter: storage_syscall_counter.clone(),
         ..Default::default()
     };
 
@@ -170,6 +181,7 @@ fn test_nested_library_call() {
         execution: CallExecution::from_retdata(retdata![stark_felt!(0_u8)]),
         vm_resources: main_call_vm_resources,
         inner_calls: vec![library_call_info, storage_call_info],
+        syscall_counter: HashMap::from([(DeprecatedSyscallSelector::LibraryCall, 2)]),
         ..Default::default()
     };
 
@@ -211,7 +223,12 @@ fn test_call_contract() {
         execution: expected_execution.clone(),
         vm_resources: VmExecutionResources { n_steps: 42, ..Default::default() },
         storage_read_values: vec![StarkFelt::ZERO, stark_felt!(value)],
+        storage_read_keys: vec![StorageKey(patricia_key!(key)); 2],
         accessed_storage_keys: HashSet::from([StorageKey(patricia_key!(key))]),
+        syscall_counter: HashMap::from([
+            (DeprecatedSyscallSelector::StorageRead, 1),
+            (DeprecatedSyscallSelector::StorageWrite, 1),
+        ]),
         ..Default::default()
     };
     let expected_call_info = CallInfo {
@@ -230,6 +247,7 @@ fn test_call_contract() {
             builtin_instance_counter: HashMap::from([(RANGE_CHECK_BUILTIN_NAME.to_string(), 1)]),
             ..Default::default()
         },
+        syscall_counter: HashMap::from([(DeprecatedSyscallSelector::CallContract, 1)]),
         ..Default::default()
     };
 
@@ -382,13 +400,21 @@ fn test_deploy(
     ExecutionMode::Execute, "block_number", calldata![stark_felt!(CURRENT_BLOCK_NUMBER)];
     "Test the syscall get_block_number in execution mode Execute")]
 #[test_case(
-    ExecutionMode::Validate, "block_number", calldata![stark_felt!(CURRENT_BLOCK_NUMBER)];
+    ExecutionMode::Validate,
+    "block_number",
+    calldata![stark_felt!(
+        CURRENT_BLOCK_NUMBER - CURRENT_BLOCK_NUMBER % constants::VALIDATE_BLOCK_NUMBER_ROUNDING
+    )];
     "Test the syscall get_block_number in execution mode Validate")]
 #[test_case(
     ExecutionMode::Execute, "block_timestamp", calldata![stark_felt!(CURRENT_BLOCK_TIMESTAMP)];
     "Test the syscall get_block_timestamp in execution mode Execute")]
 #[test_case(
-    ExecutionMode::Validate, "block_timestamp", calldata![stark_felt!(CURRENT_BLOCK_TIMESTAMP)];
+    ExecutionMode::Validate,
+    "block_timestamp",
+    calldata![stark_felt!(
+        CURRENT_BLOCK_TIMESTAMP - CURRENT_BLOCK_TIMESTAMP % constants::VALIDATE_TIMESTAMP_ROUNDING
+    )];
     "Test the syscall get_block_timestamp in execution mode Validate")]
 #[test_case(
     ExecutionMode::Execute, "sequencer_address", calldata![stark_felt!(TEST_SEQUENCER_ADDRESS)];
@@ -415,6 +441,11 @@ fn test_block_info_syscalls(
                 block_info_member_name
             ),
         );
+    } else if execution_mode == ExecutionMode::Validate {
+        assert_eq!(
+            entry_point_call.execute_directly_in_validate_mode(&mut state).unwrap().execution,
+            CallExecution::from_retdata(retdata![])
+        );
     } else {
         assert_eq!(
             entry_point_call.execute_directly(&mut state).unwrap().execution,
@@ -25,9 +25,10 @@ use starknet_api::StarknetApiError;
 use thiserror::Error;
 
 use crate::abi::constants;
+use crate::chain_id::{chain_id_as_felt, ChainIdError};
 use crate::execution::call_info::{CallInfo, OrderedEvent, OrderedL2ToL1Message};
 use crate::execution::common_hints::{
-    extended_builtin_hint_processor, ExecutionMode, HintExecutionResult,
+    extended_builtin_hint_processor, BlockInfoView, ExecutionMode, HintExecutionResult,
 };
 use crate::execution::deprecated_syscalls::{
     call_contract, delegate_call, delegate_l1_handler, deploy, emit_event, get_block_number,
@@ -38,7 +39,7 @@ use crate::execution::deprecated_syscalls::{
     SyscallResponse,
 };
 use crate::execution::entry_point::{
-    CallEntryPoint, CallType, EntryPointExecutionContext, ExecutionResources,
+    CallEntryPoint, CallFrame, CallType, EntryPointExecutionContext, ExecutionResources,
 };
 use crate::execution::errors::EntryPointExecutionError;
 use crate::execution::execution_utils::{
@@ -48,6 +49,7 @@ use crate::execution::execution_utils::{
 use crate::execution::hint_code;
 use crate::state::errors::StateError;
 use crate::state::state_api::State;
+use crate::utils::{felt_to_usize, format_address};
 
 pub type SyscallCounter = HashMap<DeprecatedSyscallSelector, usize>;
 
@@ -56,6 +58,8 @@ pub enum DeprecatedSyscallExecutionError {
     #[error("Bad syscall_ptr; expected: {expected_ptr:?}, got: {actual_ptr:?}.")]
     BadSyscallPointer { expected_ptr: Relocatable, actual_ptr: Relocatable },
     #[error(transparent)]
+    ChainIdError(#[from] ChainIdError),
+    #[error(transparent)]
     InnerCallExecutionError(#[from] EntryPointExecutionError),
     #[error("Invalid syscall input: {input:?}; {info}")]
     InvalidSyscallInput { input: StarkFelt, info: String },
@@ -69,6 +73,13 @@ pub enum DeprecatedSyscallExecutionError {
     StarknetApiError(#[from] StarknetApiError),
     #[error(transparent)]
     StateError(#[from] StateError),
+    #[error(
+        "Unauthorized call from contract {} to contract {} in execution mode Validate; \
+         validation may only call the validated account.",
+        format_address(.caller_address),
+        format_address(.callee_address)
+    )]
+    UnauthorizedInnerCall { caller_address: ContractAddress, callee_address: ContractAddress },
     #[error(transparent)]
     VirtualMachineError(#[from] VirtualMachineError),
     #[error("Unauthorized syscall {syscall_name} in execution mode {execution_mode}.")]
@@ -105,7 +116,11 @@ pub struct DeprecatedSyscallHintProcessor<'a> {
 
     // Additional information gathered during execution.
     pub read_values: Vec<StarkFelt>,
+    pub read_keys: Vec<StorageKey>,
     pub accessed_keys: HashSet<StorageKey>,
+    /// The number of invocations of each syscall by the current execution (not including inner
+    /// calls).
+    pub syscall_counter: SyscallCounter,
 
     // Additional fields.
     // Invariant: must only contain allowed hints.
@@ -136,7 +151,9 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
             read_only_segments: ReadOnlySegments::default(),
             syscall_ptr: initial_syscall_ptr,
             read_values: vec![],
+            read_keys: vec![],
             accessed_keys: HashSet::new(),
+            syscall_counter: SyscallCounter::new(),
             builtin_hint_processor: extended_builtin_hint_processor(),
             tx_signature_start_ptr: None,
             tx_info_start_ptr: None,
@@ -151,16 +168,14 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         self.execution_mode() == ExecutionMode::Validate
     }
 
-    /// Returns an error if the syscall is run in validate mode.
-    pub fn verify_not_in_validate_mode(&self, syscall_name: &str) -> DeprecatedSyscallResult<()> {
-        if self.is_validate_mode() {
-            return Err(DeprecatedSyscallExecutionError::InvalidSyscallInExecutionMode {
-                syscall_name: syscall_name.to_string(),
-                execution_mode: self.execution_mode(),
-            });
-        }
+    /// Returns the block info visible in the current execution mode.
+    pub fn block_info(&self) -> BlockInfoView {
+        self.execution_mode().block_info(&self.context.block_context)
+    }
 
-        Ok(())
+    /// Returns the chain of calls leading to the current one, outermost first.
+    pub fn call_stack(&self) -> &[CallFrame] {
+        self.context.call_stack()
     }
 
     pub fn verify_syscall_ptr(&self, actual_ptr: Relocatable) -> DeprecatedSyscallResult<()> {
@@ -186,6 +201,18 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         self.verify_syscall_ptr(initial_syscall_ptr)?;
 
         let selector = DeprecatedSyscallSelector::try_from(self.read_next_syscall_selector(vm)?)?;
+        if !self.context.block_context.versioned_constants.is_syscall_available(&selector) {
+            return Err(HintError::CustomHint(
+                format!("Syscall {selector:?} is unavailable in this Starknet version.").into(),
+            ));
+        }
+        if !self.execution_mode().is_syscall_allowed(selector) {
+            return Err(DeprecatedSyscallExecutionError::InvalidSyscallInExecutionMode {
+                syscall_name: selector.as_ref().to_string(),
+                execution_mode: self.execution_mode(),
+            }
+            .into());
+        }
         self.increment_syscall_count(&selector);
 
         match selector {
@@ -289,6 +316,7 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
     fn increment_syscall_count(&mut self, selector: &DeprecatedSyscallSelector) {
         let syscall_count = self.resources.syscall_counter.entry(*selector).or_default();
         *syscall_count += 1;
+        *self.syscall_counter.entry(*selector).or_default() += 1;
     }
 
     fn allocate_tx_signature_segment(
@@ -317,7 +345,7 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
             tx_signature_length.into(),
             tx_signature_start_ptr.into(),
             stark_felt_to_felt(account_tx_context.transaction_hash().0).into(),
-            Felt252::from_bytes_be(self.context.block_context.chain_id.0.as_bytes()).into(),
+            stark_felt_to_felt(chain_id_as_felt(&self.context.block_context.chain_id)?).into(),
             stark_felt_to_felt(account_tx_context.nonce().0).into(),
         ];
 
@@ -332,6 +360,7 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         self.accessed_keys.insert(key);
         let value = self.state.get_storage_at(self.storage_address, key)?;
         self.read_values.push(value);
+        self.read_keys.push(key);
 
         Ok(StorageReadResponse { value })
     }
@@ -375,11 +404,42 @@ impl HintProcessorLogic for DeprecatedSyscallHintProcessor<'_> {
         constants: &HashMap<String, Felt252>,
     ) -> HintExecutionResult {
         let hint = hint_data.downcast_ref::<HintProcessorData>().ok_or(HintError::WrongHintData)?;
+        if let Some(hint_extension) = &self.context.block_context.hint_extension {
+            let result = hint_extension.execute_deprecated_hint(
+                vm,
+                exec_scopes,
+                hint,
+                constants,
+                self.state,
+            );
+            if let Some(result) = result {
+                return result;
+            }
+        }
+
         if hint_code::SYSCALL_HINTS.contains(hint.code.as_str()) {
             return self.execute_next_syscall(vm, &hint.ids_data, &hint.ap_tracking);
         }
 
-        self.builtin_hint_processor.execute_hint(vm, exec_scopes, hint_data, constants)
+        let result = match self.context.block_context.deprecated_hint_aliases.get(&hint.code) {
+            Some(code) => {
+                let aliased_hint: Box<dyn Any> = Box::new(HintProcessorData {
+                    code: code.clone(),
+                    ap_tracking: hint.ap_tracking.clone(),
+                    ids_data: hint.ids_data.clone(),
+                });
+                self.builtin_hint_processor.execute_hint(vm, exec_scopes, &aliased_hint, constants)
+            }
+            None => self.builtin_hint_processor.execute_hint(vm, exec_scopes, hint_data, constants),
+        };
+        match result {
+            Err(HintError::UnknownHint(_)) => Err(self
+                .context
+                .block_context
+                .unknown_hint_policy(self.context.execution_mode)
+                .error("Unknown hint.", &hint.code)),
+            result => result,
+        }
     }
 }
 
@@ -469,5 +529,5 @@ where
     let array_data_start_ptr = vm.get_relocatable(*ptr)?;
     *ptr = (*ptr + 1)?;
 
-    Ok(felt_range_from_ptr(vm, array_data_start_ptr, usize::try_from(array_size)?)?)
+    Ok(felt_range_from_ptr(vm, array_data_start_ptr, felt_to_usize(array_size)?)?)
 }
@@ -1,7 +1,7 @@
 use cairo_felt::Felt252;
 use cairo_vm::types::relocatable::Relocatable;
 use cairo_vm::vm::vm_core::VirtualMachine;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::core::{
     calculate_contract_address, ClassHash, ContractAddress, EntryPointSelector, EthAddress,
@@ -12,7 +12,7 @@ use starknet_api::state::StorageKey;
 use starknet_api::transaction::{
     Calldata, ContractAddressSalt, EventContent, EventData, EventKey, L2ToL1Payload,
 };
-use strum_macros::EnumIter;
+use strum_macros::{AsRefStr, EnumIter};
 
 use self::hint_processor::{
     execute_inner_call, execute_library_call, felt_to_bool, read_call_params, read_calldata,
@@ -26,6 +26,9 @@ use crate::execution::execution_utils::{
     ReadOnlySegment,
 };
 
+#[cfg(test)]
+#[path = "deprecated_syscalls_fuzz_test.rs"]
+pub mod deprecated_syscalls_fuzz_test;
 #[cfg(test)]
 #[path = "deprecated_syscalls_test.rs"]
 pub mod deprecated_syscalls_test;
@@ -34,7 +37,8 @@ pub mod hint_processor;
 pub type DeprecatedSyscallResult<T> = Result<T, DeprecatedSyscallExecutionError>;
 pub type WriteResponseResult = DeprecatedSyscallResult<()>;
 
-#[derive(Clone, Copy, Debug, Deserialize, EnumIter, Eq, Hash, PartialEq)]
+#[derive(AsRefStr, Clone, Copy, Debug, Deserialize, EnumIter, Eq, Hash, PartialEq, Serialize)]
+#[strum(serialize_all = "snake_case")]
 pub enum DeprecatedSyscallSelector {
     CallContract,
     DelegateCall,
@@ -185,11 +189,13 @@ pub fn call_contract(
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<CallContractResponse> {
     let storage_address = request.contract_address;
-    // Check that the call is legal if in Validate execution mode.
-    if syscall_handler.is_validate_mode() && syscall_handler.storage_address != storage_address {
-        return Err(DeprecatedSyscallExecutionError::InvalidSyscallInExecutionMode {
-            syscall_name: "call_contract".to_string(),
-            execution_mode: syscall_handler.execution_mode(),
+    if !syscall_handler
+        .execution_mode()
+        .is_inner_call_allowed(syscall_handler.storage_address, storage_address)
+    {
+        return Err(DeprecatedSyscallExecutionError::UnauthorizedInnerCall {
+            caller_address: syscall_handler.storage_address,
+            callee_address: storage_address,
         });
     }
     let entry_point = CallEntryPoint {
@@ -396,8 +402,8 @@ pub fn get_block_number(
     _vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<GetBlockNumberResponse> {
-    // TODO(Yoni, 1/5/2024): disable for validate.
-    Ok(GetBlockNumberResponse { block_number: syscall_handler.context.block_context.block_number })
+    let block_info = syscall_handler.block_info();
+    Ok(GetBlockNumberResponse { block_number: BlockNumber(block_info.block_number) })
 }
 
 // GetBlockTimestamp syscall.
@@ -421,10 +427,8 @@ pub fn get_block_timestamp(
     _vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<GetBlockTimestampResponse> {
-    // TODO(Yoni, 1/5/2024): disable for validate.
-    Ok(GetBlockTimestampResponse {
-        block_timestamp: syscall_handler.context.block_context.block_timestamp,
-    })
+    let block_info = syscall_handler.block_info();
+    Ok(GetBlockTimestampResponse { block_timestamp: BlockTimestamp(block_info.block_timestamp) })
 }
 
 // GetCallerAddress syscall.
@@ -474,10 +478,8 @@ pub fn get_sequencer_address(
     _vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<GetSequencerAddressResponse> {
-    syscall_handler.verify_not_in_validate_mode("get_sequencer_address")?;
-    Ok(GetSequencerAddressResponse {
-        address: syscall_handler.context.block_context.sequencer_address,
-    })
+    let block_info = syscall_handler.block_info();
+    Ok(GetSequencerAddressResponse { address: block_info.sequencer_address })
 }
 
 // GetTxInfo syscall.
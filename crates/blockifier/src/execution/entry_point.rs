@@ -1,10 +1,14 @@
-use std::cell::RefCell;
 use std::cmp::min;
+use std::collections::HashSet;
+use std::iter::Sum;
+use std::ops::AddAssign;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use cairo_vm::vm::runners::cairo_runner::{
     ExecutionResources as VmExecutionResources, ResourceTracker, RunResources,
 };
+use serde::{Deserialize, Serialize};
 use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkFelt;
@@ -13,17 +17,24 @@ use starknet_api::transaction::{Calldata, TransactionVersion};
 use crate::abi::abi_utils::selector_from_name;
 use crate::abi::constants;
 use crate::block_context::BlockContext;
-use crate::execution::call_info::CallInfo;
+use crate::execution::call_info::{merge_syscall_counters, CallInfo, Retdata};
 use crate::execution::common_hints::ExecutionMode;
 use crate::execution::deprecated_syscalls::hint_processor::SyscallCounter;
 use crate::execution::errors::{EntryPointExecutionError, PreExecutionError};
 use crate::execution::execution_utils::execute_entry_point_call;
 use crate::fee::os_resources::OS_RESOURCES;
-use crate::state::state_api::State;
+use crate::fee::rounding::Rounding;
+use crate::fee::vm_resource_costs::VmResourceKey;
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::{State, StateReader};
+use crate::transaction::errors::TransactionExecutionError;
 use crate::transaction::objects::{
-    AccountTransactionContext, HasRelatedFeeType, TransactionExecutionResult,
+    AccountTransactionContext, DeprecatedAccountTransactionContext, HasRelatedFeeType,
+    TransactionExecutionResult,
 };
 use crate::transaction::transaction_types::TransactionType;
+use crate::utils::format_address;
+use crate::versioned_constants::ResourceGasMode;
 
 #[cfg(test)]
 #[path = "entry_point_test.rs"]
@@ -35,14 +46,14 @@ pub const FAULTY_CLASS_HASH: &str =
 pub type EntryPointExecutionResult<T> = Result<T, EntryPointExecutionError>;
 
 /// Represents a the type of the call (used for debugging).
-#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub enum CallType {
     #[default]
     Call = 0,
     Delegate = 1,
 }
 /// Represents a call to an entry point of a Starknet contract.
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct CallEntryPoint {
     // The class hash is not given if it can be deduced from the storage address.
     pub class_hash: Option<ClassHash>,
@@ -61,12 +72,24 @@ pub struct CallEntryPoint {
 }
 
 impl CallEntryPoint {
+    /// Executes the call against the given state, which is borrowed (only the call is consumed,
+    /// as its class hash is resolved into the returned `CallInfo`); successive calls may thus be
+    /// executed against the same state, each observing the writes of the preceding ones.
     pub fn execute(
         mut self,
         state: &mut dyn State,
         resources: &mut ExecutionResources,
         context: &mut EntryPointExecutionContext,
     ) -> EntryPointExecutionResult<CallInfo> {
+        // A constructor may only be called once, while deploying its contract.
+        if self.entry_point_type == EntryPointType::Constructor
+            && !context.deploying_contracts.remove(&self.storage_address)
+        {
+            return Err(
+                PreExecutionError::ConstructorCallOutsideDeployment(self.storage_address).into()
+            );
+        }
+
         let mut decrement_when_dropped = RecursionDepthGuard::new(
             context.current_recursion_depth.clone(),
             context.max_recursion_depth,
@@ -97,7 +120,12 @@ impl CallEntryPoint {
         self.class_hash = Some(class_hash);
         let contract_class = state.get_compiled_contract_class(class_hash)?;
 
-        execute_entry_point_call(self, contract_class, state, resources, context).map_err(|error| {
+        context.call_stack.push(CallFrame::new(&self, class_hash));
+        let execution_result =
+            execute_entry_point_call(self, contract_class, state, resources, context);
+        context.call_stack.pop();
+
+        execution_result.map_err(|error| {
             match error {
                 // On VM error, pack the stack trace into the propagated error.
                 EntryPointExecutionError::VirtualMachineExecutionError(error) => {
@@ -119,6 +147,80 @@ impl CallEntryPoint {
     }
 }
 
+/// The output of a read-only entry point call; see `execute_call`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CallOutput {
+    pub retdata: Retdata,
+    pub resources: ExecutionResources,
+}
+
+/// Executes the given external entry point for reading purposes only, with the semantics of the
+/// `starknet_call` RPC method: no fee is charged, no nonce is checked, and all state changes made
+/// by the call are discarded.
+/// A call that ends with a failure (e.g., a Cairo 1 panic) is reported as an error.
+/// The gas available to the call is `initial_gas` if given (e.g., a bound set by the caller), and
+/// the initial gas of transactions otherwise.
+pub fn execute_call<S: StateReader>(
+    state: &mut CachedState<S>,
+    block_context: &BlockContext,
+    contract_address: ContractAddress,
+    entry_point_selector: EntryPointSelector,
+    calldata: Calldata,
+    initial_gas: Option<u64>,
+) -> TransactionExecutionResult<CallOutput> {
+    let call = CallEntryPoint {
+        entry_point_type: EntryPointType::External,
+        entry_point_selector,
+        calldata,
+        storage_address: contract_address,
+        initial_gas: initial_gas.unwrap_or(block_context.versioned_constants.tx_initial_gas),
+        ..Default::default()
+    };
+    // A zero max fee disables fee enforcement, and hence the fee-derived step limit.
+    let account_tx_context =
+        AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext::default());
+    let mut context =
+        EntryPointExecutionContext::new_invoke(block_context, &account_tx_context, false)?;
+    let mut resources = ExecutionResources::default();
+
+    let mut transactional_state = CachedState::create_transactional(state);
+    let execution_result = call.execute(&mut transactional_state, &mut resources, &mut context);
+    transactional_state.abort();
+
+    let execution = execution_result.map_err(TransactionExecutionError::ExecutionError)?.execution;
+    if execution.failed {
+        return Err(TransactionExecutionError::ExecutionError(
+            EntryPointExecutionError::ExecutionFailed { error_data: execution.retdata.0 },
+        ));
+    }
+
+    Ok(CallOutput { retdata: execution.retdata, resources })
+}
+
+/// An entry point call that is currently being executed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CallFrame {
+    pub class_hash: ClassHash,
+    pub storage_address: ContractAddress,
+    pub caller_address: ContractAddress,
+    pub entry_point_type: EntryPointType,
+    pub entry_point_selector: EntryPointSelector,
+    pub call_type: CallType,
+}
+
+impl CallFrame {
+    fn new(call: &CallEntryPoint, class_hash: ClassHash) -> Self {
+        Self {
+            class_hash,
+            storage_address: call.storage_address,
+            caller_address: call.caller_address,
+            entry_point_type: call.entry_point_type,
+            entry_point_selector: call.entry_point_selector,
+            call_type: call.call_type,
+        }
+    }
+}
+
 pub struct ConstructorContext {
     pub class_hash: ClassHash,
     // Only relevant in deploy syscall.
@@ -133,6 +235,22 @@ pub struct ExecutionResources {
     pub syscall_counter: SyscallCounter,
 }
 
+impl AddAssign<&ExecutionResources> for ExecutionResources {
+    fn add_assign(&mut self, other: &ExecutionResources) {
+        self.vm_resources += &other.vm_resources;
+        merge_syscall_counters(&mut self.syscall_counter, &other.syscall_counter);
+    }
+}
+
+impl<'a> Sum<&'a ExecutionResources> for ExecutionResources {
+    fn sum<I: Iterator<Item = &'a ExecutionResources>>(iter: I) -> Self {
+        iter.fold(Self::default(), |mut total, resources| {
+            total += resources;
+            total
+        })
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct EntryPointExecutionContext {
     pub block_context: BlockContext,
@@ -145,9 +263,13 @@ pub struct EntryPointExecutionContext {
     pub n_sent_messages_to_l1: usize,
     /// Used to track error stack for call chain.
     pub error_stack: Vec<(ContractAddress, String)>,
+    /// The chain of calls currently being executed, outermost first.
+    call_stack: Vec<CallFrame>,
+    /// Contracts being deployed, whose constructor was not called yet.
+    deploying_contracts: HashSet<ContractAddress>,
 
     // Managed by dedicated guard object.
-    current_recursion_depth: Arc<RefCell<usize>>,
+    current_recursion_depth: Arc<AtomicUsize>,
     // Maximum depth is limited by the stack size, which is configured at `.cargo/config.toml`.
     max_recursion_depth: usize,
 
@@ -169,6 +291,8 @@ impl EntryPointExecutionContext {
             n_emitted_events: 0,
             n_sent_messages_to_l1: 0,
             error_stack: vec![],
+            call_stack: vec![],
+            deploying_contracts: HashSet::new(),
             account_tx_context: account_tx_context.clone(),
             current_recursion_depth: Default::default(),
             max_recursion_depth: block_context.max_recursion_depth,
@@ -227,25 +351,62 @@ impl EntryPointExecutionContext {
         }
 
         let gas_per_step =
-            block_context.vm_resource_fee_cost.get(constants::N_STEPS_RESOURCE).unwrap_or_else(
-                || panic!("{} must appear in `vm_resource_fee_cost`.", constants::N_STEPS_RESOURCE),
-            );
+            block_context.vm_resource_fee_cost.get(VmResourceKey::NSteps).unwrap_or_else(|| {
+                panic!("{} must appear in `vm_resource_fee_cost`.", constants::N_STEPS_RESOURCE)
+            });
+
+        // New transactions derive the step limit by the gas resource bounds of the gas kind steps
+        // are charged as (L1 or L2 gas); deprecated transactions derive this value from the
+        // `max_fee`.
+        // The bound is capped by the block bound below, so it saturates rather than wraps.
+        let (tx_gas_upper_bound, gas_per_step) =
+            match (account_tx_context, block_context.versioned_constants.resource_gas_mode) {
+                (AccountTransactionContext::Deprecated(context), _) => {
+                    let gas_price =
+                        block_context.gas_prices.get_by_fee_type(&account_tx_context.fee_type());
+                    (
+                        Rounding::Floor.div(context.max_fee.0, gas_price).unwrap_or(u128::MAX),
+                        gas_per_step,
+                    )
+                }
+                (
+                    AccountTransactionContext::Current(context),
+                    ResourceGasMode::SeparateL2Gas { l2_gas_per_l1_gas },
+                ) => (
+                    context.l2_resource_bounds().max_amount.into(),
+                    gas_per_step.saturating_mul(l2_gas_per_l1_gas),
+                ),
+                (
+                    AccountTransactionContext::Current(context),
+                    ResourceGasMode::AllResourcesAsL1Gas,
+                ) => (context.l1_resource_bounds()?.max_amount.into(), gas_per_step),
+            };
+        let tx_gas_upper_bound = usize::try_from(tx_gas_upper_bound).unwrap_or(usize::MAX);
+
+        // Free steps are unbounded by the gas.
+        let tx_upper_bound = gas_per_step.div_round(tx_gas_upper_bound as u128, Rounding::Floor);
+        let tx_upper_bound = usize::try_from(tx_upper_bound).unwrap_or(usize::MAX);
+        Ok(min(tx_upper_bound, block_upper_bound))
+    }
 
-        // New transactions derive the step limit by the L1 gas resource bounds; deprecated
-        // transactions derive this value from the `max_fee`.
-        let tx_gas_upper_bound = match account_tx_context {
-            AccountTransactionContext::Deprecated(context) => {
-                (context.max_fee.0
-                    / block_context.gas_prices.get_by_fee_type(&account_tx_context.fee_type()))
-                    as usize
-            }
-            AccountTransactionContext::Current(context) => {
-                context.l1_resource_bounds()?.max_amount as usize
-            }
-        };
+    /// Marks the contract at the given address as being deployed; its constructor may then be
+    /// called (once) through `CallEntryPoint::execute`.
+    pub fn start_deployment(&mut self, contract_address: ContractAddress) {
+        self.deploying_contracts.insert(contract_address);
+    }
 
-        let tx_upper_bound = (tx_gas_upper_bound as f64 / gas_per_step).floor() as usize;
-        Ok(min(tx_upper_bound, block_upper_bound))
+    pub fn call_stack(&self) -> &[CallFrame] {
+        &self.call_stack
+    }
+
+    /// Returns the call currently being executed, if any.
+    pub fn current_call(&self) -> Option<&CallFrame> {
+        self.call_stack.last()
+    }
+
+    /// Returns the number of calls currently being executed (the outermost call has depth 1).
+    pub fn current_call_depth(&self) -> usize {
+        self.call_stack.len()
     }
 
     /// Returns the available steps in run resources.
@@ -297,7 +458,7 @@ impl EntryPointExecutionContext {
             .map(|(contract_address, trace_string)| {
                 format!(
                     "Error in the called contract ({}):\n{}",
-                    contract_address.0.key(),
+                    format_address(contract_address),
                     trace_string
                 )
             })
@@ -306,6 +467,8 @@ impl EntryPointExecutionContext {
     }
 }
 
+/// Executes the constructor of the given class, for both `DeployAccount` transactions and the
+/// `deploy` syscall; a class without a constructor may only be deployed with empty calldata.
 pub fn execute_constructor_entry_point(
     state: &mut dyn State,
     resources: &mut ExecutionResources,
@@ -333,9 +496,12 @@ pub fn execute_constructor_entry_point(
         initial_gas: remaining_gas,
     };
 
+    context.start_deployment(ctor_context.storage_address);
     constructor_call.execute(state, resources, context)
 }
 
+/// Returns the call info of a constructor-less deployment, after verifying that no calldata was
+/// passed.
 pub fn handle_empty_constructor(
     ctor_context: ConstructorContext,
     calldata: Calldata,
@@ -369,20 +535,21 @@ pub fn handle_empty_constructor(
 
 // Ensure that the recursion depth does not exceed the maximum allowed depth.
 struct RecursionDepthGuard {
-    current_depth: Arc<RefCell<usize>>,
+    current_depth: Arc<AtomicUsize>,
     max_depth: usize,
 }
 
 impl RecursionDepthGuard {
-    fn new(current_depth: Arc<RefCell<usize>>, max_depth: usize) -> Self {
+    fn new(current_depth: Arc<AtomicUsize>, max_depth: usize) -> Self {
         Self { current_depth, max_depth }
     }
 
     // Tries to increment the current recursion depth and returns an error if the maximum depth
     // would be exceeded.
     fn try_increment_and_check_depth(&mut self) -> EntryPointExecutionResult<()> {
-        *self.current_depth.borrow_mut() += 1;
-        if *self.current_depth.borrow() > self.max_depth {
+        // The context, and hence the counter, is used by a single thread at a time.
+        let current_depth = self.current_depth.fetch_add(1, Ordering::Relaxed) + 1;
+        if current_depth > self.max_depth {
             return Err(EntryPointExecutionError::RecursionDepthExceeded);
         }
         Ok(())
@@ -392,6 +559,6 @@ impl RecursionDepthGuard {
 // Implementing the Drop trait to decrement the recursion depth when the guard goes out of scope.
 impl Drop for RecursionDepthGuard {
     fn drop(&mut self) {
-        *self.current_depth.borrow_mut() -= 1;
+        self.current_depth.fetch_sub(1, Ordering::Relaxed);
     }
 }
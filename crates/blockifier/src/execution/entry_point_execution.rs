@@ -2,7 +2,7 @@ use cairo_felt::Felt252;
 use cairo_vm::serde::deserialize_program::BuiltinName;
 use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
 use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
-use cairo_vm::vm::runners::builtin_runner::SEGMENT_ARENA_BUILTIN_NAME;
+use cairo_vm::vm::runners::builtin_runner::{BuiltinRunner, SEGMENT_ARENA_BUILTIN_NAME};
 use cairo_vm::vm::runners::cairo_runner::{
     CairoArg, CairoRunner, ExecutionResources as VmExecutionResources,
 };
@@ -20,8 +20,8 @@ use crate::execution::errors::{
     EntryPointExecutionError, PostExecutionError, PreExecutionError, VirtualMachineExecutionError,
 };
 use crate::execution::execution_utils::{
-    read_execution_retdata, stark_felt_to_felt, write_maybe_relocatable, write_stark_felt, Args,
-    ReadOnlySegments,
+    read_execution_retdata, read_program_output, register_visited_pcs, stark_felt_to_felt,
+    verify_retdata_segment, write_maybe_relocatable, write_stark_felt, Args, ReadOnlySegments,
 };
 use crate::execution::syscalls::hint_processor::SyscallHintProcessor;
 use crate::state::state_api::State;
@@ -110,25 +110,29 @@ pub fn initialize_execution_context<'a>(
 ) -> Result<VmExecutionContext<'a>, PreExecutionError> {
     let entry_point = contract_class.get_entry_point(&call)?;
 
-    // Instantiate Cairo runner.
+    // Instantiate Cairo runner. The runner shares the (reference-counted) program data of the
+    // cached class, while its memory is created per call; runners and VMs are therefore not
+    // pooled, as neither can be reset once used.
     let proof_mode = false;
     let mut runner = CairoRunner::new(&contract_class.0.program, "starknet", proof_mode)?;
 
-    let trace_enabled = false;
+    let trace_enabled = state.tracks_visited_pcs();
     let mut vm = VirtualMachine::new(trace_enabled);
 
-    // Initialize program with all builtins.
-    let program_builtins = [
-        BuiltinName::bitwise,
-        BuiltinName::ec_op,
-        BuiltinName::ecdsa,
-        BuiltinName::output,
-        BuiltinName::pedersen,
-        BuiltinName::poseidon,
-        BuiltinName::range_check,
-        BuiltinName::segment_arena,
-    ];
+    // Initialize the builtins used by the entry point. The VM instantiates the rest of the Starknet
+    // builtins as well, after them; those are dropped, so that they are not processed when the run
+    // ends (their segments remain, but stay empty).
+    let program_builtins = get_entry_point_builtins(&entry_point)?;
     runner.initialize_function_runner_cairo_1(&mut vm, &program_builtins)?;
+    let builtin_runners = vm.get_builtin_runners_as_mut();
+    builtin_runners.truncate(program_builtins.len());
+    assert!(
+        builtin_runners
+            .iter()
+            .map(BuiltinRunner::name)
+            .eq(program_builtins.iter().map(BuiltinName::name)),
+        "The VM must instantiate the builtins of the entry point first, in their order."
+    );
     let mut read_only_segments = ReadOnlySegments::default();
     let program_extra_data_length =
         prepare_program_extra_data(&mut vm, contract_class, &mut read_only_segments)?;
@@ -155,6 +159,38 @@ pub fn initialize_execution_context<'a>(
     })
 }
 
+/// Returns the builtins declared by the given entry point, with the segment arena last: it
+/// allocates two segments, the second of which is then the only builtin segment not excluded from
+/// the memory holes count (it is dense, though).
+fn get_entry_point_builtins(
+    entry_point: &EntryPointV1,
+) -> Result<Vec<BuiltinName>, PreExecutionError> {
+    const SUPPORTED_BUILTINS: [BuiltinName; 9] = [
+        BuiltinName::bitwise,
+        BuiltinName::ec_op,
+        BuiltinName::ecdsa,
+        BuiltinName::keccak,
+        BuiltinName::output,
+        BuiltinName::pedersen,
+        BuiltinName::poseidon,
+        BuiltinName::range_check,
+        BuiltinName::segment_arena,
+    ];
+    let mut builtins = entry_point
+        .builtins
+        .iter()
+        .map(|builtin_name| {
+            SUPPORTED_BUILTINS
+                .into_iter()
+                .find(|builtin| builtin.name() == builtin_name)
+                .ok_or_else(|| PreExecutionError::InvalidBuiltin(builtin_name.clone()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    builtins.sort_by_key(|builtin| *builtin == BuiltinName::segment_arena);
+
+    Ok(builtins)
+}
+
 fn prepare_program_extra_data(
     vm: &mut VirtualMachine,
     contract_class: &ContractClassV1,
@@ -277,8 +313,23 @@ pub fn finalize_execution(
     let args_ptr = (initial_fp - (n_total_args + 2))?;
     vm.mark_address_range_as_accessed(args_ptr, n_total_args)?;
     syscall_handler.read_only_segments.mark_as_accessed(&mut vm)?;
+    // The calldata, signature and other inputs must be identical to those seen by the OS.
+    syscall_handler.read_only_segments.validate(&vm)?;
 
-    let call_result = get_call_result(&vm, &syscall_handler)?;
+    let reserved_segment_indices = [
+        program_start_ptr.segment_index,
+        initial_fp.segment_index,
+        syscall_handler.syscall_ptr.segment_index,
+    ];
+    let call_result = get_call_result(&vm, &syscall_handler, &reserved_segment_indices)?;
+    let class_hash =
+        syscall_handler.call.class_hash.expect("The class hash must be set before execution.");
+    register_visited_pcs(
+        &mut vm,
+        syscall_handler.state,
+        class_hash,
+        runner.get_program().data_len(),
+    )?;
 
     // Take into account the VM execution resources of the current call, without inner calls.
     // Has to happen after marking holes in segments as accessed.
@@ -289,6 +340,11 @@ pub fn finalize_execution(
     syscall_handler.resources.vm_resources += &vm_resources_without_inner_calls;
 
     let full_call_vm_resources = &syscall_handler.resources.vm_resources - &previous_vm_resources;
+    let program_output = if syscall_handler.context.block_context.collect_program_outputs {
+        read_program_output(&vm)?
+    } else {
+        vec![]
+    };
     Ok(CallInfo {
         call: syscall_handler.call,
         execution: CallExecution {
@@ -297,17 +353,21 @@ pub fn finalize_execution(
             l2_to_l1_messages: syscall_handler.l2_to_l1_messages,
             failed: call_result.failed,
             gas_consumed: call_result.gas_consumed,
+            program_output,
         },
         vm_resources: full_call_vm_resources.filter_unused_builtins(),
         inner_calls: syscall_handler.inner_calls,
         storage_read_values: syscall_handler.read_values,
+        storage_read_keys: syscall_handler.read_keys,
         accessed_storage_keys: syscall_handler.accessed_keys,
+        syscall_counter: syscall_handler.syscall_counter,
     })
 }
 
 fn get_call_result(
     vm: &VirtualMachine,
     syscall_handler: &SyscallHintProcessor<'_>,
+    reserved_segment_indices: &[isize],
 ) -> Result<CallResult, PostExecutionError> {
     let return_result = vm.get_return_values(5)?;
     // Corresponds to the Cairo 1.0 enum:
@@ -326,6 +386,9 @@ fn get_call_result(
     };
 
     let retdata_size = retdata_end.sub(retdata_start)?;
+    if syscall_handler.context.block_context.verify_retdata_segments {
+        verify_retdata_segment(vm, retdata_start, retdata_size.clone(), reserved_segment_indices)?;
+    }
     // TODO(spapini): Validate implicits.
 
     let gas = &return_result[0];
@@ -1,8 +1,12 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
+use assert_matches::assert_matches;
 use cairo_vm::serde::deserialize_program::BuiltinName;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
 use num_bigint::BigInt;
 use pretty_assertions::assert_eq;
+use rstest::rstest;
 use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, PatriciaKey};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::{StarkFelt, StarkHash};
@@ -14,21 +18,31 @@ use crate::abi::abi_utils::{get_storage_var_address, selector_from_name};
 use crate::abi::constants;
 use crate::block_context::BlockContext;
 use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
-use crate::execution::contract_class::ContractClass;
-use crate::execution::entry_point::CallEntryPoint;
-use crate::execution::errors::EntryPointExecutionError;
+use crate::execution::common_hints::{ExecutionMode, UnknownHintPolicy};
+use crate::execution::contract_class::{
+    ContractClass, ContractClassV0, ContractClassV0Inner, ContractClassV1, ContractClassV1Inner,
+};
+use crate::execution::deprecated_syscalls::DeprecatedSyscallSelector;
+use crate::execution::entry_point::{
+    execute_call, CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult,
+    ExecutionResources,
+};
+use crate::execution::errors::{EntryPointExecutionError, PreExecutionError};
+use crate::execution::syscalls::hint_processor::OUT_OF_GAS_ERROR;
 use crate::retdata;
 use crate::state::cached_state::CachedState;
-use crate::state::state_api::StateReader;
+use crate::state::state_api::{State, StateReader};
 use crate::test_utils::cached_state::{create_test_state, deprecated_create_test_state};
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::dict_state_reader::DictStateReader;
 use crate::test_utils::initial_test_state::test_state;
 use crate::test_utils::{
-    create_calldata, pad_address_to_64, trivial_external_entry_point, BALANCE,
+    create_calldata, get_raw_contract_class, trivial_external_entry_point, BALANCE,
     SECURITY_TEST_CONTRACT_ADDRESS, TEST_CLASS_HASH, TEST_CONTRACT_ADDRESS,
-    TEST_CONTRACT_ADDRESS_2,
+    TEST_CONTRACT_ADDRESS_2, TEST_CONTRACT_CAIRO0_PATH,
 };
+use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::objects::{AccountTransactionContext, DeprecatedAccountTransactionContext};
 
 #[test]
 fn test_call_info_iteration() {
@@ -142,6 +156,86 @@ fn test_entry_point_with_hint() {
     );
 }
 
+/// Returns a test contract class whose `sqrt` hint differs (in a comment) from the supported one,
+/// along with the code of the hint and of the supported one.
+fn legacy_hint_class() -> (ContractClassV0, String, String) {
+    let mut raw_class: serde_json::Value =
+        serde_json::from_str(&get_raw_contract_class(TEST_CONTRACT_CAIRO0_PATH)).unwrap();
+    let sqrt_hint = raw_class["program"]["hints"]
+        .as_object_mut()
+        .unwrap()
+        .values_mut()
+        .flat_map(|hints| hints.as_array_mut().unwrap())
+        .find(|hint| hint["code"].as_str().unwrap().contains("isqrt"))
+        .unwrap();
+    let supported_code = sqrt_hint["code"].as_str().unwrap().to_string();
+    let legacy_code = supported_code.replace("import isqrt\n", "import isqrt  # Legacy.\n");
+    assert_ne!(legacy_code, supported_code);
+    sqrt_hint["code"] = legacy_code.clone().into();
+    let legacy_class = ContractClassV0::try_from_json_string(&raw_class.to_string()).unwrap();
+
+    (legacy_class, legacy_code, supported_code)
+}
+
+/// Calls `sqrt` on the given test contract class.
+fn execute_sqrt(
+    contract_class: ContractClassV0,
+    block_context: &BlockContext,
+    execution_mode: ExecutionMode,
+) -> EntryPointExecutionResult<CallInfo> {
+    let mut state = deprecated_create_test_state();
+    state.set_contract_class(class_hash!(TEST_CLASS_HASH), contract_class.into()).unwrap();
+    let mut context = EntryPointExecutionContext::new(
+        block_context,
+        &AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext::default()),
+        execution_mode,
+        true,
+    )
+    .unwrap();
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![stark_felt!(81_u8)],
+        entry_point_selector: selector_from_name("sqrt"),
+        ..trivial_external_entry_point()
+    };
+    entry_point_call.execute(&mut state, &mut ExecutionResources::default(), &mut context)
+}
+
+#[test]
+fn test_deprecated_hint_aliases() {
+    let (legacy_class, legacy_code, supported_code) = legacy_hint_class();
+    let block_context = BlockContext::create_for_testing();
+    let error =
+        execute_sqrt(legacy_class.clone(), &block_context, ExecutionMode::Execute).unwrap_err();
+    assert!(error.to_string().contains("Unknown hint."));
+
+    let block_context = BlockContext {
+        deprecated_hint_aliases: Arc::new(HashMap::from([(legacy_code, supported_code)])),
+        ..block_context
+    };
+    assert_eq!(
+        execute_sqrt(legacy_class, &block_context, ExecutionMode::Execute).unwrap().execution,
+        CallExecution::default()
+    );
+}
+
+#[rstest]
+#[case::default_in_validate(None, ExecutionMode::Validate, false)]
+#[case::default_in_execute(None, ExecutionMode::Execute, true)]
+#[case::reject(Some(UnknownHintPolicy::Reject), ExecutionMode::Execute, false)]
+#[case::report(Some(UnknownHintPolicy::ReportHintCode), ExecutionMode::Validate, true)]
+fn test_unknown_hint_policy(
+    #[case] unknown_hint_policy: Option<UnknownHintPolicy>,
+    #[case] execution_mode: ExecutionMode,
+    #[case] expect_hint_code: bool,
+) {
+    let (legacy_class, legacy_code, _) = legacy_hint_class();
+    let block_context = BlockContext { unknown_hint_policy, ..BlockContext::create_for_testing() };
+
+    let error = execute_sqrt(legacy_class, &block_context, execution_mode).unwrap_err().to_string();
+    assert!(error.contains("Unknown hint."));
+    assert_eq!(error.contains(&legacy_code), expect_hint_code);
+}
+
 #[test]
 fn test_entry_point_with_return_value() {
     let mut state = deprecated_create_test_state();
@@ -164,8 +258,19 @@ fn test_entry_point_not_found_in_contract() {
     let entry_point_call =
         CallEntryPoint { entry_point_selector, ..trivial_external_entry_point() };
     let error = entry_point_call.execute_directly(&mut state).unwrap_err();
+    assert_matches!(
+        &error,
+        EntryPointExecutionError::PreExecutionError(PreExecutionError::EntryPointNotFound {
+            selector,
+            entry_point_type: EntryPointType::External,
+            class_hash,
+            storage_address,
+        }) if *selector == entry_point_selector
+            && *class_hash == class_hash!(TEST_CLASS_HASH)
+            && *storage_address == contract_address!(TEST_CONTRACT_ADDRESS)
+    );
     assert_eq!(
-        format!("Entry point {entry_point_selector:?} not found in contract."),
+        format!("Entry point 0x2 of type External not found in contract 0x100 (class hash 0x110)."),
         format!("{error}")
     );
 }
@@ -377,9 +482,7 @@ fn test_syscall_execution_security_failures() {
     run_security_test(
         state,
         security_contract,
-        "Requested contract address \
-         ContractAddress(PatriciaKey(StarkFelt(\"\
-         0x0000000000000000000000000000000000000000000000000000000000000017\"))) is not deployed",
+        "Requested contract address 0x17 is not deployed",
         "test_bad_call_address",
         calldata![],
     );
@@ -393,10 +496,7 @@ fn test_syscall_execution_security_failures() {
     run_security_test(
         state,
         security_contract,
-        "Entry point \
-         EntryPointSelector(StarkFelt(\"\
-         0x0000000000000000000000000000000000000000000000000000000000000019\")) not found in \
-         contract",
+        "Entry point 0x19 of type External not found in contract",
         "test_bad_call_selector",
         calldata![],
     );
@@ -453,21 +553,21 @@ fn test_post_run_validation_security_failure() {
     run_security_test(
         state,
         security_contract,
-        "Validation failed: Read-only segments",
+        "Validation failed: Read-only segment",
         "test_out_of_bounds_write_to_signature_segment",
         calldata![],
     );
     run_security_test(
         state,
         security_contract,
-        "Validation failed: Read-only segments",
+        "Validation failed: Read-only segment",
         "test_out_of_bounds_write_to_tx_info_segment",
         calldata![],
     );
     run_security_test(
         state,
         security_contract,
-        "Validation failed: Read-only segments",
+        "Validation failed: Read-only segment",
         "test_write_to_call_contract_return_value",
         calldata![],
     );
@@ -475,7 +575,7 @@ fn test_post_run_validation_security_failure() {
     run_security_test(
         state,
         security_contract,
-        "Validation failed: Read-only segments",
+        "Validation failed: Read-only segment",
         "test_out_of_bounds_write_to_calldata_segment",
         calldata,
     );
@@ -536,6 +636,55 @@ fn test_cairo1_entry_point_segment_arena() {
     );
 }
 
+#[test]
+fn test_cairo1_entry_point_builtins() {
+    let mut state = create_test_state();
+    let class_hash = class_hash!(TEST_CLASS_HASH);
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("segment_arena_builtin"),
+        ..trivial_external_entry_point()
+    };
+    let ContractClass::V1(class) = state.get_compiled_contract_class(class_hash).unwrap() else {
+        panic!("Expected a Cairo 1 class.");
+    };
+
+    // Only the builtins declared by the entry point are instantiated.
+    let declared_builtins = class.get_entry_point(&entry_point_call).unwrap().builtins;
+    let call_info = entry_point_call.clone().execute_directly(&mut state).unwrap();
+    let used_builtins = call_info.vm_resources.builtin_instance_counter.keys();
+    assert!(used_builtins.clone().all(|builtin| declared_builtins.contains(builtin)));
+    // The usage is that of a run with all the Starknet builtins instantiated.
+    assert_eq!(
+        call_info.vm_resources,
+        VmExecutionResources {
+            n_steps: 95,
+            n_memory_holes: 2,
+            builtin_instance_counter: HashMap::from([
+                (BuiltinName::range_check.name().to_string(), 4),
+                (BuiltinName::segment_arena.name().to_string(), 6),
+            ]),
+        }
+    );
+
+    // Unsupported builtins are rejected.
+    let mut entry_points_by_type = class.entry_points_by_type.clone();
+    for entry_point in entry_points_by_type.get_mut(&EntryPointType::External).unwrap() {
+        if entry_point.selector == entry_point_call.entry_point_selector {
+            entry_point.builtins.push("unknown_builtin".to_string());
+        }
+    }
+    let class = ContractClassV1(Arc::new(ContractClassV1Inner {
+        entry_points_by_type,
+        ..class.0.as_ref().clone()
+    }));
+    state.set_contract_class(class_hash, class.into()).unwrap();
+    assert_matches!(
+        entry_point_call.execute_directly(&mut state).unwrap_err(),
+        EntryPointExecutionError::PreExecutionError(PreExecutionError::InvalidBuiltin(builtin))
+        if builtin == "unknown_builtin"
+    );
+}
+
 #[test]
 fn test_stack_trace() {
     let mut state = deprecated_create_test_state();
@@ -599,9 +748,7 @@ An ASSERT_EQ instruction failed: 1 != 0.
 Cairo traceback (most recent call last):
 Unknown location (pc=0:62)
 ",
-        pad_address_to_64(TEST_CONTRACT_ADDRESS),
-        pad_address_to_64(TEST_CONTRACT_ADDRESS_2),
-        pad_address_to_64(SECURITY_TEST_CONTRACT_ADDRESS)
+        TEST_CONTRACT_ADDRESS, TEST_CONTRACT_ADDRESS_2, SECURITY_TEST_CONTRACT_ADDRESS
     );
     match entry_point_call.execute_directly(&mut state).unwrap_err() {
         EntryPointExecutionError::VirtualMachineExecutionErrorWithTrace { trace, source: _ } => {
@@ -610,3 +757,347 @@ Unknown location (pc=0:62)
         other_error => panic!("Unexpected error type: {other_error:?}"),
     }
 }
+
+#[test]
+fn test_call_stack_is_unwound() {
+    let mut state = create_test_state();
+    let block_context = BlockContext::create_for_testing();
+    let mut context = EntryPointExecutionContext::new_invoke(
+        &block_context,
+        &AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext::default()),
+        true,
+    )
+    .unwrap();
+    let nested_call = |inner_entry_point_name: &str| CallEntryPoint {
+        entry_point_selector: selector_from_name("test_call_contract"),
+        calldata: create_calldata(
+            contract_address!(TEST_CONTRACT_ADDRESS),
+            inner_entry_point_name,
+            &[stark_felt!(405_u16), stark_felt!(48_u8)],
+        ),
+        ..trivial_external_entry_point()
+    };
+
+    // Successful nested execution.
+    nested_call("test_storage_read_write")
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+        .unwrap();
+    assert_eq!(context.current_call_depth(), 0);
+
+    // Failing nested execution.
+    nested_call("nonexistent_entry_point")
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+        .unwrap_err();
+    assert!(context.call_stack().is_empty());
+}
+
+/// Tests a reentrant call (A -> B -> A): each frame runs in the storage of its own address.
+#[rstest]
+#[case::cairo0(deprecated_create_test_state())]
+#[case::cairo1(create_test_state())]
+fn test_reentrant_call(#[case] mut state: CachedState<DictStateReader>) {
+    let (address_a, address_b) =
+        (contract_address!(TEST_CONTRACT_ADDRESS), contract_address!(TEST_CONTRACT_ADDRESS_2));
+    let (key, value) = (stark_felt!(1234_u16), stark_felt!(18_u8));
+    let reentrant_calldata = create_calldata(address_a, "test_storage_read_write", &[key, value]);
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_call_contract"),
+        calldata: create_calldata(address_b, "test_call_contract", &reentrant_calldata.0),
+        ..trivial_external_entry_point()
+    };
+    let call_info = entry_point_call.execute_directly(&mut state).unwrap();
+    assert_eq!(call_info.execution.retdata, retdata![value]);
+
+    // Each frame is called by the previous one, and runs in the storage of its own address.
+    let call_b = &call_info.inner_calls[0];
+    let reentrant_call = &call_b.inner_calls[0];
+    assert_eq!((call_b.call.caller_address, call_b.call.storage_address), (address_a, address_b));
+    assert_eq!(
+        (reentrant_call.call.caller_address, reentrant_call.call.storage_address),
+        (address_b, address_a)
+    );
+    let storage_key = StorageKey(patricia_key!(key));
+    assert!(call_info.accessed_storage_keys.is_empty());
+    assert!(call_b.accessed_storage_keys.is_empty());
+    assert_eq!(reentrant_call.accessed_storage_keys, HashSet::from([storage_key]));
+
+    // The write lands in the storage of A only.
+    assert_eq!(state.get_storage_at(address_a, storage_key).unwrap(), value);
+    assert_eq!(state.get_storage_at(address_b, storage_key).unwrap(), StarkFelt::ZERO);
+}
+
+/// Tests that events and messages are ordered across the frames of reentrant calls, and across the
+/// calls of a transaction (sharing an execution context).
+#[test]
+fn test_reentrant_call_ordering() {
+    let mut state = create_test_state();
+    let block_context = BlockContext::create_for_testing();
+    let mut context = EntryPointExecutionContext::new_invoke(
+        &block_context,
+        &AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext::default()),
+        true,
+    )
+    .unwrap();
+    let (address_a, address_b) =
+        (contract_address!(TEST_CONTRACT_ADDRESS), contract_address!(TEST_CONTRACT_ADDRESS_2));
+    let event_calldata = [stark_felt!(1_u8), stark_felt!(7_u8), stark_felt!(0_u8)];
+    let message_calldata = [stark_felt!(1234_u16)];
+    // Calls A -> B -> A, where the innermost call runs the given entry point.
+    let mut run_reentrant_call = |entry_point_name: &str, calldata: &[StarkFelt]| {
+        CallEntryPoint {
+            entry_point_selector: selector_from_name("test_call_contract"),
+            calldata: create_calldata(
+                address_b,
+                "test_call_contract",
+                &create_calldata(address_a, entry_point_name, calldata).0,
+            ),
+            ..trivial_external_entry_point()
+        }
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+        .unwrap()
+    };
+
+    let call_infos = [
+        run_reentrant_call("test_emit_event", &event_calldata),
+        run_reentrant_call("send_message", &message_calldata),
+        run_reentrant_call("test_emit_event", &event_calldata),
+        run_reentrant_call("send_message", &message_calldata),
+    ];
+    let innermost_calls: Vec<&CallInfo> =
+        call_infos.iter().map(|call_info| &call_info.inner_calls[0].inner_calls[0]).collect();
+    let event_orders: Vec<usize> = innermost_calls
+        .iter()
+        .flat_map(|call_info| call_info.execution.events.iter().map(|event| event.order))
+        .collect();
+    let message_orders: Vec<usize> = innermost_calls
+        .iter()
+        .flat_map(|call_info| {
+            call_info.execution.l2_to_l1_messages.iter().map(|message| message.order)
+        })
+        .collect();
+    assert_eq!(event_orders, vec![0, 1]);
+    assert_eq!(message_orders, vec![0, 1]);
+
+    // Only the innermost frames emit.
+    for call_info in &call_infos {
+        assert!(call_info.execution.events.is_empty());
+        assert!(call_info.inner_calls[0].execution.l2_to_l1_messages.is_empty());
+    }
+    assert_eq!(context.current_call_depth(), 0);
+}
+
+#[test]
+fn test_execute_call() {
+    let mut state = create_test_state();
+    let block_context = BlockContext::create_for_testing();
+    let contract_address = contract_address!(TEST_CONTRACT_ADDRESS);
+    let (key, value) = (stark_felt!(1234_u16), stark_felt!(18_u8));
+
+    let call_output = execute_call(
+        &mut state,
+        &block_context,
+        contract_address,
+        selector_from_name("test_storage_read_write"),
+        calldata![key, value],
+        None,
+    )
+    .unwrap();
+    assert_eq!(call_output.retdata, retdata![value]);
+    assert_eq!(
+        call_output.resources.syscall_counter,
+        HashMap::from([
+            (DeprecatedSyscallSelector::StorageRead, 1),
+            (DeprecatedSyscallSelector::StorageWrite, 1),
+        ])
+    );
+
+    // The storage write is discarded.
+    let storage_key = StorageKey(patricia_key!(key));
+    assert_eq!(state.get_storage_at(contract_address, storage_key).unwrap(), StarkFelt::ZERO);
+
+    // A failing call is reported as an error.
+    let error = execute_call(
+        &mut state,
+        &block_context,
+        contract_address,
+        selector_from_name("assert_eq"),
+        calldata![stark_felt!(1_u8), stark_felt!(2_u8)],
+        None,
+    )
+    .unwrap_err();
+    assert_matches!(
+        error,
+        TransactionExecutionError::ExecutionError(EntryPointExecutionError::ExecutionFailed { .. })
+    );
+
+    // The call is bounded by the given initial gas.
+    let error = execute_call(
+        &mut state,
+        &block_context,
+        contract_address,
+        selector_from_name("test_storage_read_write"),
+        calldata![key, value],
+        Some(1),
+    )
+    .unwrap_err();
+    assert_matches!(
+        error,
+        TransactionExecutionError::ExecutionError(EntryPointExecutionError::ExecutionFailed {
+            error_data
+        }) if error_data == vec![stark_felt!(OUT_OF_GAS_ERROR)]
+    );
+}
+
+/// Returns the given class, with the given entry point relabeled as the default entry point.
+fn with_default_entry_point(
+    contract_class: ContractClass,
+    entry_point_name: &str,
+) -> ContractClass {
+    let selector = selector_from_name(entry_point_name);
+    let default_selector = selector_from_name(constants::DEFAULT_ENTRY_POINT_NAME);
+    match contract_class {
+        ContractClass::V0(class) => {
+            let mut entry_points_by_type = class.entry_points_by_type.clone();
+            for entry_point in entry_points_by_type.get_mut(&EntryPointType::External).unwrap() {
+                if entry_point.selector == selector {
+                    entry_point.selector = default_selector;
+                }
+            }
+            // Keeps the entry points sorted by selector, as the lookup expects.
+            entry_points_by_type
+                .get_mut(&EntryPointType::External)
+                .unwrap()
+                .sort_by_key(|entry_point| entry_point.selector);
+            ContractClassV0(Arc::new(ContractClassV0Inner {
+                program: class.program.clone(),
+                entry_points_by_type,
+                abi: class.abi.clone(),
+            }))
+            .into()
+        }
+        ContractClass::V1(class) => {
+            let mut entry_points_by_type = class.entry_points_by_type.clone();
+            for entry_point in entry_points_by_type.get_mut(&EntryPointType::External).unwrap() {
+                if entry_point.selector == selector {
+                    entry_point.selector = default_selector;
+                }
+            }
+            // Keeps the entry points sorted by selector, as the lookup expects.
+            entry_points_by_type
+                .get_mut(&EntryPointType::External)
+                .unwrap()
+                .sort_by_key(|entry_point| entry_point.selector);
+            ContractClassV1(Arc::new(ContractClassV1Inner {
+                program: class.program.clone(),
+                entry_points_by_type,
+                hints: class.hints.clone(),
+                sierra_class: class.sierra_class.clone(),
+            }))
+            .into()
+        }
+    }
+}
+
+#[test]
+fn test_default_entry_point() {
+    for mut state in [deprecated_create_test_state(), create_test_state()] {
+        let class_hash = class_hash!(TEST_CLASS_HASH);
+        let entry_point_call = CallEntryPoint {
+            entry_point_selector: EntryPointSelector(stark_felt!(2_u8)),
+            calldata: calldata![stark_felt!(1234_u16), stark_felt!(18_u8)],
+            ..trivial_external_entry_point()
+        };
+
+        // The selector is not found, and there is no default entry point.
+        assert_matches!(
+            entry_point_call.clone().execute_directly(&mut state).unwrap_err(),
+            EntryPointExecutionError::PreExecutionError(
+                PreExecutionError::EntryPointNotFound { .. }
+            )
+        );
+
+        // Cairo 0 classes fall back to the default entry point, keeping the original selector;
+        // Cairo 1 classes have no default entry point, even if one is named `__default__`.
+        let contract_class = state.get_compiled_contract_class(class_hash).unwrap();
+        let is_cairo0 = matches!(contract_class, ContractClass::V0(_));
+        let contract_class = with_default_entry_point(contract_class, "test_storage_read_write");
+        state.set_contract_class(class_hash, contract_class).unwrap();
+        let result = entry_point_call.clone().execute_directly(&mut state);
+        if is_cairo0 {
+            let call_info = result.unwrap();
+            assert_eq!(call_info.execution.retdata, retdata![stark_felt!(18_u8)]);
+            assert_eq!(call_info.call.entry_point_selector, entry_point_call.entry_point_selector);
+        } else {
+            assert_matches!(
+                result.unwrap_err(),
+                EntryPointExecutionError::PreExecutionError(
+                    PreExecutionError::EntryPointNotFound { .. }
+                )
+            );
+        }
+    }
+}
+
+#[test]
+fn test_constructor_call() {
+    let mut state = create_test_state();
+    let block_context = BlockContext::create_for_testing();
+    let mut context = EntryPointExecutionContext::new_invoke(
+        &block_context,
+        &AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext::default()),
+        true,
+    )
+    .unwrap();
+    let contract_address = contract_address!(TEST_CONTRACT_ADDRESS);
+    let constructor_call = CallEntryPoint {
+        entry_point_type: EntryPointType::Constructor,
+        entry_point_selector: selector_from_name(constants::CONSTRUCTOR_ENTRY_POINT_NAME),
+        calldata: calldata![stark_felt!(1_u8), stark_felt!(2_u8)],
+        ..trivial_external_entry_point()
+    };
+    let mut execute_constructor = |context: &mut EntryPointExecutionContext| {
+        constructor_call.clone().execute(&mut state, &mut ExecutionResources::default(), context)
+    };
+
+    // Constructors may not be called outside of a deployment.
+    assert_matches!(
+        execute_constructor(&mut context).unwrap_err(),
+        EntryPointExecutionError::PreExecutionError(
+            PreExecutionError::ConstructorCallOutsideDeployment(address)
+        ) if address == contract_address
+    );
+
+    // The constructor runs through the standard execution path, once per deployment.
+    context.start_deployment(contract_address);
+    let call_info = execute_constructor(&mut context).unwrap();
+    assert_eq!(call_info.execution.retdata, retdata![stark_felt!(1_u8)]);
+    assert_matches!(
+        execute_constructor(&mut context).unwrap_err(),
+        EntryPointExecutionError::PreExecutionError(
+            PreExecutionError::ConstructorCallOutsideDeployment(_)
+        )
+    );
+}
+
+#[test]
+fn test_repeated_execution_isolation() {
+    for mut state in [deprecated_create_test_state(), create_test_state()] {
+        let key = stark_felt!(1234_u16);
+        let mut execute_with_value = |value: StarkFelt| {
+            let entry_point_call = CallEntryPoint {
+                calldata: calldata![key, value],
+                entry_point_selector: selector_from_name("test_storage_read_write"),
+                ..trivial_external_entry_point()
+            };
+            entry_point_call.execute_directly(&mut state).unwrap()
+        };
+
+        // Repeated executions of the same class do not observe each other's memory.
+        execute_with_value(stark_felt!(17_u8));
+        let first_call_info = execute_with_value(stark_felt!(18_u8));
+        let second_call_info = execute_with_value(stark_felt!(19_u8));
+        assert_eq!(first_call_info.execution.retdata, retdata![stark_felt!(18_u8)]);
+        assert_eq!(second_call_info.execution.retdata, retdata![stark_felt!(19_u8)]);
+        assert_eq!(first_call_info.vm_resources, second_call_info.vm_resources);
+    }
+}
@@ -1,25 +1,58 @@
 use cairo_vm::types::errors::math_errors::MathError;
+use cairo_vm::types::relocatable::Relocatable;
 use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
 use cairo_vm::vm::errors::memory_errors::MemoryError;
 use cairo_vm::vm::errors::runner_errors::RunnerError;
 use cairo_vm::vm::errors::vm_errors::{VirtualMachineError, HINT_ERROR_STR};
 use num_bigint::{BigInt, TryFromBigIntError};
-use starknet_api::core::{ContractAddress, EntryPointSelector};
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkFelt;
 use thiserror::Error;
 
+use crate::execution::entry_point::CallEntryPoint;
 use crate::execution::execution_utils::format_panic_data;
 use crate::state::errors::StateError;
+use crate::utils::{format_address, format_class_hash, format_selector};
 
 // TODO(AlonH, 21/12/2022): Implement Display for all types that appear in errors.
 
 #[derive(Debug, Error)]
-pub enum PreExecutionError {
-    #[error("Entry point {selector:?} of type {typ:?} is not unique.")]
+pub enum ContractClassError {
+    #[error("Entry point {} of type {typ:?} is defined more than once.", format_selector(.selector))]
     DuplicatedEntryPointSelector { selector: EntryPointSelector, typ: EntryPointType },
-    #[error("Entry point {0:?} not found in contract.")]
-    EntryPointNotFound(EntryPointSelector),
+    #[error("A class may define at most one constructor; got {0}.")]
+    MultipleConstructors(usize),
+    #[error(transparent)]
+    ProgramError(#[from] cairo_vm::types::errors::program_errors::ProgramError),
+    #[error(transparent)]
+    SierraCompilationError(
+        #[from] cairo_lang_starknet::casm_contract_class::StarknetSierraCompilationError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum PreExecutionError {
+    #[error("The constructor of {} may only be called while deploying it.", format_address(.0))]
+    ConstructorCallOutsideDeployment(ContractAddress),
+    #[error(
+        "Contract {} (class hash {}) has no constructor.",
+        format_address(.storage_address),
+        format_class_hash(.class_hash)
+    )]
+    ConstructorNotFound { class_hash: ClassHash, storage_address: ContractAddress },
+    #[error(
+        "Entry point {} of type {entry_point_type:?} not found in contract {} (class hash {}).",
+        format_selector(.selector),
+        format_address(.storage_address),
+        format_class_hash(.class_hash)
+    )]
+    EntryPointNotFound {
+        selector: EntryPointSelector,
+        entry_point_type: EntryPointType,
+        class_hash: ClassHash,
+        storage_address: ContractAddress,
+    },
     #[error("Fraud attempt blocked.")]
     FraudAttempt,
     #[error("Invalid builtin {0:?}.")]
@@ -30,18 +63,57 @@ pub enum PreExecutionError {
     MathError(#[from] MathError),
     #[error(transparent)]
     MemoryError(#[from] MemoryError),
-    #[error("No entry points of type {0:?} found in contract.")]
-    NoEntryPointOfTypeFound(EntryPointType),
+    #[error(
+        "L1 handler {} not found in contract {} (class hash {}); the message cannot be handled.",
+        format_selector(.selector),
+        format_address(.storage_address),
+        format_class_hash(.class_hash)
+    )]
+    L1HandlerNotFound {
+        selector: EntryPointSelector,
+        class_hash: ClassHash,
+        storage_address: ContractAddress,
+    },
     #[error(transparent)]
     ProgramError(#[from] cairo_vm::types::errors::program_errors::ProgramError),
     #[error(transparent)]
     RunnerError(Box<RunnerError>),
     #[error(transparent)]
     StateError(#[from] StateError),
-    #[error("Requested contract address {0:?} is not deployed.")]
+    #[error("Requested contract address {} is not deployed.", format_address(.0))]
     UninitializedStorageAddress(ContractAddress),
 }
 
+impl PreExecutionError {
+    /// Returns an `EntryPointNotFound` error for the given call, whose class hash must already be
+    /// resolved.
+    pub fn entry_point_not_found(call: &CallEntryPoint) -> Self {
+        Self::EntryPointNotFound {
+            selector: call.entry_point_selector,
+            entry_point_type: call.entry_point_type,
+            class_hash: call.class_hash.expect("The class hash must be set before execution."),
+            storage_address: call.storage_address,
+        }
+    }
+
+    /// Returns a `ConstructorNotFound` error for the given call; see `entry_point_not_found`.
+    pub fn constructor_not_found(call: &CallEntryPoint) -> Self {
+        Self::ConstructorNotFound {
+            class_hash: call.class_hash.expect("The class hash must be set before execution."),
+            storage_address: call.storage_address,
+        }
+    }
+
+    /// Returns an `L1HandlerNotFound` error for the given call; see `entry_point_not_found`.
+    pub fn l1_handler_not_found(call: &CallEntryPoint) -> Self {
+        Self::L1HandlerNotFound {
+            selector: call.entry_point_selector,
+            class_hash: call.class_hash.expect("The class hash must be set before execution."),
+            storage_address: call.storage_address,
+        }
+    }
+}
+
 impl From<RunnerError> for PreExecutionError {
     fn from(error: RunnerError) -> Self {
         Self::RunnerError(Box::new(error))
@@ -54,6 +126,16 @@ pub enum PostExecutionError {
     MathError(#[from] cairo_vm::types::errors::math_errors::MathError),
     #[error(transparent)]
     MemoryError(#[from] MemoryError),
+    #[error(
+        "Validation failed: Read-only segment {start_ptr} of length {length} was written to (used \
+         size: {used_size})."
+    )]
+    ReadOnlySegmentWritten { start_ptr: Relocatable, length: usize, used_size: usize },
+    #[error(
+        "Return data at {retdata_ptr} of size {retdata_size} lies outside the segments the \
+         program may return from."
+    )]
+    RetdataOutOfBounds { retdata_ptr: Relocatable, retdata_size: usize },
     #[error(transparent)]
     RetdataSizeTooBig(#[from] TryFromBigIntError<BigInt>),
     #[error("Validation failed: {0}.")]
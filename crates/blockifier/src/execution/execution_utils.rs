@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use cairo_felt::Felt252;
 use cairo_lang_runner::casm_run::format_next_item;
@@ -10,6 +10,7 @@ use cairo_vm::types::program::Program;
 use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
 use cairo_vm::vm::errors::memory_errors::MemoryError;
 use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
+use cairo_vm::vm::runners::builtin_runner::OUTPUT_BUILTIN_NAME;
 use cairo_vm::vm::runners::cairo_runner::CairoArg;
 use cairo_vm::vm::vm_core::VirtualMachine;
 use num_bigint::BigUint;
@@ -78,15 +79,65 @@ pub fn read_execution_retdata(
     retdata_size: MaybeRelocatable,
     retdata_ptr: &MaybeRelocatable,
 ) -> Result<Retdata, PostExecutionError> {
-    let retdata_size = match retdata_size {
-        MaybeRelocatable::Int(retdata_size) => usize::try_from(retdata_size.to_bigint())
-            .map_err(PostExecutionError::RetdataSizeTooBig)?,
+    let retdata_size = retdata_size_to_usize(retdata_size)?;
+
+    Ok(Retdata(felt_range_from_ptr(vm, Relocatable::try_from(retdata_ptr)?, retdata_size)?))
+}
+
+fn retdata_size_to_usize(retdata_size: MaybeRelocatable) -> Result<usize, PostExecutionError> {
+    match retdata_size {
+        MaybeRelocatable::Int(retdata_size) => {
+            usize::try_from(retdata_size.to_bigint()).map_err(PostExecutionError::RetdataSizeTooBig)
+        }
         relocatable => {
-            return Err(VirtualMachineError::ExpectedIntAtRange(Box::new(Some(relocatable))).into());
+            Err(VirtualMachineError::ExpectedIntAtRange(Box::new(Some(relocatable))).into())
         }
+    }
+}
+
+/// Verifies the return data lies within the used part of a segment written by the callee; i.e.,
+/// not in a temporary segment, nor in one of the given reserved segments (the program and
+/// execution segments of the run, and the syscall segment, whose contents are owned by the syscall
+/// handler). Must be called after the run ended, once the segment sizes are computed.
+/// See `BlockContext::verify_retdata_segments`.
+pub fn verify_retdata_segment(
+    vm: &VirtualMachine,
+    retdata_ptr: &MaybeRelocatable,
+    retdata_size: MaybeRelocatable,
+    reserved_segment_indices: &[isize],
+) -> Result<(), PostExecutionError> {
+    let retdata_ptr = Relocatable::try_from(retdata_ptr)?;
+    let retdata_size = retdata_size_to_usize(retdata_size)?;
+    let out_of_bounds = PostExecutionError::RetdataOutOfBounds { retdata_ptr, retdata_size };
+
+    let Ok(segment_index) = usize::try_from(retdata_ptr.segment_index) else {
+        return Err(out_of_bounds);
     };
+    if reserved_segment_indices.contains(&retdata_ptr.segment_index) {
+        return Err(out_of_bounds);
+    }
+    let used_size = vm.get_segment_used_size(segment_index).unwrap_or_default();
+    match retdata_ptr.offset.checked_add(retdata_size) {
+        Some(retdata_end) if retdata_end <= used_size => Ok(()),
+        _ => Err(out_of_bounds),
+    }
+}
 
-    Ok(Retdata(felt_range_from_ptr(vm, Relocatable::try_from(retdata_ptr)?, retdata_size)?))
+/// Returns the output of the given (ended) run: the contents of the output builtin segment, if the
+/// program uses the output builtin.
+pub fn read_program_output(vm: &VirtualMachine) -> Result<Vec<StarkFelt>, PostExecutionError> {
+    let Some(output_builtin) =
+        vm.get_builtin_runners().iter().find(|runner| runner.name() == OUTPUT_BUILTIN_NAME)
+    else {
+        return Ok(vec![]);
+    };
+    let output_segment_index = output_builtin.base();
+    let output_size = vm
+        .get_segment_used_size(output_segment_index)
+        .expect("Segments must contain the output builtin segment.");
+
+    let output_start_ptr = Relocatable::from((output_segment_index as isize, 0));
+    Ok(felt_range_from_ptr(vm, output_start_ptr, output_size)?)
 }
 
 pub fn stark_felt_from_ptr(
@@ -181,15 +232,20 @@ impl ReadOnlySegments {
         Ok(start_ptr)
     }
 
+    /// Verifies the run did not write to any of the segments; since memory is write-once, a write
+    /// extends the segment past its data. Must be called after the run ends (when the segment
+    /// sizes are computed).
     pub fn validate(&self, vm: &VirtualMachine) -> Result<(), PostExecutionError> {
         for segment in &self.0 {
             let used_size = vm
                 .get_segment_used_size(segment.start_ptr.segment_index as usize)
                 .expect("Segments must contain the allocated read-only segment.");
             if segment.length != used_size {
-                return Err(PostExecutionError::SecurityValidationError(
-                    "Read-only segments".to_string(),
-                ));
+                return Err(PostExecutionError::ReadOnlySegmentWritten {
+                    start_ptr: segment.start_ptr,
+                    length: segment.length,
+                    used_size,
+                });
             }
         }
 
@@ -205,6 +261,32 @@ impl ReadOnlySegments {
     }
 }
 
+/// Reports the program counters visited by the given (traced) run of the given class to the state,
+/// if the state tracks them; only program counters inside the bytecode are reported.
+pub fn register_visited_pcs(
+    vm: &mut VirtualMachine,
+    state: &mut dyn State,
+    class_hash: ClassHash,
+    bytecode_length: usize,
+) -> Result<(), VirtualMachineError> {
+    if !state.tracks_visited_pcs() {
+        return Ok(());
+    }
+
+    // Relocating the trace shifts the program counters by the base of the program segment.
+    let program_segment_base = 1;
+    vm.relocate_trace(&[program_segment_base, program_segment_base])?;
+    let visited_pcs: HashSet<usize> = vm
+        .get_relocated_trace()?
+        .iter()
+        .map(|trace_entry| trace_entry.pc - program_segment_base)
+        .filter(|pc| *pc < bytecode_length)
+        .collect();
+    state.add_visited_pcs(class_hash, &visited_pcs);
+
+    Ok(())
+}
+
 /// Instantiates the given class and assigns it an address.
 /// Returns the call info of the deployed class' constructor execution.
 pub fn execute_deployment(
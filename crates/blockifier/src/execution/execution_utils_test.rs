@@ -1,12 +1,23 @@
+use std::collections::HashMap;
 use std::iter::zip;
 
+use assert_matches::assert_matches;
 use cairo_felt::Felt252;
+use cairo_vm::serde::deserialize_program::{BuiltinName, ReferenceManager};
+use cairo_vm::types::program::Program;
+use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
+use cairo_vm::vm::runners::cairo_runner::CairoRunner;
+use cairo_vm::vm::vm_core::VirtualMachine;
 use num_bigint::BigUint;
 use num_traits::{One, Zero};
 use pretty_assertions::assert_eq;
 use starknet_api::hash::StarkFelt;
 
-use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
+use crate::execution::errors::PostExecutionError;
+use crate::execution::execution_utils::{
+    felt_to_stark_felt, read_program_output, stark_felt_to_felt, verify_retdata_segment,
+    ReadOnlySegments,
+};
 
 fn starkfelt_to_felt_pairs() -> Vec<(StarkFelt, Felt252)> {
     // The STARK prime is 2 ^ 251 + 17 * 2 ^ 192 + 1.
@@ -45,3 +56,114 @@ fn test_felt_to_stark_felt() {
         assert_eq!(felt_to_stark_felt(&felt), equivalent_stark_felt);
     }
 }
+
+#[test]
+fn test_verify_retdata_segment() {
+    // Segments 0, 1 and 3 stand for the program, execution and syscall segments; the callee wrote
+    // 7 cells to segment 2, and nothing to segment 4.
+    let reserved_segment_indices = [0, 1, 3];
+    let mut vm = VirtualMachine::new(false);
+    for _ in 0..5 {
+        vm.add_memory_segment();
+    }
+    for offset in 0..7 {
+        vm.insert_value(Relocatable::from((2, offset)), 1).unwrap();
+    }
+    vm.compute_segments_effective_sizes();
+    let verify = |retdata_ptr: (isize, usize), retdata_size: usize| {
+        verify_retdata_segment(
+            &vm,
+            &MaybeRelocatable::from(retdata_ptr),
+            MaybeRelocatable::from(retdata_size),
+            &reserved_segment_indices,
+        )
+    };
+
+    verify((2, 5), 2).unwrap();
+    verify((4, 0), 0).unwrap();
+    for (segment_index, offset, size) in [
+        // Reserved and temporary segments.
+        (0, 0, 1),
+        (1, 0, 1),
+        (3, 0, 1),
+        (-1, 0, 1),
+        // Out of the used part of the segment.
+        (2, 5, 3),
+        (2, 8, 0),
+        (4, 0, 1),
+        (2, 5, usize::MAX),
+    ] {
+        assert_matches!(
+            verify((segment_index, offset), size),
+            Err(PostExecutionError::RetdataOutOfBounds { retdata_ptr, retdata_size })
+            if retdata_ptr == Relocatable::from((segment_index, offset)) && retdata_size == size
+        );
+    }
+    assert_matches!(
+        verify_retdata_segment(
+            &vm,
+            &MaybeRelocatable::from(5),
+            MaybeRelocatable::from(0),
+            &reserved_segment_indices
+        ),
+        Err(PostExecutionError::MathError(_))
+    );
+}
+
+#[test]
+fn test_read_only_segments_validation() {
+    let mut vm = VirtualMachine::new(false);
+    let mut read_only_segments = ReadOnlySegments::default();
+    let data = vec![MaybeRelocatable::from(1), MaybeRelocatable::from(2)];
+    let start_ptr = read_only_segments.allocate(&mut vm, &data).unwrap();
+
+    // A write past the data extends the segment.
+    vm.insert_value((start_ptr + 2_usize).unwrap(), 3).unwrap();
+    vm.compute_segments_effective_sizes();
+    assert_matches!(
+        read_only_segments.validate(&vm),
+        Err(PostExecutionError::ReadOnlySegmentWritten { start_ptr: ptr, length: 2, used_size: 3 })
+        if ptr == start_ptr
+    );
+}
+
+#[test]
+fn test_read_program_output() {
+    let program = |builtins| {
+        Program::new(
+            builtins,
+            vec![],
+            None,
+            HashMap::new(),
+            ReferenceManager { references: vec![] },
+            HashMap::new(),
+            vec![],
+            None,
+        )
+        .unwrap()
+    };
+    let initialize_vm = |program: &Program| {
+        let mut runner = CairoRunner::new(program, "starknet", false).unwrap();
+        let mut vm = VirtualMachine::new(false);
+        runner.initialize_builtins(&mut vm).unwrap();
+        runner.initialize_segments(&mut vm, None);
+        vm
+    };
+
+    // The output is the contents of the output builtin segment.
+    let mut vm = initialize_vm(&program(vec![BuiltinName::output, BuiltinName::range_check]));
+    let output_base = vm.get_builtin_runners()[0].base() as isize;
+    for (offset, value) in [3_u8, 5].into_iter().enumerate() {
+        vm.insert_value(Relocatable::from((output_base, offset)), Felt252::from(value)).unwrap();
+    }
+    vm.compute_segments_effective_sizes();
+    assert_eq!(
+        read_program_output(&vm).unwrap(),
+        vec![StarkFelt::from(3_u8), StarkFelt::from(5_u8)]
+    );
+
+    // Programs that do not use the output builtin have no output.
+    let mut vm = initialize_vm(&program(vec![BuiltinName::range_check]));
+    vm.compute_segments_effective_sizes();
+    assert_eq!(read_program_output(&vm).unwrap(), vec![]);
+}
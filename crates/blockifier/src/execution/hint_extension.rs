@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use cairo_felt::Felt252;
+use cairo_lang_casm::hints::Hint;
+use cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::HintProcessorData;
+use cairo_vm::types::exec_scope::ExecutionScopes;
+use cairo_vm::vm::vm_core::VirtualMachine;
+
+use crate::execution::common_hints::HintExecutionResult;
+use crate::state::state_api::State;
+
+#[cfg(test)]
+#[path = "hint_extension_test.rs"]
+pub mod test;
+
+/// An extension point of the hint processors, set through `BlockContext::hint_extension`; allows
+/// embedders (e.g., appchains) to execute hints the blockifier does not support, or to override the
+/// execution of supported ones, without forking.
+///
+/// Each hint is offered to the extension before its default execution; returning [None] falls back
+/// to the default execution.
+pub trait HintExtension: Debug + Send + Sync {
+    /// Executes the given hint of a Cairo 0 class (including syscall hints).
+    fn execute_deprecated_hint(
+        &self,
+        _vm: &mut VirtualMachine,
+        _exec_scopes: &mut ExecutionScopes,
+        _hint: &HintProcessorData,
+        _constants: &HashMap<String, Felt252>,
+        _state: &mut dyn State,
+    ) -> Option<HintExecutionResult> {
+        None
+    }
+
+    /// Executes the given hint of a Cairo 1 class (including syscall hints).
+    fn execute_hint(
+        &self,
+        _vm: &mut VirtualMachine,
+        _exec_scopes: &mut ExecutionScopes,
+        _hint: &Hint,
+        _state: &mut dyn State,
+    ) -> Option<HintExecutionResult> {
+        None
+    }
+}
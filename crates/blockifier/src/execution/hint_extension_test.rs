@@ -0,0 +1,106 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use cairo_vm::vm::errors::hint_errors::HintError;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::Calldata;
+use starknet_api::{calldata, stark_felt};
+
+use super::*;
+use crate::abi::abi_utils::selector_from_name;
+use crate::block_context::BlockContext;
+use crate::execution::entry_point::{
+    CallEntryPoint, EntryPointExecutionContext, ExecutionResources,
+};
+use crate::state::cached_state::CachedState;
+use crate::test_utils::cached_state::{create_test_state, deprecated_create_test_state};
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::trivial_external_entry_point;
+use crate::transaction::objects::{AccountTransactionContext, DeprecatedAccountTransactionContext};
+
+/// Counts the offered hints; overrides all of them with an error, if set to.
+#[derive(Debug, Default)]
+struct CountingHintExtension {
+    n_hints: AtomicUsize,
+    n_deprecated_hints: AtomicUsize,
+    override_hints: bool,
+}
+
+impl CountingHintExtension {
+    fn result(&self) -> Option<HintExecutionResult> {
+        self.override_hints.then(|| Err(HintError::CustomHint("Overridden hint.".into())))
+    }
+}
+
+impl HintExtension for CountingHintExtension {
+    fn execute_deprecated_hint(
+        &self,
+        _vm: &mut VirtualMachine,
+        _exec_scopes: &mut ExecutionScopes,
+        _hint: &HintProcessorData,
+        _constants: &HashMap<String, Felt252>,
+        _state: &mut dyn State,
+    ) -> Option<HintExecutionResult> {
+        self.n_deprecated_hints.fetch_add(1, Ordering::Relaxed);
+        self.result()
+    }
+
+    fn execute_hint(
+        &self,
+        _vm: &mut VirtualMachine,
+        _exec_scopes: &mut ExecutionScopes,
+        _hint: &Hint,
+        _state: &mut dyn State,
+    ) -> Option<HintExecutionResult> {
+        self.n_hints.fetch_add(1, Ordering::Relaxed);
+        self.result()
+    }
+}
+
+fn execute_with_extension(
+    state: &mut CachedState<DictStateReader>,
+    hint_extension: Arc<CountingHintExtension>,
+) -> String {
+    let block_context =
+        BlockContext { hint_extension: Some(hint_extension), ..BlockContext::create_for_testing() };
+    let mut context = EntryPointExecutionContext::new_invoke(
+        &block_context,
+        &AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext::default()),
+        true,
+    )
+    .unwrap();
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![stark_felt!(1234_u16), stark_felt!(18_u8)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point()
+    };
+
+    match entry_point_call.execute(state, &mut ExecutionResources::default(), &mut context) {
+        Ok(_) => String::new(),
+        Err(error) => error.to_string(),
+    }
+}
+
+#[test]
+fn test_hint_extension() {
+    // Hints the extension does not handle fall back to the default execution.
+    let hint_extension = Arc::new(CountingHintExtension::default());
+    assert_eq!(execute_with_extension(&mut create_test_state(), hint_extension.clone()), "");
+    assert!(hint_extension.n_hints.load(Ordering::Relaxed) > 0);
+    assert_eq!(hint_extension.n_deprecated_hints.load(Ordering::Relaxed), 0);
+
+    let hint_extension = Arc::new(CountingHintExtension::default());
+    assert_eq!(
+        execute_with_extension(&mut deprecated_create_test_state(), hint_extension.clone()),
+        ""
+    );
+    assert!(hint_extension.n_deprecated_hints.load(Ordering::Relaxed) > 0);
+    assert_eq!(hint_extension.n_hints.load(Ordering::Relaxed), 0);
+
+    // Hints the extension handles override the default execution.
+    for mut state in [create_test_state(), deprecated_create_test_state()] {
+        let hint_extension =
+            Arc::new(CountingHintExtension { override_hints: true, ..Default::default() });
+        assert!(execute_with_extension(&mut state, hint_extension).contains("Overridden hint."));
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use cairo_vm::types::relocatable::Relocatable;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
+use cairo_vm::vm::vm_core::VirtualMachine;
+use starknet_api::hash::StarkFelt;
+use thiserror::Error;
+
+use super::hint_processor::{
+    create_retdata_segment, read_felt_array, SyscallExecutionError, SyscallHintProcessor,
+};
+use super::{SingleSegmentResponse, SyscallRequest, SyscallResult};
+use crate::block_context::BlockContext;
+use crate::execution::common_hints::ExecutionMode;
+use crate::execution::deprecated_syscalls::DeprecatedSyscallSelector as SyscallSelector;
+use crate::execution::entry_point::CallEntryPoint;
+use crate::state::state_api::State;
+use crate::transaction::objects::AccountTransactionContext;
+
+#[cfg(test)]
+#[path = "custom_syscalls_test.rs"]
+pub mod test;
+
+/// The custom syscall handlers, by syscall selector; see `register`.
+#[derive(Clone, Debug, Default)]
+pub struct CustomSyscalls(HashMap<StarkFelt, Arc<dyn CustomSyscallHandler>>);
+
+impl CustomSyscalls {
+    /// Registers the given handler under the given selector, which must be neither the selector of
+    /// a Starknet syscall nor that of an already registered custom syscall.
+    pub fn register(
+        &mut self,
+        selector: StarkFelt,
+        handler: Arc<dyn CustomSyscallHandler>,
+    ) -> Result<(), CustomSyscallRegistrationError> {
+        if let Ok(syscall) = SyscallSelector::try_from(selector) {
+            return Err(CustomSyscallRegistrationError::StarknetSyscallSelector {
+                selector,
+                syscall_name: syscall.as_ref().to_string(),
+            });
+        }
+        if self.0.contains_key(&selector) {
+            return Err(CustomSyscallRegistrationError::DuplicateSelector(selector));
+        }
+
+        self.0.insert(selector, handler);
+        Ok(())
+    }
+
+    pub fn get(&self, selector: &StarkFelt) -> Option<&Arc<dyn CustomSyscallHandler>> {
+        self.0.get(selector)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CustomSyscallRegistrationError {
+    #[error("A custom syscall is already registered under selector {0}.")]
+    DuplicateSelector(StarkFelt),
+    #[error("Selector {selector} is the selector of the Starknet syscall {syscall_name}.")]
+    StarknetSyscallSelector { selector: StarkFelt, syscall_name: String },
+}
+
+/// The state and context a custom syscall handler is given access to.
+pub struct CustomSyscallContext<'a> {
+    pub state: &'a mut dyn State,
+    pub block_context: &'a BlockContext,
+    pub account_tx_context: &'a AccountTransactionContext,
+    /// The call executing the syscall.
+    pub call: &'a CallEntryPoint,
+    pub execution_mode: ExecutionMode,
+}
+
+/// A handler of a chain-specific syscall (e.g., of an appchain), registered under its selector
+/// through `BlockContext::custom_syscalls`. Custom syscalls are available to Cairo 1 classes only,
+/// in all Starknet versions; unlike the Starknet syscalls, they are not counted in the syscall
+/// counters, but their OS resources are added to those of the calling call (see `os_resources`),
+/// so that fees and bouncing account for them.
+///
+/// A custom syscall takes an array of felts and returns an array of felts; i.e., its request is
+/// `(gas, input_start, input_end)`, and its response is
+/// `(gas, failure_flag, output_start, output_end)`.
+pub trait CustomSyscallHandler: Debug + Send + Sync {
+    /// Returns the gas cost of the syscall, including the base syscall cost; charged before the
+    /// execution.
+    fn gas_cost(&self) -> u64;
+
+    /// Returns the resources the OS spends on executing the syscall; the counterpart of the OS
+    /// resources the versioned constants specify per Starknet syscall.
+    fn os_resources(&self) -> VmExecutionResources;
+
+    /// Returns whether validation entry points (e.g., `__validate__`) may execute the syscall; by
+    /// default, they may not, as for the Starknet syscalls forbidden in validation.
+    fn is_allowed_in_validate(&self) -> bool {
+        false
+    }
+
+    /// Executes the syscall on the given input. A `SyscallExecutionError::SyscallError` fails the
+    /// syscall, which the calling contract may handle; any other error fails the execution.
+    fn execute(
+        &self,
+        input: &[StarkFelt],
+        context: CustomSyscallContext<'_>,
+    ) -> SyscallResult<Vec<StarkFelt>>;
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct CustomSyscallRequest {
+    pub input: Vec<StarkFelt>,
+}
+
+impl SyscallRequest for CustomSyscallRequest {
+    const SIZE: usize = 2;
+
+    fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<CustomSyscallRequest> {
+        Ok(CustomSyscallRequest { input: read_felt_array::<SyscallExecutionError>(vm, ptr)? })
+    }
+}
+
+type CustomSyscallResponse = SingleSegmentResponse;
+
+pub fn custom_syscall(
+    request: CustomSyscallRequest,
+    vm: &mut VirtualMachine,
+    syscall_handler: &mut SyscallHintProcessor<'_>,
+    handler: &dyn CustomSyscallHandler,
+) -> SyscallResult<CustomSyscallResponse> {
+    syscall_handler.resources.vm_resources += &handler.os_resources();
+    let context = CustomSyscallContext {
+        state: syscall_handler.state,
+        block_context: &syscall_handler.context.block_context,
+        account_tx_context: &syscall_handler.context.account_tx_context,
+        call: &syscall_handler.call,
+        execution_mode: syscall_handler.context.execution_mode,
+    };
+    let output = handler.execute(&request.input, context)?;
+
+    Ok(CustomSyscallResponse { segment: create_retdata_segment(vm, syscall_handler, &output)? })
+}
@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
+use cairo_felt::Felt252;
+use cairo_lang_casm::hints::StarknetHint;
+use cairo_lang_casm::operand::{CellRef, Register, ResOperand};
+use cairo_vm::types::relocatable::MaybeRelocatable;
+use pretty_assertions::assert_eq;
+use starknet_api::core::PatriciaKey;
+use starknet_api::hash::StarkHash;
+use starknet_api::state::StorageKey;
+use starknet_api::{patricia_key, stark_felt};
+
+use super::*;
+use crate::abi::abi_utils::selector_from_name;
+use crate::abi::constants;
+use crate::execution::entry_point::{EntryPointExecutionContext, ExecutionResources};
+use crate::execution::execution_utils::{
+    felt_range_from_ptr, felt_to_stark_felt, stark_felt_to_felt, ReadOnlySegments,
+};
+use crate::state::state_api::StateReader;
+use crate::test_utils::cached_state::create_test_state;
+use crate::test_utils::trivial_external_entry_point;
+use crate::transaction::objects::DeprecatedAccountTransactionContext;
+use crate::utils::felt_to_u64;
+
+const SUM_SYSCALL_SELECTOR: &str = "0x53756d";
+const SUM_SYSCALL_GAS_COST: u64 = constants::SYSCALL_BASE_GAS_COST + 1000;
+const SUM_SYSCALL_N_STEPS: usize = 100;
+const INITIAL_GAS: u64 = 1_000_000;
+
+/// Returns the sum of its input, and stores it in the storage of the calling contract.
+#[derive(Debug)]
+struct SumSyscall;
+
+impl SumSyscall {
+    fn sum_storage_key() -> StorageKey {
+        StorageKey(patricia_key!("0x5"))
+    }
+}
+
+impl CustomSyscallHandler for SumSyscall {
+    fn gas_cost(&self) -> u64 {
+        SUM_SYSCALL_GAS_COST
+    }
+
+    fn os_resources(&self) -> VmExecutionResources {
+        VmExecutionResources { n_steps: SUM_SYSCALL_N_STEPS, ..Default::default() }
+    }
+
+    fn execute(
+        &self,
+        input: &[StarkFelt],
+        context: CustomSyscallContext<'_>,
+    ) -> SyscallResult<Vec<StarkFelt>> {
+        if input.is_empty() {
+            return Err(SyscallExecutionError::SyscallError {
+                error_data: vec![stark_felt!(7_u8)],
+            });
+        }
+
+        let sum = felt_to_stark_felt(&input.iter().map(|felt| stark_felt_to_felt(*felt)).sum());
+        context.state.set_storage_at(context.call.storage_address, Self::sum_storage_key(), sum)?;
+
+        Ok(vec![sum])
+    }
+}
+
+/// Executes the custom syscall on the given input, and returns the remaining gas, the failure
+/// flag, and the output (or error data).
+fn execute_custom_syscall(
+    state: &mut dyn State,
+    resources: &mut ExecutionResources,
+    execution_mode: ExecutionMode,
+    input: &[StarkFelt],
+    selector: StarkFelt,
+) -> SyscallResult<(u64, bool, Vec<StarkFelt>)> {
+    let mut custom_syscalls = CustomSyscalls::default();
+    custom_syscalls
+        .register(StarkFelt::try_from(SUM_SYSCALL_SELECTOR).unwrap(), Arc::new(SumSyscall))
+        .unwrap();
+    let block_context = BlockContext {
+        custom_syscalls: Arc::new(custom_syscalls),
+        ..BlockContext::create_for_testing()
+    };
+    let mut context = EntryPointExecutionContext::new(
+        &block_context,
+        &AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext::default()),
+        execution_mode,
+        true,
+    )
+    .unwrap();
+
+    // Lay out the syscall request, and point the frame pointer at it.
+    let mut vm = VirtualMachine::new(false);
+    vm.add_memory_segment(); // Program segment.
+    vm.add_memory_segment(); // Execution segment.
+    let syscall_ptr = vm.add_memory_segment();
+    let input_start = vm.add_memory_segment();
+    let input_data: Vec<MaybeRelocatable> =
+        input.iter().map(|felt| stark_felt_to_felt(*felt).into()).collect();
+    let input_end = vm.load_data(input_start, &input_data)?;
+    let request: Vec<MaybeRelocatable> = vec![
+        stark_felt_to_felt(selector).into(),
+        Felt252::from(INITIAL_GAS).into(),
+        input_start.into(),
+        input_end.into(),
+    ];
+    vm.load_data(syscall_ptr, &request)?;
+    vm.insert_value(vm.get_fp(), syscall_ptr)?;
+
+    let hints = HashMap::new();
+    let mut syscall_handler = SyscallHintProcessor::new(
+        state,
+        resources,
+        &mut context,
+        syscall_ptr,
+        trivial_external_entry_point(),
+        &hints,
+        ReadOnlySegments::default(),
+    );
+    let hint = StarknetHint::SystemCall {
+        system: ResOperand::Deref(CellRef { register: Register::FP, offset: 0 }),
+    };
+    syscall_handler.execute_next_syscall(&mut vm, &hint).map_err(|error| {
+        SyscallExecutionError::InvalidSyscallInput { input: selector, info: error.to_string() }
+    })?;
+
+    // Read the response.
+    let mut response_ptr = (syscall_ptr + request.len())?;
+    let response = felt_range_from_ptr(&vm, response_ptr, 2)?;
+    response_ptr = (response_ptr + 2)?;
+    let output = read_felt_array::<SyscallExecutionError>(&vm, &mut response_ptr)?;
+
+    Ok((felt_to_u64(response[0]).unwrap(), response[1] == StarkFelt::ONE, output))
+}
+
+#[test]
+fn test_custom_syscall() {
+    let mut state = create_test_state();
+    let mut resources = ExecutionResources::default();
+    let mut execute = |input: &[StarkFelt], selector| {
+        execute_custom_syscall(&mut state, &mut resources, ExecutionMode::Execute, input, selector)
+    };
+    let selector = StarkFelt::try_from(SUM_SYSCALL_SELECTOR).unwrap();
+    let remaining_gas = INITIAL_GAS - (SUM_SYSCALL_GAS_COST - constants::SYSCALL_BASE_GAS_COST);
+
+    let input = [stark_felt!(2_u8), stark_felt!(3_u8)];
+    assert_eq!(execute(&input, selector).unwrap(), (remaining_gas, false, vec![stark_felt!(5_u8)]));
+
+    // Syscall errors are reported to the calling contract.
+    assert_eq!(execute(&[], selector).unwrap(), (remaining_gas, true, vec![stark_felt!(7_u8)]));
+
+    // Unregistered selectors are still rejected.
+    assert!(execute(&input, stark_felt!("0x123")).is_err());
+
+    // The OS resources of both executions are accounted for; custom syscalls are not counted as
+    // Starknet syscalls.
+    assert_eq!(resources.vm_resources.n_steps, 2 * SUM_SYSCALL_N_STEPS);
+    assert!(resources.syscall_counter.is_empty());
+    let storage_address = trivial_external_entry_point().storage_address;
+    assert_eq!(
+        state.get_storage_at(storage_address, SumSyscall::sum_storage_key()).unwrap(),
+        stark_felt!(5_u8)
+    );
+}
+
+#[test]
+fn test_custom_syscall_in_validate() {
+    let mut state = create_test_state();
+    let mut resources = ExecutionResources::default();
+    let selector = StarkFelt::try_from(SUM_SYSCALL_SELECTOR).unwrap();
+
+    let error = execute_custom_syscall(
+        &mut state,
+        &mut resources,
+        ExecutionMode::Validate,
+        &[stark_felt!(2_u8)],
+        selector,
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains(&format!(
+        "Unauthorized syscall custom syscall {selector} in execution mode Validate."
+    )));
+    assert_eq!(resources.vm_resources, VmExecutionResources::default());
+}
+
+#[test]
+fn test_custom_syscall_registration() {
+    let mut custom_syscalls = CustomSyscalls::default();
+    let selector = StarkFelt::try_from(SUM_SYSCALL_SELECTOR).unwrap();
+    custom_syscalls.register(selector, Arc::new(SumSyscall)).unwrap();
+
+    assert_matches!(
+        custom_syscalls.register(selector, Arc::new(SumSyscall)),
+        Err(CustomSyscallRegistrationError::DuplicateSelector(duplicate_selector))
+        if duplicate_selector == selector
+    );
+    // Starknet syscall selectors are the syscall names, as short strings.
+    let storage_read_selector = StarkFelt::from(u128::from_be_bytes(*b"\0\0\0\0\0StorageRead"));
+    assert_matches!(
+        custom_syscalls.register(storage_read_selector, Arc::new(SumSyscall)),
+        Err(CustomSyscallRegistrationError::StarknetSyscallSelector { syscall_name, .. })
+        if syscall_name == "storage_read"
+    );
+    // Not a syscall selector.
+    custom_syscalls.register(selector_from_name("sum").0, Arc::new(SumSyscall)).unwrap();
+}
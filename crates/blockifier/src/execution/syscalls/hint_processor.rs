@@ -2,7 +2,7 @@ use std::any::Any;
 use std::collections::{HashMap, HashSet};
 
 use cairo_felt::Felt252;
-use cairo_lang_casm::hints::{Hint, StarknetHint};
+use cairo_lang_casm::hints::{Hint, PythonicHint, StarknetHint};
 use cairo_lang_casm::operand::{BinOpOperand, DerefOrImmediate, Operation, Register, ResOperand};
 use cairo_lang_runner::casm_run::execute_core_hint_base;
 use cairo_vm::hint_processor::hint_processor_definition::{HintProcessorLogic, HintReference};
@@ -26,16 +26,19 @@ use thiserror::Error;
 
 use crate::abi::constants;
 use crate::abi::sierra_types::SierraTypeError;
+use crate::chain_id::{chain_id_as_felt, ChainIdError};
 use crate::execution::call_info::{CallInfo, OrderedEvent, OrderedL2ToL1Message};
 use crate::execution::common_hints::{ExecutionMode, HintExecutionResult};
 use crate::execution::entry_point::{
-    CallEntryPoint, CallType, EntryPointExecutionContext, ExecutionResources,
+    CallEntryPoint, CallFrame, CallType, EntryPointExecutionContext, EntryPointExecutionResult,
+    ExecutionResources,
 };
 use crate::execution::errors::EntryPointExecutionError;
 use crate::execution::execution_utils::{
     felt_range_from_ptr, max_fee_for_execution_info, stark_felt_from_ptr, stark_felt_to_felt,
     write_maybe_relocatable, ReadOnlySegment, ReadOnlySegments,
 };
+use crate::execution::syscalls::custom_syscalls::custom_syscall;
 use crate::execution::syscalls::secp::{
     secp256k1_add, secp256k1_get_point_from_x, secp256k1_get_xy, secp256k1_mul, secp256k1_new,
     secp256r1_add, secp256r1_get_point_from_x, secp256r1_get_xy, secp256r1_mul, secp256r1_new,
@@ -48,9 +51,12 @@ use crate::execution::syscalls::{
     SyscallResponse, SyscallResponseWrapper, SyscallResult, SyscallSelector,
 };
 use crate::state::errors::StateError;
+use crate::state::journaled_state::JournaledState;
 use crate::state::state_api::State;
 use crate::transaction::objects::{AccountTransactionContext, CurrentAccountTransactionContext};
 use crate::transaction::transaction_utils::update_remaining_gas;
+use crate::utils::{format_address, format_class_hash};
+use crate::versioned_constants::SyscallGasCosts;
 
 pub type SyscallCounter = HashMap<SyscallSelector, usize>;
 
@@ -58,11 +64,13 @@ pub type SyscallCounter = HashMap<SyscallSelector, usize>;
 pub enum SyscallExecutionError {
     #[error("Bad syscall_ptr; expected: {expected_ptr:?}, got: {actual_ptr:?}.")]
     BadSyscallPointer { expected_ptr: Relocatable, actual_ptr: Relocatable },
-    #[error("Cannot replace V1 class hash with V0 class hash: {class_hash}.")]
+    #[error("Cannot replace V1 class hash with V0 class hash: {}.", format_class_hash(.class_hash))]
     ForbiddenClassReplacement { class_hash: ClassHash },
     #[error("Invalid address domain: {address_domain}.")]
     InvalidAddressDomain { address_domain: StarkFelt },
     #[error(transparent)]
+    ChainIdError(#[from] ChainIdError),
+    #[error(transparent)]
     InnerCallExecutionError(#[from] EntryPointExecutionError),
     #[error("Invalid syscall input: {input:?}; {info}")]
     InvalidSyscallInput { input: StarkFelt, info: String },
@@ -80,10 +88,28 @@ pub enum SyscallExecutionError {
     StarknetApiError(#[from] StarknetApiError),
     #[error(transparent)]
     StateError(#[from] StateError),
+    #[error(
+        "Unauthorized call from contract {} to contract {} in execution mode Validate; \
+         validation may only call the validated account.",
+        format_address(.caller_address),
+        format_address(.callee_address)
+    )]
+    UnauthorizedInnerCall { caller_address: ContractAddress, callee_address: ContractAddress },
     #[error(transparent)]
     VirtualMachineError(#[from] VirtualMachineError),
+    #[error(
+        "Syscall segment layout mismatch; expected {expected_size} cells, got {actual_size} cells."
+    )]
+    SyscallLayoutMismatch { expected_size: usize, actual_size: usize },
+    #[error(
+        "Syscall gas cost ({gas_cost}) is lower than the base syscall gas cost ({}).",
+        constants::SYSCALL_BASE_GAS_COST
+    )]
+    SyscallGasCostTooLow { gas_cost: u64 },
     #[error("Syscall error.")]
     SyscallError { error_data: Vec<StarkFelt> },
+    #[error("Syscall {syscall_name} is unavailable in this Starknet version.")]
+    SyscallUnavailable { syscall_name: String },
 }
 
 // Needed for custom hint implementations (in our case, syscall hints) which must comply with the
@@ -134,7 +160,11 @@ pub struct SyscallHintProcessor<'a> {
 
     // Additional information gathered during execution.
     pub read_values: Vec<StarkFelt>,
+    pub read_keys: Vec<StorageKey>,
     pub accessed_keys: HashSet<StorageKey>,
+    /// The number of invocations of each syscall by the current execution (not including inner
+    /// calls).
+    pub syscall_counter: SyscallCounter,
 
     // Secp hint processors.
     pub secp256k1_hint_processor: SecpHintProcessor<ark_secp256k1::Config>,
@@ -167,7 +197,9 @@ impl<'a> SyscallHintProcessor<'a> {
             read_only_segments,
             syscall_ptr: initial_syscall_ptr,
             read_values: vec![],
+            read_keys: vec![],
             accessed_keys: HashSet::new(),
+            syscall_counter: SyscallCounter::new(),
             hints,
             execution_info_ptr: None,
             secp256k1_hint_processor: SecpHintProcessor::default(),
@@ -195,6 +227,15 @@ impl<'a> SyscallHintProcessor<'a> {
         self.execution_mode() == ExecutionMode::Validate
     }
 
+    /// Returns the chain of calls leading to the current one, outermost first.
+    pub fn call_stack(&self) -> &[CallFrame] {
+        self.context.call_stack()
+    }
+
+    pub fn gas_costs(&self) -> &SyscallGasCosts {
+        &self.context.block_context.versioned_constants.gas_costs
+    }
+
     pub fn verify_syscall_ptr(&self, actual_ptr: Relocatable) -> SyscallResult<()> {
         if actual_ptr != self.syscall_ptr {
             return Err(SyscallExecutionError::BadSyscallPointer {
@@ -206,6 +247,24 @@ impl<'a> SyscallHintProcessor<'a> {
         Ok(())
     }
 
+    /// Fails if the given syscall may not be executed: if it is unavailable in the Starknet version
+    /// of the block, or forbidden in the current execution mode.
+    pub fn verify_syscall_allowed(&self, selector: SyscallSelector) -> SyscallResult<()> {
+        if !self.context.block_context.versioned_constants.is_syscall_available(&selector) {
+            return Err(SyscallExecutionError::SyscallUnavailable {
+                syscall_name: selector.as_ref().to_string(),
+            });
+        }
+        if !self.execution_mode().is_syscall_allowed(selector) {
+            return Err(SyscallExecutionError::InvalidSyscallInExecutionMode {
+                syscall_name: selector.as_ref().to_string(),
+                execution_mode: self.execution_mode(),
+            });
+        }
+
+        Ok(())
+    }
+
     /// Infers and executes the next syscall.
     /// Must comply with the API of a hint function, as defined by the `HintProcessor`.
     pub fn execute_next_syscall(
@@ -214,14 +273,36 @@ impl<'a> SyscallHintProcessor<'a> {
         hint: &StarknetHint,
     ) -> HintExecutionResult {
         let StarknetHint::SystemCall { system: syscall } = hint else {
-            return Err(HintError::CustomHint(
-                "Test functions are unsupported on starknet.".into(),
-            ));
+            return Err(self
+                .context
+                .block_context
+                .unknown_hint_policy(self.context.execution_mode)
+                .error("Test functions are unsupported on starknet.", &hint.get_pythonic_hint()));
         };
         let initial_syscall_ptr = get_ptr_from_res_operand_unchecked(vm, syscall);
         self.verify_syscall_ptr(initial_syscall_ptr)?;
 
-        let selector = SyscallSelector::try_from(self.read_next_syscall_selector(vm)?)?;
+        let selector = self.read_next_syscall_selector(vm)?;
+        if let Some(handler) = self.context.block_context.custom_syscalls.get(&selector).cloned() {
+            if self.is_validate_mode() && !handler.is_allowed_in_validate() {
+                return Err(SyscallExecutionError::InvalidSyscallInExecutionMode {
+                    syscall_name: format!("custom syscall {selector}"),
+                    execution_mode: self.execution_mode(),
+                }
+                .into());
+            }
+            let gas_cost = handler.gas_cost().max(constants::SYSCALL_BASE_GAS_COST);
+            return self.execute_syscall(
+                vm,
+                |request, vm, syscall_handler, _remaining_gas| {
+                    custom_syscall(request, vm, syscall_handler, handler.as_ref())
+                },
+                gas_cost,
+            );
+        }
+
+        let selector = SyscallSelector::try_from(selector)?;
+        self.verify_syscall_allowed(selector)?;
 
         // Keccak resource usage depends on the input length, so we increment the syscall count
         // in the syscall execution callback.
@@ -231,70 +312,70 @@ impl<'a> SyscallHintProcessor<'a> {
 
         match selector {
             SyscallSelector::CallContract => {
-                self.execute_syscall(vm, call_contract, constants::CALL_CONTRACT_GAS_COST)
+                self.execute_syscall(vm, call_contract, self.gas_costs().call_contract)
             }
-            SyscallSelector::Deploy => self.execute_syscall(vm, deploy, constants::DEPLOY_GAS_COST),
+            SyscallSelector::Deploy => self.execute_syscall(vm, deploy, self.gas_costs().deploy),
             SyscallSelector::EmitEvent => {
-                self.execute_syscall(vm, emit_event, constants::EMIT_EVENT_GAS_COST)
+                self.execute_syscall(vm, emit_event, self.gas_costs().emit_event)
             }
             SyscallSelector::GetBlockHash => {
-                self.execute_syscall(vm, get_block_hash, constants::GET_BLOCK_HASH_GAS_COST)
+                self.execute_syscall(vm, get_block_hash, self.gas_costs().get_block_hash)
             }
             SyscallSelector::GetExecutionInfo => {
-                self.execute_syscall(vm, get_execution_info, constants::GET_EXECUTION_INFO_GAS_COST)
+                self.execute_syscall(vm, get_execution_info, self.gas_costs().get_execution_info)
             }
-            SyscallSelector::Keccak => self.execute_syscall(vm, keccak, constants::KECCAK_GAS_COST),
+            SyscallSelector::Keccak => self.execute_syscall(vm, keccak, self.gas_costs().keccak),
             SyscallSelector::LibraryCall => {
-                self.execute_syscall(vm, library_call, constants::LIBRARY_CALL_GAS_COST)
+                self.execute_syscall(vm, library_call, self.gas_costs().library_call)
             }
             SyscallSelector::LibraryCallL1Handler => {
-                self.execute_syscall(vm, library_call_l1_handler, constants::LIBRARY_CALL_GAS_COST)
+                self.execute_syscall(vm, library_call_l1_handler, self.gas_costs().library_call)
             }
             SyscallSelector::ReplaceClass => {
-                self.execute_syscall(vm, replace_class, constants::REPLACE_CLASS_GAS_COST)
+                self.execute_syscall(vm, replace_class, self.gas_costs().replace_class)
             }
             SyscallSelector::Secp256k1Add => {
-                self.execute_syscall(vm, secp256k1_add, constants::SECP256K1_ADD_GAS_COST)
+                self.execute_syscall(vm, secp256k1_add, self.gas_costs().secp256k1_add)
             }
             SyscallSelector::Secp256k1GetPointFromX => self.execute_syscall(
                 vm,
                 secp256k1_get_point_from_x,
-                constants::SECP256K1_GET_POINT_FROM_X_GAS_COST,
+                self.gas_costs().secp256k1_get_point_from_x,
             ),
             SyscallSelector::Secp256k1GetXy => {
-                self.execute_syscall(vm, secp256k1_get_xy, constants::SECP256K1_GET_XY_GAS_COST)
+                self.execute_syscall(vm, secp256k1_get_xy, self.gas_costs().secp256k1_get_xy)
             }
             SyscallSelector::Secp256k1Mul => {
-                self.execute_syscall(vm, secp256k1_mul, constants::SECP256K1_MUL_GAS_COST)
+                self.execute_syscall(vm, secp256k1_mul, self.gas_costs().secp256k1_mul)
             }
             SyscallSelector::Secp256k1New => {
-                self.execute_syscall(vm, secp256k1_new, constants::SECP256K1_NEW_GAS_COST)
+                self.execute_syscall(vm, secp256k1_new, self.gas_costs().secp256k1_new)
             }
             SyscallSelector::Secp256r1Add => {
-                self.execute_syscall(vm, secp256r1_add, constants::SECP256R1_ADD_GAS_COST)
+                self.execute_syscall(vm, secp256r1_add, self.gas_costs().secp256r1_add)
             }
             SyscallSelector::Secp256r1GetPointFromX => self.execute_syscall(
                 vm,
                 secp256r1_get_point_from_x,
-                constants::SECP256R1_GET_POINT_FROM_X_GAS_COST,
+                self.gas_costs().secp256r1_get_point_from_x,
             ),
             SyscallSelector::Secp256r1GetXy => {
-                self.execute_syscall(vm, secp256r1_get_xy, constants::SECP256R1_GET_XY_GAS_COST)
+                self.execute_syscall(vm, secp256r1_get_xy, self.gas_costs().secp256r1_get_xy)
             }
             SyscallSelector::Secp256r1Mul => {
-                self.execute_syscall(vm, secp256r1_mul, constants::SECP256R1_MUL_GAS_COST)
+                self.execute_syscall(vm, secp256r1_mul, self.gas_costs().secp256r1_mul)
             }
             SyscallSelector::Secp256r1New => {
-                self.execute_syscall(vm, secp256r1_new, constants::SECP256R1_NEW_GAS_COST)
+                self.execute_syscall(vm, secp256r1_new, self.gas_costs().secp256r1_new)
             }
             SyscallSelector::SendMessageToL1 => {
-                self.execute_syscall(vm, send_message_to_l1, constants::SEND_MESSAGE_TO_L1_GAS_COST)
+                self.execute_syscall(vm, send_message_to_l1, self.gas_costs().send_message_to_l1)
             }
             SyscallSelector::StorageRead => {
-                self.execute_syscall(vm, storage_read, constants::STORAGE_READ_GAS_COST)
+                self.execute_syscall(vm, storage_read, self.gas_costs().storage_read)
             }
             SyscallSelector::StorageWrite => {
-                self.execute_syscall(vm, storage_write, constants::STORAGE_WRITE_GAS_COST)
+                self.execute_syscall(vm, storage_write, self.gas_costs().storage_write)
             }
             _ => Err(HintError::UnknownHint(
                 format!("Unsupported syscall selector {selector:?}.").into(),
@@ -361,10 +442,14 @@ impl<'a> SyscallHintProcessor<'a> {
         ) -> SyscallResult<Response>,
     {
         // Refund `SYSCALL_BASE_GAS_COST` as it was pre-charged.
-        let required_gas = syscall_gas_cost - constants::SYSCALL_BASE_GAS_COST;
+        let required_gas = syscall_gas_cost
+            .checked_sub(constants::SYSCALL_BASE_GAS_COST)
+            .ok_or(SyscallExecutionError::SyscallGasCostTooLow { gas_cost: syscall_gas_cost })?;
 
+        let request_ptr = self.syscall_ptr;
         let SyscallRequestWrapper { gas_counter, request } =
             SyscallRequestWrapper::<Request>::read(vm, &mut self.syscall_ptr)?;
+        self.verify_syscall_layout(request_ptr, SyscallRequestWrapper::<Request>::SIZE)?;
 
         if gas_counter < required_gas {
             //  Out of gas failure.
@@ -372,7 +457,7 @@ impl<'a> SyscallHintProcessor<'a> {
                 StarkFelt::try_from(OUT_OF_GAS_ERROR).map_err(SyscallExecutionError::from)?;
             let response: SyscallResponseWrapper<Response> =
                 SyscallResponseWrapper::Failure { gas_counter, error_data: vec![out_of_gas_error] };
-            response.write(vm, &mut self.syscall_ptr)?;
+            self.write_syscall_response(vm, response)?;
 
             return Ok(());
         }
@@ -390,7 +475,37 @@ impl<'a> SyscallHintProcessor<'a> {
             Err(error) => return Err(error.into()),
         };
 
+        self.write_syscall_response(vm, response)?;
+
+        Ok(())
+    }
+
+    /// Writes the response, and verifies it occupies the size the OS expects.
+    fn write_syscall_response<Response: SyscallResponse>(
+        &mut self,
+        vm: &mut VirtualMachine,
+        response: SyscallResponseWrapper<Response>,
+    ) -> SyscallResult<()> {
+        let response_ptr = self.syscall_ptr;
+        let expected_size = response.size();
         response.write(vm, &mut self.syscall_ptr)?;
+        self.verify_syscall_layout(response_ptr, expected_size)
+    }
+
+    /// Verifies the syscall pointer advanced from the given pointer by exactly the expected size;
+    /// otherwise, the OS would read the following syscalls from different cells.
+    fn verify_syscall_layout(
+        &self,
+        start_ptr: Relocatable,
+        expected_size: usize,
+    ) -> SyscallResult<()> {
+        let actual_size = (self.syscall_ptr - start_ptr)?;
+        if actual_size != expected_size {
+            return Err(SyscallExecutionError::SyscallLayoutMismatch {
+                expected_size,
+                actual_size,
+            });
+        }
 
         Ok(())
     }
@@ -404,6 +519,7 @@ impl<'a> SyscallHintProcessor<'a> {
     pub fn increment_syscall_count_by(&mut self, selector: &SyscallSelector, n: usize) {
         let syscall_count = self.resources.syscall_counter.entry(*selector).or_default();
         *syscall_count += n;
+        *self.syscall_counter.entry(*selector).or_default() += n;
     }
 
     fn increment_syscall_count(&mut self, selector: &SyscallSelector) {
@@ -434,22 +550,12 @@ impl<'a> SyscallHintProcessor<'a> {
         &mut self,
         vm: &mut VirtualMachine,
     ) -> SyscallResult<Relocatable> {
-        let block_context = &self.context.block_context;
-        let block_info: Vec<StarkFelt> = if self.is_validate_mode() {
-            vec![
-                // TODO(Yoni, 1/5/2024): set the number to be zero for `validate`.
-                StarkFelt::from(block_context.block_number.0),
-                // TODO(Yoni, 1/5/2024): set the timestamp to be zero for `validate`.
-                StarkFelt::from(block_context.block_timestamp.0),
-                StarkFelt::ZERO,
-            ]
-        } else {
-            vec![
-                StarkFelt::from(block_context.block_number.0),
-                StarkFelt::from(block_context.block_timestamp.0),
-                *block_context.sequencer_address.0.key(),
-            ]
-        };
+        let block_info = self.execution_mode().block_info(&self.context.block_context);
+        let block_info = vec![
+            StarkFelt::from(block_info.block_number),
+            StarkFelt::from(block_info.block_timestamp),
+            *block_info.sequencer_address.0.key(),
+        ];
         let (block_info_segment_start_ptr, _) = self.allocate_data_segment(vm, block_info)?;
 
         Ok(block_info_segment_start_ptr)
@@ -478,7 +584,7 @@ impl<'a> SyscallHintProcessor<'a> {
             tx_signature_start_ptr.into(),
             tx_signature_end_ptr.into(),
             stark_felt_to_felt((self.context.account_tx_context).transaction_hash().0).into(),
-            Felt252::from_bytes_be(self.context.block_context.chain_id.0.as_bytes()).into(),
+            stark_felt_to_felt(chain_id_as_felt(&self.context.block_context.chain_id)?).into(),
             stark_felt_to_felt((self.context.account_tx_context).nonce().0).into(),
         ];
 
@@ -532,6 +638,7 @@ impl<'a> SyscallHintProcessor<'a> {
         self.accessed_keys.insert(key);
         let value = self.state.get_storage_at(self.storage_address(), key)?;
         self.read_values.push(value);
+        self.read_keys.push(key);
 
         Ok(StorageReadResponse { value })
     }
@@ -595,6 +702,12 @@ impl HintProcessorLogic for SyscallHintProcessor<'_> {
         _constants: &HashMap<String, Felt252>,
     ) -> HintExecutionResult {
         let hint = hint_data.downcast_ref::<Hint>().ok_or(HintError::WrongHintData)?;
+        if let Some(hint_extension) = &self.context.block_context.hint_extension {
+            if let Some(result) = hint_extension.execute_hint(vm, exec_scopes, hint, self.state) {
+                return result;
+            }
+        }
+
         match hint {
             Hint::Core(hint) => execute_core_hint_base(vm, exec_scopes, hint),
             Hint::Starknet(hint) => self.execute_next_syscall(vm, hint),
@@ -637,23 +750,50 @@ pub fn read_call_params(
     Ok((function_selector, calldata))
 }
 
-pub fn execute_inner_call(
-    call: CallEntryPoint,
-    vm: &mut VirtualMachine,
+/// Runs an inner call (or deployment) as a journaled call frame. When it succeeds, its state writes
+/// are applied to the state of the caller. When it fails, they are reverted along with its events
+/// and messages (the failed call is not added to the call tree, and the orders of its outputs are
+/// reused); the caller then receives the failure, and may continue.
+pub fn execute_call_frame(
     syscall_handler: &mut SyscallHintProcessor<'_>,
-    remaining_gas: &mut u64,
-) -> SyscallResult<ReadOnlySegment> {
-    let call_info =
-        call.execute(syscall_handler.state, syscall_handler.resources, syscall_handler.context)?;
-    let raw_retdata = &call_info.execution.retdata.0;
+    execute: impl FnOnce(
+        &mut dyn State,
+        &mut ExecutionResources,
+        &mut EntryPointExecutionContext,
+    ) -> EntryPointExecutionResult<CallInfo>,
+) -> SyscallResult<CallInfo> {
+    let context = &mut *syscall_handler.context;
+    let (n_emitted_events, n_sent_messages_to_l1) =
+        (context.n_emitted_events, context.n_sent_messages_to_l1);
+    let mut frame_state = JournaledState::new(syscall_handler.state);
+    let call_info = execute(&mut frame_state, syscall_handler.resources, context)?;
 
     if call_info.execution.failed {
+        context.n_emitted_events = n_emitted_events;
+        context.n_sent_messages_to_l1 = n_sent_messages_to_l1;
         // TODO(spapini): Append an error word according to starknet spec if needed.
         // Something like "EXECUTION_ERROR".
-        return Err(SyscallExecutionError::SyscallError { error_data: raw_retdata.clone() });
+        return Err(SyscallExecutionError::SyscallError {
+            error_data: call_info.execution.retdata.0,
+        });
     }
+    frame_state.commit()?;
+
+    Ok(call_info)
+}
+
+pub fn execute_inner_call(
+    call: CallEntryPoint,
+    vm: &mut VirtualMachine,
+    syscall_handler: &mut SyscallHintProcessor<'_>,
+    remaining_gas: &mut u64,
+) -> SyscallResult<ReadOnlySegment> {
+    let call_info = execute_call_frame(syscall_handler, |state, resources, context| {
+        call.execute(state, resources, context)
+    })?;
 
-    let retdata_segment = create_retdata_segment(vm, syscall_handler, raw_retdata)?;
+    let retdata_segment =
+        create_retdata_segment(vm, syscall_handler, &call_info.execution.retdata.0)?;
     update_remaining_gas(remaining_gas, &call_info);
 
     syscall_handler.inner_calls.push(call_info);
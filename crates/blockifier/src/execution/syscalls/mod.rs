@@ -14,9 +14,9 @@ use starknet_api::transaction::{
 };
 
 use self::hint_processor::{
-    create_retdata_segment, execute_inner_call, execute_library_call, felt_to_bool,
-    read_call_params, read_calldata, read_felt_array, write_segment, SyscallExecutionError,
-    SyscallHintProcessor, BLOCK_NUMBER_OUT_OF_RANGE_ERROR,
+    create_retdata_segment, execute_call_frame, execute_inner_call, execute_library_call,
+    felt_to_bool, read_call_params, read_calldata, read_felt_array, write_segment,
+    SyscallExecutionError, SyscallHintProcessor, BLOCK_NUMBER_OUT_OF_RANGE_ERROR,
 };
 use crate::abi::constants;
 use crate::execution::call_info::{MessageToL1, OrderedEvent, OrderedL2ToL1Message};
@@ -30,9 +30,16 @@ use crate::execution::execution_utils::{
 use crate::execution::syscalls::hint_processor::{INVALID_INPUT_LENGTH_ERROR, OUT_OF_GAS_ERROR};
 use crate::transaction::transaction_utils::update_remaining_gas;
 
+pub mod custom_syscalls;
 pub mod hint_processor;
 mod secp;
 
+#[cfg(test)]
+#[path = "syscalls_fuzz_test.rs"]
+pub mod syscalls_fuzz_test;
+#[cfg(test)]
+#[path = "syscalls_layout_test.rs"]
+pub mod syscalls_layout_test;
 #[cfg(test)]
 #[path = "syscalls_test.rs"]
 pub mod syscalls_test;
@@ -42,11 +49,26 @@ pub type WriteResponseResult = SyscallResult<()>;
 
 type SyscallSelector = DeprecatedSyscallSelector;
 
+/// The size of the syscall request header, i.e., the selector and the gas counter.
+pub const REQUEST_HEADER_SIZE: usize = 2;
+/// The size of the syscall response header, i.e., the gas counter and the failure flag.
+pub const RESPONSE_HEADER_SIZE: usize = 2;
+/// The size of the body of a failure response, i.e., the start and end of the error data.
+pub const FAILURE_RESPONSE_BODY_SIZE: usize = 2;
+
 pub trait SyscallRequest: Sized {
+    /// The number of memory cells the request occupies; maintained by hand, to match the OS
+    /// struct of the request (see `syscalls_layout_test`).
+    const SIZE: usize;
+
     fn read(_vm: &VirtualMachine, _ptr: &mut Relocatable) -> SyscallResult<Self>;
 }
 
 pub trait SyscallResponse {
+    /// The number of memory cells the response occupies; maintained by hand, to match the OS
+    /// struct of the response (see `syscalls_layout_test`).
+    const SIZE: usize;
+
     fn write(self, _vm: &mut VirtualMachine, _ptr: &mut Relocatable) -> WriteResponseResult;
 }
 
@@ -56,6 +78,9 @@ pub struct SyscallRequestWrapper<T: SyscallRequest> {
     pub request: T,
 }
 impl<T: SyscallRequest> SyscallRequest for SyscallRequestWrapper<T> {
+    // The selector is read separately, before the request.
+    const SIZE: usize = REQUEST_HEADER_SIZE - 1 + T::SIZE;
+
     fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<Self> {
         let gas_counter = felt_from_ptr(vm, ptr)?;
         let gas_counter =
@@ -72,7 +97,19 @@ pub enum SyscallResponseWrapper<T: SyscallResponse> {
     Success { gas_counter: u64, response: T },
     Failure { gas_counter: u64, error_data: Vec<StarkFelt> },
 }
+impl<T: SyscallResponse> SyscallResponseWrapper<T> {
+    /// The number of memory cells the response occupies; a failure response has a fixed size.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Success { .. } => Self::SIZE,
+            Self::Failure { .. } => RESPONSE_HEADER_SIZE + FAILURE_RESPONSE_BODY_SIZE,
+        }
+    }
+}
 impl<T: SyscallResponse> SyscallResponse for SyscallResponseWrapper<T> {
+    // The size of a success response.
+    const SIZE: usize = RESPONSE_HEADER_SIZE + T::SIZE;
+
     fn write(self, vm: &mut VirtualMachine, ptr: &mut Relocatable) -> WriteResponseResult {
         match self {
             Self::Success { gas_counter, response } => {
@@ -108,6 +145,8 @@ impl<T: SyscallResponse> SyscallResponse for SyscallResponseWrapper<T> {
 pub struct EmptyRequest;
 
 impl SyscallRequest for EmptyRequest {
+    const SIZE: usize = 0;
+
     fn read(_vm: &VirtualMachine, _ptr: &mut Relocatable) -> SyscallResult<EmptyRequest> {
         Ok(EmptyRequest)
     }
@@ -117,6 +156,8 @@ impl SyscallRequest for EmptyRequest {
 pub struct EmptyResponse;
 
 impl SyscallResponse for EmptyResponse {
+    const SIZE: usize = 0;
+
     fn write(self, _vm: &mut VirtualMachine, _ptr: &mut Relocatable) -> WriteResponseResult {
         Ok(())
     }
@@ -128,6 +169,8 @@ pub struct SingleSegmentResponse {
 }
 
 impl SyscallResponse for SingleSegmentResponse {
+    const SIZE: usize = 2;
+
     fn write(self, vm: &mut VirtualMachine, ptr: &mut Relocatable) -> WriteResponseResult {
         write_segment(vm, ptr, self.segment)
     }
@@ -143,6 +186,8 @@ pub struct CallContractRequest {
 }
 
 impl SyscallRequest for CallContractRequest {
+    const SIZE: usize = 4;
+
     fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<CallContractRequest> {
         let contract_address = ContractAddress::try_from(stark_felt_from_ptr(vm, ptr)?)?;
         let (function_selector, calldata) = read_call_params(vm, ptr)?;
@@ -160,10 +205,13 @@ pub fn call_contract(
     remaining_gas: &mut u64,
 ) -> SyscallResult<CallContractResponse> {
     let storage_address = request.contract_address;
-    if syscall_handler.is_validate_mode() && syscall_handler.storage_address() != storage_address {
-        return Err(SyscallExecutionError::InvalidSyscallInExecutionMode {
-            syscall_name: "call_contract".to_string(),
-            execution_mode: syscall_handler.execution_mode(),
+    if !syscall_handler
+        .execution_mode()
+        .is_inner_call_allowed(syscall_handler.storage_address(), storage_address)
+    {
+        return Err(SyscallExecutionError::UnauthorizedInnerCall {
+            caller_address: syscall_handler.storage_address(),
+            callee_address: storage_address,
         });
     }
     let entry_point = CallEntryPoint {
@@ -193,6 +241,8 @@ pub struct DeployRequest {
 }
 
 impl SyscallRequest for DeployRequest {
+    const SIZE: usize = 5;
+
     fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<DeployRequest> {
         let class_hash = ClassHash(stark_felt_from_ptr(vm, ptr)?);
         let contract_address_salt = ContractAddressSalt(stark_felt_from_ptr(vm, ptr)?);
@@ -218,6 +268,8 @@ pub struct DeployResponse {
 }
 
 impl SyscallResponse for DeployResponse {
+    const SIZE: usize = 3;
+
     fn write(self, vm: &mut VirtualMachine, ptr: &mut Relocatable) -> WriteResponseResult {
         write_stark_felt(vm, ptr, *self.contract_address.0.key())?;
         write_segment(vm, ptr, self.constructor_retdata)
@@ -248,14 +300,17 @@ pub fn deploy(
         storage_address: deployed_contract_address,
         caller_address: deployer_address,
     };
-    let call_info = execute_deployment(
-        syscall_handler.state,
-        syscall_handler.resources,
-        syscall_handler.context,
-        ctor_context,
-        request.constructor_calldata,
-        *remaining_gas,
-    )?;
+    // As for inner calls, a failing constructor reverts the deployment.
+    let call_info = execute_call_frame(syscall_handler, |state, resources, context| {
+        execute_deployment(
+            state,
+            resources,
+            context,
+            ctor_context,
+            request.constructor_calldata,
+            *remaining_gas,
+        )
+    })?;
 
     let constructor_retdata =
         create_retdata_segment(vm, syscall_handler, &call_info.execution.retdata.0)?;
@@ -274,6 +329,8 @@ pub struct EmitEventRequest {
 }
 
 impl SyscallRequest for EmitEventRequest {
+    const SIZE: usize = 4;
+
     // The Cairo struct contains: `keys_len`, `keys`, `data_len`, `data`·
     fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<EmitEventRequest> {
         let keys =
@@ -309,6 +366,8 @@ pub struct GetBlockHashRequest {
 }
 
 impl SyscallRequest for GetBlockHashRequest {
+    const SIZE: usize = 1;
+
     fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<GetBlockHashRequest> {
         let felt = felt_from_ptr(vm, ptr)?;
         let block_number = BlockNumber(felt.to_u64().ok_or_else(|| {
@@ -328,6 +387,8 @@ pub struct GetBlockHashResponse {
 }
 
 impl SyscallResponse for GetBlockHashResponse {
+    const SIZE: usize = 1;
+
     fn write(self, vm: &mut VirtualMachine, ptr: &mut Relocatable) -> WriteResponseResult {
         write_stark_felt(vm, ptr, self.block_hash.0)?;
         Ok(())
@@ -344,13 +405,6 @@ pub fn get_block_hash(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     _remaining_gas: &mut u64,
 ) -> SyscallResult<GetBlockHashResponse> {
-    if syscall_handler.is_validate_mode() {
-        return Err(SyscallExecutionError::InvalidSyscallInExecutionMode {
-            syscall_name: "get_block_hash".to_string(),
-            execution_mode: syscall_handler.execution_mode(),
-        });
-    }
-
     let requested_block_number = request.block_number.0;
     let current_block_number = syscall_handler.context.block_context.block_number.0;
 
@@ -380,6 +434,8 @@ pub struct GetExecutionInfoResponse {
 }
 
 impl SyscallResponse for GetExecutionInfoResponse {
+    const SIZE: usize = 1;
+
     fn write(self, vm: &mut VirtualMachine, ptr: &mut Relocatable) -> WriteResponseResult {
         write_maybe_relocatable(vm, ptr, self.execution_info_ptr)?;
         Ok(())
@@ -406,6 +462,8 @@ pub struct LibraryCallRequest {
 }
 
 impl SyscallRequest for LibraryCallRequest {
+    const SIZE: usize = 4;
+
     fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<LibraryCallRequest> {
         let class_hash = ClassHash(stark_felt_from_ptr(vm, ptr)?);
         let (function_selector, calldata) = read_call_params(vm, ptr)?;
@@ -466,6 +524,8 @@ pub struct ReplaceClassRequest {
 }
 
 impl SyscallRequest for ReplaceClassRequest {
+    const SIZE: usize = 1;
+
     fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<ReplaceClassRequest> {
         let class_hash = ClassHash(stark_felt_from_ptr(vm, ptr)?);
 
@@ -506,6 +566,8 @@ pub struct SendMessageToL1Request {
 }
 
 impl SyscallRequest for SendMessageToL1Request {
+    const SIZE: usize = 3;
+
     // The Cairo struct contains: `to_address`, `payload_size`, `payload`.
     fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<SendMessageToL1Request> {
         let to_address = EthAddress::try_from(stark_felt_from_ptr(vm, ptr)?)?;
@@ -544,6 +606,8 @@ pub struct StorageReadRequest {
 }
 
 impl SyscallRequest for StorageReadRequest {
+    const SIZE: usize = 2;
+
     fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<StorageReadRequest> {
         let address_domain = stark_felt_from_ptr(vm, ptr)?;
         if address_domain != StarkFelt::from(0_u8) {
@@ -560,6 +624,8 @@ pub struct StorageReadResponse {
 }
 
 impl SyscallResponse for StorageReadResponse {
+    const SIZE: usize = 1;
+
     fn write(self, vm: &mut VirtualMachine, ptr: &mut Relocatable) -> WriteResponseResult {
         write_stark_felt(vm, ptr, self.value)?;
         Ok(())
@@ -585,6 +651,8 @@ pub struct StorageWriteRequest {
 }
 
 impl SyscallRequest for StorageWriteRequest {
+    const SIZE: usize = 3;
+
     fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<StorageWriteRequest> {
         let address_domain = stark_felt_from_ptr(vm, ptr)?;
         if address_domain != StarkFelt::from(0_u8) {
@@ -616,6 +684,8 @@ pub struct KeccakRequest {
 }
 
 impl SyscallRequest for KeccakRequest {
+    const SIZE: usize = 2;
+
     fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<KeccakRequest> {
         let input_start = vm.get_relocatable(*ptr)?;
         *ptr = (*ptr + 1)?;
@@ -632,6 +702,8 @@ pub struct KeccakResponse {
 }
 
 impl SyscallResponse for KeccakResponse {
+    const SIZE: usize = 2;
+
     fn write(self, vm: &mut VirtualMachine, ptr: &mut Relocatable) -> WriteResponseResult {
         write_felt(vm, ptr, self.result_low)?;
         write_felt(vm, ptr, self.result_high)?;
@@ -659,7 +731,7 @@ pub fn keccak(
         });
     }
 
-    let gas_cost = n_rounds as u64 * constants::KECCAK_ROUND_COST_GAS_COST;
+    let gas_cost = n_rounds as u64 * syscall_handler.gas_costs().keccak_round_cost;
     if gas_cost > *remaining_gas {
         let out_of_gas_error =
             StarkFelt::try_from(OUT_OF_GAS_ERROR).map_err(SyscallExecutionError::from)?;
@@ -144,6 +144,8 @@ pub struct SecpOptionalEcPointResponse {
 }
 
 impl SyscallResponse for SecpOptionalEcPointResponse {
+    const SIZE: usize = 2;
+
     fn write(self, vm: &mut VirtualMachine, ptr: &mut Relocatable) -> WriteResponseResult {
         match self.optional_ec_point_id {
             Some(id) => {
@@ -162,6 +164,8 @@ impl SyscallResponse for SecpOptionalEcPointResponse {
 }
 
 impl SyscallResponse for SecpOpRespone {
+    const SIZE: usize = 1;
+
     fn write(self, vm: &mut VirtualMachine, ptr: &mut Relocatable) -> WriteResponseResult {
         write_maybe_relocatable(vm, ptr, self.ec_point_id)?;
         Ok(())
@@ -177,6 +181,8 @@ pub struct SecpAddRequest {
 }
 
 impl SyscallRequest for SecpAddRequest {
+    const SIZE: usize = 2;
+
     fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<SecpAddRequest> {
         Ok(SecpAddRequest { lhs_id: felt_from_ptr(vm, ptr)?, rhs_id: felt_from_ptr(vm, ptr)? })
     }
@@ -212,7 +218,16 @@ pub struct SecpGetPointFromXRequest {
     y_parity: bool,
 }
 
+#[cfg(test)]
+impl SecpGetPointFromXRequest {
+    pub fn new(x: BigUint, y_parity: bool) -> Self {
+        Self { x, y_parity }
+    }
+}
+
 impl SyscallRequest for SecpGetPointFromXRequest {
+    const SIZE: usize = 3;
+
     fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<SecpGetPointFromXRequest> {
         let x = SierraU256::from_memory(vm, ptr)?.to_biguint();
 
@@ -249,6 +264,8 @@ pub struct SecpGetXyRequest {
 }
 
 impl SyscallRequest for SecpGetXyRequest {
+    const SIZE: usize = 1;
+
     fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<SecpGetXyRequest> {
         Ok(SecpGetXyRequest { ec_point_id: felt_from_ptr(vm, ptr)? })
     }
@@ -257,6 +274,8 @@ impl SyscallRequest for SecpGetXyRequest {
 type SecpGetXyResponse = EcPointCoordinates;
 
 impl SyscallResponse for SecpGetXyResponse {
+    const SIZE: usize = 4;
+
     fn write(self, vm: &mut VirtualMachine, ptr: &mut Relocatable) -> WriteResponseResult {
         write_u256(vm, ptr, self.x)?;
         write_u256(vm, ptr, self.y)?;
@@ -291,6 +310,8 @@ pub struct SecpMulRequest {
 }
 
 impl SyscallRequest for SecpMulRequest {
+    const SIZE: usize = 3;
+
     fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<SecpMulRequest> {
         let ec_point_id = felt_from_ptr(vm, ptr)?;
         let multiplier = SierraU256::from_memory(vm, ptr)?.to_biguint();
@@ -323,6 +344,8 @@ pub fn secp256r1_mul(
 type SecpNewRequest = EcPointCoordinates;
 
 impl SyscallRequest for SecpNewRequest {
+    const SIZE: usize = 4;
+
     fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<SecpNewRequest> {
         let x = SierraU256::from_memory(vm, ptr)?.to_biguint();
         let y = SierraU256::from_memory(vm, ptr)?.to_biguint();
@@ -0,0 +1,145 @@
+//! Randomized tests that feed syscall request decoding with malformed memory; e.g., values of the
+//! wrong type, out-of-range felts, dangling pointers and holes. Decoding may fail, but must never
+//! panic, as the memory is written by (possibly malicious) contract code.
+
+use std::any::type_name;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use cairo_felt::Felt252;
+use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
+use cairo_vm::vm::vm_core::VirtualMachine;
+use num_traits::{Bounded, One};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::execution::deprecated_syscalls::DeprecatedSyscallSelector;
+use crate::execution::execution_utils::felt_to_stark_felt;
+use crate::execution::syscalls::hint_processor::{read_call_params, read_calldata};
+use crate::execution::syscalls::secp::{
+    EcPointCoordinates, SecpAddRequest, SecpGetPointFromXRequest, SecpGetXyRequest, SecpMulRequest,
+};
+use crate::execution::syscalls::{
+    CallContractRequest, DeployRequest, EmitEventRequest, EmptyRequest, GetBlockHashRequest,
+    KeccakRequest, LibraryCallRequest, ReplaceClassRequest, SendMessageToL1Request,
+    StorageReadRequest, StorageWriteRequest, SyscallRequest, SyscallRequestWrapper,
+};
+
+const N_ITERATIONS: u64 = 500;
+const N_SEGMENTS: usize = 4;
+const MAX_SEGMENT_SIZE: usize = 24;
+
+/// Returns a random memory value, biased towards values that are likely to hit edge cases.
+fn random_value(rng: &mut StdRng) -> MaybeRelocatable {
+    let felt = match rng.gen_range(0..10) {
+        0..=2 => Felt252::from(rng.gen_range(0_u8..8)),
+        3 => Felt252::from(rng.gen::<u64>()),
+        4 => Felt252::from(rng.gen::<u128>()),
+        5 => Felt252::from_bytes_be(&rng.gen::<[u8; 32]>()),
+        6 => match rng.gen_range(0..4) {
+            0 => Felt252::max_value(),
+            1 => Felt252::one() << 64_u32,
+            2 => Felt252::one() << 128_u32,
+            _ => Felt252::one() << 251_u32,
+        },
+        _ => {
+            // A pointer; possibly to a missing segment, or beyond the end of its segment.
+            let segment_index = rng.gen_range(-1..=N_SEGMENTS as isize);
+            let offset = rng.gen_range(0..MAX_SEGMENT_SIZE + 4);
+            return Relocatable::from((segment_index, offset)).into();
+        }
+    };
+
+    felt.into()
+}
+
+/// Returns the encoding of a random request field: a felt, or an array, in either the Cairo 1
+/// (start and end pointers) or the Cairo 0 (size and pointer) layout.
+fn random_field(rng: &mut StdRng) -> Vec<MaybeRelocatable> {
+    let data_segment = rng.gen_range(1..N_SEGMENTS as isize);
+    let start_offset = rng.gen_range(0..MAX_SEGMENT_SIZE);
+    // May exceed the segment.
+    let size = rng.gen_range(0..=MAX_SEGMENT_SIZE - start_offset + 2);
+    let start = Relocatable::from((data_segment, start_offset));
+    let end = Relocatable::from((data_segment, start_offset + size));
+    match rng.gen_range(0..4) {
+        0 => vec![start.into(), end.into()],
+        1 => vec![Felt252::from(size).into(), start.into()],
+        _ => vec![random_value(rng)],
+    }
+}
+
+/// Creates a VM whose memory holds a random request, and returns it along with a pointer to the
+/// request. The request is made of random fields, some of which are then corrupted (overwritten
+/// or removed); the arrays it points to are filled with random bytes.
+pub fn random_memory(rng: &mut StdRng) -> (VirtualMachine, Relocatable) {
+    let mut vm = VirtualMachine::new(false);
+    let request_ptr = vm.add_memory_segment();
+    for _ in 1..N_SEGMENTS {
+        let data_ptr = vm.add_memory_segment();
+        let data: Vec<MaybeRelocatable> =
+            (0..MAX_SEGMENT_SIZE).map(|_| Felt252::from(rng.gen::<u8>()).into()).collect();
+        vm.load_data(data_ptr, &data).unwrap();
+    }
+
+    let n_fields = rng.gen_range(0..8);
+    let request: Vec<MaybeRelocatable> = (0..n_fields).flat_map(|_| random_field(rng)).collect();
+    for (offset, value) in request.into_iter().enumerate() {
+        let value = match rng.gen_range(0..12) {
+            0 => continue,
+            1 => random_value(rng),
+            _ => value,
+        };
+        vm.insert_value((request_ptr + offset).unwrap(), value).unwrap();
+    }
+
+    (vm, request_ptr)
+}
+
+/// Runs `read` over many random memories, and asserts that it doesn't panic.
+pub fn fuzz<T>(name: &str, read: impl Fn(&VirtualMachine, &mut Relocatable) -> T) {
+    for seed in 0..N_ITERATIONS {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (vm, mut ptr) = random_memory(&mut rng);
+        let result = catch_unwind(AssertUnwindSafe(|| read(&vm, &mut ptr)));
+        assert!(result.is_ok(), "Reading {name} panicked; seed: {seed}.");
+    }
+}
+
+fn fuzz_request<T: SyscallRequest>() {
+    fuzz(type_name::<T>(), |vm, ptr| SyscallRequestWrapper::<T>::read(vm, ptr).is_ok());
+}
+
+#[test]
+fn test_fuzz_syscall_requests() {
+    fuzz_request::<CallContractRequest>();
+    fuzz_request::<DeployRequest>();
+    fuzz_request::<EmitEventRequest>();
+    fuzz_request::<EmptyRequest>();
+    fuzz_request::<GetBlockHashRequest>();
+    fuzz_request::<KeccakRequest>();
+    fuzz_request::<LibraryCallRequest>();
+    fuzz_request::<ReplaceClassRequest>();
+    fuzz_request::<SendMessageToL1Request>();
+    fuzz_request::<StorageReadRequest>();
+    fuzz_request::<StorageWriteRequest>();
+    fuzz_request::<EcPointCoordinates>();
+    fuzz_request::<SecpAddRequest>();
+    fuzz_request::<SecpGetPointFromXRequest>();
+    fuzz_request::<SecpGetXyRequest>();
+    fuzz_request::<SecpMulRequest>();
+}
+
+#[test]
+fn test_fuzz_calldata() {
+    fuzz("calldata", |vm, ptr| read_calldata(vm, ptr).is_ok());
+    fuzz("call params", |vm, ptr| read_call_params(vm, ptr).is_ok());
+}
+
+#[test]
+fn test_fuzz_syscall_selector() {
+    fuzz("syscall selector", |vm, ptr| {
+        vm.get_integer(*ptr)
+            .map(|selector| DeprecatedSyscallSelector::try_from(felt_to_stark_felt(&selector)))
+            .is_ok()
+    });
+}
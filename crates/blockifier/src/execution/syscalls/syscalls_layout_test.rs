@@ -0,0 +1,356 @@
+//! Tests that syscall requests and responses are laid out in memory as the OS expects: each request
+//! is decoded from its canonical encoding, and each response is encoded into it, occupying exactly
+//! `SIZE` cells (after the header).
+
+use std::fmt::Debug;
+
+use cairo_felt::Felt252;
+use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
+use cairo_vm::vm::vm_core::VirtualMachine;
+use num_bigint::BigUint;
+use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, EthAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{
+    Calldata, ContractAddressSalt, EventContent, EventData, EventKey, L2ToL1Payload,
+};
+use starknet_api::{calldata, contract_address, patricia_key, stark_felt};
+
+use crate::execution::call_info::MessageToL1;
+use crate::execution::execution_utils::ReadOnlySegment;
+use crate::execution::syscalls::custom_syscalls::CustomSyscallRequest;
+use crate::execution::syscalls::secp::{
+    EcPointCoordinates, SecpAddRequest, SecpGetPointFromXRequest, SecpGetXyRequest, SecpMulRequest,
+    SecpOpRespone, SecpOptionalEcPointResponse,
+};
+use crate::execution::syscalls::{
+    CallContractRequest, DeployRequest, DeployResponse, EmitEventRequest, EmptyRequest,
+    EmptyResponse, GetBlockHashRequest, GetBlockHashResponse, GetExecutionInfoResponse,
+    KeccakRequest, KeccakResponse, LibraryCallRequest, ReplaceClassRequest, SendMessageToL1Request,
+    SingleSegmentResponse, StorageReadRequest, StorageReadResponse, StorageWriteRequest,
+    SyscallRequest, SyscallRequestWrapper, SyscallResponse, SyscallResponseWrapper,
+    FAILURE_RESPONSE_BODY_SIZE, REQUEST_HEADER_SIZE, RESPONSE_HEADER_SIZE,
+};
+
+const GAS_COUNTER: u64 = 1000;
+
+fn felt(value: u64) -> MaybeRelocatable {
+    Felt252::from(value).into()
+}
+
+/// Loads the array into a new segment, and returns its encoding: its start and end pointers.
+fn array(vm: &mut VirtualMachine, values: &[u64]) -> Vec<MaybeRelocatable> {
+    let start = vm.add_memory_segment();
+    let end = vm.load_data(start, &values.iter().copied().map(felt).collect()).unwrap();
+    vec![start.into(), end.into()]
+}
+
+/// Decodes the request from its encoding, preceded by the gas counter, and asserts that it reads
+/// exactly the encoding.
+fn assert_request_round_trip<T: SyscallRequest + Debug + PartialEq>(
+    mut vm: VirtualMachine,
+    encoding: Vec<MaybeRelocatable>,
+    expected_request: T,
+) {
+    assert_eq!(encoding.len(), T::SIZE);
+    assert_eq!(SyscallRequestWrapper::<T>::SIZE, REQUEST_HEADER_SIZE - 1 + T::SIZE);
+
+    let request_ptr = vm.add_memory_segment();
+    let cells = [vec![felt(GAS_COUNTER)], encoding].concat();
+    vm.load_data(request_ptr, &cells).unwrap();
+
+    let mut ptr = request_ptr;
+    let SyscallRequestWrapper { gas_counter, request } =
+        SyscallRequestWrapper::<T>::read(&vm, &mut ptr).unwrap();
+    assert_eq!(gas_counter, GAS_COUNTER);
+    assert_eq!(request, expected_request);
+    assert_eq!((ptr - request_ptr).unwrap(), SyscallRequestWrapper::<T>::SIZE);
+}
+
+/// Encodes the response as a success, and asserts that it writes exactly the given encoding,
+/// preceded by the header.
+fn assert_response_layout<T: SyscallResponse>(
+    mut vm: VirtualMachine,
+    response: T,
+    expected_encoding: Vec<MaybeRelocatable>,
+) {
+    assert_eq!(expected_encoding.len(), T::SIZE);
+
+    let response = SyscallResponseWrapper::Success { gas_counter: GAS_COUNTER, response };
+    assert_eq!(response.size(), RESPONSE_HEADER_SIZE + T::SIZE);
+    let response_ptr = vm.add_memory_segment();
+    let mut ptr = response_ptr;
+    response.write(&mut vm, &mut ptr).unwrap();
+
+    assert_eq!((ptr - response_ptr).unwrap(), SyscallResponseWrapper::<T>::SIZE);
+    let expected_cells = [vec![felt(GAS_COUNTER), felt(0)], expected_encoding].concat();
+    assert_eq!(
+        vm.get_continuous_range(response_ptr, SyscallResponseWrapper::<T>::SIZE).unwrap(),
+        expected_cells
+    );
+}
+
+#[test]
+fn test_request_layouts() {
+    let vm = VirtualMachine::new(false);
+    assert_request_round_trip(vm, vec![], EmptyRequest);
+
+    let mut vm = VirtualMachine::new(false);
+    let calldata_encoding = array(&mut vm, &[3, 4]);
+    assert_request_round_trip(
+        vm,
+        [vec![felt(1), felt(2)], calldata_encoding].concat(),
+        CallContractRequest {
+            contract_address: contract_address!(1_u8),
+            function_selector: EntryPointSelector(stark_felt!(2_u8)),
+            calldata: calldata![stark_felt!(3_u8), stark_felt!(4_u8)],
+        },
+    );
+
+    let mut vm = VirtualMachine::new(false);
+    let calldata_encoding = array(&mut vm, &[3]);
+    assert_request_round_trip(
+        vm,
+        [vec![felt(1), felt(2)], calldata_encoding].concat(),
+        LibraryCallRequest {
+            class_hash: ClassHash(stark_felt!(1_u8)),
+            function_selector: EntryPointSelector(stark_felt!(2_u8)),
+            calldata: calldata![stark_felt!(3_u8)],
+        },
+    );
+
+    let mut vm = VirtualMachine::new(false);
+    let calldata_encoding = array(&mut vm, &[3]);
+    assert_request_round_trip(
+        vm,
+        [vec![felt(1), felt(2)], calldata_encoding, vec![felt(1)]].concat(),
+        DeployRequest {
+            class_hash: ClassHash(stark_felt!(1_u8)),
+            contract_address_salt: ContractAddressSalt(stark_felt!(2_u8)),
+            constructor_calldata: calldata![stark_felt!(3_u8)],
+            deploy_from_zero: true,
+        },
+    );
+
+    let mut vm = VirtualMachine::new(false);
+    let keys_encoding = array(&mut vm, &[1]);
+    let data_encoding = array(&mut vm, &[2, 3]);
+    assert_request_round_trip(
+        vm,
+        [keys_encoding, data_encoding].concat(),
+        EmitEventRequest {
+            content: EventContent {
+                keys: vec![EventKey(stark_felt!(1_u8))],
+                data: EventData(vec![stark_felt!(2_u8), stark_felt!(3_u8)]),
+            },
+        },
+    );
+
+    let vm = VirtualMachine::new(false);
+    assert_request_round_trip(
+        vm,
+        vec![felt(5)],
+        GetBlockHashRequest { block_number: BlockNumber(5) },
+    );
+
+    let vm = VirtualMachine::new(false);
+    assert_request_round_trip(
+        vm,
+        vec![felt(1)],
+        ReplaceClassRequest { class_hash: ClassHash(stark_felt!(1_u8)) },
+    );
+
+    let mut vm = VirtualMachine::new(false);
+    let payload_encoding = array(&mut vm, &[2]);
+    assert_request_round_trip(
+        vm,
+        [vec![felt(1)], payload_encoding].concat(),
+        SendMessageToL1Request {
+            message: MessageToL1 {
+                to_address: EthAddress::try_from(stark_felt!(1_u8)).unwrap(),
+                payload: L2ToL1Payload(vec![stark_felt!(2_u8)]),
+            },
+        },
+    );
+
+    let vm = VirtualMachine::new(false);
+    assert_request_round_trip(
+        vm,
+        vec![felt(0), felt(1)],
+        StorageReadRequest {
+            address_domain: stark_felt!(0_u8),
+            address: StorageKey(patricia_key!(1_u8)),
+        },
+    );
+
+    let vm = VirtualMachine::new(false);
+    assert_request_round_trip(
+        vm,
+        vec![felt(0), felt(1), felt(2)],
+        StorageWriteRequest {
+            address_domain: stark_felt!(0_u8),
+            address: StorageKey(patricia_key!(1_u8)),
+            value: stark_felt!(2_u8),
+        },
+    );
+
+    let mut vm = VirtualMachine::new(false);
+    let input_encoding = array(&mut vm, &[1, 2]);
+    let (Ok(input_start), Ok(input_end)) =
+        (Relocatable::try_from(&input_encoding[0]), Relocatable::try_from(&input_encoding[1]))
+    else {
+        panic!("The array encoding consists of pointers.");
+    };
+    assert_request_round_trip(vm, input_encoding, KeccakRequest { input_start, input_end });
+
+    let mut vm = VirtualMachine::new(false);
+    let input_encoding = array(&mut vm, &[1, 2]);
+    assert_request_round_trip(
+        vm,
+        input_encoding,
+        CustomSyscallRequest { input: vec![stark_felt!(1_u8), stark_felt!(2_u8)] },
+    );
+}
+
+#[test]
+fn test_secp_request_layouts() {
+    // A u256 is encoded as its low and high 128-bit words.
+    let u256 = |low: u64, high: u64| (BigUint::from(high) << 128) + BigUint::from(low);
+
+    let vm = VirtualMachine::new(false);
+    assert_request_round_trip(
+        vm,
+        vec![felt(1), felt(2), felt(3), felt(4)],
+        EcPointCoordinates { x: u256(1, 2), y: u256(3, 4) },
+    );
+
+    let vm = VirtualMachine::new(false);
+    assert_request_round_trip(
+        vm,
+        vec![felt(1), felt(2)],
+        SecpAddRequest { lhs_id: Felt252::from(1), rhs_id: Felt252::from(2) },
+    );
+
+    let vm = VirtualMachine::new(false);
+    assert_request_round_trip(
+        vm,
+        vec![felt(1), felt(2), felt(1)],
+        SecpGetPointFromXRequest::new(u256(1, 2), true),
+    );
+
+    let vm = VirtualMachine::new(false);
+    assert_request_round_trip(
+        vm,
+        vec![felt(1)],
+        SecpGetXyRequest { ec_point_id: Felt252::from(1) },
+    );
+
+    let vm = VirtualMachine::new(false);
+    assert_request_round_trip(
+        vm,
+        vec![felt(1), felt(2), felt(3)],
+        SecpMulRequest { ec_point_id: Felt252::from(1), multiplier: u256(2, 3) },
+    );
+}
+
+#[test]
+fn test_response_layouts() {
+    let vm = VirtualMachine::new(false);
+    assert_response_layout(vm, EmptyResponse, vec![]);
+
+    let mut vm = VirtualMachine::new(false);
+    let start_ptr = vm.add_memory_segment();
+    let segment = ReadOnlySegment { start_ptr, length: 3 };
+    assert_response_layout(
+        vm,
+        SingleSegmentResponse { segment },
+        vec![start_ptr.into(), (start_ptr + 3_usize).unwrap().into()],
+    );
+
+    let mut vm = VirtualMachine::new(false);
+    let start_ptr = vm.add_memory_segment();
+    assert_response_layout(
+        vm,
+        DeployResponse {
+            contract_address: contract_address!(1_u8),
+            constructor_retdata: ReadOnlySegment { start_ptr, length: 0 },
+        },
+        vec![felt(1), start_ptr.into(), start_ptr.into()],
+    );
+
+    let vm = VirtualMachine::new(false);
+    assert_response_layout(
+        vm,
+        GetBlockHashResponse { block_hash: BlockHash(stark_felt!(1_u8)) },
+        vec![felt(1)],
+    );
+
+    let mut vm = VirtualMachine::new(false);
+    let execution_info_ptr = vm.add_memory_segment();
+    assert_response_layout(
+        vm,
+        GetExecutionInfoResponse { execution_info_ptr },
+        vec![execution_info_ptr.into()],
+    );
+
+    let vm = VirtualMachine::new(false);
+    assert_response_layout(vm, StorageReadResponse { value: stark_felt!(1_u8) }, vec![felt(1)]);
+
+    let vm = VirtualMachine::new(false);
+    assert_response_layout(
+        vm,
+        KeccakResponse { result_low: Felt252::from(1), result_high: Felt252::from(2) },
+        vec![felt(1), felt(2)],
+    );
+
+    let vm = VirtualMachine::new(false);
+    assert_response_layout(
+        vm,
+        EcPointCoordinates { x: BigUint::from(1_u8), y: BigUint::from(2_u8) << 128 },
+        vec![felt(1), felt(0), felt(0), felt(2)],
+    );
+
+    let vm = VirtualMachine::new(false);
+    assert_response_layout(vm, SecpOpRespone { ec_point_id: 1 }, vec![felt(1)]);
+
+    // An option is encoded as its variant index, followed by its value (if any).
+    let vm = VirtualMachine::new(false);
+    assert_response_layout(
+        vm,
+        SecpOptionalEcPointResponse { optional_ec_point_id: Some(1) },
+        vec![felt(0), felt(1)],
+    );
+    let vm = VirtualMachine::new(false);
+    assert_response_layout(
+        vm,
+        SecpOptionalEcPointResponse { optional_ec_point_id: None },
+        vec![felt(1), felt(0)],
+    );
+}
+
+#[test]
+fn test_failure_response_layout() {
+    let mut vm = VirtualMachine::new(false);
+    let response = SyscallResponseWrapper::<StorageReadResponse>::Failure {
+        gas_counter: GAS_COUNTER,
+        error_data: vec![stark_felt!(1_u8), stark_felt!(2_u8)],
+    };
+    // Regardless of the size of a success response.
+    assert_eq!(response.size(), RESPONSE_HEADER_SIZE + FAILURE_RESPONSE_BODY_SIZE);
+
+    let response_ptr = vm.add_memory_segment();
+    let mut ptr = response_ptr;
+    response.write(&mut vm, &mut ptr).unwrap();
+    assert_eq!((ptr - response_ptr).unwrap(), RESPONSE_HEADER_SIZE + FAILURE_RESPONSE_BODY_SIZE);
+
+    let cells = vm.get_continuous_range(response_ptr, 4).unwrap();
+    assert_eq!(cells[..2], [felt(GAS_COUNTER), felt(1)]);
+    let (Ok(error_start), Ok(error_end)) =
+        (Relocatable::try_from(&cells[2]), Relocatable::try_from(&cells[3]))
+    else {
+        panic!("The error data is encoded as its start and end pointers.");
+    };
+    assert_eq!(vm.get_continuous_range(error_start, 2).unwrap(), [felt(1), felt(2)]);
+    assert_eq!((error_end - error_start).unwrap(), 2);
+}
@@ -3,6 +3,7 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use assert_matches::assert_matches;
 use cairo_felt::Felt252;
 use cairo_lang_utils::byte_array::BYTE_ARRAY_MAGIC;
+use cairo_vm::types::relocatable::Relocatable;
 use cairo_vm::vm::runners::builtin_runner::RANGE_CHECK_BUILTIN_NAME;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
 use itertools::concat;
@@ -12,6 +13,7 @@ use starknet_api::core::{
     calculate_contract_address, ChainId, ClassHash, ContractAddress, EthAddress, Nonce, PatriciaKey,
 };
 use starknet_api::data_availability::DataAvailabilityMode;
+use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::state::StorageKey;
 use starknet_api::transaction::{
@@ -30,12 +32,16 @@ use crate::execution::call_info::{
 };
 use crate::execution::common_hints::ExecutionMode;
 use crate::execution::contract_class::ContractClassV0;
-use crate::execution::entry_point::{CallEntryPoint, CallType};
+use crate::execution::entry_point::{
+    CallEntryPoint, CallType, EntryPointExecutionContext, ExecutionResources,
+};
 use crate::execution::errors::{EntryPointExecutionError, VirtualMachineExecutionError};
-use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
+use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt, ReadOnlySegments};
 use crate::execution::syscalls::hint_processor::{
+    execute_call_frame, SyscallExecutionError, SyscallHintProcessor,
     BLOCK_NUMBER_OUT_OF_RANGE_ERROR, L1_GAS, L2_GAS, OUT_OF_GAS_ERROR,
 };
+use crate::execution::syscalls::SyscallSelector;
 use crate::state::state_api::{State, StateReader};
 use crate::test_utils::cached_state::{create_deploy_test_state, create_test_state};
 use crate::test_utils::contracts::FeatureContract;
@@ -101,14 +107,30 @@ fn test_call_contract() {
         calldata,
         ..trivial_external_entry_point()
     };
+    let call_info = entry_point_call.execute_directly(&mut state).unwrap();
     assert_eq!(
-        entry_point_call.execute_directly(&mut state).unwrap().execution,
+        call_info.execution,
         CallExecution {
             retdata: retdata![stark_felt!(48_u8)],
             gas_consumed: REQUIRED_GAS_CALL_CONTRACT_TEST,
             ..CallExecution::default()
         }
     );
+
+    // Syscalls are counted per call, and summarized over the call tree.
+    assert_eq!(call_info.syscall_counter, HashMap::from([(SyscallSelector::CallContract, 1)]));
+    assert_eq!(
+        call_info.inner_calls[0].syscall_counter,
+        HashMap::from([(SyscallSelector::StorageRead, 1), (SyscallSelector::StorageWrite, 1)])
+    );
+    assert_eq!(
+        call_info.summarize_syscalls(),
+        HashMap::from([
+            (SyscallSelector::CallContract, 1),
+            (SyscallSelector::StorageRead, 1),
+            (SyscallSelector::StorageWrite, 1),
+        ])
+    );
 }
 
 #[test]
@@ -227,7 +249,7 @@ fn verify_compiler_version(contract: FeatureContract, expected_version: &str) {
     TransactionVersion::ONE,
     false,
     false;
-    "Validate execution mode: block info fields should be zeroed. Transaction V1.")]
+    "Validate execution mode: block info fields should be rounded and the sequencer address zeroed. Transaction V1.")]
 #[test_case(
     ExecutionMode::Execute,
     contract_address!(StarkFelt::try_from(TEST_SEQUENCER_ADDRESS).unwrap()),
@@ -241,7 +263,7 @@ fn verify_compiler_version(contract: FeatureContract, expected_version: &str) {
     TransactionVersion::THREE,
     false,
     false;
-    "Validate execution mode: block info fields should be zeroed. Transaction V3.")]
+    "Validate execution mode: block info fields should be rounded and the sequencer address zeroed. Transaction V3.")]
 #[test_case(
     ExecutionMode::Execute,
     contract_address!(StarkFelt::try_from(TEST_SEQUENCER_ADDRESS).unwrap()),
@@ -284,13 +306,20 @@ fn test_get_execution_info(
         BALANCE,
         &[(legacy_contract, 1), (test_contract, 1)],
     );
+    let (block_number, block_timestamp) = match execution_mode {
+        ExecutionMode::Execute => (CURRENT_BLOCK_NUMBER, CURRENT_BLOCK_TIMESTAMP),
+        // Rounded down to multiples of 100 blocks and of an hour.
+        ExecutionMode::Validate => {
+            (CURRENT_BLOCK_NUMBER / 100 * 100, CURRENT_BLOCK_TIMESTAMP / 3600 * 3600)
+        }
+    };
     let expected_block_info = [
-        stark_felt!(CURRENT_BLOCK_NUMBER),    // Block number.
-        stark_felt!(CURRENT_BLOCK_TIMESTAMP), // Block timestamp.
+        stark_felt!(block_number),    // Block number.
+        stark_felt!(block_timestamp), // Block timestamp.
         *sequencer_address.0.key(),
     ];
 
-    let (test_contract_address, expected_unsupported_fields) = if is_legacy {
+    let (test_contract_address, mut expected_unsupported_fields) = if is_legacy {
         verify_compiler_version(legacy_contract, "2.1.0");
         (legacy_contract.get_instance_address(0), vec![])
     } else {
@@ -370,6 +399,21 @@ fn test_get_execution_info(
                 StarkFelt::ZERO,                   // Max price per unit.
             ];
         }
+        // Paymasters are not supported yet, but their data is still exposed to contracts.
+        let paymaster_data = PaymasterData(vec![stark_felt!(7_u8), stark_felt!(8_u8)]);
+        let account_deployment_data = AccountDeploymentData(vec![stark_felt!(9_u8)]);
+        if !is_legacy {
+            expected_unsupported_fields = vec![
+                StarkFelt::ZERO,   // Tip.
+                stark_felt!(2_u8), // Length of paymaster data array.
+                stark_felt!(7_u8), // Paymaster data.
+                stark_felt!(8_u8), // Paymaster data.
+                StarkFelt::ZERO,   // Nonce DA.
+                StarkFelt::ZERO,   // Fee DA.
+                stark_felt!(1_u8), // Length of account deployment data array.
+                stark_felt!(9_u8), // Account deployment data.
+            ];
+        }
         account_tx_context = AccountTransactionContext::Current(CurrentAccountTransactionContext {
             common_fields: CommonAccountFields {
                 transaction_hash: tx_hash,
@@ -392,8 +436,8 @@ fn test_get_execution_info(
             tip: Tip::default(),
             nonce_data_availability_mode: DataAvailabilityMode::L1,
             fee_data_availability_mode: DataAvailabilityMode::L1,
-            paymaster_data: PaymasterData::default(),
-            account_deployment_data: AccountDeploymentData::default(),
+            paymaster_data,
+            account_deployment_data,
         });
     }
 
@@ -547,6 +591,8 @@ fn test_nested_library_call() {
         n_memory_holes: 1,
         builtin_instance_counter: HashMap::from([(RANGE_CHECK_BUILTIN_NAME.to_string(), 5)]),
     };
+    let storage_syscall_counter =
+        HashMap::from([(SyscallSelector::StorageRead, 1), (SyscallSelector::StorageWrite, 1)]);
     let nested_storage_call_info = CallInfo {
         call: nested_storage_entry_point,
         execution: CallExecution {
@@ -556,7 +602,9 @@ fn test_nested_library_call() {
         },
         vm_resources: storage_entry_point_vm_resources.clone(),
         storage_read_values: vec![stark_felt!(value + 1)],
+        storage_read_keys: vec![StorageKey(patricia_key!(key + 1))],
         accessed_storage_keys: HashSet::from([StorageKey(patricia_key!(key + 1))]),
+        syscall_counter: storage_syscall_counter.clone(),
         ..Default::default()
     };
     let library_call_vm_resources = VmExecutionResources {
@@ -573,6 +621,7 @@ fn test_nested_library_call() {
         },
         vm_resources: library_call_vm_resources,
         inner_calls: vec![nested_storage_call_info],
+        syscall_counter: HashMap::from([(SyscallSelector::LibraryCall, 1)]),
         ..Default::default()
     };
     let storage_call_info = CallInfo {
@@ -584,7 +633,9 @@ fn test_nested_library_call() {
         },
         vm_resources: storage_entry_point_vm_resources,
         storage_read_values: vec![stark_felt!(value)],
+        storage_read_keys: vec![StorageKey(patricia_key!(key))],
         accessed_storage_keys: HashSet::from([StorageKey(patricia_key!(key))]),
+        syscall_counter: storage_syscall_counter.clone(),
         ..Default::default()
     };
 
@@ -602,6 +653,7 @@ fn test_nested_library_call() {
         },
         vm_resources: main_call_vm_resources,
         inner_calls: vec![library_call_info, storage_call_info],
+        syscall_counter: HashMap::from([(SyscallSelector::LibraryCall, 2)]),
         ..Default::default()
     };
 
@@ -803,7 +855,15 @@ fn test_deploy(
     )
     .unwrap();
     let deploy_call = &entry_point_call.execute_directly(&mut state).unwrap().inner_calls[0];
+    // The constructor call info is uniform, whether or not the class has a constructor.
     assert_eq!(deploy_call.call.storage_address, contract_address);
+    assert_eq!(deploy_call.call.caller_address, contract_address!(TEST_CONTRACT_ADDRESS));
+    assert_eq!(deploy_call.call.class_hash, Some(class_hash));
+    assert_eq!(deploy_call.call.entry_point_type, EntryPointType::Constructor);
+    assert_eq!(
+        deploy_call.call.entry_point_selector,
+        selector_from_name(constants::CONSTRUCTOR_ENTRY_POINT_NAME)
+    );
     let mut retdata = retdata![];
     let gas_consumed = if constructor_calldata.0.is_empty() {
         0
@@ -818,6 +878,95 @@ fn test_deploy(
     assert_eq!(state.get_class_hash_at(contract_address).unwrap(), class_hash);
 }
 
+#[test]
+fn test_deploy_with_failing_constructor() {
+    let mut state = create_deploy_test_state();
+    // The constructor fails to deserialize its (missing) arguments.
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_deploy"),
+        calldata: calldata![
+            stark_felt!(TEST_CLASS_HASH),     // Class hash.
+            ContractAddressSalt::default().0, // Contract_address_salt.
+            stark_felt!(0_u8),                // Calldata length.
+            stark_felt!(0_u8)                 // deploy_from_zero.
+        ],
+        ..trivial_external_entry_point()
+    };
+    let error = entry_point_call.execute_directly(&mut state).unwrap_err().to_string();
+    assert!(error.contains("Failed to deserialize param #1"));
+
+    // The deployment is reverted with the constructor.
+    let contract_address = calculate_contract_address(
+        ContractAddressSalt::default(),
+        class_hash!(TEST_CLASS_HASH),
+        &calldata![],
+        contract_address!(TEST_CONTRACT_ADDRESS),
+    )
+    .unwrap();
+    assert_eq!(state.get_class_hash_at(contract_address).unwrap(), ClassHash::default());
+}
+
+/// Tests that a failed call frame is reverted (its state writes, and the orders of its events and
+/// messages), while a successful one is applied.
+#[test]
+fn test_execute_call_frame() {
+    let mut state = create_test_state();
+    let mut resources = ExecutionResources::default();
+    let block_context = BlockContext::create_for_testing();
+    let mut context = EntryPointExecutionContext::new_invoke(
+        &block_context,
+        &AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext::default()),
+        true,
+    )
+    .unwrap();
+    let hints = HashMap::new();
+    let mut syscall_handler = SyscallHintProcessor::new(
+        &mut state,
+        &mut resources,
+        &mut context,
+        Relocatable::from((0, 0)),
+        trivial_external_entry_point(),
+        &hints,
+        ReadOnlySegments::default(),
+    );
+
+    let (contract_address, key) =
+        (contract_address!(TEST_CONTRACT_ADDRESS), StorageKey(patricia_key!("0x10")));
+    let run_call_frame = |syscall_handler: &mut SyscallHintProcessor<'_>, failed: bool| {
+        execute_call_frame(syscall_handler, |state, _resources, context| {
+            state.set_storage_at(contract_address, key, stark_felt!(7_u8))?;
+            context.n_emitted_events += 2;
+            context.n_sent_messages_to_l1 += 1;
+            let retdata = retdata![stark_felt!(1_u8)];
+            Ok(CallInfo {
+                execution: CallExecution { retdata, failed, ..Default::default() },
+                ..Default::default()
+            })
+        })
+    };
+    let output_orders = |syscall_handler: &SyscallHintProcessor<'_>| {
+        (syscall_handler.context.n_emitted_events, syscall_handler.context.n_sent_messages_to_l1)
+    };
+
+    assert_matches!(
+        run_call_frame(&mut syscall_handler, true),
+        Err(SyscallExecutionError::SyscallError { error_data })
+        if error_data == vec![stark_felt!(1_u8)]
+    );
+    assert_eq!(output_orders(&syscall_handler), (0, 0));
+    assert_eq!(
+        syscall_handler.state.get_storage_at(contract_address, key).unwrap(),
+        StarkFelt::ZERO
+    );
+
+    run_call_frame(&mut syscall_handler, false).unwrap();
+    assert_eq!(output_orders(&syscall_handler), (2, 1));
+    assert_eq!(
+        syscall_handler.state.get_storage_at(contract_address, key).unwrap(),
+        stark_felt!(7_u8)
+    );
+}
+
 #[test]
 fn test_out_of_gas() {
     let mut state = create_test_state();
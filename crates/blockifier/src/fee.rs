@@ -1,7 +1,13 @@
 pub mod actual_cost;
 pub mod eth_gas_constants;
 pub mod fee_checks;
+pub mod fee_model;
+pub mod fee_transfer_delegate;
 pub mod fee_utils;
+pub mod gas_price;
 pub mod gas_usage;
 pub mod os_resources;
 pub mod os_usage;
+pub mod rounding;
+pub mod sequencer_fee;
+pub mod vm_resource_costs;
@@ -5,10 +5,12 @@ use crate::abi::constants as abi_constants;
 use crate::block_context::BlockContext;
 use crate::execution::call_info::CallInfo;
 use crate::execution::entry_point::ExecutionResources;
-use crate::state::cached_state::{CachedState, StateChanges, StateChangesCount};
+use crate::fee::fee_utils::calculate_tx_gas_vector;
+use crate::state::cached_state::{CachedState, StateChangesKeys};
 use crate::state::state_api::{StateReader, StateResult};
 use crate::transaction::objects::{
-    AccountTransactionContext, HasRelatedFeeType, ResourcesMapping, TransactionExecutionResult,
+    AccountTransactionContext, GasVector, HasRelatedFeeType, ResourcesMapping,
+    TransactionExecutionResult,
 };
 use crate::transaction::transaction_types::TransactionType;
 use crate::transaction::transaction_utils::{calculate_l1_gas_usage, calculate_tx_resources};
@@ -18,6 +20,7 @@ use crate::transaction::transaction_utils::{calculate_l1_gas_usage, calculate_tx
 pub struct ActualCost {
     pub actual_fee: Fee,
     pub actual_resources: ResourcesMapping,
+    pub actual_gas: GasVector,
 }
 
 impl ActualCost {
@@ -40,7 +43,8 @@ pub struct ActualCostBuilder<'a> {
     pub block_context: BlockContext,
     validate_call_info: Option<&'a CallInfo>,
     execute_call_info: Option<&'a CallInfo>,
-    state_changes: StateChanges,
+    // Only the keys of the state changes are required for pricing.
+    state_changes_keys: StateChangesKeys,
     sender_address: Option<ContractAddress>,
     l1_payload_size: Option<usize>,
     n_reverted_steps: usize,
@@ -60,7 +64,7 @@ impl<'a> ActualCostBuilder<'a> {
             tx_type,
             validate_call_info: None,
             execute_call_info: None,
-            state_changes: StateChanges::default(),
+            state_changes_keys: StateChangesKeys::default(),
             l1_payload_size: None,
             n_reverted_steps: 0,
         }
@@ -101,7 +105,7 @@ impl<'a> ActualCostBuilder<'a> {
 
         let new_state_changes = state
             .get_actual_state_changes_for_fee_charge(fee_token_address, self.sender_address)?;
-        self.state_changes = StateChanges::merge(vec![self.state_changes, new_state_changes]);
+        self.state_changes_keys.extend(new_state_changes.into_keys());
         Ok(self)
     }
 
@@ -123,7 +127,7 @@ impl<'a> ActualCostBuilder<'a> {
         execution_resources: &ExecutionResources,
         n_reverted_steps: usize,
     ) -> TransactionExecutionResult<ActualCost> {
-        let state_changes_count = StateChangesCount::from(&self.state_changes);
+        let state_changes_count = self.state_changes_keys.count();
         let non_optional_call_infos =
             self.validate_call_info.into_iter().chain(self.execute_call_info);
         let l1_gas_usage = calculate_l1_gas_usage(
@@ -138,15 +142,13 @@ impl<'a> ActualCostBuilder<'a> {
         *actual_resources.0.get_mut(&abi_constants::N_STEPS_RESOURCE.to_string()).unwrap() +=
             n_reverted_steps;
 
-        let actual_fee = if self.account_tx_context.enforce_fee()?
-        // L1 handler transactions are not charged an L2 fee but it is compared to the L1 fee.
-            || self.tx_type == TransactionType::L1Handler
-        {
-            self.account_tx_context.calculate_tx_fee(&actual_resources, &self.block_context)?
-        } else {
-            Fee(0)
-        };
+        let actual_gas = calculate_tx_gas_vector(&actual_resources, &self.block_context)?;
+        // The fee is reported even if it is not enforced (i.e., a zero max fee), in which case it
+        // is not charged. L1 handler transactions are not charged an L2 fee but it is compared to
+        // the L1 fee.
+        let actual_fee =
+            self.account_tx_context.calculate_tx_fee(&actual_resources, &self.block_context)?;
 
-        Ok(ActualCost { actual_fee, actual_resources })
+        Ok(ActualCost { actual_fee, actual_resources, actual_gas })
     }
 }
@@ -4,17 +4,19 @@ use thiserror::Error;
 
 use crate::block_context::BlockContext;
 use crate::fee::actual_cost::ActualCost;
-use crate::fee::fee_utils::{
-    calculate_tx_l1_gas_usage, get_balance_and_if_covers_fee, get_fee_by_l1_gas_usage,
-};
+use crate::fee::fee_utils::{calculate_tx_gas_vector, get_balance_and_if_covers_fee};
 use crate::state::state_api::StateReader;
 use crate::transaction::errors::TransactionExecutionError;
-use crate::transaction::objects::{AccountTransactionContext, FeeType, TransactionExecutionResult};
+use crate::transaction::objects::{
+    AccountTransactionContext, GasVector, HasRelatedFeeType, TransactionExecutionResult,
+};
 
 #[derive(Clone, Copy, Debug, Error)]
 pub enum FeeCheckError {
     #[error("Insufficient max L1 gas: max amount: {max_amount}, actual used: {actual_amount}.")]
     MaxL1GasAmountExceeded { max_amount: u128, actual_amount: u128 },
+    #[error("Insufficient max L2 gas: max amount: {max_amount}, actual used: {actual_amount}.")]
+    MaxL2GasAmountExceeded { max_amount: u128, actual_amount: u128 },
     #[error("Insufficient max fee: max fee: {max_fee:?}, actual fee: {actual_fee:?}")]
     MaxFeeExceeded { max_fee: Fee, actual_fee: Fee },
     #[error(
@@ -67,16 +69,26 @@ impl FeeCheckReport {
             // If the error is resource overdraft, the recommended fee is the resource bounds.
             // If the transaction passed pre-validation checks (i.e. balance initially covered the
             // resource bounds), the sender should be able to pay this fee.
-            FeeCheckError::MaxFeeExceeded { .. } | FeeCheckError::MaxL1GasAmountExceeded { .. } => {
-                match account_tx_context {
-                    AccountTransactionContext::Current(context) => get_fee_by_l1_gas_usage(
+            FeeCheckError::MaxFeeExceeded { .. }
+            | FeeCheckError::MaxL1GasAmountExceeded { .. }
+            | FeeCheckError::MaxL2GasAmountExceeded { .. } => match account_tx_context {
+                AccountTransactionContext::Current(context) => {
+                    let mut max_gas = GasVector {
+                        l1_gas: context.l1_resource_bounds()?.max_amount.into(),
+                        ..Default::default()
+                    };
+                    if block_context.versioned_constants.resource_gas_mode.charges_l2_gas() {
+                        max_gas.l2_gas = context.l2_resource_bounds().max_amount.into();
+                    }
+                    block_context.fee_model().calculate_fee(
                         block_context,
-                        context.l1_resource_bounds()?.max_amount as u128,
-                        &FeeType::Strk,
-                    ),
-                    AccountTransactionContext::Deprecated(context) => context.max_fee,
+                        &max_gas,
+                        &account_tx_context.fee_type(),
+                        account_tx_context.charged_tip(block_context),
+                    )?
                 }
-            }
+                AccountTransactionContext::Deprecated(context) => context.max_fee,
+            },
         };
         Ok(Self { recommended_fee, error: Some(error) })
     }
@@ -88,20 +100,29 @@ impl FeeCheckReport {
         account_tx_context: &AccountTransactionContext,
         actual_cost: &ActualCost,
     ) -> TransactionExecutionResult<()> {
-        let ActualCost { actual_fee, actual_resources } = actual_cost;
+        let ActualCost { actual_fee, actual_resources, .. } = actual_cost;
 
         // First, compare the actual resources used against the upper bound(s) defined by the
         // sender.
         match account_tx_context {
             AccountTransactionContext::Current(context) => {
+                let actual_gas = calculate_tx_gas_vector(actual_resources, block_context)?;
+
                 // Check L1 gas limit.
                 let max_l1_gas = context.l1_resource_bounds()?.max_amount as u128;
-                let actual_used_l1_gas =
-                    calculate_tx_l1_gas_usage(actual_resources, block_context)?;
-                if actual_used_l1_gas > max_l1_gas {
+                if actual_gas.l1_gas > max_l1_gas {
                     return Err(FeeCheckError::MaxL1GasAmountExceeded {
                         max_amount: max_l1_gas,
-                        actual_amount: actual_used_l1_gas,
+                        actual_amount: actual_gas.l1_gas,
+                    })?;
+                }
+
+                // Check L2 gas limit; no L2 gas is used unless it is charged.
+                let max_l2_gas = context.l2_resource_bounds().max_amount as u128;
+                if actual_gas.l2_gas > max_l2_gas {
+                    return Err(FeeCheckError::MaxL2GasAmountExceeded {
+                        max_amount: max_l2_gas,
+                        actual_amount: actual_gas.l2_gas,
                     })?;
                 }
             }
@@ -0,0 +1,45 @@
+use std::fmt::Debug;
+
+use starknet_api::transaction::{Fee, Tip};
+
+use crate::block_context::BlockContext;
+use crate::fee::fee_utils::get_fee_by_gas_vector;
+use crate::transaction::objects::{FeeType, GasVector, TransactionFeeResult};
+
+#[cfg(test)]
+#[path = "fee_model_test.rs"]
+pub mod test;
+
+/// Prices the gas consumed by a transaction; set through `BlockContext::fee_model`. Allows
+/// embedders (e.g., appchains) to charge by an alternative formula, while reusing the resource
+/// accounting, which converts the resources of a transaction into gas.
+///
+/// The model is applied to actual fees and to the minimal fees required of transactions; bounds of
+/// V3 transactions are still checked against the per-gas prices of the block context.
+pub trait FeeModel: Debug + Send + Sync {
+    /// Returns the fee of the given gas, paid in the fee token of the given type, with the given
+    /// tip per unit of L1 gas.
+    fn calculate_fee(
+        &self,
+        block_context: &BlockContext,
+        gas_vector: &GasVector,
+        fee_type: &FeeType,
+        tip: Tip,
+    ) -> TransactionFeeResult<Fee>;
+}
+
+/// The Starknet formula: each kind of gas is charged by its price in the block context.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StarknetFeeModel;
+
+impl FeeModel for StarknetFeeModel {
+    fn calculate_fee(
+        &self,
+        block_context: &BlockContext,
+        gas_vector: &GasVector,
+        fee_type: &FeeType,
+        tip: Tip,
+    ) -> TransactionFeeResult<Fee> {
+        get_fee_by_gas_vector(block_context, gas_vector, fee_type, tip)
+    }
+}
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
+use rstest::rstest;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+
+use super::*;
+use crate::invoke_tx_args;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_calldata, CairoVersion, BALANCE, MAX_FEE};
+use crate::transaction::errors::{
+    TransactionExecutionError, TransactionFeeError, TransactionPreValidationError,
+};
+use crate::transaction::objects::{TransactionExecutionInfo, TransactionExecutionResult};
+use crate::transaction::test_utils::{block_context, run_invoke_tx};
+
+const FLAT_FEE: Fee = Fee(1000);
+
+/// Charges the same fee for every transaction.
+#[derive(Debug)]
+struct FlatFeeModel;
+
+impl FeeModel for FlatFeeModel {
+    fn calculate_fee(
+        &self,
+        _block_context: &BlockContext,
+        _gas_vector: &GasVector,
+        _fee_type: &FeeType,
+        _tip: Tip,
+    ) -> TransactionFeeResult<Fee> {
+        Ok(FLAT_FEE)
+    }
+}
+
+fn run_tx(
+    block_context: &BlockContext,
+    max_fee: Fee,
+) -> TransactionExecutionResult<TransactionExecutionInfo> {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let mut state = test_state(block_context, BALANCE, &[(account, 1), (test_contract, 1)]);
+
+    run_invoke_tx(
+        &mut state,
+        block_context,
+        invoke_tx_args! {
+            max_fee,
+            sender_address: account.get_instance_address(0),
+            calldata: create_calldata(
+                test_contract.get_instance_address(0),
+                "return_result",
+                &[stark_felt!(2_u8)],
+            ),
+        },
+    )
+}
+
+#[rstest]
+fn test_default_fee_model(block_context: BlockContext) {
+    let gas_vector = GasVector { l1_gas: 3, l1_data_gas: 0, l2_gas: 5 };
+    assert_eq!(
+        block_context
+            .fee_model()
+            .calculate_fee(&block_context, &gas_vector, &FeeType::Eth, Tip(2))
+            .unwrap(),
+        get_fee_by_gas_vector(&block_context, &gas_vector, &FeeType::Eth, Tip(2)).unwrap()
+    );
+}
+
+#[rstest]
+fn test_custom_fee_model(block_context: BlockContext) {
+    let block_context = BlockContext { fee_model: Some(Arc::new(FlatFeeModel)), ..block_context };
+
+    let execution_info = run_tx(&block_context, Fee(MAX_FEE)).unwrap();
+    assert_eq!(execution_info.actual_fee, FLAT_FEE);
+    // The transferred amount is the fee of the model.
+    assert_eq!(
+        execution_info.fee_transfer_call_info.unwrap().call.calldata.0[1],
+        StarkFelt::from(FLAT_FEE.0)
+    );
+
+    // The minimal fee of a transaction is the fee of the model as well.
+    assert_matches!(
+        run_tx(&block_context, Fee(FLAT_FEE.0 - 1)),
+        Err(TransactionExecutionError::TransactionPreValidationError(
+            TransactionPreValidationError::TransactionFeeError(
+                TransactionFeeError::MaxFeeTooLow { min_fee, .. }
+            )
+        )) if min_fee == FLAT_FEE
+    );
+}
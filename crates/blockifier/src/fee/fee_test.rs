@@ -5,12 +5,22 @@ use cairo_vm::vm::runners::builtin_runner::{
     BITWISE_BUILTIN_NAME, HASH_BUILTIN_NAME, POSEIDON_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME,
     SIGNATURE_BUILTIN_NAME,
 };
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
+use starknet_api::transaction::Fee;
 
 use crate::abi::constants;
 use crate::block_context::BlockContext;
-use crate::fee::fee_utils::calculate_l1_gas_by_vm_usage;
+use crate::execution::entry_point::ExecutionResources;
+use crate::fee::fee_utils::{
+    calculate_l1_gas_by_vm_usage, calculate_tx_fee, calculate_tx_gas_vector,
+    get_fee_by_l1_gas_usage,
+};
+use crate::fee::vm_resource_costs::Ratio;
 use crate::transaction::errors::TransactionFeeError;
-use crate::transaction::objects::ResourcesMapping;
+use crate::transaction::objects::{FeeType, GasVector, ResourcesMapping};
+use crate::transaction::transaction_types::TransactionType;
+use crate::transaction::transaction_utils::calculate_tx_resources;
+use crate::versioned_constants::ResourceGasMode;
 
 fn get_vm_resource_usage() -> ResourcesMapping {
     ResourcesMapping(HashMap::from([
@@ -32,7 +42,7 @@ fn test_calculate_l1_gas_by_vm_usage() {
     // Verify calculation - in our case, n_steps is the heaviest resource.
     let l1_gas_by_vm_usage = vm_resource_usage.0.get(constants::N_STEPS_RESOURCE).unwrap();
     assert_eq!(
-        *l1_gas_by_vm_usage as f64,
+        Ratio::from(*l1_gas_by_vm_usage as u128),
         calculate_l1_gas_by_vm_usage(&block_context, &vm_resource_usage).unwrap()
     );
 
@@ -44,3 +54,89 @@ fn test_calculate_l1_gas_by_vm_usage() {
         calculate_l1_gas_by_vm_usage(&block_context, &invalid_vm_resource_usage).unwrap_err();
     assert_matches!(error, TransactionFeeError::CairoResourcesNotContainedInFeeCosts);
 }
+
+#[test]
+fn test_calculate_tx_gas_vector() {
+    let block_context = BlockContext::create_for_account_testing();
+    let l1_gas_usage = 1000;
+    let mut resources = get_vm_resource_usage();
+    resources.0.insert(constants::GAS_USAGE.to_string(), l1_gas_usage);
+
+    // All Cairo resources are converted to L1 gas; n_steps is the heaviest resource.
+    let n_steps = resources.0[constants::N_STEPS_RESOURCE];
+    assert_eq!(
+        calculate_tx_gas_vector(&resources, &block_context).unwrap(),
+        GasVector { l1_gas: (l1_gas_usage + n_steps) as u128, l1_data_gas: 0, l2_gas: 0 }
+    );
+}
+
+#[test]
+fn test_fee_overflow() {
+    let block_context = BlockContext::create_for_account_testing();
+    let gas_price = block_context.gas_prices.eth_l1_gas_price;
+
+    assert_eq!(
+        get_fee_by_l1_gas_usage(&block_context, 2, &FeeType::Eth).unwrap(),
+        Fee(2 * gas_price)
+    );
+    let error = get_fee_by_l1_gas_usage(&block_context, u128::MAX, &FeeType::Eth).unwrap_err();
+    assert_matches!(
+        error,
+        TransactionFeeError::FeeOverflow { gas_usage: u128::MAX, gas_price: price }
+        if price == gas_price
+    );
+}
+
+#[test]
+fn test_separate_l2_gas_mode() {
+    let l2_gas_per_l1_gas = 100;
+    let mut block_context = BlockContext::create_for_account_testing();
+    block_context.versioned_constants.resource_gas_mode =
+        ResourceGasMode::SeparateL2Gas { l2_gas_per_l1_gas };
+    let l1_gas_usage = 1000;
+    let mut resources = get_vm_resource_usage();
+    resources.0.insert(constants::GAS_USAGE.to_string(), l1_gas_usage);
+
+    // Only the Cairo resources are charged as L2 gas; n_steps is the heaviest resource.
+    let n_steps = resources.0[constants::N_STEPS_RESOURCE] as u128;
+    let gas_vector = calculate_tx_gas_vector(&resources, &block_context).unwrap();
+    assert_eq!(
+        gas_vector,
+        GasVector {
+            l1_gas: l1_gas_usage as u128,
+            l1_data_gas: 0,
+            l2_gas: n_steps * l2_gas_per_l1_gas
+        }
+    );
+
+    let gas_prices = &block_context.gas_prices;
+    assert_eq!(
+        calculate_tx_fee(&resources, &block_context, &FeeType::Strk).unwrap(),
+        Fee(gas_vector.l1_gas * gas_prices.strk_l1_gas_price
+            + gas_vector.l2_gas * gas_prices.strk_l2_gas_price)
+    );
+}
+
+#[test]
+fn test_memory_holes_charged_as_steps() {
+    let block_context = BlockContext::create_for_account_testing();
+    let tx_fee_with_memory_holes = |n_memory_holes: usize| {
+        let execution_resources = ExecutionResources {
+            vm_resources: VmExecutionResources {
+                n_steps: 1800,
+                n_memory_holes,
+                builtin_instance_counter: HashMap::default(),
+            },
+            ..Default::default()
+        };
+        let resources =
+            calculate_tx_resources(&execution_resources, 0, TransactionType::InvokeFunction)
+                .unwrap();
+        (resources.n_steps(), calculate_tx_fee(&resources, &block_context, &FeeType::Eth).unwrap())
+    };
+
+    let (n_steps, fee) = tx_fee_with_memory_holes(0);
+    let (n_steps_with_holes, fee_with_holes) = tx_fee_with_memory_holes(100);
+    assert_eq!(n_steps_with_holes, n_steps + 100);
+    assert!(fee_with_holes > fee);
+}
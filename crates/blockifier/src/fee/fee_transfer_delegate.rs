@@ -0,0 +1,39 @@
+use std::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+use starknet_api::core::ContractAddress;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::Fee;
+
+use crate::transaction::objects::FeeType;
+
+#[cfg(test)]
+#[path = "fee_transfer_delegate_test.rs"]
+pub mod test;
+
+/// An extension point of fee charging, set through `BlockContext::fee_transfer_delegate`; allows
+/// embedders whose fee mechanism is not a fee token contract (e.g., an appchain charging through
+/// its native balances) to keep the blockifier's fee metering.
+///
+/// Fees are computed and recorded (see `TransactionExecutionInfo::actual_fee`) as usual, but
+/// balances are read from the delegate, and no fee transfer call is executed; instead, the
+/// transfer is returned in `TransactionExecutionInfo::delegated_fee_transfer`, for the embedder to
+/// apply if (and only if) it commits the transaction.
+pub trait FeeTransferDelegate: Debug + Send + Sync {
+    /// Returns the balance of the given account, as a (low, high) u256 pair, in the fee token of
+    /// the given type.
+    fn get_fee_balance(
+        &self,
+        account_address: ContractAddress,
+        fee_type: &FeeType,
+    ) -> Result<(StarkFelt, StarkFelt), String>;
+}
+
+/// A fee transfer left to the embedder, as fees are charged through a `FeeTransferDelegate`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DelegatedFeeTransfer {
+    pub sender_address: ContractAddress,
+    pub sequencer_address: ContractAddress,
+    pub fee_type: FeeType,
+    pub amount: Fee,
+}
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
+use rstest::rstest;
+use starknet_api::stark_felt;
+
+use super::*;
+use crate::block_context::BlockContext;
+use crate::invoke_tx_args;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_calldata, CairoVersion, BALANCE, MAX_FEE};
+use crate::transaction::errors::{
+    TransactionExecutionError, TransactionFeeError, TransactionPreValidationError,
+};
+use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::test_utils::{block_context, run_invoke_tx};
+
+/// Holds a single balance for all accounts, or fails to read it.
+#[derive(Debug)]
+struct BalanceDelegate {
+    balance: Option<u128>,
+}
+
+impl FeeTransferDelegate for BalanceDelegate {
+    fn get_fee_balance(
+        &self,
+        _account_address: ContractAddress,
+        _fee_type: &FeeType,
+    ) -> Result<(StarkFelt, StarkFelt), String> {
+        match self.balance {
+            Some(balance) => Ok((StarkFelt::from(balance), StarkFelt::ZERO)),
+            None => Err("Balance unavailable.".to_string()),
+        }
+    }
+}
+
+fn run_with_delegate(
+    block_context: BlockContext,
+    delegate: BalanceDelegate,
+) -> (Result<TransactionExecutionInfo, TransactionExecutionError>, (StarkFelt, StarkFelt)) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let block_context =
+        BlockContext { fee_transfer_delegate: Some(Arc::new(delegate)), ..block_context };
+    // The fee token balance is irrelevant, and should be left untouched.
+    let mut state = test_state(&block_context, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let sender_address = account.get_instance_address(0);
+
+    let result = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            max_fee: Fee(MAX_FEE),
+            sender_address,
+            calldata: create_calldata(
+                test_contract.get_instance_address(0),
+                "return_result",
+                &[stark_felt!(2_u8)],
+            ),
+        },
+    );
+    let fee_token_balance = state
+        .get_fee_token_balance(sender_address, block_context.fee_token_address(&FeeType::Eth))
+        .unwrap();
+    (result, fee_token_balance)
+}
+
+#[rstest]
+fn test_delegated_fee_transfer(block_context: BlockContext) {
+    let sequencer_address = block_context.sequencer_address;
+    let delegate = BalanceDelegate { balance: Some(BALANCE) };
+    let (result, fee_token_balance) = run_with_delegate(block_context, delegate);
+
+    // The transfer is returned rather than applied.
+    let execution_info = result.unwrap();
+    assert!(execution_info.actual_fee.0 > 0);
+    assert!(execution_info.fee_transfer_call_info.is_none());
+    assert_eq!(fee_token_balance, (StarkFelt::from(BALANCE), StarkFelt::ZERO));
+    assert_eq!(
+        execution_info.delegated_fee_transfer,
+        Some(DelegatedFeeTransfer {
+            sender_address: FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0)
+                .get_instance_address(0),
+            sequencer_address,
+            fee_type: FeeType::Eth,
+            amount: execution_info.actual_fee,
+        })
+    );
+}
+
+#[rstest]
+fn test_delegated_fee_balance(block_context: BlockContext) {
+    let delegate = BalanceDelegate { balance: Some(MAX_FEE - 1) };
+    let (result, _) = run_with_delegate(block_context, delegate);
+
+    assert_matches!(
+        result,
+        Err(TransactionExecutionError::TransactionPreValidationError(
+            TransactionPreValidationError::TransactionFeeError(
+                TransactionFeeError::MaxFeeExceedsBalance { .. }
+            )
+        ))
+    );
+}
+
+#[rstest]
+fn test_delegated_fee_balance_failure(block_context: BlockContext) {
+    let delegate = BalanceDelegate { balance: None };
+    let (result, _) = run_with_delegate(block_context, delegate);
+
+    assert_matches!(
+        result,
+        Err(TransactionExecutionError::TransactionPreValidationError(
+            TransactionPreValidationError::TransactionFeeError(
+                TransactionFeeError::FeeTransferDelegateError(error)
+            )
+        )) if error == "Balance unavailable."
+    );
+}
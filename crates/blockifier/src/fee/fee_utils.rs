@@ -1,15 +1,19 @@
-use std::collections::HashSet;
+use std::str::FromStr;
 
 use starknet_api::hash::StarkFelt;
-use starknet_api::transaction::Fee;
+use starknet_api::transaction::{Fee, Tip};
 
 use crate::abi::constants;
 use crate::block_context::BlockContext;
+use crate::fee::rounding::Rounding;
+use crate::fee::vm_resource_costs::{Ratio, VmResourceKey};
 use crate::state::state_api::StateReader;
 use crate::transaction::errors::TransactionFeeError;
 use crate::transaction::objects::{
-    AccountTransactionContext, FeeType, HasRelatedFeeType, ResourcesMapping, TransactionFeeResult,
+    AccountTransactionContext, FeeType, GasVector, HasRelatedFeeType, ResourcesMapping,
+    TransactionFeeResult,
 };
+use crate::versioned_constants::ResourceGasMode;
 
 #[cfg(test)]
 #[path = "fee_test.rs"]
@@ -30,10 +34,13 @@ pub fn extract_l1_gas_and_vm_usage(resources: &ResourcesMapping) -> (usize, Reso
 pub fn calculate_l1_gas_by_vm_usage(
     block_context: &BlockContext,
     vm_resource_usage: &ResourcesMapping,
-) -> TransactionFeeResult<f64> {
+) -> TransactionFeeResult<Ratio> {
     let vm_resource_fee_costs = &block_context.vm_resource_fee_cost;
-    let vm_resource_names = HashSet::<&String>::from_iter(vm_resource_usage.0.keys());
-    if !vm_resource_names.is_subset(&HashSet::from_iter(vm_resource_fee_costs.keys())) {
+    let is_charged = |resource_name: &String| {
+        VmResourceKey::from_str(resource_name)
+            .is_ok_and(|key| vm_resource_fee_costs.get(key).is_some())
+    };
+    if !vm_resource_usage.0.keys().all(is_charged) {
         return Err(TransactionFeeError::CairoResourcesNotContainedInFeeCosts);
     };
 
@@ -41,33 +48,89 @@ pub fn calculate_l1_gas_by_vm_usage(
     let vm_l1_gas_usage = vm_resource_fee_costs
         .iter()
         .map(|(key, resource_val)| {
-            (*resource_val) * vm_resource_usage.0.get(key).cloned().unwrap_or_default() as f64
+            let usage = vm_resource_usage.0.get(key.as_str()).copied().unwrap_or_default();
+            resource_val.saturating_mul(usage as u128)
         })
-        .fold(f64::NAN, f64::max);
+        .max()
+        .unwrap_or(Ratio::ZERO);
 
     Ok(vm_l1_gas_usage)
 }
 
 /// Computes and returns the total L1 gas consumption.
 /// We add the l1_gas_usage (which may include, for example, the direct cost of L2-to-L1 messages)
-/// to the gas consumed by Cairo VM resource.
+/// to the gas consumed by Cairo VM resource, unless the latter is charged as L2 gas.
 pub fn calculate_tx_l1_gas_usage(
     resources: &ResourcesMapping,
     block_context: &BlockContext,
 ) -> TransactionFeeResult<u128> {
+    Ok(calculate_tx_gas_vector(resources, block_context)?.l1_gas)
+}
+
+/// Computes and returns the gas consumed by a transaction with the given resources, per gas kind.
+/// Fractional gas amounts are rounded up.
+pub fn calculate_tx_gas_vector(
+    resources: &ResourcesMapping,
+    block_context: &BlockContext,
+) -> TransactionFeeResult<GasVector> {
     let (l1_gas_usage, vm_resources) = extract_l1_gas_and_vm_usage(resources);
     let l1_gas_by_vm_usage = calculate_l1_gas_by_vm_usage(block_context, &vm_resources)?;
-    let total_l1_gas_usage = l1_gas_usage as f64 + l1_gas_by_vm_usage;
 
-    Ok(total_l1_gas_usage.ceil() as u128)
+    Ok(match block_context.versioned_constants.resource_gas_mode {
+        ResourceGasMode::AllResourcesAsL1Gas => GasVector {
+            l1_gas: (l1_gas_usage as u128).saturating_add(l1_gas_by_vm_usage.round(Rounding::Ceil)),
+            ..Default::default()
+        },
+        ResourceGasMode::SeparateL2Gas { l2_gas_per_l1_gas } => GasVector {
+            l1_gas: l1_gas_usage as u128,
+            l2_gas: l1_gas_by_vm_usage.mul_round(l2_gas_per_l1_gas, Rounding::Ceil),
+            ..Default::default()
+        },
+    })
 }
 
+/// Returns the fee of the given amount of L1 gas, at the given price; fails on overflow.
+pub fn calculate_fee_by_gas_price(
+    l1_gas_usage: u128,
+    gas_price: u128,
+) -> TransactionFeeResult<Fee> {
+    l1_gas_usage
+        .checked_mul(gas_price)
+        .map(Fee)
+        .ok_or(TransactionFeeError::FeeOverflow { gas_usage: l1_gas_usage, gas_price })
+}
+
+/// Returns the fee of the given amount of L1 gas, by the fee model of the block context.
 pub fn get_fee_by_l1_gas_usage(
     block_context: &BlockContext,
     l1_gas_usage: u128,
     fee_type: &FeeType,
-) -> Fee {
-    Fee(l1_gas_usage * block_context.gas_prices.get_by_fee_type(fee_type))
+) -> TransactionFeeResult<Fee> {
+    let gas_vector = GasVector { l1_gas: l1_gas_usage, ..Default::default() };
+    block_context.fee_model().calculate_fee(block_context, &gas_vector, fee_type, Tip::default())
+}
+
+/// Returns the fee of the given gas, at the current gas prices, with the given tip added to the
+/// L1 gas price; fails on overflow. This is the formula of `StarknetFeeModel`.
+pub fn get_fee_by_gas_vector(
+    block_context: &BlockContext,
+    gas_vector: &GasVector,
+    fee_type: &FeeType,
+    tip: Tip,
+) -> TransactionFeeResult<Fee> {
+    let l1_gas_price =
+        block_context.gas_prices.get_by_fee_type(fee_type).saturating_add(tip.0.into());
+    let l1_gas_fee = calculate_fee_by_gas_price(gas_vector.l1_gas, l1_gas_price)?;
+    let l2_gas_fee = calculate_fee_by_gas_price(
+        gas_vector.l2_gas,
+        block_context.gas_prices.get_l2_gas_price_by_fee_type(fee_type),
+    )?;
+
+    l1_gas_fee
+        .0
+        .checked_add(l2_gas_fee.0)
+        .map(Fee)
+        .ok_or(TransactionFeeError::FeeSumOverflow { l1_gas_fee, l2_gas_fee })
 }
 
 /// Calculates the fee that should be charged, given execution resources.
@@ -76,8 +139,19 @@ pub fn calculate_tx_fee(
     block_context: &BlockContext,
     fee_type: &FeeType,
 ) -> TransactionFeeResult<Fee> {
-    let l1_gas_usage = calculate_tx_l1_gas_usage(resources, block_context)?;
-    Ok(get_fee_by_l1_gas_usage(block_context, l1_gas_usage, fee_type))
+    calculate_tx_fee_with_tip(resources, block_context, fee_type, Tip::default())
+}
+
+/// Calculates the fee that should be charged, given execution resources, and a tip per unit of L1
+/// gas, by the fee model of the block context.
+pub fn calculate_tx_fee_with_tip(
+    resources: &ResourcesMapping,
+    block_context: &BlockContext,
+    fee_type: &FeeType,
+    tip: Tip,
+) -> TransactionFeeResult<Fee> {
+    let gas_vector = calculate_tx_gas_vector(resources, block_context)?;
+    block_context.fee_model().calculate_fee(block_context, &gas_vector, fee_type, tip)
 }
 
 /// Returns the current fee balance and a boolean indicating whether the balance covers the fee.
@@ -87,10 +161,15 @@ pub fn get_balance_and_if_covers_fee(
     block_context: &BlockContext,
     fee: Fee,
 ) -> TransactionFeeResult<(StarkFelt, StarkFelt, bool)> {
-    let (balance_low, balance_high) = state.get_fee_token_balance(
-        account_tx_context.sender_address(),
-        block_context.fee_token_address(&account_tx_context.fee_type()),
-    )?;
+    let sender_address = account_tx_context.sender_address();
+    let fee_type = account_tx_context.fee_type();
+    let (balance_low, balance_high) = match &block_context.fee_transfer_delegate {
+        Some(delegate) => delegate
+            .get_fee_balance(sender_address, &fee_type)
+            .map_err(TransactionFeeError::FeeTransferDelegateError)?,
+        None => state
+            .get_fee_token_balance(sender_address, block_context.fee_token_address(&fee_type))?,
+    };
     Ok((
         balance_low,
         balance_high,
@@ -107,21 +186,25 @@ pub fn verify_can_pay_committed_bounds(
     account_tx_context: &AccountTransactionContext,
     block_context: &BlockContext,
 ) -> TransactionFeeResult<()> {
-    let committed_fee = match account_tx_context {
-        AccountTransactionContext::Current(context) => {
-            let l1_bounds = context.l1_resource_bounds()?;
-            // Sender will not be charged by `max_price_per_unit`, but this check should not depend
-            // on the current gas price.
-            Fee(l1_bounds.max_amount as u128 * l1_bounds.max_price_per_unit)
-        }
-        AccountTransactionContext::Deprecated(context) => context.max_fee,
-    };
+    // Sender will not be charged by `max_price_per_unit`, but this check should not depend on the
+    // current gas price.
+    let committed_fee = account_tx_context.committed_fee(block_context)?;
     let (balance_low, balance_high, can_pay) =
         get_balance_and_if_covers_fee(state, account_tx_context, block_context, committed_fee)?;
     if can_pay {
         Ok(())
     } else {
         Err(match account_tx_context {
+            AccountTransactionContext::Current(context)
+                if block_context.versioned_constants.resource_gas_mode.charges_l2_gas() =>
+            {
+                TransactionFeeError::GasBoundsExceedBalance {
+                    l1_bounds: context.l1_resource_bounds()?,
+                    l2_bounds: context.l2_resource_bounds(),
+                    balance_low,
+                    balance_high,
+                }
+            }
             AccountTransactionContext::Current(context) => {
                 let l1_bounds = context.l1_resource_bounds()?;
                 TransactionFeeError::L1GasBoundsExceedBalance {
@@ -0,0 +1,80 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::fee::rounding::Rounding;
+use crate::utils::saturating_mul_div;
+
+#[cfg(test)]
+#[path = "gas_price_test.rs"]
+pub mod test;
+
+#[derive(Debug, Error)]
+pub enum GasPriceUpdateError {
+    #[error("The gas target must be positive.")]
+    ZeroGasTarget,
+    #[error("The max change denominator must be positive.")]
+    ZeroMaxChangeDenominator,
+}
+
+pub type GasPriceUpdateResult<T> = Result<T, GasPriceUpdateError>;
+
+/// Computes the gas price of the next block from the gas used in the previous one, EIP-1559 style:
+/// the price rises when the usage exceeds the target, and falls when it is below the target, by at
+/// most `1 / max_change_denominator` of the price per block.
+///
+/// The update is not applied by the blockifier; it is offered to sequencers, which set the gas
+/// prices of each block context.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GasPriceUpdater {
+    gas_target: u128,
+    max_change_denominator: u128,
+    min_gas_price: u128,
+}
+
+impl GasPriceUpdater {
+    pub fn new(
+        gas_target: u128,
+        max_change_denominator: u128,
+        min_gas_price: u128,
+    ) -> GasPriceUpdateResult<Self> {
+        if gas_target == 0 {
+            return Err(GasPriceUpdateError::ZeroGasTarget);
+        }
+        if max_change_denominator == 0 {
+            return Err(GasPriceUpdateError::ZeroMaxChangeDenominator);
+        }
+
+        Ok(Self { gas_target, max_change_denominator, min_gas_price })
+    }
+
+    /// The EIP-1559 parameters: a maximal change of 1/8 per block, and no minimal price.
+    pub fn with_gas_target(gas_target: u128) -> GasPriceUpdateResult<Self> {
+        Self::new(gas_target, 8, 0)
+    }
+
+    /// Returns the gas price of the next block, given the price and the gas used in the previous
+    /// one. An increase is of at least 1 (so that a price of 1 can rise), and the result is at
+    /// least the minimal price; it saturates at `u128::MAX`.
+    pub fn next_gas_price(&self, gas_price: u128, gas_used: u128) -> u128 {
+        let (gas_delta, is_increase) = match gas_used.cmp(&self.gas_target) {
+            Ordering::Equal => return gas_price.max(self.min_gas_price),
+            Ordering::Greater => (gas_used - self.gas_target, true),
+            Ordering::Less => (self.gas_target - gas_used, false),
+        };
+
+        // The divisor fits for any practical parameters (and saturates otherwise); the delta is
+        // bounded by `gas_price` when the price decreases.
+        let divisor = self.gas_target.saturating_mul(self.max_change_denominator);
+        let price_delta = saturating_mul_div(gas_price, gas_delta, divisor, Rounding::Floor)
+            .expect("The gas target and max change denominator are positive.");
+        let next_gas_price = if is_increase {
+            gas_price.saturating_add(price_delta.max(1))
+        } else {
+            gas_price - price_delta
+        };
+
+        next_gas_price.max(self.min_gas_price)
+    }
+}
@@ -0,0 +1,40 @@
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use crate::fee::gas_price::{GasPriceUpdateError, GasPriceUpdater};
+
+const GAS_TARGET: u128 = 1000;
+
+#[rstest]
+#[case::at_target(GAS_TARGET, 800)]
+#[case::full_block(2 * GAS_TARGET, 900)]
+#[case::above_full_block(4 * GAS_TARGET, 1100)]
+#[case::half_target(GAS_TARGET / 2, 750)]
+#[case::empty_block(0, 700)]
+fn test_next_gas_price(#[case] gas_used: u128, #[case] expected_gas_price: u128) {
+    let updater = GasPriceUpdater::with_gas_target(GAS_TARGET).unwrap();
+    assert_eq!(updater.next_gas_price(800, gas_used), expected_gas_price);
+}
+
+#[test]
+fn test_next_gas_price_bounds() {
+    let updater = GasPriceUpdater::new(GAS_TARGET, 8, 5).unwrap();
+    // A small price still rises.
+    assert_eq!(updater.next_gas_price(5, GAS_TARGET + 1), 6);
+    // The price does not fall below the minimum.
+    assert_eq!(updater.next_gas_price(5, 0), 5);
+    assert_eq!(updater.next_gas_price(0, GAS_TARGET), 5);
+    // No overflow in intermediate values, and saturation at the maximal price.
+    assert_eq!(updater.next_gas_price(u128::MAX, u128::MAX), u128::MAX);
+    assert_eq!(updater.next_gas_price(u128::MAX, 0), u128::MAX - u128::MAX / 8);
+}
+
+#[test]
+fn test_invalid_parameters() {
+    assert_matches!(GasPriceUpdater::new(0, 8, 0), Err(GasPriceUpdateError::ZeroGasTarget));
+    assert_matches!(
+        GasPriceUpdater::new(GAS_TARGET, 0, 0),
+        Err(GasPriceUpdateError::ZeroMaxChangeDenominator)
+    );
+}
@@ -1,8 +1,8 @@
 use std::collections::HashMap;
 
-use starknet_api::transaction::Fee;
+use starknet_api::transaction::{Fee, Tip};
 
-use super::fee_utils::{calculate_tx_l1_gas_usage, get_fee_by_l1_gas_usage};
+use super::fee_utils::calculate_tx_gas_vector;
 use crate::abi::constants;
 use crate::block_context::BlockContext;
 use crate::fee::eth_gas_constants;
@@ -10,7 +10,8 @@ use crate::fee::os_resources::OS_RESOURCES;
 use crate::state::cached_state::StateChangesCount;
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::objects::{
-    HasRelatedFeeType, ResourcesMapping, TransactionExecutionResult, TransactionPreValidationResult,
+    GasVector, HasRelatedFeeType, ResourcesMapping, TransactionExecutionResult,
+    TransactionPreValidationResult,
 };
 
 #[cfg(test)]
@@ -166,6 +167,14 @@ pub fn estimate_minimal_l1_gas(
     block_context: &BlockContext,
     tx: &AccountTransaction,
 ) -> TransactionPreValidationResult<u128> {
+    Ok(estimate_minimal_gas_vector(block_context, tx)?.l1_gas)
+}
+
+/// Return an estimated lower bound for the gas on an account transaction, per gas kind.
+pub fn estimate_minimal_gas_vector(
+    block_context: &BlockContext,
+    tx: &AccountTransaction,
+) -> TransactionPreValidationResult<GasVector> {
     // TODO(Dori, 1/8/2023): Give names to the constant VM step estimates and regression-test them.
     let os_steps_for_type = OS_RESOURCES.resources_for_tx_type(&tx.tx_type()).n_steps;
     let gas_cost: usize = match tx {
@@ -197,13 +206,18 @@ pub fn estimate_minimal_l1_gas(
         (constants::N_STEPS_RESOURCE.to_string(), os_steps_for_type),
     ]));
 
-    Ok(calculate_tx_l1_gas_usage(&resources, block_context)?)
+    Ok(calculate_tx_gas_vector(&resources, block_context)?)
 }
 
 pub fn estimate_minimal_fee(
     block_context: &BlockContext,
     tx: &AccountTransaction,
 ) -> TransactionExecutionResult<Fee> {
-    let estimated_minimal_l1_gas = estimate_minimal_l1_gas(block_context, tx)?;
-    Ok(get_fee_by_l1_gas_usage(block_context, estimated_minimal_l1_gas, &tx.fee_type()))
+    let estimated_minimal_gas = estimate_minimal_gas_vector(block_context, tx)?;
+    Ok(block_context.fee_model().calculate_fee(
+        block_context,
+        &estimated_minimal_gas,
+        &tx.fee_type(),
+        Tip::default(),
+    )?)
 }
@@ -0,0 +1,29 @@
+#[cfg(test)]
+#[path = "rounding_test.rs"]
+pub mod test;
+
+/// The direction in which a fractional amount is rounded to an integer, in fee computations.
+/// All fee-related rounding goes through this type, so that amounts match the OS (and any other
+/// implementation replaying the same transactions) to the unit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    /// Rounds up; used for charged amounts (e.g., gas derived from execution resources), which
+    /// must never be undercharged.
+    Ceil,
+    /// Rounds down; used for amounts derived from what was paid or committed (e.g., refunds and
+    /// the step limit derived from the max fee), which must never exceed it.
+    Floor,
+}
+
+impl Rounding {
+    /// Divides the given numerator by the given denominator, rounding the quotient.
+    /// Returns [None] if the denominator is zero.
+    pub fn div(self, numerator: u128, denominator: u128) -> Option<u128> {
+        let quotient = numerator.checked_div(denominator)?;
+        match self {
+            Self::Floor => Some(quotient),
+            Self::Ceil if numerator % denominator == 0 => Some(quotient),
+            Self::Ceil => Some(quotient + 1),
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use pretty_assertions::assert_eq;
+
+use crate::fee::rounding::Rounding;
+
+#[test]
+fn test_div() {
+    for numerator in 0..200_u128 {
+        for denominator in 1..20_u128 {
+            let floor = Rounding::Floor.div(numerator, denominator).unwrap();
+            let ceil = Rounding::Ceil.div(numerator, denominator).unwrap();
+
+            // The floor is the largest multiple not above the numerator, and the ceiling is the
+            // smallest multiple not below it.
+            assert!(floor * denominator <= numerator && numerator < (floor + 1) * denominator);
+            let ceil_multiple = ceil * denominator;
+            assert!(numerator <= ceil_multiple && ceil_multiple < numerator + denominator);
+            assert_eq!(ceil - floor, u128::from(numerator % denominator != 0));
+        }
+    }
+
+    assert_eq!(Rounding::Ceil.div(u128::MAX, 1), Some(u128::MAX));
+    assert_eq!(Rounding::Ceil.div(u128::MAX, 2), Some(u128::MAX / 2 + 1));
+    assert_eq!(Rounding::Floor.div(1, 0), None);
+    assert_eq!(Rounding::Ceil.div(1, 0), None);
+}
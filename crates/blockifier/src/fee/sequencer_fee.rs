@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+
+use num_bigint::BigUint;
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::Fee;
+
+use crate::abi::abi_utils::get_fee_token_var_address;
+use crate::abi::sierra_types::next_storage_key;
+use crate::block_context::BlockContext;
+use crate::execution::contract_class::ContractClass;
+use crate::state::state_api::{State, StateReader, StateResult};
+use crate::transaction::errors::TransactionFeeError;
+use crate::transaction::objects::{TransactionExecutionInfo, TransactionFeeResult};
+use crate::utils::{biguint_to_felt, felt_to_biguint};
+
+#[cfg(test)]
+#[path = "sequencer_fee_test.rs"]
+pub mod test;
+
+/// Returns the storage keys of the sequencer balance (low, high) in the fee token contracts.
+pub fn get_sequencer_balance_keys(block_context: &BlockContext) -> (StorageKey, StorageKey) {
+    let low_key = get_fee_token_var_address(block_context.sequencer_address);
+    let high_key = next_storage_key(&low_key)
+        .expect("Failed to compute the storage key of the sequencer balance (high).");
+
+    (low_key, high_key)
+}
+
+/// Returns whether the fee of a transaction sent by the given address is paid by the sequencer to
+/// itself.
+pub fn is_self_fee_transfer(block_context: &BlockContext, sender_address: ContractAddress) -> bool {
+    sender_address == block_context.sequencer_address
+}
+
+/// A state view in which the sequencer balance in the given fee token is zero and writes to it
+/// are dropped; fee transfers executed on top of it do not touch the sequencer balance, and thus
+/// do not conflict with one another.
+pub(crate) struct SequencerBalanceMask<'a> {
+    state: &'a mut dyn State,
+    fee_token_address: ContractAddress,
+    sequencer_balance_keys: [StorageKey; 2],
+}
+
+impl<'a> SequencerBalanceMask<'a> {
+    pub fn new(
+        state: &'a mut dyn State,
+        block_context: &BlockContext,
+        fee_token_address: ContractAddress,
+    ) -> Self {
+        let (low_key, high_key) = get_sequencer_balance_keys(block_context);
+        Self { state, fee_token_address, sequencer_balance_keys: [low_key, high_key] }
+    }
+
+    fn is_masked(&self, contract_address: ContractAddress, key: StorageKey) -> bool {
+        contract_address == self.fee_token_address && self.sequencer_balance_keys.contains(&key)
+    }
+}
+
+impl StateReader for SequencerBalanceMask<'_> {
+    fn get_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        if self.is_masked(contract_address, key) {
+            return Ok(StarkFelt::ZERO);
+        }
+        self.state.get_storage_at(contract_address, key)
+    }
+
+    fn get_nonce_at(&mut self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        self.state.get_nonce_at(contract_address)
+    }
+
+    fn get_class_hash_at(&mut self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.state.get_class_hash_at(contract_address)
+    }
+
+    fn get_compiled_contract_class(&mut self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.state.get_compiled_contract_class(class_hash)
+    }
+
+    fn get_compiled_class_hash(&mut self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.state.get_compiled_class_hash(class_hash)
+    }
+}
+
+impl State for SequencerBalanceMask<'_> {
+    fn set_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+        value: StarkFelt,
+    ) -> StateResult<()> {
+        if self.is_masked(contract_address, key) {
+            return Ok(());
+        }
+        self.state.set_storage_at(contract_address, key, value)
+    }
+
+    fn increment_nonce(&mut self, contract_address: ContractAddress) -> StateResult<()> {
+        self.state.increment_nonce(contract_address)
+    }
+
+    fn set_class_hash_at(
+        &mut self,
+        contract_address: ContractAddress,
+        class_hash: ClassHash,
+    ) -> StateResult<()> {
+        self.state.set_class_hash_at(contract_address, class_hash)
+    }
+
+    fn set_contract_class(
+        &mut self,
+        class_hash: ClassHash,
+        contract_class: ContractClass,
+    ) -> StateResult<()> {
+        self.state.set_contract_class(class_hash, contract_class)
+    }
+
+    fn set_compiled_class_hash(
+        &mut self,
+        class_hash: ClassHash,
+        compiled_class_hash: CompiledClassHash,
+    ) -> StateResult<()> {
+        self.state.set_compiled_class_hash(class_hash, compiled_class_hash)
+    }
+}
+
+/// Aggregates the fees of transactions executed in concurrency mode, whose fee transfers did not
+/// credit the sequencer, and credits them to the sequencer balance at once.
+///
+/// Transactions sent by the sequencer itself transfer their fee in full, and must observe its
+/// actual balance; `prepare_for_sender` should be called before executing each transaction.
+#[derive(Clone, Debug, Default)]
+pub struct DeferredSequencerFees {
+    // Fee token address to the total amount not yet credited.
+    pending_fees: HashMap<ContractAddress, BigUint>,
+}
+
+impl DeferredSequencerFees {
+    /// Registers the fee of the given executed transaction, and fills in the sequencer balance
+    /// read by its fee transfer, so that the execution info matches a sequential execution; fails
+    /// if the fee transfer did not read the sequencer balance.
+    pub fn add(
+        &mut self,
+        state: &mut dyn StateReader,
+        block_context: &BlockContext,
+        tx_execution_info: &mut TransactionExecutionInfo,
+    ) -> TransactionFeeResult<()> {
+        let Some(fee_transfer_call_info) = tx_execution_info.fee_transfer_call_info.as_mut() else {
+            return Ok(());
+        };
+        if is_self_fee_transfer(block_context, fee_transfer_call_info.call.caller_address) {
+            // The fee was transferred in full.
+            return Ok(());
+        }
+
+        let fee_token_address = fee_transfer_call_info.call.storage_address;
+        let (low_key, high_key) = get_sequencer_balance_keys(block_context);
+        let read_keys = &fee_transfer_call_info.storage_read_keys;
+        if read_keys.len() != fee_transfer_call_info.storage_read_values.len()
+            || [low_key, high_key].iter().any(|key| {
+                !read_keys.contains(key)
+                    || !fee_transfer_call_info.accessed_storage_keys.contains(key)
+            })
+        {
+            return Err(TransactionFeeError::SequencerBalanceReadMismatch { fee_token_address });
+        }
+
+        let (low, high) = self.sequencer_balance(state, block_context, fee_token_address)?;
+        for (key, value) in
+            read_keys.iter().zip(fee_transfer_call_info.storage_read_values.iter_mut())
+        {
+            if *key == low_key {
+                *value = low;
+            } else if *key == high_key {
+                *value = high;
+            }
+        }
+
+        *self.pending_fees.entry(fee_token_address).or_default() +=
+            BigUint::from(tx_execution_info.actual_fee.0);
+
+        Ok(())
+    }
+
+    /// Returns the sequencer balance in the given fee token, including the pending fees.
+    pub fn sequencer_balance(
+        &self,
+        state: &mut dyn StateReader,
+        block_context: &BlockContext,
+        fee_token_address: ContractAddress,
+    ) -> StateResult<(StarkFelt, StarkFelt)> {
+        let balance =
+            state.get_fee_token_balance(block_context.sequencer_address, fee_token_address)?;
+        match self.pending_fees.get(&fee_token_address) {
+            Some(pending_fee) => Ok(add_to_balance(balance, pending_fee)),
+            None => Ok(balance),
+        }
+    }
+
+    /// Credits the pending fees if the given sender is the sequencer, whose transaction must
+    /// observe its actual balance (e.g., to pay its fee); otherwise, does nothing.
+    pub fn prepare_for_sender(
+        &mut self,
+        state: &mut dyn State,
+        block_context: &BlockContext,
+        sender_address: ContractAddress,
+    ) -> StateResult<()> {
+        if is_self_fee_transfer(block_context, sender_address) {
+            self.credit_sequencer(state, block_context)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the pending fees to the sequencer balance; should be called at the end of the block,
+    /// and before executing transactions sent by the sequencer (see `prepare_for_sender`).
+    pub fn credit_sequencer(
+        &mut self,
+        state: &mut dyn State,
+        block_context: &BlockContext,
+    ) -> StateResult<()> {
+        let (low_key, high_key) = get_sequencer_balance_keys(block_context);
+        for (fee_token_address, pending_fee) in self.pending_fees.drain() {
+            let balance =
+                state.get_fee_token_balance(block_context.sequencer_address, fee_token_address)?;
+            let (low, high) = add_to_balance(balance, &pending_fee);
+            state.set_storage_at(fee_token_address, low_key, low)?;
+            state.set_storage_at(fee_token_address, high_key, high)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The total fees collected by the sequencer in a block, per fee token; lets the embedder credit
+/// the sequencer at once, or cross-check the fee transfers of the block.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CollectedFees {
+    // Fee token address to the total amount collected.
+    fees: HashMap<ContractAddress, Fee>,
+}
+
+impl CollectedFees {
+    /// Registers the fee of the given executed transaction, if it was charged.
+    pub fn add(&mut self, tx_execution_info: &TransactionExecutionInfo) {
+        let Some(fee_transfer_call_info) = &tx_execution_info.fee_transfer_call_info else {
+            return;
+        };
+
+        let fee_token_address = fee_transfer_call_info.call.storage_address;
+        let total_fee = self.fees.entry(fee_token_address).or_default();
+        // The sum of a block's fees is bounded by the total supply of the fee token.
+        *total_fee = Fee(total_fee.0.saturating_add(tx_execution_info.actual_fee.0));
+    }
+
+    /// Adds the fees collected by `other` (e.g., by a committed transaction) to `self`.
+    pub fn extend(&mut self, other: CollectedFees) {
+        for (fee_token_address, fee) in other.fees {
+            let total_fee = self.fees.entry(fee_token_address).or_default();
+            *total_fee = Fee(total_fee.0.saturating_add(fee.0));
+        }
+    }
+
+    /// Returns the total fee collected in the given fee token.
+    pub fn get(&self, fee_token_address: ContractAddress) -> Fee {
+        self.fees.get(&fee_token_address).copied().unwrap_or_default()
+    }
+
+    pub fn fees(&self) -> &HashMap<ContractAddress, Fee> {
+        &self.fees
+    }
+}
+
+/// Adds the given amount to a (low, high) u256 balance.
+fn add_to_balance((low, high): (StarkFelt, StarkFelt), amount: &BigUint) -> (StarkFelt, StarkFelt) {
+    let balance = (felt_to_biguint(high) << 128) + felt_to_biguint(low);
+    let new_balance = balance + amount;
+    let low_mask = (BigUint::from(1_u8) << 128) - 1_u8;
+    let to_felt = |value: BigUint| {
+        biguint_to_felt(value).expect("A 128-bit value should be smaller than the Stark prime.")
+    };
+
+    (to_felt(&new_balance & low_mask), to_felt(new_balance >> 128))
+}
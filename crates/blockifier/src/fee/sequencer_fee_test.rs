@@ -0,0 +1,241 @@
+use assert_matches::assert_matches;
+use rstest::rstest;
+use starknet_api::core::ContractAddress;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::Fee;
+
+use super::{CollectedFees, DeferredSequencerFees};
+use crate::abi::abi_utils::get_fee_token_var_address;
+use crate::block_context::BlockContext;
+use crate::execution::call_info::CallInfo;
+use crate::execution::entry_point::CallEntryPoint;
+use crate::invoke_tx_args;
+use crate::state::cached_state::{CachedState, CommitmentStateDiff};
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_calldata, CairoVersion, NonceManager, BALANCE, MAX_FEE};
+use crate::transaction::errors::TransactionFeeError;
+use crate::transaction::objects::{FeeType, TransactionExecutionInfo};
+use crate::transaction::test_utils::{block_context, run_invoke_tx};
+
+/// Executes an invoke transaction per sender, and returns the execution infos, the resulting
+/// state diff and the final sequencer balance.
+fn execute_txs(
+    block_context: &BlockContext,
+    senders: &[ContractAddress],
+) -> (Vec<TransactionExecutionInfo>, CommitmentStateDiff, (StarkFelt, StarkFelt)) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let mut state: CachedState<DictStateReader> =
+        test_state(block_context, BALANCE, &[(account, 2), (test_contract, 1)]);
+    let mut nonce_manager = NonceManager::default();
+    let mut deferred_fees = DeferredSequencerFees::default();
+
+    let mut execution_infos = vec![];
+    for &sender_address in senders {
+        if block_context.concurrency_mode {
+            deferred_fees.prepare_for_sender(&mut state, block_context, sender_address).unwrap();
+        }
+        let mut execution_info = run_invoke_tx(
+            &mut state,
+            block_context,
+            invoke_tx_args! {
+                max_fee: Fee(MAX_FEE),
+                sender_address,
+                calldata: create_calldata(
+                    test_contract.get_instance_address(0),
+                    "return_result",
+                    &[stark_felt!(2_u8)],
+                ),
+                nonce: nonce_manager.next(sender_address),
+            },
+        )
+        .unwrap();
+        if block_context.concurrency_mode {
+            deferred_fees.add(&mut state, block_context, &mut execution_info).unwrap();
+        }
+        execution_infos.push(execution_info);
+    }
+    deferred_fees.credit_sequencer(&mut state, block_context).unwrap();
+
+    let sequencer_balance = state
+        .get_fee_token_balance(
+            block_context.sequencer_address,
+            block_context.fee_token_address(&FeeType::Eth),
+        )
+        .unwrap();
+    (execution_infos, state.to_state_diff(), sequencer_balance)
+}
+
+#[rstest]
+#[case::sequencer_is_not_a_sender(false)]
+#[case::sequencer_is_a_sender(true)]
+fn test_deferred_fees_match_sequential_execution(
+    block_context: BlockContext,
+    #[case] sequencer_is_a_sender: bool,
+) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let (first_account, second_account) =
+        (account.get_instance_address(0), account.get_instance_address(1));
+    let mut block_context = block_context;
+    if sequencer_is_a_sender {
+        block_context.sequencer_address = second_account;
+    }
+    let senders = [first_account, second_account, first_account, second_account];
+
+    let sequential_result = execute_txs(&block_context, &senders);
+    let concurrent_result =
+        execute_txs(&BlockContext { concurrency_mode: true, ..block_context.clone() }, &senders);
+
+    assert_eq!(concurrent_result, sequential_result);
+    // The sequencer pays its own fees to itself.
+    let (execution_infos, _, (sequencer_balance_low, _)) = sequential_result;
+    let credited_fee: u128 = senders
+        .iter()
+        .zip(execution_infos)
+        .filter(|(&sender_address, _)| sender_address != block_context.sequencer_address)
+        .map(|(_, execution_info)| execution_info.actual_fee.0)
+        .sum();
+    let initial_balance = if sequencer_is_a_sender { BALANCE } else { 0 };
+    assert_eq!(sequencer_balance_low, StarkFelt::from(initial_balance + credited_fee));
+}
+
+#[rstest]
+fn test_fee_transfer_does_not_touch_sequencer_balance(block_context: BlockContext) {
+    let block_context = BlockContext { concurrency_mode: true, ..block_context };
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let mut state = test_state(&block_context, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let fee_token_address = block_context.fee_token_address(&FeeType::Eth);
+
+    let mut execution_info = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            max_fee: Fee(MAX_FEE),
+            sender_address: account.get_instance_address(0),
+            calldata: create_calldata(
+                test_contract.get_instance_address(0),
+                "return_result",
+                &[stark_felt!(2_u8)],
+            ),
+        },
+    )
+    .unwrap();
+    let actual_fee = execution_info.actual_fee;
+    let mut deferred_fees = DeferredSequencerFees::default();
+    deferred_fees.add(&mut state, &block_context, &mut execution_info).unwrap();
+
+    let sequencer_address = block_context.sequencer_address;
+    assert_eq!(
+        state.get_fee_token_balance(sequencer_address, fee_token_address).unwrap(),
+        (StarkFelt::ZERO, StarkFelt::ZERO)
+    );
+    assert_eq!(
+        deferred_fees.sequencer_balance(&mut state, &block_context, fee_token_address).unwrap(),
+        (StarkFelt::from(actual_fee.0), StarkFelt::ZERO)
+    );
+
+    deferred_fees.credit_sequencer(&mut state, &block_context).unwrap();
+    assert_eq!(
+        state.get_fee_token_balance(sequencer_address, fee_token_address).unwrap(),
+        (StarkFelt::from(actual_fee.0), StarkFelt::ZERO)
+    );
+}
+
+#[rstest]
+fn test_fee_transfer_without_sequencer_balance_reads(block_context: BlockContext) {
+    let block_context = BlockContext { concurrency_mode: true, ..block_context };
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let mut state: CachedState<DictStateReader> =
+        test_state(&block_context, BALANCE, &[(account, 1)]);
+    let fee_token_address = block_context.fee_token_address(&FeeType::Eth);
+
+    // A fee transfer whose reads do not include the sequencer balance.
+    let mut execution_info = TransactionExecutionInfo {
+        fee_transfer_call_info: Some(CallInfo {
+            call: CallEntryPoint {
+                storage_address: fee_token_address,
+                caller_address: account.get_instance_address(0),
+                ..Default::default()
+            },
+            storage_read_values: vec![StarkFelt::from(BALANCE), StarkFelt::ZERO],
+            storage_read_keys: vec![get_fee_token_var_address(account.get_instance_address(0)); 2],
+            ..Default::default()
+        }),
+        actual_fee: Fee(1),
+        ..Default::default()
+    };
+    let mut deferred_fees = DeferredSequencerFees::default();
+    assert_matches!(
+        deferred_fees.add(&mut state, &block_context, &mut execution_info),
+        Err(TransactionFeeError::SequencerBalanceReadMismatch { fee_token_address: address })
+        if address == fee_token_address
+    );
+    assert_eq!(
+        deferred_fees.sequencer_balance(&mut state, &block_context, fee_token_address).unwrap(),
+        (StarkFelt::ZERO, StarkFelt::ZERO)
+    );
+}
+
+#[rstest]
+fn test_sequencer_self_fee_transfer(
+    block_context: BlockContext,
+    #[values(false, true)] concurrency_mode: bool,
+) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let account_address = account.get_instance_address(0);
+    let block_context =
+        BlockContext { sequencer_address: account_address, concurrency_mode, ..block_context };
+    let (execution_infos, _, sequencer_balance) = execute_txs(&block_context, &[account_address]);
+
+    // The fee is transferred, and is neither lost nor counted twice.
+    let execution_info = &execution_infos[0];
+    let actual_fee = execution_info.actual_fee.0;
+    assert!(actual_fee > 0);
+    assert_eq!(sequencer_balance, (StarkFelt::from(BALANCE), StarkFelt::ZERO));
+    // The sender balance is read before the debit, and the recipient balance after it.
+    let storage_read_values =
+        &execution_info.fee_transfer_call_info.as_ref().unwrap().storage_read_values;
+    assert_eq!(
+        storage_read_values[..],
+        [BALANCE, 0, BALANCE, 0, BALANCE - actual_fee, 0, BALANCE - actual_fee, 0]
+            .map(StarkFelt::from)
+    );
+}
+
+#[rstest]
+fn test_collected_fees(block_context: BlockContext) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let senders = [account.get_instance_address(0), account.get_instance_address(1)];
+    let (execution_infos, _, _) = execute_txs(&block_context, &senders);
+
+    let mut collected_fees = CollectedFees::default();
+    let mut committed_fees = CollectedFees::default();
+    for execution_info in &execution_infos {
+        let mut tx_fees = CollectedFees::default();
+        tx_fees.add(execution_info);
+        committed_fees.extend(tx_fees);
+        collected_fees.add(execution_info);
+    }
+
+    let total_fee: u128 = execution_infos.iter().map(|info| info.actual_fee.0).sum();
+    assert!(total_fee > 0);
+    let eth_fee_token_address = block_context.fee_token_address(&FeeType::Eth);
+    assert_eq!(collected_fees.get(eth_fee_token_address), Fee(total_fee));
+    assert_eq!(collected_fees.get(block_context.fee_token_address(&FeeType::Strk)), Fee(0));
+    assert_eq!(collected_fees.fees().len(), 1);
+    assert_eq!(committed_fees, collected_fees);
+}
+
+#[test]
+fn test_add_to_balance_carry() {
+    let balance = (StarkFelt::from(u128::MAX), StarkFelt::ONE);
+    assert_eq!(
+        super::add_to_balance(balance, &2_u8.into()),
+        (StarkFelt::ONE, StarkFelt::from(2_u8))
+    );
+}
@@ -0,0 +1,226 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use cairo_vm::vm::runners::builtin_runner::{
+    BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME, KECCAK_BUILTIN_NAME,
+    OUTPUT_BUILTIN_NAME, POSEIDON_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME,
+    SEGMENT_ARENA_BUILTIN_NAME, SIGNATURE_BUILTIN_NAME,
+};
+use num_bigint::BigUint;
+use num_integer::Integer;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::abi::constants;
+use crate::fee::rounding::Rounding;
+use crate::transaction::errors::ParseError;
+use crate::utils::saturating_mul_div;
+
+#[cfg(test)]
+#[path = "vm_resource_costs_test.rs"]
+pub mod test;
+
+/// A Cairo VM resource that may be charged for; its name, as it appears in `ResourcesMapping` and
+/// in the legacy fee weights config, is given by `as_str`.
+#[derive(Clone, Copy, Debug, EnumIter, Eq, Hash, PartialEq)]
+pub enum VmResourceKey {
+    NSteps,
+    NMemoryHoles,
+    Bitwise,
+    EcOp,
+    Ecdsa,
+    Keccak,
+    Output,
+    Pedersen,
+    Poseidon,
+    RangeCheck,
+    SegmentArena,
+}
+
+impl VmResourceKey {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NSteps => constants::N_STEPS_RESOURCE,
+            Self::NMemoryHoles => constants::N_MEMORY_HOLES_RESOURCE,
+            Self::Bitwise => BITWISE_BUILTIN_NAME,
+            Self::EcOp => EC_OP_BUILTIN_NAME,
+            Self::Ecdsa => SIGNATURE_BUILTIN_NAME,
+            Self::Keccak => KECCAK_BUILTIN_NAME,
+            Self::Output => OUTPUT_BUILTIN_NAME,
+            Self::Pedersen => HASH_BUILTIN_NAME,
+            Self::Poseidon => POSEIDON_BUILTIN_NAME,
+            Self::RangeCheck => RANGE_CHECK_BUILTIN_NAME,
+            Self::SegmentArena => SEGMENT_ARENA_BUILTIN_NAME,
+        }
+    }
+}
+
+impl FromStr for VmResourceKey {
+    type Err = ParseError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::iter()
+            .find(|key| key.as_str() == name)
+            .ok_or_else(|| ParseError::UnknownVmResource(name.to_string()))
+    }
+}
+
+/// A non-negative rational number, kept in lowest terms; used instead of floating point in fee
+/// computations, so that fees are identical on all targets.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Ratio {
+    numerator: u128,
+    denominator: u128,
+}
+
+impl Ratio {
+    pub const ZERO: Self = Self { numerator: 0, denominator: 1 };
+
+    /// Returns [None] if the denominator is zero.
+    pub fn new(numerator: u128, denominator: u128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+
+        let gcd = numerator.gcd(&denominator);
+        Some(Self { numerator: numerator / gcd, denominator: denominator / gcd })
+    }
+
+    pub fn numerator(&self) -> u128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> u128 {
+        self.denominator
+    }
+
+    /// Multiplies the ratio by the given amount; the numerator saturates at `u128::MAX`.
+    pub fn saturating_mul(self, amount: u128) -> Self {
+        let gcd = amount.gcd(&self.denominator);
+        Self {
+            numerator: self.numerator.saturating_mul(amount / gcd),
+            denominator: self.denominator / gcd,
+        }
+    }
+
+    /// Returns the reciprocal of the ratio, or [None] if it is zero.
+    pub fn recip(self) -> Option<Self> {
+        Self::new(self.denominator, self.numerator)
+    }
+
+    /// Rounds the ratio to an integer.
+    pub fn round(self, rounding: Rounding) -> u128 {
+        rounding.div(self.numerator, self.denominator).expect("The denominator is positive.")
+    }
+
+    /// Returns the product of the ratio by the given amount, rounded to an integer; saturates at
+    /// `u128::MAX`.
+    pub fn mul_round(self, amount: u128, rounding: Rounding) -> u128 {
+        saturating_mul_div(amount, self.numerator, self.denominator, rounding)
+            .expect("The denominator is positive.")
+    }
+
+    /// Returns the quotient of the given amount by the ratio, rounded to an integer; saturates at
+    /// `u128::MAX` (e.g., when dividing by zero).
+    pub fn div_round(self, amount: u128, rounding: Rounding) -> u128 {
+        saturating_mul_div(amount, self.denominator, self.numerator, rounding).unwrap_or(u128::MAX)
+    }
+
+    /// The nearest floating-point value; for the legacy (floating-point) representation only.
+    pub fn to_f64(self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+}
+
+impl From<u128> for Ratio {
+    fn from(value: u128) -> Self {
+        Self { numerator: value, denominator: 1 }
+    }
+}
+
+impl Ord for Ratio {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Cross-multiplied over big integers, as the products may exceed 128 bits.
+        (BigUint::from(self.numerator) * other.denominator)
+            .cmp(&(BigUint::from(other.numerator) * self.denominator))
+    }
+}
+
+impl PartialOrd for Ratio {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Converts the exact decimal value of the shortest representation of the given number (e.g.,
+/// `0.0025` is 1/400); fails on negative and non-finite numbers.
+impl TryFrom<f64> for Ratio {
+    type Error = ParseError;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        let invalid_cost = || ParseError::InvalidVmResourceCost(value.to_string());
+        if !value.is_finite() || value.is_sign_negative() && value != 0.0 {
+            return Err(invalid_cost());
+        }
+
+        // The `Display` representation of floats has no exponent.
+        let decimal = value.to_string();
+        let (integer_part, fraction_part) = decimal.split_once('.').unwrap_or((&decimal, ""));
+        let denominator = u32::try_from(fraction_part.len())
+            .ok()
+            .and_then(|n_digits| 10_u128.checked_pow(n_digits))
+            .ok_or_else(invalid_cost)?;
+        let numerator =
+            format!("{integer_part}{fraction_part}").parse::<u128>().map_err(|_| invalid_cost())?;
+
+        Ok(Self::new(numerator, denominator).expect("The denominator is positive."))
+    }
+}
+
+/// The L1 gas weight of each charged Cairo VM resource (per unit); (de)serialized in the legacy
+/// (floating-point) format.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(try_from = "HashMap<String, f64>", into = "HashMap<String, f64>")]
+pub struct VmResourceFeeCosts(HashMap<VmResourceKey, Ratio>);
+
+impl VmResourceFeeCosts {
+    pub fn get(&self, key: VmResourceKey) -> Option<Ratio> {
+        self.0.get(&key).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (VmResourceKey, Ratio)> + '_ {
+        self.0.iter().map(|(&key, &cost)| (key, cost))
+    }
+
+    /// Returns the costs keyed by resource name, as in the legacy config format.
+    pub fn to_legacy_map(&self) -> HashMap<String, f64> {
+        self.iter().map(|(key, cost)| (key.as_str().to_string(), cost.to_f64())).collect()
+    }
+}
+
+impl From<HashMap<VmResourceKey, Ratio>> for VmResourceFeeCosts {
+    fn from(costs: HashMap<VmResourceKey, Ratio>) -> Self {
+        Self(costs)
+    }
+}
+
+impl From<VmResourceFeeCosts> for HashMap<String, f64> {
+    fn from(costs: VmResourceFeeCosts) -> Self {
+        costs.to_legacy_map()
+    }
+}
+
+/// Converts from the legacy config format, keyed by resource name.
+impl TryFrom<HashMap<String, f64>> for VmResourceFeeCosts {
+    type Error = ParseError;
+
+    fn try_from(costs: HashMap<String, f64>) -> Result<Self, Self::Error> {
+        costs
+            .into_iter()
+            .map(|(name, cost)| Ok((name.parse()?, Ratio::try_from(cost)?)))
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use assert_matches::assert_matches;
+use strum::IntoEnumIterator;
+
+use crate::abi::constants;
+use crate::fee::rounding::Rounding;
+use crate::fee::vm_resource_costs::{Ratio, VmResourceFeeCosts, VmResourceKey};
+use crate::transaction::errors::ParseError;
+
+#[test]
+fn test_resource_key_names() {
+    for key in VmResourceKey::iter() {
+        assert_eq!(key.as_str().parse::<VmResourceKey>().unwrap(), key);
+    }
+    assert_eq!(VmResourceKey::NSteps.as_str(), constants::N_STEPS_RESOURCE);
+    assert_eq!(VmResourceKey::RangeCheck.as_str(), "range_check_builtin");
+    assert_matches!(
+        "l1_gas_usage".parse::<VmResourceKey>(),
+        Err(ParseError::UnknownVmResource(name)) if name == constants::GAS_USAGE
+    );
+}
+
+#[test]
+fn test_legacy_map_round_trip() {
+    let legacy_costs = HashMap::from([
+        (constants::N_STEPS_RESOURCE.to_string(), 0.005),
+        ("pedersen_builtin".to_string(), 0.16),
+        ("range_check_builtin".to_string(), 0.08),
+    ]);
+    let costs = VmResourceFeeCosts::try_from(legacy_costs.clone()).unwrap();
+    assert_eq!(costs.get(VmResourceKey::Pedersen), Ratio::new(4, 25));
+    assert_eq!(costs.get(VmResourceKey::Poseidon), None);
+    assert_eq!(costs.to_legacy_map(), legacy_costs);
+
+    let unknown_costs = HashMap::from([("unknown_builtin".to_string(), 1.0)]);
+    assert_matches!(
+        VmResourceFeeCosts::try_from(unknown_costs),
+        Err(ParseError::UnknownVmResource(_))
+    );
+}
+
+#[test]
+fn test_ratio_from_f64() {
+    assert_eq!(Ratio::try_from(0.0025).unwrap(), Ratio::new(1, 400).unwrap());
+    assert_eq!(Ratio::try_from(3.0).unwrap(), Ratio::from(3));
+    assert_eq!(Ratio::try_from(0.0).unwrap(), Ratio::ZERO);
+    assert_eq!(Ratio::try_from(1.5).unwrap().to_f64(), 1.5);
+    for invalid_cost in [-1.0, f64::NAN, f64::INFINITY] {
+        assert_matches!(Ratio::try_from(invalid_cost), Err(ParseError::InvalidVmResourceCost(_)));
+    }
+}
+
+#[test]
+fn test_ratio_arithmetic() {
+    let cost = Ratio::new(1, 400).unwrap();
+    assert_eq!(cost.saturating_mul(1000), Ratio::new(5, 2).unwrap());
+    assert_eq!(cost.saturating_mul(1000).round(Rounding::Ceil), 3);
+    assert_eq!(cost.saturating_mul(1000).round(Rounding::Floor), 2);
+    assert_eq!(cost.recip(), Some(Ratio::from(400)));
+    assert_eq!(Ratio::ZERO.recip(), None);
+    assert_eq!(Ratio::new(1, 0), None);
+    assert_eq!(cost.mul_round(1000, Rounding::Ceil), 3);
+    assert_eq!(cost.div_round(3, Rounding::Floor), 1200);
+    assert_eq!(Ratio::ZERO.div_round(3, Rounding::Floor), u128::MAX);
+    // The numerator saturates.
+    assert_eq!(Ratio::from(2).saturating_mul(u128::MAX), Ratio::from(u128::MAX));
+
+    assert!(Ratio::new(1, 3).unwrap() < Ratio::new(34, 100).unwrap());
+    assert!(Ratio::from(u128::MAX) > Ratio::new(u128::MAX, 2).unwrap());
+}
@@ -1,10 +1,21 @@
 pub mod abi;
 pub mod block_context;
 pub mod block_execution;
+pub mod bouncer;
+pub mod chain_id;
 pub mod execution;
 pub mod fee;
+pub mod metrics;
+pub mod os_input;
+pub mod replay_capsule;
+#[cfg(feature = "cbor")]
+pub mod serialization;
 pub mod state;
+pub mod stateful_validator;
+pub mod stateless_validator;
 #[cfg(any(feature = "testing", test))]
 pub mod test_utils;
 pub mod transaction;
+pub mod types;
 pub mod utils;
+pub mod versioned_constants;
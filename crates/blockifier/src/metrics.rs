@@ -0,0 +1,65 @@
+use std::fmt::Debug;
+
+use crate::abi::constants::N_STEPS_RESOURCE;
+use crate::transaction::objects::{TransactionExecutionInfo, TransactionExecutionResult};
+
+#[cfg(test)]
+#[path = "metrics_test.rs"]
+pub mod test;
+
+/// The labels of a metric, as (name, value) pairs; e.g., `[("cache", "storage")]`.
+pub type MetricLabels<'a> = &'a [(&'static str, &'static str)];
+
+/// Receives the metrics of the blockifier, set through `BlockContext::metrics_sink`; allows
+/// embedders to bridge them to their metrics system (e.g., Prometheus) without the blockifier
+/// depending on one.
+///
+/// Transaction executions are reported as they complete. Caches and the bouncer are reported on
+/// request (see `CachedState::report_cache_metrics` and `Bouncer::report`), typically once per
+/// block.
+pub trait MetricsSink: Debug + Send + Sync {
+    /// Increments a monotonic counter by the given value.
+    fn increment_counter(&self, name: &'static str, labels: MetricLabels<'_>, value: u64);
+
+    /// Sets a gauge to the given value.
+    fn set_gauge(&self, name: &'static str, labels: MetricLabels<'_>, value: f64);
+}
+
+// Transaction execution.
+pub const TRANSACTIONS_COMMITTED: &str = "blockifier_transactions_committed";
+pub const TRANSACTIONS_REVERTED: &str = "blockifier_transactions_reverted";
+pub const TRANSACTIONS_FAILED: &str = "blockifier_transactions_failed";
+pub const EXECUTED_STEPS: &str = "blockifier_executed_steps";
+
+// State caches; labeled by cache.
+pub const STATE_CACHE_HITS: &str = "blockifier_state_cache_hits";
+pub const STATE_CACHE_MISSES: &str = "blockifier_state_cache_misses";
+
+// Global contract class cache; hits and misses are cumulative since the creation of the cache.
+pub const CLASS_CACHE_HITS: &str = "blockifier_class_cache_hits";
+pub const CLASS_CACHE_MISSES: &str = "blockifier_class_cache_misses";
+pub const CLASS_CACHE_ENTRIES: &str = "blockifier_class_cache_entries";
+pub const CLASS_CACHE_SIZE_IN_BYTES: &str = "blockifier_class_cache_size_in_bytes";
+
+// Bouncer; labeled by resource.
+pub const BOUNCER_ACCUMULATED_WEIGHT: &str = "blockifier_bouncer_accumulated_weight";
+pub const BOUNCER_MAX_WEIGHT: &str = "blockifier_bouncer_max_weight";
+
+/// Reports the execution of a transaction: committed (possibly reverted) or failed, and the steps
+/// it ran.
+pub fn report_execution(
+    metrics_sink: &dyn MetricsSink,
+    execution_result: &TransactionExecutionResult<TransactionExecutionInfo>,
+) {
+    let Ok(tx_execution_info) = execution_result else {
+        metrics_sink.increment_counter(TRANSACTIONS_FAILED, &[], 1);
+        return;
+    };
+
+    metrics_sink.increment_counter(TRANSACTIONS_COMMITTED, &[], 1);
+    if tx_execution_info.is_reverted() {
+        metrics_sink.increment_counter(TRANSACTIONS_REVERTED, &[], 1);
+    }
+    let n_steps = tx_execution_info.actual_resources.0.get(N_STEPS_RESOURCE).copied().unwrap_or(0);
+    metrics_sink.increment_counter(EXECUTED_STEPS, &[], n_steps as u64);
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use rstest::rstest;
+use starknet_api::core::Nonce;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::Fee;
+
+use super::*;
+use crate::block_context::BlockContext;
+use crate::bouncer::{Bouncer, BouncerConfig, BouncerWeights};
+use crate::invoke_tx_args;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_calldata, CairoVersion, BALANCE, MAX_FEE};
+use crate::transaction::test_utils::{block_context, run_invoke_tx};
+
+type MetricKey = (&'static str, Vec<(&'static str, &'static str)>);
+
+/// Records the latest value of every metric.
+#[derive(Debug, Default)]
+struct RecordingSink {
+    metrics: Mutex<HashMap<MetricKey, f64>>,
+}
+
+impl RecordingSink {
+    fn get(&self, name: &'static str, labels: MetricLabels<'_>) -> f64 {
+        self.metrics.lock().unwrap().get(&(name, labels.to_vec())).copied().unwrap_or_default()
+    }
+}
+
+impl MetricsSink for RecordingSink {
+    fn increment_counter(&self, name: &'static str, labels: MetricLabels<'_>, value: u64) {
+        *self.metrics.lock().unwrap().entry((name, labels.to_vec())).or_default() += value as f64;
+    }
+
+    fn set_gauge(&self, name: &'static str, labels: MetricLabels<'_>, value: f64) {
+        self.metrics.lock().unwrap().insert((name, labels.to_vec()), value);
+    }
+}
+
+#[rstest]
+fn test_execution_metrics(block_context: BlockContext) {
+    let metrics_sink = Arc::new(RecordingSink::default());
+    let block_context = BlockContext { metrics_sink: Some(metrics_sink.clone()), ..block_context };
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let mut state = test_state(&block_context, BALANCE, &[(account, 1), (test_contract, 1)]);
+
+    let mut run_tx = |max_fee: Fee, nonce: u8| {
+        run_invoke_tx(
+            &mut state,
+            &block_context,
+            invoke_tx_args! {
+                max_fee,
+                sender_address: account.get_instance_address(0),
+                calldata: create_calldata(
+                    test_contract.get_instance_address(0),
+                    "return_result",
+                    &[stark_felt!(2_u8)],
+                ),
+                nonce: Nonce(stark_felt!(nonce)),
+            },
+        )
+    };
+    let tx_execution_info = run_tx(Fee(MAX_FEE), 0).unwrap();
+    run_tx(Fee(1), 1).unwrap_err();
+
+    assert_eq!(metrics_sink.get(TRANSACTIONS_COMMITTED, &[]), 1.0);
+    assert_eq!(metrics_sink.get(TRANSACTIONS_REVERTED, &[]), 0.0);
+    assert_eq!(metrics_sink.get(TRANSACTIONS_FAILED, &[]), 1.0);
+    assert_eq!(
+        metrics_sink.get(EXECUTED_STEPS, &[]),
+        tx_execution_info.actual_resources.0[N_STEPS_RESOURCE] as f64
+    );
+
+    // The accumulated state reports its caches.
+    state.report_cache_metrics(metrics_sink.as_ref());
+    let stats = state.cache_stats();
+    assert_eq!(
+        metrics_sink.get(STATE_CACHE_HITS, &[("cache", "storage")]),
+        stats.storage.hits as f64
+    );
+    assert_eq!(
+        metrics_sink.get(STATE_CACHE_MISSES, &[("cache", "nonces")]),
+        stats.nonces.misses as f64
+    );
+}
+
+#[test]
+fn test_cache_and_bouncer_metrics() {
+    let metrics_sink = RecordingSink::default();
+    let block_context = BlockContext::create_for_account_testing();
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let mut state = test_state(&block_context, BALANCE, &[(test_contract, 1)]);
+
+    state.get_compiled_contract_class(test_contract.get_class_hash()).unwrap();
+    state.move_classes_to_global_cache();
+    state.report_cache_metrics(&metrics_sink);
+    assert_eq!(metrics_sink.get(STATE_CACHE_MISSES, &[("cache", "contract_classes")]), 1.0);
+    assert_eq!(metrics_sink.get(CLASS_CACHE_ENTRIES, &[]), 1.0);
+    assert_eq!(
+        metrics_sink.get(CLASS_CACHE_SIZE_IN_BYTES, &[]),
+        test_contract.get_class().approximate_size_in_bytes() as f64
+    );
+
+    let mut bouncer =
+        Bouncer::new(BouncerConfig { max_declared_classes: 2, ..BouncerConfig::default() });
+    bouncer.try_update(&BouncerWeights { n_declared_classes: 1, n_deployed_contracts: 3 }).unwrap();
+    bouncer.report(&metrics_sink);
+    let declared_classes = [("resource", "declared_classes")];
+    assert_eq!(metrics_sink.get(BOUNCER_ACCUMULATED_WEIGHT, &declared_classes), 1.0);
+    assert_eq!(metrics_sink.get(BOUNCER_MAX_WEIGHT, &declared_classes), 2.0);
+    assert_eq!(
+        metrics_sink.get(BOUNCER_ACCUMULATED_WEIGHT, &[("resource", "deployed_contracts")]),
+        3.0
+    );
+}
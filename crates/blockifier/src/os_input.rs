@@ -0,0 +1,127 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use starknet_api::block::{BlockNumber, BlockTimestamp};
+use starknet_api::core::{ChainId, ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{Fee, TransactionHash};
+use thiserror::Error;
+
+use crate::block_context::BlockContext;
+use crate::state::cached_state::{CachedState, CommitmentStateDiff, StateMaps};
+use crate::state::state_api::StateReader;
+use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::transaction_types::TransactionType;
+
+#[cfg(test)]
+#[path = "os_input_test.rs"]
+pub mod test;
+
+#[derive(Debug, Error)]
+pub enum OsInputError {
+    #[error("State reads were not recorded; call `record_state_reads` before execution.")]
+    StateReadsNotRecorded,
+    #[error(transparent)]
+    SerdeError(#[from] serde_json::Error),
+}
+
+pub type OsInputResult<T> = Result<T, OsInputError>;
+
+/// Structured input for re-executing (and proving) a block in the Starknet OS: the executed
+/// transactions, the state values they read and the state diff they induced.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct OsInput {
+    pub chain_id: ChainId,
+    pub block_number: BlockNumber,
+    pub block_timestamp: BlockTimestamp,
+    pub sequencer_address: ContractAddress,
+    pub transactions: Vec<OsTransactionInput>,
+    pub state_witness: OsStateWitness,
+    pub state_diff: CommitmentStateDiff,
+}
+
+impl OsInput {
+    /// Builds the OS input of the transactions executed on the given state; the state must have
+    /// recorded its reads from the start of the block (see `CachedState::record_state_reads`).
+    pub fn create<S: StateReader>(
+        block_context: &BlockContext,
+        state: &mut CachedState<S>,
+        transactions: Vec<OsTransactionInput>,
+    ) -> OsInputResult<Self> {
+        let state_witness =
+            OsStateWitness::from(state.state_reads().ok_or(OsInputError::StateReadsNotRecorded)?);
+
+        Ok(Self {
+            chain_id: block_context.chain_id.clone(),
+            block_number: block_context.block_number,
+            block_timestamp: block_context.block_timestamp,
+            sequencer_address: block_context.sequencer_address,
+            transactions,
+            state_witness,
+            state_diff: state.to_state_diff(),
+        })
+    }
+
+    pub fn to_json_string(&self) -> OsInputResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct OsTransactionInput {
+    pub tx_hash: TransactionHash,
+    pub tx_type: TransactionType,
+    pub actual_fee: Fee,
+    pub revert_error: Option<String>,
+    pub executed_class_hashes: BTreeSet<ClassHash>,
+}
+
+impl OsTransactionInput {
+    pub fn new(
+        tx_hash: TransactionHash,
+        tx_type: TransactionType,
+        execution_info: &TransactionExecutionInfo,
+    ) -> Self {
+        Self {
+            tx_hash,
+            tx_type,
+            actual_fee: execution_info.actual_fee,
+            revert_error: execution_info.revert_error.clone(),
+            executed_class_hashes: execution_info.get_executed_class_hashes().into_iter().collect(),
+        }
+    }
+}
+
+/// The state values read during the execution of a block, ordered by key.
+/// Classes are referenced by their hashes.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct OsStateWitness {
+    pub storage: BTreeMap<ContractAddress, BTreeMap<StorageKey, StarkFelt>>,
+    pub nonces: BTreeMap<ContractAddress, Nonce>,
+    pub class_hashes: BTreeMap<ContractAddress, ClassHash>,
+    pub compiled_class_hashes: BTreeMap<ClassHash, CompiledClassHash>,
+    pub classes: BTreeSet<ClassHash>,
+}
+
+impl From<&StateMaps> for OsStateWitness {
+    fn from(state_reads: &StateMaps) -> Self {
+        let mut storage: BTreeMap<ContractAddress, BTreeMap<StorageKey, StarkFelt>> =
+            BTreeMap::new();
+        for (&(address, key), &value) in &state_reads.storage {
+            storage.entry(address).or_default().insert(key, value);
+        }
+
+        Self {
+            storage,
+            nonces: state_reads.nonces.iter().map(|(k, v)| (*k, *v)).collect(),
+            class_hashes: state_reads.class_hashes.iter().map(|(k, v)| (*k, *v)).collect(),
+            compiled_class_hashes: state_reads
+                .compiled_class_hashes
+                .iter()
+                .map(|(k, v)| (*k, *v))
+                .collect(),
+            classes: state_reads.classes.keys().copied().collect(),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use assert_matches::assert_matches;
+use rstest::rstest;
+use starknet_api::core::Nonce;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{Fee, TransactionHash};
+
+use super::{OsInput, OsInputError, OsTransactionInput};
+use crate::block_context::BlockContext;
+use crate::invoke_tx_args;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_calldata, CairoVersion, BALANCE, MAX_FEE};
+use crate::transaction::test_utils::{block_context, run_invoke_tx};
+use crate::transaction::transaction_types::TransactionType;
+
+#[rstest]
+fn test_os_input_of_invoke(block_context: BlockContext) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let account_address = account.get_instance_address(0);
+    let contract_address = test_contract.get_instance_address(0);
+    let mut state = test_state(&block_context, BALANCE, &[(account, 1), (test_contract, 1)]);
+    state.record_state_reads();
+
+    let storage_key = stark_felt!(15_u8);
+    let execution_info = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            max_fee: Fee(MAX_FEE),
+            sender_address: account_address,
+            calldata: create_calldata(
+                contract_address,
+                "test_storage_read_write",
+                &[storage_key, stark_felt!(7_u8)],
+            ),
+        },
+    )
+    .unwrap();
+    let tx_hash = TransactionHash(stark_felt!(1_u8));
+    let tx_input =
+        OsTransactionInput::new(tx_hash, TransactionType::InvokeFunction, &execution_info);
+    let os_input = OsInput::create(&block_context, &mut state, vec![tx_input.clone()]).unwrap();
+
+    assert_eq!(os_input.block_number, block_context.block_number);
+    assert_eq!(os_input.transactions, vec![tx_input]);
+    assert_eq!(os_input.transactions[0].actual_fee, execution_info.actual_fee);
+    assert!(os_input.transactions[0]
+        .executed_class_hashes
+        .contains(&test_contract.get_class_hash()));
+
+    // The witness holds the values before the execution.
+    let witness = &os_input.state_witness;
+    assert_eq!(witness.nonces[&account_address], Nonce(StarkFelt::ZERO));
+    assert_eq!(witness.class_hashes[&contract_address], test_contract.get_class_hash());
+    assert_eq!(
+        witness.storage[&contract_address][&StorageKey::try_from(storage_key).unwrap()],
+        StarkFelt::ZERO
+    );
+    assert!(witness.classes.contains(&account.get_class_hash()));
+    assert_eq!(os_input.state_diff.address_to_nonce[&account_address], Nonce(StarkFelt::ONE));
+
+    os_input.to_json_string().unwrap();
+}
+
+#[rstest]
+fn test_os_input_requires_recorded_reads(block_context: BlockContext) {
+    let mut state = test_state(&block_context, BALANCE, &[]);
+    assert_matches!(
+        OsInput::create(&block_context, &mut state, vec![]),
+        Err(OsInputError::StateReadsNotRecorded)
+    );
+}
@@ -0,0 +1,282 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{Fee, TransactionHash};
+use thiserror::Error;
+
+use crate::block_context::BlockContext;
+use crate::execution::contract_class::ContractClass;
+use crate::execution::errors::ContractClassError;
+use crate::state::cached_state::{CachedState, StateEntries, StateMaps};
+use crate::state::errors::StateError;
+use crate::state::state_api::{StateReader, StateResult};
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::objects::{TransactionExecutionInfo, TransactionExecutionResult};
+use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transactions::{
+    DeclareTransaction, DeployAccountTransaction, ExecutableTransaction, InvokeTransaction,
+    L1HandlerTransaction,
+};
+use crate::utils::{format_address, format_class_hash, format_felt};
+
+#[cfg(test)]
+#[path = "replay_capsule_test.rs"]
+pub mod test;
+
+#[derive(Debug, Error)]
+pub enum ReplayCapsuleError {
+    #[error("The raw class of {} is missing.", format_class_hash(.0))]
+    MissingRawClass(ClassHash),
+    #[error(transparent)]
+    ContractClassError(#[from] ContractClassError),
+    #[error(transparent)]
+    SerdeError(#[from] serde_json::Error),
+    #[error(transparent)]
+    TransactionExecutionError(#[from] TransactionExecutionError),
+}
+
+pub type ReplayCapsuleResult<T> = Result<T, ReplayCapsuleError>;
+
+/// A self-contained reproduction of the execution of a transaction: the transaction, its block
+/// context, and every state value and class it read; e.g., to attach to a bug report.
+///
+/// Classes are kept in their raw (Cairo 0 or CASM) JSON, since compiled classes do not round-trip
+/// through serialization. The extensions of the block context (e.g., custom syscalls) are not
+/// serialized, and must be reinstalled by the replaying party.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ReplayCapsule {
+    pub block_context: BlockContext,
+    pub transaction: CapsuleTransaction,
+    pub charge_fee: bool,
+    pub validate: bool,
+    pub state_reads: StateEntries,
+    pub raw_classes: BTreeMap<ClassHash, String>,
+}
+
+impl ReplayCapsule {
+    /// Captures the given execution; `state_reads` are the values the execution read from the
+    /// underlying state (see `CachedState::record_state_reads`), and `raw_classes` must contain the
+    /// raw classes it read, as well as the declared class, if any.
+    pub fn new(
+        block_context: &BlockContext,
+        transaction: &Transaction,
+        charge_fee: bool,
+        validate: bool,
+        state_reads: &StateMaps,
+        raw_classes: &HashMap<ClassHash, String>,
+    ) -> ReplayCapsuleResult<Self> {
+        let get_raw_class = |class_hash: &ClassHash| {
+            raw_classes
+                .get(class_hash)
+                .cloned()
+                .ok_or(ReplayCapsuleError::MissingRawClass(*class_hash))
+        };
+
+        Ok(Self {
+            block_context: block_context.clone(),
+            transaction: CapsuleTransaction::new(transaction, get_raw_class)?,
+            charge_fee,
+            validate,
+            state_reads: StateEntries::from(state_reads),
+            raw_classes: state_reads
+                .classes
+                .keys()
+                .map(|class_hash| Ok((*class_hash, get_raw_class(class_hash)?)))
+                .collect::<ReplayCapsuleResult<_>>()?,
+        })
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> ReplayCapsuleResult<Self> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    pub fn to_bytes(&self) -> ReplayCapsuleResult<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// Re-executes the transaction on the captured state.
+    pub fn replay(
+        &self,
+    ) -> ReplayCapsuleResult<TransactionExecutionResult<TransactionExecutionInfo>> {
+        let contract_classes = self
+            .raw_classes
+            .iter()
+            .map(|(class_hash, raw_class)| {
+                Ok((*class_hash, ContractClass::from_json_str(raw_class)?))
+            })
+            .collect::<ReplayCapsuleResult<_>>()?;
+        let mut state = CachedState::from(CapsuleStateReader {
+            state_reads: self.state_reads.clone(),
+            contract_classes,
+        });
+
+        let transaction = self.transaction.to_transaction()?;
+        Ok(transaction.execute(&mut state, &self.block_context, self.charge_fee, self.validate))
+    }
+}
+
+/// Re-executes the transaction of the serialized capsule; see `ReplayCapsule::to_bytes`.
+pub fn replay_capsule(
+    bytes: &[u8],
+) -> ReplayCapsuleResult<TransactionExecutionResult<TransactionExecutionInfo>> {
+    ReplayCapsule::from_bytes(bytes)?.replay()
+}
+
+/// A serializable transaction; the class of a declare transaction is kept in its raw JSON.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum CapsuleTransaction {
+    Declare {
+        tx: starknet_api::transaction::DeclareTransaction,
+        tx_hash: TransactionHash,
+        only_query: bool,
+        raw_contract_class: String,
+    },
+    DeployAccount {
+        tx: starknet_api::transaction::DeployAccountTransaction,
+        tx_hash: TransactionHash,
+        contract_address: ContractAddress,
+        only_query: bool,
+    },
+    Invoke {
+        tx: starknet_api::transaction::InvokeTransaction,
+        tx_hash: TransactionHash,
+        only_query: bool,
+    },
+    L1Handler {
+        tx: starknet_api::transaction::L1HandlerTransaction,
+        tx_hash: TransactionHash,
+        paid_fee_on_l1: Fee,
+    },
+}
+
+impl CapsuleTransaction {
+    fn new(
+        transaction: &Transaction,
+        get_raw_class: impl Fn(&ClassHash) -> ReplayCapsuleResult<String>,
+    ) -> ReplayCapsuleResult<Self> {
+        Ok(match transaction {
+            Transaction::AccountTransaction(AccountTransaction::Declare(tx)) => Self::Declare {
+                tx: tx.tx().clone(),
+                tx_hash: tx.tx_hash(),
+                only_query: tx.only_query(),
+                raw_contract_class: get_raw_class(&tx.class_hash())?,
+            },
+            Transaction::AccountTransaction(AccountTransaction::DeployAccount(tx)) => {
+                Self::DeployAccount {
+                    tx: tx.tx.clone(),
+                    tx_hash: tx.tx_hash,
+                    contract_address: tx.contract_address,
+                    only_query: tx.only_query,
+                }
+            }
+            Transaction::AccountTransaction(AccountTransaction::Invoke(tx)) => {
+                Self::Invoke { tx: tx.tx.clone(), tx_hash: tx.tx_hash, only_query: tx.only_query }
+            }
+            Transaction::L1HandlerTransaction(tx) => Self::L1Handler {
+                tx: tx.tx.clone(),
+                tx_hash: tx.tx_hash,
+                paid_fee_on_l1: tx.paid_fee_on_l1,
+            },
+        })
+    }
+
+    pub fn to_transaction(&self) -> ReplayCapsuleResult<Transaction> {
+        let account_tx = match self {
+            Self::Declare { tx, tx_hash, only_query, raw_contract_class } => {
+                let contract_class = ContractClass::from_json_str(raw_contract_class)?;
+                let declare_tx = if *only_query {
+                    DeclareTransaction::new_for_query(tx.clone(), *tx_hash, contract_class)?
+                } else {
+                    DeclareTransaction::new(tx.clone(), *tx_hash, contract_class)?
+                };
+                AccountTransaction::Declare(declare_tx)
+            }
+            Self::DeployAccount { tx, tx_hash, contract_address, only_query } => {
+                AccountTransaction::DeployAccount(DeployAccountTransaction {
+                    tx: tx.clone(),
+                    tx_hash: *tx_hash,
+                    contract_address: *contract_address,
+                    only_query: *only_query,
+                })
+            }
+            Self::Invoke { tx, tx_hash, only_query } => {
+                AccountTransaction::Invoke(InvokeTransaction {
+                    tx: tx.clone(),
+                    tx_hash: *tx_hash,
+                    only_query: *only_query,
+                })
+            }
+            Self::L1Handler { tx, tx_hash, paid_fee_on_l1 } => {
+                return Ok(Transaction::L1HandlerTransaction(L1HandlerTransaction {
+                    tx: tx.clone(),
+                    tx_hash: *tx_hash,
+                    paid_fee_on_l1: *paid_fee_on_l1,
+                }));
+            }
+        };
+
+        Ok(Transaction::AccountTransaction(account_tx))
+    }
+}
+
+/// Serves the state reads of a capsule. The replayed execution reads the same entries, so a read
+/// the capsule did not record means the capsule does not match its execution, and fails.
+struct CapsuleStateReader {
+    state_reads: StateEntries,
+    contract_classes: HashMap<ClassHash, ContractClass>,
+}
+
+impl StateReader for CapsuleStateReader {
+    fn get_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        let contract_storage = self.state_reads.storage.get(&contract_address);
+        contract_storage.and_then(|storage| storage.get(&key)).copied().ok_or_else(|| {
+            StateError::MissingCapsuleEntry(format!(
+                "storage at key {} of {}",
+                format_felt(key.0.key()),
+                format_address(&contract_address)
+            ))
+        })
+    }
+
+    fn get_nonce_at(&mut self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        self.state_reads.nonces.get(&contract_address).copied().ok_or_else(|| {
+            StateError::MissingCapsuleEntry(format!(
+                "nonce of {}",
+                format_address(&contract_address)
+            ))
+        })
+    }
+
+    fn get_class_hash_at(&mut self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.state_reads.class_hashes.get(&contract_address).copied().ok_or_else(|| {
+            StateError::MissingCapsuleEntry(format!(
+                "class hash of {}",
+                format_address(&contract_address)
+            ))
+        })
+    }
+
+    fn get_compiled_contract_class(&mut self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.contract_classes
+            .get(&class_hash)
+            .cloned()
+            .ok_or(StateError::UndeclaredClassHash(class_hash))
+    }
+
+    fn get_compiled_class_hash(&mut self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.state_reads.compiled_class_hashes.get(&class_hash).copied().ok_or_else(|| {
+            StateError::MissingCapsuleEntry(format!(
+                "compiled class hash of {}",
+                format_class_hash(&class_hash)
+            ))
+        })
+    }
+}
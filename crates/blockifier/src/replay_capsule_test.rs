@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use assert_matches::assert_matches;
+use rstest::rstest;
+use starknet_api::core::{ClassHash, ContractAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::Fee;
+use starknet_api::{class_hash, contract_address, patricia_key, stark_felt};
+
+use super::{replay_capsule, CapsuleStateReader, ReplayCapsule, ReplayCapsuleError};
+use crate::block_context::BlockContext;
+use crate::invoke_tx_args;
+use crate::state::cached_state::StateEntries;
+use crate::state::errors::StateError;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_calldata, CairoVersion, BALANCE, MAX_FEE};
+use crate::transaction::test_utils::{account_invoke_tx, block_context};
+use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transactions::ExecutableTransaction;
+
+#[rstest]
+fn test_replay_capsule(block_context: BlockContext) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let mut state = test_state(&block_context, BALANCE, &[(account, 1), (test_contract, 1)]);
+    state.record_state_reads();
+
+    let transaction = Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address: account.get_instance_address(0),
+        calldata: create_calldata(
+            test_contract.get_instance_address(0),
+            "test_storage_read_write",
+            &[stark_felt!(15_u8), stark_felt!(7_u8)],
+        ),
+    }));
+    let execution_info =
+        transaction.clone().execute(&mut state, &block_context, true, true).unwrap();
+
+    let state_reads = state.state_reads().unwrap();
+    let mut raw_classes: HashMap<_, _> = [account, test_contract]
+        .iter()
+        .map(|contract| (contract.get_class_hash(), contract.get_raw_class()))
+        .collect();
+    let erc20 = FeatureContract::ERC20;
+    raw_classes.insert(erc20.get_class_hash(), erc20.get_raw_class());
+
+    let capsule =
+        ReplayCapsule::new(&block_context, &transaction, true, true, state_reads, &raw_classes)
+            .unwrap();
+    let bytes = capsule.to_bytes().unwrap();
+    assert_eq!(replay_capsule(&bytes).unwrap().unwrap(), execution_info);
+
+    // Every class read must be captured.
+    raw_classes.remove(&test_contract.get_class_hash());
+    assert_matches!(
+        ReplayCapsule::new(&block_context, &transaction, true, true, state_reads, &raw_classes),
+        Err(ReplayCapsuleError::MissingRawClass(class_hash))
+        if class_hash == test_contract.get_class_hash()
+    );
+}
+
+#[test]
+fn test_capsule_state_reader_missing_entries() {
+    let mut reader = CapsuleStateReader {
+        state_reads: StateEntries::default(),
+        contract_classes: HashMap::new(),
+    };
+    let address = contract_address!("0x1");
+    let class_hash = class_hash!("0x2");
+
+    assert_matches!(
+        reader.get_storage_at(address, StorageKey(patricia_key!("0x3"))),
+        Err(StateError::MissingCapsuleEntry(entry)) if entry == "storage at key 0x3 of 0x1"
+    );
+    assert_matches!(
+        reader.get_nonce_at(address),
+        Err(StateError::MissingCapsuleEntry(entry)) if entry == "nonce of 0x1"
+    );
+    assert_matches!(
+        reader.get_class_hash_at(address),
+        Err(StateError::MissingCapsuleEntry(entry)) if entry == "class hash of 0x1"
+    );
+    assert_matches!(
+        reader.get_compiled_class_hash(class_hash),
+        Err(StateError::MissingCapsuleEntry(entry)) if entry == "compiled class hash of 0x2"
+    );
+    assert_matches!(
+        reader.get_compiled_contract_class(class_hash),
+        Err(StateError::UndeclaredClassHash(hash)) if hash == class_hash
+    );
+}
@@ -0,0 +1,36 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::state::cached_state::{CommitmentStateDiff, StateCacheDump};
+use crate::transaction::objects::TransactionExecutionInfo;
+
+#[cfg(test)]
+#[path = "serialization_test.rs"]
+pub mod test;
+
+#[derive(Debug, Error)]
+pub enum BinarySerializationError {
+    #[error(transparent)]
+    CborError(#[from] serde_cbor::Error),
+}
+
+pub type BinarySerializationResult<T> = Result<T, BinarySerializationError>;
+
+/// Compact (CBOR) encoding of execution results, so that they can be cached or persisted; e.g., by
+/// replay tools, or for crash recovery.
+pub trait BinarySerializable: Serialize + DeserializeOwned {
+    fn to_bytes(&self) -> BinarySerializationResult<Vec<u8>> {
+        Ok(serde_cbor::to_vec(self)?)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> BinarySerializationResult<Self> {
+        Ok(serde_cbor::from_slice(bytes)?)
+    }
+}
+
+impl BinarySerializable for TransactionExecutionInfo {}
+
+impl BinarySerializable for CommitmentStateDiff {}
+
+impl BinarySerializable for StateCacheDump {}
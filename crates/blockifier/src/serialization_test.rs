@@ -0,0 +1,62 @@
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::Fee;
+
+use crate::block_context::BlockContext;
+use crate::invoke_tx_args;
+use crate::serialization::{BinarySerializable, BinarySerializationError};
+use crate::state::cached_state::CommitmentStateDiff;
+use crate::test_utils::{create_calldata, CairoVersion};
+use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::test_utils::{
+    block_context, create_test_init_data, max_fee, run_invoke_tx, TestInitData,
+};
+
+#[rstest]
+fn test_execution_info_round_trip(
+    block_context: BlockContext,
+    max_fee: Fee,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context, cairo_version);
+    let mut run_tx = |entry_point_name: &str, entry_point_args: &[StarkFelt]| {
+        run_invoke_tx(
+            &mut state,
+            &block_context,
+            invoke_tx_args! {
+                max_fee,
+                sender_address: account_address,
+                calldata: create_calldata(contract_address, entry_point_name, entry_point_args),
+                nonce: nonce_manager.next(account_address),
+            },
+        )
+        .unwrap()
+    };
+
+    // A successful transaction, with storage accesses, and a reverted one.
+    let successful = run_tx("test_storage_read_write", &[stark_felt!(15_u8), stark_felt!(7_u8)]);
+    let reverted = run_tx("nonexistent_entry_point", &[]);
+    assert!(reverted.is_reverted());
+
+    for tx_execution_info in [successful, reverted] {
+        let bytes = tx_execution_info.to_bytes().unwrap();
+        assert_eq!(TransactionExecutionInfo::from_bytes(&bytes).unwrap(), tx_execution_info);
+    }
+
+    let state_diff = state.to_state_diff();
+    assert!(!state_diff.storage_updates.is_empty());
+    let bytes = state_diff.to_bytes().unwrap();
+    assert_eq!(CommitmentStateDiff::from_bytes(&bytes).unwrap(), state_diff);
+}
+
+#[test]
+fn test_malformed_bytes() {
+    assert_matches!(
+        TransactionExecutionInfo::from_bytes(&[0xff, 0x00]),
+        Err(BinarySerializationError::CborError(_))
+    );
+}
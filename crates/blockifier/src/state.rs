@@ -1,3 +1,6 @@
 pub mod cached_state;
 pub mod errors;
+pub mod historical_state;
+pub mod journaled_state;
+pub mod runtime_state;
 pub mod state_api;
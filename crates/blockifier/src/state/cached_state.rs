@@ -1,15 +1,21 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex, MutexGuard};
 
 use cached::{Cached, SizedCache};
 use derive_more::IntoIterator;
 use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey;
 
 use crate::abi::abi_utils::get_fee_token_var_address;
-use crate::execution::contract_class::ContractClass;
+use crate::execution::contract_class::{CompressedContractClassV0, ContractClass};
+use crate::metrics::{
+    MetricsSink, CLASS_CACHE_ENTRIES, CLASS_CACHE_HITS, CLASS_CACHE_MISSES,
+    CLASS_CACHE_SIZE_IN_BYTES, STATE_CACHE_HITS, STATE_CACHE_MISSES,
+};
 use crate::state::errors::StateError;
 use crate::state::state_api::{State, StateReader, StateResult};
 use crate::utils::subtract_mappings;
@@ -30,8 +36,21 @@ pub struct CachedState<S: StateReader> {
     // Invariant: read/write access is managed by CachedState.
     cache: StateCache,
     class_hash_to_class: ContractClassMapping,
+    // The classes set through this state (i.e., declared); they are not part of the underlying
+    // state.
+    declared_class_hashes: HashSet<ClassHash>,
     // Invariant: managed by CachedState.
     global_class_hash_to_class: GlobalContractCache,
+    // Values read from the underlying state; recorded only if enabled.
+    state_reads: Option<StateMaps>,
+    // The program counters visited per class; tracked only if enabled.
+    visited_pcs: Option<HashMap<ClassHash, HashSet<usize>>>,
+    cache_stats: CachedStateStats,
+    // The maximal number of written entries; unlimited if not set.
+    max_n_writes: Option<usize>,
+    // The entries written since the first checkpoint was taken, with their previous values, in
+    // write order; kept only once a checkpoint is taken (see `snapshot`).
+    write_journal: Option<Vec<JournalEntry>>,
 }
 
 impl<S: StateReader> CachedState<S> {
@@ -40,7 +59,13 @@ impl<S: StateReader> CachedState<S> {
             state,
             cache: StateCache::default(),
             class_hash_to_class: HashMap::default(),
+            declared_class_hashes: HashSet::default(),
             global_class_hash_to_class,
+            state_reads: None,
+            visited_pcs: None,
+            cache_stats: CachedStateStats::default(),
+            max_n_writes: None,
+            write_journal: None,
         }
     }
 
@@ -49,7 +74,247 @@ impl<S: StateReader> CachedState<S> {
     /// will either all happen (will be committed) or none of them (will be discarded).
     pub fn create_transactional(state: &mut CachedState<S>) -> TransactionalState<'_, S> {
         let global_class_hash_to_class = state.global_class_hash_to_class.clone();
-        CachedState::new(MutRefState::new(state), global_class_hash_to_class)
+        let record_state_reads = state.state_reads.is_some();
+        let track_visited_pcs = state.visited_pcs.is_some();
+        // Conservative: entries written by both states count twice.
+        let max_n_writes = state
+            .max_n_writes
+            .map(|max_n_writes| max_n_writes.saturating_sub(state.cache.n_writes()));
+        let mut transactional_state =
+            CachedState::new(MutRefState::new(state), global_class_hash_to_class);
+        if record_state_reads {
+            transactional_state.record_state_reads();
+        }
+        if track_visited_pcs {
+            transactional_state.track_visited_pcs();
+        }
+        if let Some(max_n_writes) = max_n_writes {
+            transactional_state.limit_writes(max_n_writes);
+        }
+
+        transactional_state
+    }
+
+    /// Creates a transactional instance from the given cached state that shares none of its
+    /// caches: its class cache starts empty, rather than being the global one, and it is meant to
+    /// be dropped, so that its recorded reads and visited program counters are not merged into the
+    /// given state. Values it reads through the given state are still cached there.
+    /// Used to execute a transaction without affecting later executions; e.g., by the determinism
+    /// audit.
+    pub fn create_isolated_transactional(state: &mut CachedState<S>) -> TransactionalState<'_, S> {
+        let mut transactional_state = Self::create_transactional(state);
+        transactional_state.global_class_hash_to_class = GlobalContractCache::default();
+        transactional_state
+    }
+
+    /// Starts recording the values read from the underlying state, so that the executed
+    /// transactions can later be re-executed without access to the full state (e.g., in an OS
+    /// run). Entries written before being read are read on their first write, so that their
+    /// previous values are recorded too. Values that were already cached when the recording
+    /// started are not recorded.
+    pub fn record_state_reads(&mut self) {
+        self.state_reads.get_or_insert_with(StateMaps::default);
+    }
+
+    /// Returns the values read from the underlying state since the recording started, if it did.
+    pub fn state_reads(&self) -> Option<&StateMaps> {
+        self.state_reads.as_ref()
+    }
+
+    /// Starts tracking the program counters visited during execution, per class; this traces the
+    /// executions, and thus slows them down.
+    pub fn track_visited_pcs(&mut self) {
+        self.visited_pcs.get_or_insert_with(HashMap::default);
+    }
+
+    /// Returns the program counters visited since the tracking started, per class, if it did.
+    /// Program counters are offsets into the bytecode of the class.
+    pub fn visited_pcs(&self) -> Option<&HashMap<ClassHash, HashSet<usize>>> {
+        self.visited_pcs.as_ref()
+    }
+
+    /// Merges the program counters visited by a child state into those of this state.
+    pub fn absorb_visited_pcs(
+        &mut self,
+        child_visited_pcs: Option<HashMap<ClassHash, HashSet<usize>>>,
+    ) {
+        for (class_hash, pcs) in child_visited_pcs.into_iter().flatten() {
+            self.add_visited_pcs(class_hash, &pcs);
+        }
+    }
+
+    /// Limits the number of entries (storage cells, nonces, class hashes and compiled class hashes)
+    /// this state may write, bounding its memory; a write of a new entry beyond the limit fails
+    /// with `StateError::WriteLimitExceeded`. Transactional states created from this state are
+    /// limited to the remaining number of writes.
+    pub fn limit_writes(&mut self, max_n_writes: usize) {
+        self.max_n_writes = Some(max_n_writes);
+    }
+
+    /// Fails if writing a new entry would exceed the write limit; rewrites are always allowed.
+    fn check_write_limit(&self, is_new_entry: bool) -> StateResult<()> {
+        match self.max_n_writes {
+            Some(max_n_writes) if is_new_entry && self.cache.n_writes() >= max_n_writes => {
+                Err(StateError::WriteLimitExceeded { max_n_writes })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Records a write in the write journal, if kept (see `snapshot`).
+    fn journal_write(&mut self, journal_entry: JournalEntry) {
+        if let Some(write_journal) = &mut self.write_journal {
+            write_journal.push(journal_entry);
+        }
+    }
+
+    /// Records the declaration of the given class in the write journal, if kept.
+    fn journal_class_declaration(&mut self, class_hash: ClassHash) {
+        if let Some(write_journal) = &mut self.write_journal {
+            write_journal.push(JournalEntry::DeclaredClass {
+                class_hash,
+                previous_class: self.class_hash_to_class.get(&class_hash).cloned(),
+                was_declared: self.declared_class_hashes.contains(&class_hash),
+            });
+        }
+    }
+
+    /// Returns the hit and miss counts of the caches of this state; lookups that miss are
+    /// forwarded to the underlying state, which counts its own.
+    pub fn cache_stats(&self) -> CachedStateStats {
+        self.cache_stats
+    }
+
+    /// Reports the cache statistics of this state (see `CachedStateStats::report`) and the
+    /// occupancy of the global contract class cache.
+    pub fn report_cache_metrics(&mut self, metrics_sink: &dyn MetricsSink) {
+        self.cache_stats.report(metrics_sink);
+        self.global_class_hash_to_class.report(metrics_sink);
+    }
+
+    /// Merges the classes read by a child state into the recorded reads of this state.
+    /// Other reads of the child state pass through this state, which records them itself;
+    /// classes, however, may be fetched directly from the global cache.
+    pub fn absorb_class_reads(&mut self, child_state_reads: Option<StateMaps>) {
+        let (Some(state_reads), Some(child_state_reads)) =
+            (&mut self.state_reads, child_state_reads)
+        else {
+            return;
+        };
+
+        // Skip classes declared during the execution; they are not part of the state.
+        for (class_hash, contract_class) in child_state_reads.classes {
+            if !self.declared_class_hashes.contains(&class_hash) {
+                state_reads.classes.insert(class_hash, contract_class);
+            }
+        }
+    }
+
+    /// Returns a checkpoint of the writes done through this state so far, to which it can later be
+    /// rolled back (see `restore`); e.g., to speculatively execute transactions in different
+    /// orders. From the first checkpoint on, each write records the previous value of its entry,
+    /// so that taking a checkpoint costs no copy.
+    pub fn snapshot(&mut self) -> StateCheckpoint {
+        let write_journal = self.write_journal.get_or_insert_with(Vec::new);
+        StateCheckpoint { n_journal_entries: write_journal.len() }
+    }
+
+    /// Rolls this state back to the given checkpoint, which must have been taken from it, undoing
+    /// all writes (including class declarations) done since, in reverse order; checkpoints taken
+    /// since are invalidated.
+    /// Values read from the underlying state remain cached, since it is unaffected by writes; as
+    /// do the recorded reads and the visited program counters, if tracked.
+    pub fn restore(&mut self, checkpoint: StateCheckpoint) {
+        let write_journal = self
+            .write_journal
+            .as_mut()
+            .expect("The checkpoint must have been taken from this state.");
+        assert!(
+            checkpoint.n_journal_entries <= write_journal.len(),
+            "The checkpoint must not have been invalidated by restoring an earlier one."
+        );
+
+        let cache = &mut self.cache;
+        for journal_entry in write_journal.drain(checkpoint.n_journal_entries..).rev() {
+            match journal_entry {
+                JournalEntry::Storage(contract_storage_key, previous_value) => {
+                    undo_write(&mut cache.storage_writes, contract_storage_key, previous_value)
+                }
+                JournalEntry::Nonce(contract_address, previous_value) => {
+                    undo_write(&mut cache.nonce_writes, contract_address, previous_value)
+                }
+                JournalEntry::ClassHash(contract_address, previous_value) => {
+                    undo_write(&mut cache.class_hash_writes, contract_address, previous_value)
+                }
+                JournalEntry::CompiledClassHash(class_hash, previous_value) => {
+                    undo_write(&mut cache.compiled_class_hash_writes, class_hash, previous_value)
+                }
+                JournalEntry::DeclaredClass { class_hash, previous_class, was_declared } => {
+                    undo_write(&mut self.class_hash_to_class, class_hash, previous_class);
+                    if !was_declared {
+                        self.declared_class_hashes.remove(&class_hash);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the contents of the caches of this state: the values read from the underlying state
+    /// and the values written, so that an execution can be captured (e.g., serialized, with a
+    /// failing transaction) and reproduced without access to the underlying state.
+    /// Classes are referenced by their hashes; see `StateCacheDump`.
+    pub fn dump_cache(&self) -> StateCacheDump {
+        let cache = &self.cache;
+        StateCacheDump {
+            initial_values: StateEntries::new(
+                &cache.storage_initial_values,
+                &cache.nonce_initial_values,
+                &cache.class_hash_initial_values,
+                &cache.compiled_class_hash_initial_values,
+            ),
+            writes: StateEntries::new(
+                &cache.storage_writes,
+                &cache.nonce_writes,
+                &cache.class_hash_writes,
+                &cache.compiled_class_hash_writes,
+            ),
+            class_hashes: self.class_hash_to_class.keys().copied().collect(),
+        }
+    }
+
+    /// Replaces the contents of the caches of this state by the given dump (see `dump_cache`); the
+    /// classes of the dump are fetched into the class cache, and must be available to this state
+    /// (otherwise, the state is left unchanged). Invalidates the checkpoints taken from this state.
+    /// To re-execute the transactions that led to the dump, restore its initial values only.
+    pub fn restore_cache(&mut self, dump: StateCacheDump) -> StateResult<()> {
+        let StateCacheDump { initial_values, writes, class_hashes } = dump;
+        let mut class_hash_to_class = ContractClassMapping::default();
+        for class_hash in class_hashes {
+            let contract_class = match self.global_class_hash_to_class.get(&class_hash) {
+                Some(contract_class) => contract_class,
+                None => self.state.get_compiled_contract_class(class_hash)?,
+            };
+            class_hash_to_class.insert(class_hash, contract_class);
+        }
+
+        self.class_hash_to_class = class_hash_to_class;
+        self.declared_class_hashes.clear();
+        self.write_journal = None;
+        self.cache = StateCache {
+            nonce_initial_values: initial_values.nonces.into_iter().collect(),
+            class_hash_initial_values: initial_values.class_hashes.into_iter().collect(),
+            storage_initial_values: StateEntries::flatten_storage(initial_values.storage).collect(),
+            compiled_class_hash_initial_values: initial_values
+                .compiled_class_hashes
+                .into_iter()
+                .collect(),
+            nonce_writes: writes.nonces.into_iter().collect(),
+            class_hash_writes: writes.class_hashes.into_iter().collect(),
+            storage_writes: StateEntries::flatten_storage(writes.storage).collect(),
+            compiled_class_hash_writes: writes.compiled_class_hashes.into_iter().collect(),
+        };
+
+        Ok(())
     }
 
     /// Returns the storage changes done through this state.
@@ -107,7 +372,7 @@ impl<S: StateReader> CachedState<S> {
     pub fn move_classes_to_global_cache(&mut self) {
         let contract_class_updates: Vec<_> = self.class_hash_to_class.drain().collect();
         for (key, value) in contract_class_updates {
-            self.global_class_hash_to_class().cache_set(key, value);
+            self.global_class_hash_to_class.set(key, value);
         }
     }
 
@@ -120,6 +385,37 @@ impl<S: StateReader> CachedState<S> {
     }
 
     pub fn update_cache(&mut self, cache_updates: StateCache) {
+        if let Some(write_journal) = &mut self.write_journal {
+            let cache = &self.cache;
+            write_journal.extend(cache_updates.nonce_writes.keys().map(|&contract_address| {
+                JournalEntry::Nonce(
+                    contract_address,
+                    cache.nonce_writes.get(&contract_address).copied(),
+                )
+            }));
+            write_journal.extend(cache_updates.class_hash_writes.keys().map(|&contract_address| {
+                JournalEntry::ClassHash(
+                    contract_address,
+                    cache.class_hash_writes.get(&contract_address).copied(),
+                )
+            }));
+            write_journal.extend(cache_updates.storage_writes.keys().map(
+                |&contract_storage_key| {
+                    JournalEntry::Storage(
+                        contract_storage_key,
+                        cache.storage_writes.get(&contract_storage_key).copied(),
+                    )
+                },
+            ));
+            write_journal.extend(cache_updates.compiled_class_hash_writes.keys().map(
+                |&class_hash| {
+                    JournalEntry::CompiledClassHash(
+                        class_hash,
+                        cache.compiled_class_hash_writes.get(&class_hash).copied(),
+                    )
+                },
+            ));
+        }
         self.cache.nonce_writes.extend(cache_updates.nonce_writes);
         self.cache.class_hash_writes.extend(cache_updates.class_hash_writes);
         self.cache.storage_writes.extend(cache_updates.storage_writes);
@@ -129,9 +425,14 @@ impl<S: StateReader> CachedState<S> {
     pub fn update_contract_class_caches(
         &mut self,
         local_contract_cache_updates: ContractClassMapping,
+        declared_class_hashes: HashSet<ClassHash>,
         global_contract_cache: GlobalContractCache,
     ) {
+        for &class_hash in &declared_class_hashes {
+            self.journal_class_declaration(class_hash);
+        }
         self.class_hash_to_class.extend(local_contract_cache_updates);
+        self.declared_class_hashes.extend(declared_class_hashes);
         self.global_class_hash_to_class = global_contract_cache;
     }
 
@@ -174,6 +475,21 @@ impl<S: StateReader> CachedState<S> {
         Ok(())
     }
 
+    /// Returns the number of written cells that are omitted from the state diff (see
+    /// `to_state_diff`), since their value is unchanged.
+    pub fn squashed_write_counts(&mut self) -> StateResult<SquashedWriteCounts> {
+        self.update_initial_values_of_write_only_access()?;
+
+        let cache = &self.cache;
+        Ok(SquashedWriteCounts {
+            storage: cache.storage_writes.len() - cache.get_storage_updates().len(),
+            nonces: cache.nonce_writes.len() - cache.get_nonce_updates().len(),
+            class_hashes: cache.class_hash_writes.len() - cache.get_class_hash_updates().len(),
+        })
+    }
+
+    /// Returns the changes done through this state; writes that leave a cell with its initial
+    /// value are omitted.
     pub fn to_state_diff(&mut self) -> CommitmentStateDiff {
         type StorageDiff = IndexMap<ContractAddress, IndexMap<StorageKey, StarkFelt>>;
 
@@ -184,8 +500,7 @@ impl<S: StateReader> CachedState<S> {
         let state_cache = &self.cache;
         let class_hash_updates = state_cache.get_class_hash_updates();
         let storage_diffs = state_cache.get_storage_updates();
-        let nonces =
-            subtract_mappings(&state_cache.nonce_writes, &state_cache.nonce_initial_values);
+        let nonces = state_cache.get_nonce_updates();
         let declared_classes = state_cache.compiled_class_hash_writes.clone();
 
         CommitmentStateDiff {
@@ -209,9 +524,14 @@ impl<S: StateReader> StateReader for CachedState<S> {
         contract_address: ContractAddress,
         key: StorageKey,
     ) -> StateResult<StarkFelt> {
-        if self.cache.get_storage_at(contract_address, key).is_none() {
+        let cached = self.cache.get_storage_at(contract_address, key).is_some();
+        self.cache_stats.storage.record(cached);
+        if !cached {
             let storage_value = self.state.get_storage_at(contract_address, key)?;
             self.cache.set_storage_initial_value(contract_address, key, storage_value);
+            if let Some(state_reads) = &mut self.state_reads {
+                state_reads.storage.insert((contract_address, key), storage_value);
+            }
         }
 
         let value = self.cache.get_storage_at(contract_address, key).unwrap_or_else(|| {
@@ -221,9 +541,14 @@ impl<S: StateReader> StateReader for CachedState<S> {
     }
 
     fn get_nonce_at(&mut self, contract_address: ContractAddress) -> StateResult<Nonce> {
-        if self.cache.get_nonce_at(contract_address).is_none() {
+        let cached = self.cache.get_nonce_at(contract_address).is_some();
+        self.cache_stats.nonces.record(cached);
+        if !cached {
             let nonce = self.state.get_nonce_at(contract_address)?;
             self.cache.set_nonce_initial_value(contract_address, nonce);
+            if let Some(state_reads) = &mut self.state_reads {
+                state_reads.nonces.insert(contract_address, nonce);
+            }
         }
 
         let nonce = self
@@ -234,9 +559,14 @@ impl<S: StateReader> StateReader for CachedState<S> {
     }
 
     fn get_class_hash_at(&mut self, contract_address: ContractAddress) -> StateResult<ClassHash> {
-        if self.cache.get_class_hash_at(contract_address).is_none() {
+        let cached = self.cache.get_class_hash_at(contract_address).is_some();
+        self.cache_stats.class_hashes.record(cached);
+        if !cached {
             let class_hash = self.state.get_class_hash_at(contract_address)?;
             self.cache.set_class_hash_initial_value(contract_address, class_hash);
+            if let Some(state_reads) = &mut self.state_reads {
+                state_reads.class_hashes.insert(contract_address, class_hash);
+            }
         }
 
         let class_hash = self
@@ -250,19 +580,19 @@ impl<S: StateReader> StateReader for CachedState<S> {
     // Clippy solution don't work because it required two mutable ref to self
     // Could probably be solved with interior mutability
     fn get_compiled_contract_class(&mut self, class_hash: ClassHash) -> StateResult<ContractClass> {
-        if !self.class_hash_to_class.contains_key(&class_hash) {
-            let contract_class = self.global_class_hash_to_class().cache_get(&class_hash).cloned();
-
-            match contract_class {
-                Some(contract_class_from_global_cache) => {
-                    self.class_hash_to_class.insert(class_hash, contract_class_from_global_cache);
-                }
-                None => {
-                    let contract_class_from_db =
-                        self.state.get_compiled_contract_class(class_hash)?;
-                    self.class_hash_to_class.insert(class_hash, contract_class_from_db);
-                }
+        let cached = self.class_hash_to_class.contains_key(&class_hash);
+        self.cache_stats.contract_classes.record(cached);
+        if !cached {
+            let contract_class = self.global_class_hash_to_class.get(&class_hash);
+
+            let contract_class = match contract_class {
+                Some(contract_class_from_global_cache) => contract_class_from_global_cache,
+                None => self.state.get_compiled_contract_class(class_hash)?,
+            };
+            if let Some(state_reads) = &mut self.state_reads {
+                state_reads.classes.insert(class_hash, contract_class.clone());
             }
+            self.class_hash_to_class.insert(class_hash, contract_class);
         }
 
         let contract_class = self
@@ -275,9 +605,14 @@ impl<S: StateReader> StateReader for CachedState<S> {
     }
 
     fn get_compiled_class_hash(&mut self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
-        if self.cache.get_compiled_class_hash(class_hash).is_none() {
+        let cached = self.cache.get_compiled_class_hash(class_hash).is_some();
+        self.cache_stats.compiled_class_hashes.record(cached);
+        if !cached {
             let compiled_class_hash = self.state.get_compiled_class_hash(class_hash)?;
             self.cache.set_compiled_class_hash_initial_value(class_hash, compiled_class_hash);
+            if let Some(state_reads) = &mut self.state_reads {
+                state_reads.compiled_class_hashes.insert(class_hash, compiled_class_hash);
+            }
         }
 
         let compiled_class_hash = self
@@ -295,6 +630,13 @@ impl<S: StateReader> State for CachedState<S> {
         key: StorageKey,
         value: StarkFelt,
     ) -> StateResult<()> {
+        if self.state_reads.is_some() {
+            self.get_storage_at(contract_address, key)?;
+        }
+        let contract_storage_key = (contract_address, key);
+        let previous_value = self.cache.storage_writes.get(&contract_storage_key).copied();
+        self.check_write_limit(previous_value.is_none())?;
+        self.journal_write(JournalEntry::Storage(contract_storage_key, previous_value));
         self.cache.set_storage_value(contract_address, key, value);
 
         Ok(())
@@ -302,9 +644,12 @@ impl<S: StateReader> State for CachedState<S> {
 
     fn increment_nonce(&mut self, contract_address: ContractAddress) -> StateResult<()> {
         let current_nonce = self.get_nonce_at(contract_address)?;
-        let current_nonce_as_u64 = usize::try_from(current_nonce.0)? as u64;
+        let current_nonce_as_u64 = u64::try_from(current_nonce.0)?;
         let next_nonce_val = 1_u64 + current_nonce_as_u64;
         let next_nonce = Nonce(StarkFelt::from(next_nonce_val));
+        let previous_value = self.cache.nonce_writes.get(&contract_address).copied();
+        self.check_write_limit(previous_value.is_none())?;
+        self.journal_write(JournalEntry::Nonce(contract_address, previous_value));
         self.cache.set_nonce_value(contract_address, next_nonce);
 
         Ok(())
@@ -319,6 +664,12 @@ impl<S: StateReader> State for CachedState<S> {
             return Err(StateError::OutOfRangeContractAddress);
         }
 
+        if self.state_reads.is_some() {
+            self.get_class_hash_at(contract_address)?;
+        }
+        let previous_value = self.cache.class_hash_writes.get(&contract_address).copied();
+        self.check_write_limit(previous_value.is_none())?;
+        self.journal_write(JournalEntry::ClassHash(contract_address, previous_value));
         self.cache.set_class_hash_write(contract_address, class_hash);
         Ok(())
     }
@@ -328,7 +679,9 @@ impl<S: StateReader> State for CachedState<S> {
         class_hash: ClassHash,
         contract_class: ContractClass,
     ) -> StateResult<()> {
+        self.journal_class_declaration(class_hash);
         self.class_hash_to_class.insert(class_hash, contract_class);
+        self.declared_class_hashes.insert(class_hash);
         Ok(())
     }
 
@@ -337,9 +690,25 @@ impl<S: StateReader> State for CachedState<S> {
         class_hash: ClassHash,
         compiled_class_hash: CompiledClassHash,
     ) -> StateResult<()> {
+        if self.state_reads.is_some() {
+            self.get_compiled_class_hash(class_hash)?;
+        }
+        let previous_value = self.cache.compiled_class_hash_writes.get(&class_hash).copied();
+        self.check_write_limit(previous_value.is_none())?;
+        self.journal_write(JournalEntry::CompiledClassHash(class_hash, previous_value));
         self.cache.set_compiled_class_hash_write(class_hash, compiled_class_hash);
         Ok(())
     }
+
+    fn tracks_visited_pcs(&self) -> bool {
+        self.visited_pcs.is_some()
+    }
+
+    fn add_visited_pcs(&mut self, class_hash: ClassHash, pcs: &HashSet<usize>) {
+        if let Some(visited_pcs) = &mut self.visited_pcs {
+            visited_pcs.entry(class_hash).or_default().extend(pcs);
+        }
+    }
 }
 
 #[cfg(any(feature = "testing", test))]
@@ -349,13 +718,113 @@ impl Default for CachedState<crate::test_utils::dict_state_reader::DictStateRead
             state: Default::default(),
             cache: Default::default(),
             class_hash_to_class: Default::default(),
+            declared_class_hashes: Default::default(),
             global_class_hash_to_class: Default::default(),
+            state_reads: None,
+            visited_pcs: None,
+            cache_stats: Default::default(),
+            max_n_writes: None,
+            write_journal: None,
         }
     }
 }
 
 pub type StorageEntry = (ContractAddress, StorageKey);
 
+/// A checkpoint of the writes done through a `CachedState`; see `CachedState::snapshot`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StateCheckpoint {
+    // The length of the write journal of the state when the checkpoint was taken.
+    n_journal_entries: usize,
+}
+
+/// A write done through a `CachedState`, with the previous value of the written entry (`None` if
+/// it was not written before); see `CachedState::restore`.
+#[derive(Debug)]
+enum JournalEntry {
+    Storage(StorageEntry, Option<StarkFelt>),
+    Nonce(ContractAddress, Option<Nonce>),
+    ClassHash(ContractAddress, Option<ClassHash>),
+    CompiledClassHash(ClassHash, Option<CompiledClassHash>),
+    DeclaredClass {
+        class_hash: ClassHash,
+        previous_class: Option<ContractClass>,
+        was_declared: bool,
+    },
+}
+
+fn undo_write<K: Eq + std::hash::Hash, V>(
+    map: &mut HashMap<K, V>,
+    key: K,
+    previous_value: Option<V>,
+) {
+    match previous_value {
+        Some(previous_value) => map.insert(key, previous_value),
+        None => map.remove(&key),
+    };
+}
+
+/// The contents of the caches of a `CachedState`; see `CachedState::dump_cache`.
+/// Classes are referenced by their hashes, since compiled classes do not round-trip through
+/// serialization.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateCacheDump {
+    pub initial_values: StateEntries,
+    pub writes: StateEntries,
+    pub class_hashes: BTreeSet<ClassHash>,
+}
+
+/// State entries, ordered by key; storage is nested by contract, so that the entries serialize to
+/// JSON objects.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateEntries {
+    pub storage: BTreeMap<ContractAddress, BTreeMap<StorageKey, StarkFelt>>,
+    pub nonces: BTreeMap<ContractAddress, Nonce>,
+    pub class_hashes: BTreeMap<ContractAddress, ClassHash>,
+    pub compiled_class_hashes: BTreeMap<ClassHash, CompiledClassHash>,
+}
+
+impl StateEntries {
+    fn new(
+        storage: &HashMap<StorageEntry, StarkFelt>,
+        nonces: &HashMap<ContractAddress, Nonce>,
+        class_hashes: &HashMap<ContractAddress, ClassHash>,
+        compiled_class_hashes: &HashMap<ClassHash, CompiledClassHash>,
+    ) -> Self {
+        let mut nested_storage: BTreeMap<ContractAddress, BTreeMap<StorageKey, StarkFelt>> =
+            BTreeMap::new();
+        for (&(address, key), &value) in storage {
+            nested_storage.entry(address).or_default().insert(key, value);
+        }
+
+        Self {
+            storage: nested_storage,
+            nonces: nonces.iter().map(|(k, v)| (*k, *v)).collect(),
+            class_hashes: class_hashes.iter().map(|(k, v)| (*k, *v)).collect(),
+            compiled_class_hashes: compiled_class_hashes.iter().map(|(k, v)| (*k, *v)).collect(),
+        }
+    }
+
+    fn flatten_storage(
+        storage: BTreeMap<ContractAddress, BTreeMap<StorageKey, StarkFelt>>,
+    ) -> impl Iterator<Item = (StorageEntry, StarkFelt)> {
+        storage.into_iter().flat_map(|(address, contract_storage)| {
+            contract_storage.into_iter().map(move |(key, value)| ((address, key), value))
+        })
+    }
+}
+
+impl From<&StateMaps> for StateEntries {
+    fn from(state_maps: &StateMaps) -> Self {
+        Self::new(
+            &state_maps.storage,
+            &state_maps.nonces,
+            &state_maps.class_hashes,
+            &state_maps.compiled_class_hashes,
+        )
+    }
+}
+
 #[derive(Debug, Default, IntoIterator)]
 pub struct StorageView(pub HashMap<StorageEntry, StarkFelt>);
 
@@ -423,6 +892,13 @@ impl StateCache {
         self.storage_initial_values.insert(contract_storage_key, value);
     }
 
+    fn n_writes(&self) -> usize {
+        self.storage_writes.len()
+            + self.nonce_writes.len()
+            + self.class_hash_writes.len()
+            + self.compiled_class_hash_writes.len()
+    }
+
     fn set_storage_value(
         &mut self,
         contract_address: ContractAddress,
@@ -575,6 +1051,14 @@ impl<'a, S: State + ?Sized> State for MutRefState<'a, S> {
     ) -> StateResult<()> {
         self.0.set_compiled_class_hash(class_hash, compiled_class_hash)
     }
+
+    fn tracks_visited_pcs(&self) -> bool {
+        self.0.tracks_visited_pcs()
+    }
+
+    fn add_visited_pcs(&mut self, class_hash: ClassHash, pcs: &HashSet<usize>) {
+        self.0.add_visited_pcs(class_hash, pcs)
+    }
 }
 
 pub type TransactionalState<'a, S> = CachedState<MutRefState<'a, CachedState<S>>>;
@@ -587,12 +1071,22 @@ impl<'a, S: StateReader> TransactionalState<'a, S> {
         tx_executed_class_hashes: HashSet<ClassHash>,
         tx_visited_storage_entries: HashSet<StorageEntry>,
     ) -> StagedTransactionalState {
-        let TransactionalState { cache, class_hash_to_class, global_class_hash_to_class, .. } =
-            self;
+        let TransactionalState {
+            cache,
+            class_hash_to_class,
+            declared_class_hashes,
+            global_class_hash_to_class,
+            state_reads,
+            visited_pcs,
+            ..
+        } = self;
         StagedTransactionalState {
             cache,
             class_hash_to_class,
+            declared_class_hashes,
             global_class_hash_to_class,
+            state_reads,
+            visited_pcs,
             tx_executed_class_hashes,
             tx_visited_storage_entries,
         }
@@ -602,13 +1096,22 @@ impl<'a, S: StateReader> TransactionalState<'a, S> {
     pub fn commit(self) {
         let state = self.state.0;
         let child_cache = self.cache;
+        state.absorb_class_reads(self.state_reads);
+        state.absorb_visited_pcs(self.visited_pcs);
         state.update_cache(child_cache);
-        state
-            .update_contract_class_caches(self.class_hash_to_class, self.global_class_hash_to_class)
+        state.update_contract_class_caches(
+            self.class_hash_to_class,
+            self.declared_class_hashes,
+            self.global_class_hash_to_class,
+        )
     }
 
-    /// Drops `self`.
-    pub fn abort(self) {}
+    /// Drops `self`; the recorded state reads and visited program counters, if any, are kept, as
+    /// the aborted execution still took place (e.g., when reverting a transaction).
+    pub fn abort(self) {
+        self.state.0.absorb_class_reads(self.state_reads);
+        self.state.0.absorb_visited_pcs(self.visited_pcs);
+    }
 }
 
 /// Represents the interim state, containing the changes made by a transaction after execution but
@@ -617,15 +1120,87 @@ impl<'a, S: StateReader> TransactionalState<'a, S> {
 pub struct StagedTransactionalState {
     pub cache: StateCache,
     pub class_hash_to_class: ContractClassMapping,
+    pub declared_class_hashes: HashSet<ClassHash>,
     pub global_class_hash_to_class: GlobalContractCache,
+    pub state_reads: Option<StateMaps>,
+    pub visited_pcs: Option<HashMap<ClassHash, HashSet<usize>>>,
 
     // Maintained for counting purposes.
     pub tx_executed_class_hashes: HashSet<ClassHash>,
     pub tx_visited_storage_entries: HashSet<StorageEntry>,
 }
 
+/// The hit and miss counts of a single cache.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    fn record(&mut self, hit: bool) {
+        match hit {
+            true => self.hits += 1,
+            false => self.misses += 1,
+        }
+    }
+}
+
+/// The hit and miss counts of the caches of a `CachedState`, per cache type.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CachedStateStats {
+    pub storage: CacheStats,
+    pub nonces: CacheStats,
+    pub class_hashes: CacheStats,
+    pub compiled_class_hashes: CacheStats,
+    pub contract_classes: CacheStats,
+}
+
+impl CachedStateStats {
+    /// Reports the hit and miss counts, labeled by cache.
+    pub fn report(&self, metrics_sink: &dyn MetricsSink) {
+        for (cache, stats) in [
+            ("storage", self.storage),
+            ("nonces", self.nonces),
+            ("class_hashes", self.class_hashes),
+            ("compiled_class_hashes", self.compiled_class_hashes),
+            ("contract_classes", self.contract_classes),
+        ] {
+            metrics_sink.increment_counter(STATE_CACHE_HITS, &[("cache", cache)], stats.hits);
+            metrics_sink.increment_counter(STATE_CACHE_MISSES, &[("cache", cache)], stats.misses);
+        }
+    }
+}
+
+/// The number of cells written through a `CachedState` whose final value equals their initial
+/// one, per cell type; e.g., due to write-then-revert patterns. Such writes are squashed, i.e.,
+/// omitted from the state diff, so they incur no data availability cost.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SquashedWriteCounts {
+    pub storage: usize,
+    pub nonces: usize,
+    pub class_hashes: usize,
+}
+
+impl SquashedWriteCounts {
+    pub fn total(&self) -> usize {
+        self.storage + self.nonces + self.class_hashes
+    }
+}
+
+/// Holds a value for each of the state entries it covers; e.g., the values read from the state
+/// during an execution.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StateMaps {
+    pub nonces: HashMap<ContractAddress, Nonce>,
+    pub class_hashes: HashMap<ContractAddress, ClassHash>,
+    pub storage: HashMap<StorageEntry, StarkFelt>,
+    pub compiled_class_hashes: HashMap<ClassHash, CompiledClassHash>,
+    pub classes: ContractClassMapping,
+}
+
 /// Holds uncommitted changes induced on Starknet contracts.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct CommitmentStateDiff {
     // Contract instance attributes (per address).
     pub address_to_class_hash: IndexMap<ContractAddress, ClassHash>,
@@ -661,6 +1236,78 @@ impl StateChanges {
 
         merged_state_changes
     }
+
+    /// Returns the keys of the state changes, dropping their values.
+    pub fn into_keys(self) -> StateChangesKeys {
+        StateChangesKeys {
+            storage_keys: self.storage_updates.into_keys().collect(),
+            class_hash_keys: self.class_hash_updates.into_keys().collect(),
+            compiled_class_hash_keys: self.compiled_class_hash_updates.into_keys().collect(),
+            modified_contracts: self.modified_contracts,
+        }
+    }
+}
+
+/// Holds the keys of the state changes, without their values; this suffices for counting the state
+/// changes (e.g., for data availability pricing), while the full diff is required for commitments.
+/// Unlike state changes, keys can be merged in any order.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StateChangesKeys {
+    pub storage_keys: HashSet<StorageEntry>,
+    pub class_hash_keys: HashSet<ContractAddress>,
+    pub compiled_class_hash_keys: HashSet<ClassHash>,
+    pub modified_contracts: HashSet<ContractAddress>,
+}
+
+impl StateChangesKeys {
+    /// Merges the given state changes keys into a single one.
+    pub fn merge(state_changes_keys: Vec<Self>) -> Self {
+        let mut merged_keys = Self::default();
+        for keys in state_changes_keys {
+            merged_keys.extend(keys);
+        }
+
+        merged_keys
+    }
+
+    pub fn extend(&mut self, other: Self) {
+        self.storage_keys.extend(other.storage_keys);
+        self.class_hash_keys.extend(other.class_hash_keys);
+        self.compiled_class_hash_keys.extend(other.compiled_class_hash_keys);
+        self.modified_contracts.extend(other.modified_contracts);
+    }
+
+    /// Returns the keys that are not contained in the given keys; e.g., the keys a transaction
+    /// adds to those already accumulated in the block.
+    pub fn difference(&self, other: &Self) -> Self {
+        Self {
+            storage_keys: self.storage_keys.difference(&other.storage_keys).copied().collect(),
+            class_hash_keys: self
+                .class_hash_keys
+                .difference(&other.class_hash_keys)
+                .copied()
+                .collect(),
+            compiled_class_hash_keys: self
+                .compiled_class_hash_keys
+                .difference(&other.compiled_class_hash_keys)
+                .copied()
+                .collect(),
+            modified_contracts: self
+                .modified_contracts
+                .difference(&other.modified_contracts)
+                .copied()
+                .collect(),
+        }
+    }
+
+    pub fn count(&self) -> StateChangesCount {
+        StateChangesCount {
+            n_storage_updates: self.storage_keys.len(),
+            n_class_hash_updates: self.class_hash_keys.len(),
+            n_compiled_class_hash_updates: self.compiled_class_hash_keys.len(),
+            n_modified_contracts: self.modified_contracts.len(),
+        }
+    }
 }
 
 /// Holds the number of state changes.
@@ -683,31 +1330,155 @@ impl From<&StateChanges> for StateChangesCount {
     }
 }
 
-// Note: `ContractClassLRUCache` key-value types must align with `ContractClassMapping`.
-type ContractClassLRUCache = SizedCache<ClassHash, ContractClass>;
+/// A contract class as kept in the global cache.
+#[derive(Clone, Debug, Eq, PartialEq, derive_more::From)]
+pub enum CachedContractClass {
+    Plain(ContractClass),
+    CompressedV0(CompressedContractClassV0),
+}
+
+impl CachedContractClass {
+    /// Compresses Cairo 0 classes; other classes (or, unexpectedly, classes whose program fails to
+    /// serialize) are kept as is.
+    pub fn compress(contract_class: ContractClass) -> Self {
+        if let ContractClass::V0(class) = &contract_class {
+            if let Ok(compressed_class) = class.compress() {
+                return Self::CompressedV0(compressed_class);
+            }
+        }
+
+        Self::Plain(contract_class)
+    }
+
+    pub fn approximate_size_in_bytes(&self) -> usize {
+        match self {
+            Self::Plain(contract_class) => contract_class.approximate_size_in_bytes(),
+            Self::CompressedV0(compressed_class) => compressed_class.approximate_size_in_bytes(),
+        }
+    }
+
+    pub fn decompress(&self) -> ContractClass {
+        match self {
+            Self::Plain(contract_class) => contract_class.clone(),
+            Self::CompressedV0(compressed_class) => compressed_class
+                .decompress()
+                .expect("Compressed classes are decompressible, as they are compressed in-process.")
+                .into(),
+        }
+    }
+}
+
+type ContractClassLRUCache = SizedCache<ClassHash, CachedContractClass>;
 type LockedContractClassCache<'a> = MutexGuard<'a, ContractClassLRUCache>;
 #[derive(Debug, Clone)]
 // Thread-safe LRU cache for contract classes, optimized for inter-language sharing when
 // `blockifier` compiles as a shared library.
-pub struct GlobalContractCache(pub Arc<Mutex<ContractClassLRUCache>>);
+pub struct GlobalContractCache {
+    cache: Arc<Mutex<ContractClassLRUCache>>,
+    // The total approximate size of the cached classes; updated while holding the cache lock.
+    size_in_bytes: Arc<AtomicUsize>,
+    compress_classes: bool,
+    // The memory budget of the cached classes, by their approximate sizes; unlimited if not set.
+    max_size_in_bytes: Option<usize>,
+}
 
 impl GlobalContractCache {
     // TODO(Arni, 7/1/2024): make this configurable via a CachedState constructor argument.
     const CACHE_SIZE: usize = 100;
 
+    /// If `compress_classes` is set, Cairo 0 classes are stored with compressed programs, trading
+    /// a decompression on every read for memory.
+    pub fn new(compress_classes: bool) -> Self {
+        Self {
+            cache: Arc::new(Mutex::new(ContractClassLRUCache::with_size(Self::CACHE_SIZE))),
+            size_in_bytes: Arc::new(AtomicUsize::new(0)),
+            compress_classes,
+            max_size_in_bytes: None,
+        }
+    }
+
+    /// Bounds the approximate memory of the cached classes (see
+    /// `ContractClass::approximate_size_in_bytes`), in addition to their number: the least
+    /// recently used classes are evicted to fit the budget, and larger classes are not cached.
+    pub fn with_max_size_in_bytes(self, max_size_in_bytes: usize) -> Self {
+        Self { max_size_in_bytes: Some(max_size_in_bytes), ..self }
+    }
+
     /// Locks the cache for atomic access. Although conceptually shared, writing to this cache is
     /// only possible for one writer at a time.
+    /// Classes set through the lock are not accounted for in the size of the cache (and in its
+    /// budget); use `set` instead.
     pub fn lock(&mut self) -> LockedContractClassCache<'_> {
-        self.0.lock().expect("Global contract cache is poisoned.")
+        self.cache.lock().expect("Global contract cache is poisoned.")
+    }
+
+    pub fn get(&mut self, class_hash: &ClassHash) -> Option<ContractClass> {
+        // Decompress outside of the lock.
+        let cached_class = self.lock().cache_get(class_hash).cloned();
+        cached_class.as_ref().map(CachedContractClass::decompress)
+    }
+
+    pub fn set(&mut self, class_hash: ClassHash, contract_class: ContractClass) {
+        let cached_class = if self.compress_classes {
+            CachedContractClass::compress(contract_class)
+        } else {
+            CachedContractClass::Plain(contract_class)
+        };
+        let class_size_in_bytes = cached_class.approximate_size_in_bytes();
+        if self
+            .max_size_in_bytes
+            .is_some_and(|max_size_in_bytes| class_size_in_bytes > max_size_in_bytes)
+        {
+            return;
+        }
+
+        let mut cache = self.cache.lock().expect("Global contract cache is poisoned.");
+        let mut size_in_bytes = self.size_in_bytes.load(Ordering::Relaxed);
+        let evict_least_recently_used =
+            |cache: &mut LockedContractClassCache<'_>, size_in_bytes: &mut usize| {
+                let least_recently_used =
+                    *cache.key_order().last().expect("The cache is not empty.");
+                let evicted_class =
+                    cache.cache_remove(&least_recently_used).expect("The key is in the cache.");
+                *size_in_bytes -= evicted_class.approximate_size_in_bytes();
+            };
+        // A replaced class frees its size; when full, the cache would evict its least recently
+        // used class on insertion, which is done here instead, so that the size is kept track of.
+        if let Some(replaced_class) = cache.cache_remove(&class_hash) {
+            size_in_bytes -= replaced_class.approximate_size_in_bytes();
+        }
+        if cache.cache_capacity().is_some_and(|capacity| cache.cache_size() >= capacity) {
+            evict_least_recently_used(&mut cache, &mut size_in_bytes);
+        }
+        cache.cache_set(class_hash, cached_class);
+        size_in_bytes += class_size_in_bytes;
+        if let Some(max_size_in_bytes) = self.max_size_in_bytes {
+            while size_in_bytes > max_size_in_bytes {
+                evict_least_recently_used(&mut cache, &mut size_in_bytes);
+            }
+        }
+        self.size_in_bytes.store(size_in_bytes, Ordering::Relaxed);
     }
 
     pub fn clear(&mut self) {
-        self.lock().cache_clear();
+        let mut cache = self.cache.lock().expect("Global contract cache is poisoned.");
+        cache.cache_clear();
+        self.size_in_bytes.store(0, Ordering::Relaxed);
+    }
+
+    /// Reports the occupancy of the cache, and its cumulative hit and miss counts.
+    pub fn report(&mut self, metrics_sink: &dyn MetricsSink) {
+        let cache = self.cache.lock().expect("Global contract cache is poisoned.");
+        let size_in_bytes = self.size_in_bytes.load(Ordering::Relaxed);
+        metrics_sink.set_gauge(CLASS_CACHE_ENTRIES, &[], cache.cache_size() as f64);
+        metrics_sink.set_gauge(CLASS_CACHE_SIZE_IN_BYTES, &[], size_in_bytes as f64);
+        metrics_sink.set_gauge(CLASS_CACHE_HITS, &[], cache.cache_hits().unwrap_or(0) as f64);
+        metrics_sink.set_gauge(CLASS_CACHE_MISSES, &[], cache.cache_misses().unwrap_or(0) as f64);
     }
 }
 
 impl Default for GlobalContractCache {
     fn default() -> Self {
-        Self(Arc::new(Mutex::new(ContractClassLRUCache::with_size(Self::CACHE_SIZE))))
+        Self::new(false)
     }
 }
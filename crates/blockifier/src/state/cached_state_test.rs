@@ -1,17 +1,30 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use assert_matches::assert_matches;
 use indexmap::indexmap;
 use pretty_assertions::assert_eq;
 use starknet_api::core::PatriciaKey;
 use starknet_api::hash::StarkHash;
-use starknet_api::{class_hash, contract_address, patricia_key, stark_felt};
+use starknet_api::transaction::{Calldata, Fee};
+use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
 
+use crate::abi::abi_utils::selector_from_name;
 use crate::block_context::BlockContext;
+use crate::execution::contract_class::ContractClass;
+use crate::execution::entry_point::{CallEntryPoint, EntryPointExecutionContext};
+use crate::invoke_tx_args;
 use crate::state::cached_state::*;
-use crate::test_utils::cached_state::deprecated_create_test_state;
+use crate::test_utils::cached_state::{create_test_state, deprecated_create_test_state};
+use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::dict_state_reader::DictStateReader;
-use crate::test_utils::{get_test_contract_class, TEST_CLASS_HASH, TEST_EMPTY_CONTRACT_CLASS_HASH};
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{
+    create_calldata, get_test_contract_class, trivial_external_entry_point, CairoVersion, BALANCE,
+    MAX_FEE, TEST_CLASS_HASH, TEST_CONTRACT_ADDRESS, TEST_EMPTY_CONTRACT_CLASS_HASH,
+};
+use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::test_utils::run_invoke_tx;
+use crate::transaction::transaction_execution::Transaction;
 
 fn set_initial_state_values(
     state: &mut CachedState<DictStateReader>,
@@ -255,6 +268,107 @@ fn cached_state_state_diff_conversion() {
     assert_eq!(expected_state_diff, state.to_state_diff());
 }
 
+#[test]
+fn test_squashed_writes() {
+    let contract_address = contract_address!(TEST_CONTRACT_ADDRESS);
+    let [unchanged_key, reverted_key, changed_key] =
+        [0x10_u8, 0x20, 0x30].map(|key| StorageKey(patricia_key!(key)));
+    let mut state = CachedState::from(DictStateReader {
+        storage_view: HashMap::from([((contract_address, reverted_key), stark_felt!(1_u8))]),
+        address_to_class_hash: HashMap::from([(contract_address, class_hash!(TEST_CLASS_HASH))]),
+        ..Default::default()
+    });
+
+    // Rewrite the initial value, write and revert, and write a new value.
+    state.set_storage_at(contract_address, unchanged_key, StarkFelt::ZERO).unwrap();
+    state.set_storage_at(contract_address, reverted_key, stark_felt!(2_u8)).unwrap();
+    state.set_storage_at(contract_address, reverted_key, stark_felt!(1_u8)).unwrap();
+    state.set_storage_at(contract_address, changed_key, stark_felt!(3_u8)).unwrap();
+    state.set_class_hash_at(contract_address, class_hash!(TEST_CLASS_HASH)).unwrap();
+    state.increment_nonce(contract_address).unwrap();
+
+    assert_eq!(
+        state.squashed_write_counts().unwrap(),
+        SquashedWriteCounts { storage: 2, nonces: 0, class_hashes: 1 }
+    );
+    let state_diff = state.to_state_diff();
+    assert_eq!(
+        state_diff.storage_updates,
+        indexmap! {contract_address => indexmap! {changed_key => stark_felt!(3_u8)}}
+    );
+    assert!(state_diff.address_to_class_hash.is_empty());
+    assert_eq!(state_diff.address_to_nonce.len(), 1);
+}
+
+#[test]
+fn test_snapshot_and_restore() {
+    let contract_address = contract_address!(TEST_CONTRACT_ADDRESS);
+    let key = StorageKey(patricia_key!("0x10"));
+    let declared_class_hash = class_hash!("0x999");
+    let mut state = deprecated_create_test_state();
+
+    state.set_storage_at(contract_address, key, stark_felt!(1_u8)).unwrap();
+    let checkpoint = state.snapshot();
+    let state_diff_at_checkpoint = state.to_state_diff();
+
+    // Write, increment the nonce, replace the class and declare one.
+    state.set_storage_at(contract_address, key, stark_felt!(2_u8)).unwrap();
+    state.increment_nonce(contract_address).unwrap();
+    state.set_class_hash_at(contract_address, declared_class_hash).unwrap();
+    state.set_contract_class(declared_class_hash, get_test_contract_class()).unwrap();
+    state.set_compiled_class_hash(declared_class_hash, CompiledClassHash::default()).unwrap();
+
+    // Nested checkpoint.
+    let nested_checkpoint = state.snapshot();
+    state.set_storage_at(contract_address, key, stark_felt!(3_u8)).unwrap();
+    state.restore(nested_checkpoint);
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), stark_felt!(2_u8));
+
+    // The same checkpoint may be restored more than once.
+    for _ in 0..2 {
+        state.restore(checkpoint.clone());
+        assert_eq!(state.get_storage_at(contract_address, key).unwrap(), stark_felt!(1_u8));
+        assert_eq!(state.get_nonce_at(contract_address).unwrap(), Nonce::default());
+        assert_eq!(
+            state.get_class_hash_at(contract_address).unwrap(),
+            class_hash!(TEST_CLASS_HASH)
+        );
+        assert_matches!(
+            state.get_compiled_contract_class(declared_class_hash).unwrap_err(),
+            StateError::UndeclaredClassHash(_)
+        );
+        assert_eq!(state.to_state_diff(), state_diff_at_checkpoint);
+
+        state.set_storage_at(contract_address, key, stark_felt!(4_u8)).unwrap();
+    }
+}
+
+#[test]
+fn test_restore_after_transactional_commit() {
+    let contract_address = contract_address!(TEST_CONTRACT_ADDRESS);
+    let key = StorageKey(patricia_key!("0x10"));
+    let declared_class_hash = class_hash!("0x999");
+    let mut state = deprecated_create_test_state();
+    state.set_storage_at(contract_address, key, stark_felt!(1_u8)).unwrap();
+    let checkpoint = state.snapshot();
+
+    // The writes committed by a transactional state are undone as well.
+    let mut transactional_state = CachedState::create_transactional(&mut state);
+    transactional_state.set_storage_at(contract_address, key, stark_felt!(2_u8)).unwrap();
+    transactional_state.increment_nonce(contract_address).unwrap();
+    transactional_state.set_contract_class(declared_class_hash, get_test_contract_class()).unwrap();
+    transactional_state.commit();
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), stark_felt!(2_u8));
+
+    state.restore(checkpoint);
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), stark_felt!(1_u8));
+    assert_eq!(state.get_nonce_at(contract_address).unwrap(), Nonce::default());
+    assert_matches!(
+        state.get_compiled_contract_class(declared_class_hash).unwrap_err(),
+        StateError::UndeclaredClassHash(_)
+    );
+}
+
 fn create_state_changes_for_test<S: StateReader>(
     state: &mut CachedState<S>,
     fee_token_address: ContractAddress,
@@ -371,6 +485,187 @@ fn test_state_changes_merge() {
     );
 }
 
+#[test]
+fn test_state_changes_keys() {
+    let mut state: CachedState<DictStateReader> = CachedState::default();
+    let state_changes = create_state_changes_for_test(&mut state, contract_address!("0x17"));
+    let keys = state_changes.clone().into_keys();
+    assert_eq!(keys.count(), StateChangesCount::from(&state_changes));
+
+    // Keys are merged regardless of order, and each key is counted once.
+    let new_contract_address = contract_address!("0x111");
+    let other_keys = StateChangesKeys {
+        storage_keys: HashSet::from([(new_contract_address, StorageKey(patricia_key!("0x10")))]),
+        modified_contracts: HashSet::from([new_contract_address]),
+        ..Default::default()
+    };
+    let merged_keys = StateChangesKeys::merge(vec![keys.clone(), other_keys.clone(), keys.clone()]);
+    assert_eq!(merged_keys, StateChangesKeys::merge(vec![other_keys.clone(), keys.clone()]));
+    assert_eq!(
+        merged_keys.count(),
+        StateChangesCount {
+            n_storage_updates: 3,
+            n_modified_contracts: 3,
+            n_class_hash_updates: 1,
+            n_compiled_class_hash_updates: 1
+        }
+    );
+
+    // The difference yields the keys added on top of the given ones.
+    assert_eq!(merged_keys.difference(&keys), other_keys);
+    assert_eq!(keys.difference(&merged_keys), StateChangesKeys::default());
+}
+
+#[test]
+fn test_cache_stats() {
+    let mut state: CachedState<DictStateReader> = CachedState::default();
+    let contract_address = contract_address!("0x100");
+    let key = StorageKey(patricia_key!("0x10"));
+    let written_key = StorageKey(patricia_key!("0x11"));
+
+    state.get_storage_at(contract_address, key).unwrap();
+    state.get_storage_at(contract_address, key).unwrap();
+    // Written values are cached as well.
+    state.set_storage_at(contract_address, written_key, stark_felt!("0x1")).unwrap();
+    state.get_storage_at(contract_address, written_key).unwrap();
+    state.get_nonce_at(contract_address).unwrap();
+
+    assert_eq!(
+        state.cache_stats(),
+        CachedStateStats {
+            storage: CacheStats { hits: 2, misses: 1 },
+            nonces: CacheStats { hits: 0, misses: 1 },
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_visited_pcs() {
+    for mut state in [deprecated_create_test_state(), create_test_state()] {
+        let class_hash = class_hash!(TEST_CLASS_HASH);
+        let bytecode_length =
+            state.get_compiled_contract_class(class_hash).unwrap().bytecode_length();
+        let entry_point_call = CallEntryPoint {
+            calldata: calldata![stark_felt!(1234_u16), stark_felt!(18_u8)],
+            entry_point_selector: selector_from_name("test_storage_read_write"),
+            ..trivial_external_entry_point()
+        };
+        // Visited PCs are not tracked by default.
+        entry_point_call.clone().execute_directly(&mut state).unwrap();
+        assert_eq!(state.visited_pcs(), None);
+
+        // Visited PCs are tracked through transactional states, whether committed or aborted.
+        state.track_visited_pcs();
+        let mut transactional_state = CachedState::create_transactional(&mut state);
+        entry_point_call.clone().execute_directly(&mut transactional_state).unwrap();
+        transactional_state.abort();
+        let visited_pcs = state.visited_pcs().unwrap()[&class_hash].clone();
+        assert!(!visited_pcs.is_empty());
+        assert!(visited_pcs.iter().all(|pc| *pc < bytecode_length));
+
+        // Re-executing visits the same PCs.
+        entry_point_call.clone().execute_directly(&mut state).unwrap();
+        assert_eq!(state.visited_pcs().unwrap()[&class_hash], visited_pcs);
+    }
+}
+
+#[test]
+fn test_compressed_global_contract_cache() {
+    let mut global_cache = GlobalContractCache::new(true);
+    for cairo_version in [CairoVersion::Cairo0, CairoVersion::Cairo1] {
+        let test_contract = FeatureContract::TestContract(cairo_version);
+        let (class_hash, contract_class) =
+            (test_contract.get_class_hash(), test_contract.get_class());
+        global_cache.set(class_hash, contract_class.clone());
+
+        // Only Cairo 0 classes are compressed.
+        let cached_class = global_cache.lock().cache_get(&class_hash).cloned().unwrap();
+        match cairo_version {
+            CairoVersion::Cairo0 => {
+                assert_matches!(cached_class, CachedContractClass::CompressedV0(_))
+            }
+            CairoVersion::Cairo1 => assert_matches!(cached_class, CachedContractClass::Plain(_)),
+        }
+        assert_eq!(global_cache.get(&class_hash).unwrap(), contract_class);
+    }
+}
+
+#[test]
+fn test_global_contract_cache_size_budget() {
+    let cairo0_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let cairo1_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let cairo0_size = cairo0_contract.get_class().approximate_size_in_bytes();
+    let cairo1_size = cairo1_contract.get_class().approximate_size_in_bytes();
+    let max_size_in_bytes = cairo0_size.max(cairo1_size);
+    let mut global_cache = GlobalContractCache::default().with_max_size_in_bytes(max_size_in_bytes);
+
+    // The classes do not fit together; the least recently used one is evicted.
+    global_cache.set(cairo0_contract.get_class_hash(), cairo0_contract.get_class());
+    global_cache.set(cairo1_contract.get_class_hash(), cairo1_contract.get_class());
+    assert_eq!(global_cache.lock().cache_size(), 1);
+    assert!(global_cache.get(&cairo1_contract.get_class_hash()).is_some());
+
+    // Classes larger than the budget are not cached.
+    let mut global_cache = GlobalContractCache::default().with_max_size_in_bytes(cairo0_size - 1);
+    global_cache.set(cairo0_contract.get_class_hash(), cairo0_contract.get_class());
+    assert_eq!(global_cache.lock().cache_size(), 0);
+}
+
+#[test]
+fn test_global_contract_cache_size_tracking() {
+    let contract_class = FeatureContract::TestContract(CairoVersion::Cairo0).get_class();
+    let class_size = contract_class.approximate_size_in_bytes();
+    let mut global_cache = GlobalContractCache::default();
+    let size_in_bytes = |global_cache: &GlobalContractCache| {
+        global_cache.size_in_bytes.load(std::sync::atomic::Ordering::Relaxed)
+    };
+
+    // Replacing a class does not count it twice.
+    global_cache.set(class_hash!("0x1"), contract_class.clone());
+    global_cache.set(class_hash!("0x1"), contract_class.clone());
+    assert_eq!(size_in_bytes(&global_cache), class_size);
+
+    // Classes evicted when the cache is full no longer count.
+    for class_hash in 0..=GlobalContractCache::CACHE_SIZE {
+        global_cache.set(class_hash!(class_hash as u128 + 2), contract_class.clone());
+    }
+    assert_eq!(global_cache.lock().cache_size(), GlobalContractCache::CACHE_SIZE);
+    assert_eq!(size_in_bytes(&global_cache), GlobalContractCache::CACHE_SIZE * class_size);
+
+    global_cache.clear();
+    assert_eq!(size_in_bytes(&global_cache), 0);
+}
+
+#[test]
+fn test_write_limit() {
+    let mut state = CachedState::from(DictStateReader::default());
+    state.limit_writes(2);
+    let contract_address = contract_address!("0x100");
+    let (key0, key1) = (StorageKey(patricia_key!("0x10")), StorageKey(patricia_key!("0x11")));
+
+    state.set_storage_at(contract_address, key0, stark_felt!(1_u8)).unwrap();
+    state.increment_nonce(contract_address).unwrap();
+    // Rewrites do not count against the limit.
+    state.set_storage_at(contract_address, key0, stark_felt!(2_u8)).unwrap();
+    state.increment_nonce(contract_address).unwrap();
+    assert_matches!(
+        state.set_storage_at(contract_address, key1, stark_felt!(1_u8)),
+        Err(StateError::WriteLimitExceeded { max_n_writes: 2 })
+    );
+    assert_matches!(
+        state.set_class_hash_at(contract_address, class_hash!(TEST_CLASS_HASH)),
+        Err(StateError::WriteLimitExceeded { .. })
+    );
+
+    // Transactional states are limited to the remaining writes.
+    let mut transactional_state = CachedState::create_transactional(&mut state);
+    assert_matches!(
+        transactional_state.set_storage_at(contract_address, key1, stark_felt!(1_u8)),
+        Err(StateError::WriteLimitExceeded { max_n_writes: 0 })
+    );
+}
+
 #[test]
 fn global_contract_cache_is_used() {
     // Initialize the global cache with a single class, and initialize an empty state with this
@@ -378,7 +673,7 @@ fn global_contract_cache_is_used() {
     let mut global_cache = GlobalContractCache::default();
     let class_hash = class_hash!(TEST_CLASS_HASH);
     let contract_class = get_test_contract_class();
-    global_cache.lock().cache_set(class_hash, contract_class.clone());
+    global_cache.lock().cache_set(class_hash, contract_class.clone().into());
     assert_eq!(global_cache.lock().cache_size(), 1);
     let mut state = CachedState::new(DictStateReader::default(), global_cache.clone());
 
@@ -397,3 +692,229 @@ fn global_contract_cache_is_used() {
     assert_eq!(global_cache.lock().cache_hits().unwrap(), 1);
     assert_eq!(global_cache.lock().cache_size(), 1);
 }
+
+#[test]
+fn test_state_reads_recording() {
+    let contract_address = contract_address!("0x100");
+    let key = StorageKey(patricia_key!("0x10"));
+    let written_key = StorageKey(patricia_key!("0x20"));
+    let storage_value = stark_felt!("0x1");
+    let previous_storage_value = stark_felt!("0x4");
+    let deployed_contract_address = contract_address!("0x200");
+    let nonce = Nonce(stark_felt!("0x2"));
+    let class_hash = class_hash!(TEST_CLASS_HASH);
+    let compiled_class_hash = CompiledClassHash(stark_felt!("0x3"));
+    let contract_class = get_test_contract_class();
+    let mut state = CachedState::from(DictStateReader {
+        storage_view: HashMap::from([
+            ((contract_address, key), storage_value),
+            ((contract_address, written_key), previous_storage_value),
+        ]),
+        address_to_nonce: HashMap::from([(contract_address, nonce)]),
+        address_to_class_hash: HashMap::from([(contract_address, class_hash)]),
+        class_hash_to_class: HashMap::from([(class_hash, contract_class.clone())]),
+        class_hash_to_compiled_class_hash: HashMap::from([(class_hash, compiled_class_hash)]),
+    });
+
+    // Reads performed before the recording starts are not recorded.
+    state.get_nonce_at(contract_address).unwrap();
+    assert!(state.state_reads().is_none());
+    state.record_state_reads();
+
+    state.get_storage_at(contract_address, key).unwrap();
+    state.get_nonce_at(contract_address).unwrap();
+    state.get_class_hash_at(contract_address).unwrap();
+    state.get_compiled_class_hash(class_hash).unwrap();
+    state.get_compiled_contract_class(class_hash).unwrap();
+    // Entries written before being read are recorded by their previous values.
+    state.set_storage_at(contract_address, written_key, storage_value).unwrap();
+    state.get_storage_at(contract_address, written_key).unwrap();
+    state.set_class_hash_at(deployed_contract_address, class_hash).unwrap();
+
+    assert_eq!(
+        state.state_reads().unwrap(),
+        &StateMaps {
+            nonces: HashMap::new(),
+            class_hashes: HashMap::from([
+                (contract_address, class_hash),
+                (deployed_contract_address, ClassHash::default()),
+            ]),
+            storage: HashMap::from([
+                ((contract_address, key), storage_value),
+                ((contract_address, written_key), previous_storage_value),
+            ]),
+            compiled_class_hashes: HashMap::from([(class_hash, compiled_class_hash)]),
+            classes: HashMap::from([(class_hash, contract_class)]),
+        }
+    );
+}
+
+#[test]
+fn test_state_reads_recording_through_transactional_state() {
+    let contract_address = contract_address!("0x100");
+    let key = StorageKey(patricia_key!("0x10"));
+    let written_key = StorageKey(patricia_key!("0x20"));
+    let transactionally_written_key = StorageKey(patricia_key!("0x30"));
+    let storage_value = stark_felt!("0x1");
+    let cached_class_hash = class_hash!(TEST_CLASS_HASH);
+    let declared_class_hash = class_hash!(TEST_EMPTY_CONTRACT_CLASS_HASH);
+    let contract_class = get_test_contract_class();
+    let mut state = CachedState::from(DictStateReader {
+        storage_view: HashMap::from([((contract_address, key), storage_value)]),
+        ..Default::default()
+    });
+    state.global_class_hash_to_class().cache_set(cached_class_hash, contract_class.clone().into());
+    state.record_state_reads();
+    state.set_storage_at(contract_address, written_key, storage_value).unwrap();
+    state.set_contract_class(declared_class_hash, contract_class.clone()).unwrap();
+
+    // Reads of the transactional state are recorded in the parent state, unless they are served
+    // from values written during the execution (whose previous values were recorded on write).
+    for commit in [true, false] {
+        let mut transactional_state = CachedState::create_transactional(&mut state);
+        transactional_state.get_storage_at(contract_address, key).unwrap();
+        transactional_state.get_storage_at(contract_address, written_key).unwrap();
+        transactional_state
+            .set_storage_at(contract_address, transactionally_written_key, storage_value)
+            .unwrap();
+        transactional_state.get_compiled_contract_class(cached_class_hash).unwrap();
+        transactional_state.get_compiled_contract_class(declared_class_hash).unwrap();
+        if commit {
+            transactional_state.commit();
+        } else {
+            transactional_state.abort();
+        }
+
+        let state_reads = state.state_reads().unwrap();
+        assert_eq!(
+            state_reads.storage,
+            HashMap::from([
+                ((contract_address, key), storage_value),
+                ((contract_address, written_key), StarkFelt::ZERO),
+                ((contract_address, transactionally_written_key), StarkFelt::ZERO),
+            ])
+        );
+        assert_eq!(
+            state_reads.classes,
+            HashMap::from([(cached_class_hash, contract_class.clone())])
+        );
+    }
+}
+
+#[test]
+fn test_class_reads_of_classes_cached_before_recording() {
+    let class_hash = class_hash!(TEST_CLASS_HASH);
+    let contract_class = get_test_contract_class();
+    let mut state = CachedState::from(DictStateReader::default());
+    state.global_class_hash_to_class().cache_set(class_hash, contract_class.clone().into());
+    // Cached by the parent state, but not recorded.
+    state.get_compiled_contract_class(class_hash).unwrap();
+    state.record_state_reads();
+
+    // The transactional state reads the class from the global cache; the read is recorded, as the
+    // class was not declared.
+    let mut transactional_state = CachedState::create_transactional(&mut state);
+    transactional_state.get_compiled_contract_class(class_hash).unwrap();
+    transactional_state.commit();
+
+    assert_eq!(state.state_reads().unwrap().classes, HashMap::from([(class_hash, contract_class)]));
+}
+
+#[test]
+fn test_isolated_transactional_state() {
+    let class_hash = class_hash!(TEST_CLASS_HASH);
+    let mut state = CachedState::from(DictStateReader::default());
+    state.global_class_hash_to_class().cache_set(class_hash, get_test_contract_class().into());
+    state.track_visited_pcs();
+
+    // The isolated state does not share the global class cache; what it visits is not merged.
+    let mut isolated_state = CachedState::create_isolated_transactional(&mut state);
+    assert!(isolated_state.global_class_hash_to_class().cache_get(&class_hash).is_none());
+    isolated_state.add_visited_pcs(class_hash, &HashSet::from([0]));
+    drop(isolated_state);
+    assert_eq!(state.visited_pcs(), Some(&HashMap::new()));
+}
+
+#[test]
+fn test_state_sharing_across_threads() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<BlockContext>();
+    assert_send_sync::<ContractClass>();
+    assert_send_sync::<GlobalContractCache>();
+    assert_send_sync::<CachedState<DictStateReader>>();
+    assert_send_sync::<EntryPointExecutionContext>();
+    assert_send_sync::<Transaction>();
+    assert_send_sync::<TransactionExecutionInfo>();
+
+    // Each worker executes against its own state, over a shared class cache.
+    let global_contract_cache = GlobalContractCache::default();
+    std::thread::scope(|scope| {
+        for value in 1..=4_u8 {
+            let global_contract_cache = global_contract_cache.clone();
+            scope.spawn(move || {
+                let mut state =
+                    CachedState::new(deprecated_create_test_state().state, global_contract_cache);
+                let entry_point_call = CallEntryPoint {
+                    entry_point_selector: selector_from_name("test_storage_read_write"),
+                    calldata: calldata![stark_felt!(15_u8), stark_felt!(value)],
+                    ..trivial_external_entry_point()
+                };
+                let call_info = entry_point_call.execute_directly(&mut state).unwrap();
+                assert_eq!(call_info.execution.retdata.as_felt(), Some(stark_felt!(value)));
+                state.move_classes_to_global_cache();
+            });
+        }
+    });
+
+    let class_hash = class_hash!(TEST_CLASS_HASH);
+    assert!(global_contract_cache.clone().lock().cache_get(&class_hash).is_some());
+}
+
+#[test]
+fn test_cache_dump_and_restore() {
+    let block_context = BlockContext::create_for_account_testing();
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let mut state = test_state(&block_context, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let invoke_args = invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address: account.get_instance_address(0),
+        calldata: create_calldata(
+            test_contract.get_instance_address(0),
+            "test_storage_read_write",
+            &[stark_felt!(1_u8), stark_felt!(2_u8)],
+        ),
+    };
+    let tx_execution_info = run_invoke_tx(&mut state, &block_context, invoke_args.clone()).unwrap();
+
+    // The dump round-trips through JSON.
+    let dump = state.dump_cache();
+    let raw_dump = serde_json::to_string(&dump).unwrap();
+    assert_eq!(serde_json::from_str::<StateCacheDump>(&raw_dump).unwrap(), dump);
+    assert!(dump.class_hashes.contains(&test_contract.get_class_hash()));
+
+    // The execution is reproduced given the initial values and the classes alone.
+    let class_hash_to_class = dump
+        .class_hashes
+        .iter()
+        .map(|&class_hash| (class_hash, state.get_compiled_contract_class(class_hash).unwrap()))
+        .collect();
+    let mut replay_state =
+        CachedState::from(DictStateReader { class_hash_to_class, ..Default::default() });
+    replay_state
+        .restore_cache(StateCacheDump { writes: StateEntries::default(), ..dump.clone() })
+        .unwrap();
+    assert_eq!(
+        run_invoke_tx(&mut replay_state, &block_context, invoke_args).unwrap(),
+        tx_execution_info
+    );
+    assert_eq!(replay_state.dump_cache(), dump);
+
+    // A dump whose classes are not available is not restored.
+    let mut empty_state = CachedState::from(DictStateReader::default());
+    assert_matches!(
+        empty_state.restore_cache(dump.clone()),
+        Err(StateError::UndeclaredClassHash(_))
+    );
+    assert_eq!(empty_state.dump_cache(), StateCacheDump::default());
+}
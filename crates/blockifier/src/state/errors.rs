@@ -3,16 +3,26 @@ use starknet_api::core::{ClassHash, ContractAddress};
 use starknet_api::StarknetApiError;
 use thiserror::Error;
 
+use crate::execution::errors::ContractClassError;
+use crate::utils::{format_address, format_class_hash};
+
 #[derive(Debug, Error)]
 pub enum StateError {
+    #[error(transparent)]
+    ContractClassError(#[from] ContractClassError),
     #[error("Cannot deploy contract at address 0.")]
     OutOfRangeContractAddress,
     #[error(transparent)]
     ProgramError(#[from] ProgramError),
-    #[error("Requested {0:?} is unavailable for deployment.")]
+    /// A read of the replayed execution that its replay capsule did not record.
+    #[error("The replay capsule did not record the {0}.")]
+    MissingCapsuleEntry(String),
+    #[error("Requested contract address {} is unavailable for deployment.", format_address(.0))]
     UnavailableContractAddress(ContractAddress),
-    #[error("Class with hash {0:#?} is not declared.")]
+    #[error("Class with hash {} is not declared.", format_class_hash(.0))]
     UndeclaredClassHash(ClassHash),
+    #[error("Exceeded the limit of {max_n_writes} state writes.")]
+    WriteLimitExceeded { max_n_writes: usize },
     #[error(transparent)]
     StarknetApiError(#[from] StarknetApiError),
     /// Represents all unexpected errors that may occur while reading from state.
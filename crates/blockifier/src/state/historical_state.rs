@@ -0,0 +1,89 @@
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+use crate::execution::contract_class::ContractClass;
+use crate::state::state_api::{StateReader, StateResult};
+
+#[cfg(test)]
+#[path = "historical_state_test.rs"]
+pub mod test;
+
+/// Reads the Starknet state as it was at any given block; e.g., from an archive node. The state
+/// "at" a block is the state its transactions were executed on, i.e., the state after the previous
+/// block.
+///
+/// The defaults are those of `StateReader`.
+pub trait HistoricalStateReader {
+    fn get_storage_at(
+        &self,
+        block_number: BlockNumber,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt>;
+
+    fn get_nonce_at(
+        &self,
+        block_number: BlockNumber,
+        contract_address: ContractAddress,
+    ) -> StateResult<Nonce>;
+
+    fn get_class_hash_at(
+        &self,
+        block_number: BlockNumber,
+        contract_address: ContractAddress,
+    ) -> StateResult<ClassHash>;
+
+    fn get_compiled_contract_class(
+        &self,
+        block_number: BlockNumber,
+        class_hash: ClassHash,
+    ) -> StateResult<ContractClass>;
+
+    fn get_compiled_class_hash(
+        &self,
+        block_number: BlockNumber,
+        class_hash: ClassHash,
+    ) -> StateResult<CompiledClassHash>;
+}
+
+/// Exposes the state at the given block as a `StateReader`; typically wrapped by a `CachedState`,
+/// on which the transactions of the block are replayed (see `BlockContext::with_block_header`).
+#[derive(Debug)]
+pub struct HistoricalState<R: HistoricalStateReader> {
+    pub reader: R,
+    pub block_number: BlockNumber,
+}
+
+impl<R: HistoricalStateReader> HistoricalState<R> {
+    pub fn new(reader: R, block_number: BlockNumber) -> Self {
+        Self { reader, block_number }
+    }
+}
+
+impl<R: HistoricalStateReader> StateReader for HistoricalState<R> {
+    fn get_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        self.reader.get_storage_at(self.block_number, contract_address, key)
+    }
+
+    fn get_nonce_at(&mut self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        self.reader.get_nonce_at(self.block_number, contract_address)
+    }
+
+    fn get_class_hash_at(&mut self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.reader.get_class_hash_at(self.block_number, contract_address)
+    }
+
+    fn get_compiled_contract_class(&mut self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.reader.get_compiled_contract_class(self.block_number, class_hash)
+    }
+
+    fn get_compiled_class_hash(&mut self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.reader.get_compiled_class_hash(self.block_number, class_hash)
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::{BTreeMap, HashMap};
+
+use assert_matches::assert_matches;
+use starknet_api::core::PatriciaKey;
+use starknet_api::hash::StarkHash;
+use starknet_api::transaction::Calldata;
+use starknet_api::{calldata, class_hash, patricia_key, stark_felt};
+
+use super::*;
+use crate::abi::abi_utils::selector_from_name;
+use crate::execution::entry_point::CallEntryPoint;
+use crate::state::cached_state::CachedState;
+use crate::state::errors::StateError;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::{trivial_external_entry_point, CairoVersion};
+
+/// An example archive: storage values by the block in which they were written; classes are never
+/// overwritten, and are thus kept along with the block in which they were declared.
+#[derive(Debug, Default)]
+struct Archive {
+    storage: HashMap<(ContractAddress, StorageKey), BTreeMap<BlockNumber, StarkFelt>>,
+    class_hashes: HashMap<ContractAddress, (BlockNumber, ClassHash)>,
+    contract_classes: HashMap<ClassHash, (BlockNumber, ContractClass)>,
+}
+
+impl HistoricalStateReader for Archive {
+    fn get_storage_at(
+        &self,
+        block_number: BlockNumber,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        let values = self.storage.get(&(contract_address, key));
+        // The last value written before the block.
+        let value = values.and_then(|values| values.range(..block_number).next_back());
+        Ok(value.map(|(_, value)| *value).unwrap_or_default())
+    }
+
+    fn get_nonce_at(
+        &self,
+        _block_number: BlockNumber,
+        _contract_address: ContractAddress,
+    ) -> StateResult<Nonce> {
+        Ok(Nonce::default())
+    }
+
+    fn get_class_hash_at(
+        &self,
+        block_number: BlockNumber,
+        contract_address: ContractAddress,
+    ) -> StateResult<ClassHash> {
+        match self.class_hashes.get(&contract_address) {
+            Some((deployment_block_number, class_hash))
+                if *deployment_block_number < block_number =>
+            {
+                Ok(*class_hash)
+            }
+            _ => Ok(ClassHash::default()),
+        }
+    }
+
+    fn get_compiled_contract_class(
+        &self,
+        block_number: BlockNumber,
+        class_hash: ClassHash,
+    ) -> StateResult<ContractClass> {
+        match self.contract_classes.get(&class_hash) {
+            Some((declaration_block_number, contract_class))
+                if *declaration_block_number < block_number =>
+            {
+                Ok(contract_class.clone())
+            }
+            _ => Err(StateError::UndeclaredClassHash(class_hash)),
+        }
+    }
+
+    fn get_compiled_class_hash(
+        &self,
+        _block_number: BlockNumber,
+        _class_hash: ClassHash,
+    ) -> StateResult<CompiledClassHash> {
+        Ok(CompiledClassHash::default())
+    }
+}
+
+#[test]
+fn test_historical_state() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let (class_hash, contract_address) =
+        (test_contract.get_class_hash(), test_contract.get_instance_address(0));
+    let key = StorageKey(patricia_key!("0x10"));
+    let archive = Archive {
+        storage: HashMap::from([(
+            (contract_address, key),
+            BTreeMap::from([
+                (BlockNumber(3), stark_felt!(1_u8)),
+                (BlockNumber(7), stark_felt!(2_u8)),
+            ]),
+        )]),
+        class_hashes: HashMap::from([(contract_address, (BlockNumber(2), class_hash))]),
+        contract_classes: HashMap::from([(
+            class_hash,
+            (BlockNumber(1), test_contract.get_class()),
+        )]),
+    };
+
+    // The state at a block precedes its writes.
+    let mut state = HistoricalState::new(archive, BlockNumber(3));
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), stark_felt!(0_u8));
+    state.block_number = BlockNumber(5);
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), stark_felt!(1_u8));
+    state.block_number = BlockNumber(8);
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), stark_felt!(2_u8));
+    state.block_number = BlockNumber(1);
+    assert_eq!(state.get_class_hash_at(contract_address).unwrap(), ClassHash::default());
+    assert_matches!(
+        state.get_compiled_contract_class(class_hash),
+        Err(StateError::UndeclaredClassHash(_))
+    );
+    assert_eq!(
+        state.get_compiled_class_hash(class_hash!("0x1")).unwrap(),
+        CompiledClassHash::default()
+    );
+
+    // Calls are executed on the historical state.
+    state.block_number = BlockNumber(5);
+    let mut state = CachedState::from(state);
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![*key.0.key(), stark_felt!(3_u8)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        code_address: Some(contract_address),
+        storage_address: contract_address,
+        ..trivial_external_entry_point()
+    };
+    entry_point_call.execute_directly(&mut state).unwrap();
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), stark_felt!(3_u8));
+    assert_eq!(state.state.get_storage_at(contract_address, key).unwrap(), stark_felt!(1_u8));
+}
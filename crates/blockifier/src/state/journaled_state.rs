@@ -0,0 +1,166 @@
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+use crate::execution::contract_class::ContractClass;
+use crate::state::errors::StateError;
+use crate::state::state_api::{State, StateReader, StateResult};
+
+#[cfg(test)]
+#[path = "journaled_state_test.rs"]
+pub mod test;
+
+/// Journals the writes of a call (and of its inner calls) on top of the state of its caller.
+///
+/// The writes are visible to the call and its inner calls as they are made, and to the caller once
+/// committed, i.e., when the call succeeds. Dropping the journal reverts them; e.g., when a Cairo 1
+/// call fails, and the caller continues with the failure. Visited program counters are not
+/// journaled, as the execution took place either way.
+pub struct JournaledState<'a> {
+    state: &'a mut dyn State,
+    // The writes are replayed in order on commit.
+    storage_writes: IndexMap<(ContractAddress, StorageKey), StarkFelt>,
+    nonce_increments: IndexMap<ContractAddress, u64>,
+    class_hash_writes: IndexMap<ContractAddress, ClassHash>,
+    contract_class_writes: IndexMap<ClassHash, ContractClass>,
+    compiled_class_hash_writes: IndexMap<ClassHash, CompiledClassHash>,
+}
+
+impl<'a> JournaledState<'a> {
+    pub fn new(state: &'a mut dyn State) -> Self {
+        Self {
+            state,
+            storage_writes: IndexMap::new(),
+            nonce_increments: IndexMap::new(),
+            class_hash_writes: IndexMap::new(),
+            contract_class_writes: IndexMap::new(),
+            compiled_class_hash_writes: IndexMap::new(),
+        }
+    }
+
+    /// Applies the journaled writes to the underlying state.
+    pub fn commit(self) -> StateResult<()> {
+        for ((contract_address, key), value) in self.storage_writes {
+            self.state.set_storage_at(contract_address, key, value)?;
+        }
+        for (contract_address, n_increments) in self.nonce_increments {
+            for _ in 0..n_increments {
+                self.state.increment_nonce(contract_address)?;
+            }
+        }
+        for (contract_address, class_hash) in self.class_hash_writes {
+            self.state.set_class_hash_at(contract_address, class_hash)?;
+        }
+        for (class_hash, contract_class) in self.contract_class_writes {
+            self.state.set_contract_class(class_hash, contract_class)?;
+        }
+        for (class_hash, compiled_class_hash) in self.compiled_class_hash_writes {
+            self.state.set_compiled_class_hash(class_hash, compiled_class_hash)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl StateReader for JournaledState<'_> {
+    fn get_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        match self.storage_writes.get(&(contract_address, key)) {
+            Some(value) => Ok(*value),
+            None => self.state.get_storage_at(contract_address, key),
+        }
+    }
+
+    fn get_nonce_at(&mut self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        let nonce = self.state.get_nonce_at(contract_address)?;
+        match self.nonce_increments.get(&contract_address) {
+            Some(n_increments) => {
+                Ok(Nonce(StarkFelt::from(u64::try_from(nonce.0)? + n_increments)))
+            }
+            None => Ok(nonce),
+        }
+    }
+
+    fn get_class_hash_at(&mut self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        match self.class_hash_writes.get(&contract_address) {
+            Some(class_hash) => Ok(*class_hash),
+            None => self.state.get_class_hash_at(contract_address),
+        }
+    }
+
+    fn get_compiled_contract_class(&mut self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        match self.contract_class_writes.get(&class_hash) {
+            Some(contract_class) => Ok(contract_class.clone()),
+            None => self.state.get_compiled_contract_class(class_hash),
+        }
+    }
+
+    fn get_compiled_class_hash(&mut self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        match self.compiled_class_hash_writes.get(&class_hash) {
+            Some(compiled_class_hash) => Ok(*compiled_class_hash),
+            None => self.state.get_compiled_class_hash(class_hash),
+        }
+    }
+}
+
+impl State for JournaledState<'_> {
+    fn set_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+        value: StarkFelt,
+    ) -> StateResult<()> {
+        self.storage_writes.insert((contract_address, key), value);
+        Ok(())
+    }
+
+    fn increment_nonce(&mut self, contract_address: ContractAddress) -> StateResult<()> {
+        *self.nonce_increments.entry(contract_address).or_default() += 1;
+        Ok(())
+    }
+
+    fn set_class_hash_at(
+        &mut self,
+        contract_address: ContractAddress,
+        class_hash: ClassHash,
+    ) -> StateResult<()> {
+        if contract_address == ContractAddress::default() {
+            return Err(StateError::OutOfRangeContractAddress);
+        }
+
+        self.class_hash_writes.insert(contract_address, class_hash);
+        Ok(())
+    }
+
+    fn set_contract_class(
+        &mut self,
+        class_hash: ClassHash,
+        contract_class: ContractClass,
+    ) -> StateResult<()> {
+        self.contract_class_writes.insert(class_hash, contract_class);
+        Ok(())
+    }
+
+    fn set_compiled_class_hash(
+        &mut self,
+        class_hash: ClassHash,
+        compiled_class_hash: CompiledClassHash,
+    ) -> StateResult<()> {
+        self.compiled_class_hash_writes.insert(class_hash, compiled_class_hash);
+        Ok(())
+    }
+
+    fn tracks_visited_pcs(&self) -> bool {
+        self.state.tracks_visited_pcs()
+    }
+
+    fn add_visited_pcs(&mut self, class_hash: ClassHash, pcs: &HashSet<usize>) {
+        self.state.add_visited_pcs(class_hash, pcs)
+    }
+}
@@ -0,0 +1,47 @@
+use assert_matches::assert_matches;
+use starknet_api::core::PatriciaKey;
+use starknet_api::hash::StarkHash;
+use starknet_api::{class_hash, contract_address, patricia_key, stark_felt};
+
+use super::*;
+use crate::state::cached_state::CachedState;
+use crate::test_utils::dict_state_reader::DictStateReader;
+
+#[test]
+fn test_journaled_state() {
+    let mut state = CachedState::from(DictStateReader::default());
+    let contract_address = contract_address!("0x100");
+    let key = StorageKey(patricia_key!("0x10"));
+    let class_hash = class_hash!("0x20");
+    state.set_storage_at(contract_address, key, stark_felt!(1_u8)).unwrap();
+
+    // Writes are visible through the journal, and through nested journals.
+    let mut journaled_state = JournaledState::new(&mut state);
+    journaled_state.set_storage_at(contract_address, key, stark_felt!(2_u8)).unwrap();
+    journaled_state.increment_nonce(contract_address).unwrap();
+    let mut inner_journaled_state = JournaledState::new(&mut journaled_state);
+    assert_eq!(
+        inner_journaled_state.get_storage_at(contract_address, key).unwrap(),
+        stark_felt!(2_u8)
+    );
+    inner_journaled_state.increment_nonce(contract_address).unwrap();
+    inner_journaled_state.set_class_hash_at(contract_address, class_hash).unwrap();
+    assert_eq!(
+        inner_journaled_state.get_nonce_at(contract_address).unwrap(),
+        Nonce(stark_felt!(2_u8))
+    );
+    assert_matches!(
+        inner_journaled_state.set_class_hash_at(ContractAddress::default(), class_hash),
+        Err(StateError::OutOfRangeContractAddress)
+    );
+
+    // A dropped journal is reverted.
+    drop(inner_journaled_state);
+    assert_eq!(journaled_state.get_class_hash_at(contract_address).unwrap(), ClassHash::default());
+    assert_eq!(journaled_state.get_nonce_at(contract_address).unwrap(), Nonce(stark_felt!(1_u8)));
+
+    // A committed journal is applied to the underlying state.
+    journaled_state.commit().unwrap();
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), stark_felt!(2_u8));
+    assert_eq!(state.get_nonce_at(contract_address).unwrap(), Nonce(stark_felt!(1_u8)));
+}
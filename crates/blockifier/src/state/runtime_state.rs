@@ -0,0 +1,173 @@
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+use crate::execution::contract_class::ContractClass;
+use crate::state::errors::StateError;
+use crate::state::state_api::{State, StateReader, StateResult};
+
+#[cfg(test)]
+#[path = "runtime_state_test.rs"]
+pub mod test;
+
+/// An error of a runtime storage; allocation-free, so that it converts to the error type of the
+/// runtime (e.g., `DispatchError::Other` in Substrate runtimes).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RuntimeStorageError(pub &'static str);
+
+pub type RuntimeStorageResult<T> = Result<T, RuntimeStorageError>;
+
+impl From<RuntimeStorageError> for StateError {
+    fn from(error: RuntimeStorageError) -> Self {
+        StateError::StateReadError(error.0.to_string())
+    }
+}
+
+/// Maps state errors onto static messages, for runtimes that cannot carry dynamic errors.
+impl From<&StateError> for RuntimeStorageError {
+    fn from(error: &StateError) -> Self {
+        Self(match error {
+            StateError::ContractClassError(_) => "Invalid contract class.",
+            StateError::OutOfRangeContractAddress => "Cannot deploy contract at address 0.",
+            StateError::MissingCapsuleEntry(_) => "Replay capsule entry is missing.",
+            StateError::ProgramError(_) => "Invalid program.",
+            StateError::UnavailableContractAddress(_) => "Contract address is unavailable.",
+            StateError::UndeclaredClassHash(_) => "Class is not declared.",
+            StateError::WriteLimitExceeded { .. } => "Exceeded the limit of state writes.",
+            StateError::StarknetApiError(_) => "Starknet API error.",
+            StateError::StateReadError(_) => "Failed to read from state.",
+        })
+    }
+}
+
+/// The Starknet state as kept in the storage of a runtime (e.g., the pallet storage of a Substrate
+/// chain): entries are read and written one key at a time, and missing entries are `None`.
+/// Implementations need neither `Rc` nor `HashMap`, and can thus be provided by `no_std` runtimes.
+pub trait RuntimeStorage {
+    fn storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> RuntimeStorageResult<Option<StarkFelt>>;
+
+    fn nonce_at(&self, contract_address: ContractAddress) -> RuntimeStorageResult<Option<Nonce>>;
+
+    fn class_hash_at(
+        &self,
+        contract_address: ContractAddress,
+    ) -> RuntimeStorageResult<Option<ClassHash>>;
+
+    fn contract_class(&self, class_hash: ClassHash) -> RuntimeStorageResult<Option<ContractClass>>;
+
+    fn compiled_class_hash(
+        &self,
+        class_hash: ClassHash,
+    ) -> RuntimeStorageResult<Option<CompiledClassHash>>;
+
+    fn insert_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+        value: StarkFelt,
+    ) -> RuntimeStorageResult<()>;
+
+    fn insert_nonce_at(
+        &mut self,
+        contract_address: ContractAddress,
+        nonce: Nonce,
+    ) -> RuntimeStorageResult<()>;
+
+    fn insert_class_hash_at(
+        &mut self,
+        contract_address: ContractAddress,
+        class_hash: ClassHash,
+    ) -> RuntimeStorageResult<()>;
+
+    fn insert_contract_class(
+        &mut self,
+        class_hash: ClassHash,
+        contract_class: ContractClass,
+    ) -> RuntimeStorageResult<()>;
+
+    fn insert_compiled_class_hash(
+        &mut self,
+        class_hash: ClassHash,
+        compiled_class_hash: CompiledClassHash,
+    ) -> RuntimeStorageResult<()>;
+}
+
+/// Exposes a runtime storage as a `State`; typically wrapped by a `CachedState`, so that the
+/// storage is written only once the block (or transaction) is committed.
+#[derive(Debug, Default)]
+pub struct RuntimeState<R: RuntimeStorage>(pub R);
+
+impl<R: RuntimeStorage> StateReader for RuntimeState<R> {
+    fn get_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        Ok(self.0.storage_at(contract_address, key)?.unwrap_or_default())
+    }
+
+    fn get_nonce_at(&mut self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        Ok(self.0.nonce_at(contract_address)?.unwrap_or_default())
+    }
+
+    fn get_class_hash_at(&mut self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        Ok(self.0.class_hash_at(contract_address)?.unwrap_or_default())
+    }
+
+    fn get_compiled_contract_class(&mut self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.0.contract_class(class_hash)?.ok_or(StateError::UndeclaredClassHash(class_hash))
+    }
+
+    fn get_compiled_class_hash(&mut self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        Ok(self.0.compiled_class_hash(class_hash)?.unwrap_or_default())
+    }
+}
+
+impl<R: RuntimeStorage> State for RuntimeState<R> {
+    fn set_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+        value: StarkFelt,
+    ) -> StateResult<()> {
+        Ok(self.0.insert_storage_at(contract_address, key, value)?)
+    }
+
+    fn increment_nonce(&mut self, contract_address: ContractAddress) -> StateResult<()> {
+        let current_nonce = u64::try_from(self.get_nonce_at(contract_address)?.0)?;
+        let next_nonce = Nonce(StarkFelt::from(current_nonce + 1));
+        Ok(self.0.insert_nonce_at(contract_address, next_nonce)?)
+    }
+
+    fn set_class_hash_at(
+        &mut self,
+        contract_address: ContractAddress,
+        class_hash: ClassHash,
+    ) -> StateResult<()> {
+        if contract_address == ContractAddress::default() {
+            return Err(StateError::OutOfRangeContractAddress);
+        }
+
+        Ok(self.0.insert_class_hash_at(contract_address, class_hash)?)
+    }
+
+    fn set_contract_class(
+        &mut self,
+        class_hash: ClassHash,
+        contract_class: ContractClass,
+    ) -> StateResult<()> {
+        Ok(self.0.insert_contract_class(class_hash, contract_class)?)
+    }
+
+    fn set_compiled_class_hash(
+        &mut self,
+        class_hash: ClassHash,
+        compiled_class_hash: CompiledClassHash,
+    ) -> StateResult<()> {
+        Ok(self.0.insert_compiled_class_hash(class_hash, compiled_class_hash)?)
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+
+use assert_matches::assert_matches;
+use starknet_api::core::PatriciaKey;
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::Calldata;
+use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
+
+use super::*;
+use crate::abi::abi_utils::selector_from_name;
+use crate::execution::entry_point::CallEntryPoint;
+use crate::state::cached_state::CachedState;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::{trivial_external_entry_point, CairoVersion};
+
+/// An example runtime storage, over ordered maps (as available in `no_std` environments).
+#[derive(Debug, Default)]
+struct BTreeMapStorage {
+    storage: BTreeMap<(ContractAddress, StorageKey), StarkFelt>,
+    nonces: BTreeMap<ContractAddress, Nonce>,
+    class_hashes: BTreeMap<ContractAddress, ClassHash>,
+    contract_classes: BTreeMap<ClassHash, ContractClass>,
+    compiled_class_hashes: BTreeMap<ClassHash, CompiledClassHash>,
+}
+
+impl RuntimeStorage for BTreeMapStorage {
+    fn storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> RuntimeStorageResult<Option<StarkFelt>> {
+        Ok(self.storage.get(&(contract_address, key)).copied())
+    }
+
+    fn nonce_at(&self, contract_address: ContractAddress) -> RuntimeStorageResult<Option<Nonce>> {
+        Ok(self.nonces.get(&contract_address).copied())
+    }
+
+    fn class_hash_at(
+        &self,
+        contract_address: ContractAddress,
+    ) -> RuntimeStorageResult<Option<ClassHash>> {
+        Ok(self.class_hashes.get(&contract_address).copied())
+    }
+
+    fn contract_class(&self, class_hash: ClassHash) -> RuntimeStorageResult<Option<ContractClass>> {
+        Ok(self.contract_classes.get(&class_hash).cloned())
+    }
+
+    fn compiled_class_hash(
+        &self,
+        class_hash: ClassHash,
+    ) -> RuntimeStorageResult<Option<CompiledClassHash>> {
+        Ok(self.compiled_class_hashes.get(&class_hash).copied())
+    }
+
+    fn insert_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+        value: StarkFelt,
+    ) -> RuntimeStorageResult<()> {
+        self.storage.insert((contract_address, key), value);
+        Ok(())
+    }
+
+    fn insert_nonce_at(
+        &mut self,
+        contract_address: ContractAddress,
+        nonce: Nonce,
+    ) -> RuntimeStorageResult<()> {
+        self.nonces.insert(contract_address, nonce);
+        Ok(())
+    }
+
+    fn insert_class_hash_at(
+        &mut self,
+        contract_address: ContractAddress,
+        class_hash: ClassHash,
+    ) -> RuntimeStorageResult<()> {
+        self.class_hashes.insert(contract_address, class_hash);
+        Ok(())
+    }
+
+    fn insert_contract_class(
+        &mut self,
+        class_hash: ClassHash,
+        contract_class: ContractClass,
+    ) -> RuntimeStorageResult<()> {
+        self.contract_classes.insert(class_hash, contract_class);
+        Ok(())
+    }
+
+    fn insert_compiled_class_hash(
+        &mut self,
+        class_hash: ClassHash,
+        compiled_class_hash: CompiledClassHash,
+    ) -> RuntimeStorageResult<()> {
+        self.compiled_class_hashes.insert(class_hash, compiled_class_hash);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_execute_over_runtime_state() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let contract_address = test_contract.get_instance_address(0);
+    let mut runtime_state = RuntimeState(BTreeMapStorage::default());
+    runtime_state
+        .set_contract_class(test_contract.get_class_hash(), test_contract.get_class())
+        .unwrap();
+    runtime_state.set_class_hash_at(contract_address, test_contract.get_class_hash()).unwrap();
+
+    let (key, value) = (stark_felt!(1234_u16), stark_felt!(18_u8));
+    let mut state = CachedState::from(runtime_state);
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![key, value],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        code_address: Some(contract_address),
+        storage_address: contract_address,
+        ..trivial_external_entry_point()
+    };
+    entry_point_call.execute_directly(&mut state).unwrap();
+
+    // The write is cached until the state diff is applied to the runtime storage.
+    let key = StorageKey(PatriciaKey::try_from(key).unwrap());
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), value);
+    assert_eq!(state.state.get_storage_at(contract_address, key).unwrap(), StarkFelt::ZERO);
+}
+
+#[test]
+fn test_runtime_state() {
+    let mut state = RuntimeState(BTreeMapStorage::default());
+    let contract_address = contract_address!("0x100");
+    let key = StorageKey(patricia_key!("0x10"));
+
+    state.set_storage_at(contract_address, key, stark_felt!(7_u8)).unwrap();
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), stark_felt!(7_u8));
+    state.increment_nonce(contract_address).unwrap();
+    state.increment_nonce(contract_address).unwrap();
+    assert_eq!(state.get_nonce_at(contract_address).unwrap(), Nonce(stark_felt!(2_u8)));
+
+    // Missing entries have the default values, except for classes.
+    let class_hash = class_hash!("0x20");
+    assert_eq!(state.get_class_hash_at(contract_address).unwrap(), ClassHash::default());
+    assert_eq!(state.get_compiled_class_hash(class_hash).unwrap(), CompiledClassHash::default());
+    let error = state.get_compiled_contract_class(class_hash).unwrap_err();
+    assert_matches!(error, StateError::UndeclaredClassHash(hash) if hash == class_hash);
+    assert_eq!(RuntimeStorageError::from(&error), RuntimeStorageError("Class is not declared."));
+
+    assert_matches!(
+        state.set_class_hash_at(ContractAddress::default(), class_hash),
+        Err(StateError::OutOfRangeContractAddress)
+    );
+}
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey;
@@ -100,4 +102,13 @@ pub trait State: StateReader {
         class_hash: ClassHash,
         compiled_class_hash: CompiledClassHash,
     ) -> StateResult<()>;
+
+    /// Returns whether the program counters visited during execution should be reported through
+    /// `add_visited_pcs`; reporting requires tracing the VM, and is disabled by default.
+    fn tracks_visited_pcs(&self) -> bool {
+        false
+    }
+
+    /// Marks the given program counters of the given class as visited.
+    fn add_visited_pcs(&mut self, _class_hash: ClassHash, _pcs: &HashSet<usize>) {}
 }
@@ -0,0 +1,173 @@
+use starknet_api::core::Nonce;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::TransactionHash;
+use thiserror::Error;
+
+use crate::block_context::BlockContext;
+use crate::execution::call_info::CallInfo;
+use crate::execution::entry_point::ExecutionResources;
+use crate::fee::actual_cost::ActualCost;
+use crate::fee::fee_checks::PostValidationReport;
+use crate::state::cached_state::CachedState;
+use crate::state::errors::StateError;
+use crate::state::state_api::StateReader;
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::errors::{TransactionExecutionError, TransactionPreValidationError};
+use crate::transaction::objects::AccountTransactionContext;
+use crate::transaction::transactions::{ExecutableTransaction, ValidatableTransaction};
+
+#[cfg(test)]
+#[path = "stateful_validator_test.rs"]
+pub mod test;
+
+#[derive(Debug, Error)]
+pub enum StatefulValidatorError {
+    #[error(transparent)]
+    StateError(#[from] StateError),
+    #[error(transparent)]
+    TransactionExecutionError(#[from] TransactionExecutionError),
+    #[error(transparent)]
+    TransactionPreValidationError(#[from] TransactionPreValidationError),
+}
+
+pub type StatefulValidatorResult<T> = Result<T, StatefulValidatorError>;
+
+/// Manages state-related transaction validations for pre-execution flows (e.g., a mempool
+/// admitting incoming transactions): nonce and balance checks, and the `__validate__` run.
+/// The `__execute__` entry point is not run, and no fee is charged.
+pub struct StatefulValidator<S: StateReader> {
+    pub state: CachedState<S>,
+    pub block_context: BlockContext,
+    /// Transactions sent right after an unprocessed deploy account transaction skip `__validate__`
+    /// if their nonce does not exceed this one.
+    max_nonce_for_validation_skip: Nonce,
+}
+
+impl<S: StateReader> StatefulValidator<S> {
+    pub fn create(
+        state: CachedState<S>,
+        block_context: BlockContext,
+        max_nonce_for_validation_skip: Nonce,
+    ) -> Self {
+        Self { state, block_context, max_nonce_for_validation_skip }
+    }
+
+    /// Performs the stateful validations of the given transaction, and applies its pre-execution
+    /// effects (e.g., nonce increment) to the state, so that subsequent transactions of the same
+    /// account are validated against it.
+    /// `deploy_account_tx_hash` indicates that a deploy account transaction of the sender was
+    /// received, but may not have been processed yet.
+    pub fn perform_validations(
+        &mut self,
+        tx: AccountTransaction,
+        deploy_account_tx_hash: Option<TransactionHash>,
+    ) -> StatefulValidatorResult<()> {
+        // Deploy account transactions should be fully executed, since the constructor must run
+        // before `__validate_deploy__`. The execution already includes all necessary validations,
+        // so they are skipped here.
+        if let AccountTransaction::DeployAccount(_) = tx {
+            let charge_fee = true;
+            let validate = true;
+            tx.execute(&mut self.state, &self.block_context, charge_fee, validate)?;
+            return Ok(());
+        }
+
+        let account_tx_context = tx.get_account_tx_context();
+        // First, we check if the transaction should be skipped due to the deploy account not being
+        // processed. It is done before the pre-validations checks because, in these checks, we
+        // change the state (more precisely, we increment the nonce).
+        let skip_validate = self.skip_validate_due_to_unprocessed_deploy_account(
+            &account_tx_context,
+            deploy_account_tx_hash,
+        )?;
+        self.perform_pre_validation_stage(&tx, &account_tx_context)?;
+
+        if skip_validate {
+            return Ok(());
+        }
+
+        // `__validate__` call.
+        let initial_gas = self.block_context.versioned_constants.tx_initial_gas;
+        let (_optional_call_info, actual_cost) =
+            self.validate(&tx, &account_tx_context, initial_gas)?;
+
+        // Post validations.
+        PostValidationReport::verify(&self.block_context, &account_tx_context, &actual_cost)?;
+
+        Ok(())
+    }
+
+    fn perform_pre_validation_stage(
+        &mut self,
+        tx: &AccountTransaction,
+        account_tx_context: &AccountTransactionContext,
+    ) -> StatefulValidatorResult<()> {
+        // The nonce of the transaction may be ahead of the account nonce, as previous transactions
+        // of the account may still be pending.
+        let strict_nonce_check = false;
+        // Run pre-validation in charge fee mode to perform fee and balance related checks.
+        let charge_fee = true;
+        tx.perform_pre_validation_stage(
+            &mut self.state,
+            account_tx_context,
+            &self.block_context,
+            charge_fee,
+            strict_nonce_check,
+        )?;
+
+        Ok(())
+    }
+
+    // Check if deploy account was submitted but not processed yet. If so, then skip
+    // `__validate__` method for subsequent transactions for a better user experience.
+    // (they will otherwise fail solely because the deploy account hasn't been processed yet).
+    fn skip_validate_due_to_unprocessed_deploy_account(
+        &mut self,
+        account_tx_context: &AccountTransactionContext,
+        deploy_account_tx_hash: Option<TransactionHash>,
+    ) -> StatefulValidatorResult<bool> {
+        let nonce = self.state.get_nonce_at(account_tx_context.sender_address())?;
+        let tx_nonce = account_tx_context.nonce();
+
+        let deploy_account_not_processed =
+            deploy_account_tx_hash.is_some() && nonce == Nonce(StarkFelt::ZERO);
+        let is_post_deploy_nonce = Nonce(StarkFelt::ONE) <= tx_nonce;
+        let nonce_small_enough_to_qualify_for_validation_skip =
+            tx_nonce <= self.max_nonce_for_validation_skip;
+
+        let skip_validate = deploy_account_not_processed
+            && is_post_deploy_nonce
+            && nonce_small_enough_to_qualify_for_validation_skip;
+
+        Ok(skip_validate)
+    }
+
+    fn validate(
+        &mut self,
+        tx: &AccountTransaction,
+        account_tx_context: &AccountTransactionContext,
+        mut remaining_gas: u64,
+    ) -> StatefulValidatorResult<(Option<CallInfo>, ActualCost)> {
+        let mut execution_resources = ExecutionResources::default();
+
+        let limit_steps_by_resources = true;
+        let validate_call_info = tx.validate_tx(
+            &mut self.state,
+            &mut execution_resources,
+            account_tx_context,
+            &mut remaining_gas,
+            &self.block_context,
+            limit_steps_by_resources,
+        )?;
+
+        // The nonce increment of the pre-validation stage is already applied to the state, and is
+        // therefore taken into consideration when calculating the validation fee.
+        let actual_cost = tx
+            .into_actual_cost_builder(&self.block_context)
+            .with_validate_call_info(&validate_call_info)
+            .try_add_state_changes(&mut self.state)?
+            .build(&execution_resources)?;
+
+        Ok((validate_call_info, actual_cost))
+    }
+}
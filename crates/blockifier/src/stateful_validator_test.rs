@@ -0,0 +1,134 @@
+use assert_matches::assert_matches;
+use rstest::rstest;
+use starknet_api::core::Nonce;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::{Fee, TransactionHash};
+
+use super::{StatefulValidator, StatefulValidatorError};
+use crate::block_context::BlockContext;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::{fund_account, test_state};
+use crate::test_utils::{CairoVersion, NonceManager, BALANCE, MAX_FEE};
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::objects::FeeType;
+use crate::transaction::test_utils::{
+    block_context, create_account_tx_for_validate_test, FaultyAccountTxCreatorArgs, INVALID, VALID,
+};
+use crate::transaction::transaction_types::TransactionType;
+
+#[rstest]
+#[case::valid(VALID)]
+#[case::invalid(INVALID)]
+fn test_invoke_validations(
+    block_context: BlockContext,
+    #[case] scenario: u64,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let faulty_account = FeatureContract::FaultyAccount(cairo_version);
+    let sender_address = faulty_account.get_instance_address(0);
+    let state = test_state(&block_context, BALANCE, &[(faulty_account, 1)]);
+    let fee_token_address = block_context.fee_token_address(&FeeType::Eth);
+    let mut validator = StatefulValidator::create(state, block_context, Nonce(StarkFelt::ONE));
+
+    let tx = create_account_tx_for_validate_test(
+        &mut NonceManager::default(),
+        FaultyAccountTxCreatorArgs {
+            tx_type: TransactionType::InvokeFunction,
+            scenario,
+            sender_address,
+            max_fee: Fee(MAX_FEE),
+            ..Default::default()
+        },
+    );
+    let result = validator.perform_validations(tx, None);
+
+    if scenario == INVALID {
+        assert_matches!(
+            result.unwrap_err(),
+            StatefulValidatorError::TransactionExecutionError(
+                TransactionExecutionError::ValidateTransactionError(_)
+            )
+        );
+        return;
+    }
+
+    result.unwrap();
+    // The nonce is incremented, but no fee is charged.
+    assert_eq!(validator.state.get_nonce_at(sender_address).unwrap(), Nonce(StarkFelt::ONE));
+    assert_eq!(
+        validator.state.get_fee_token_balance(sender_address, fee_token_address).unwrap(),
+        (StarkFelt::from(BALANCE), StarkFelt::ZERO)
+    );
+}
+
+#[rstest]
+#[case::deploy_account_not_received(None, Nonce(StarkFelt::ONE), false)]
+#[case::nonce_too_large(Some(TransactionHash::default()), Nonce(StarkFelt::ZERO), false)]
+#[case::unprocessed_deploy_account(Some(TransactionHash::default()), Nonce(StarkFelt::ONE), true)]
+fn test_validate_skip_for_unprocessed_deploy_account(
+    block_context: BlockContext,
+    #[case] deploy_account_tx_hash: Option<TransactionHash>,
+    #[case] max_nonce_for_validation_skip: Nonce,
+    #[case] should_skip_validate: bool,
+) {
+    let faulty_account = FeatureContract::FaultyAccount(CairoVersion::Cairo1);
+    let sender_address = faulty_account.get_instance_address(0);
+    let state = test_state(&block_context, BALANCE, &[(faulty_account, 1)]);
+    let mut validator =
+        StatefulValidator::create(state, block_context, max_nonce_for_validation_skip);
+
+    // The account nonce is zero, as if the deploy account transaction was not processed yet.
+    let mut nonce_manager = NonceManager::default();
+    nonce_manager.next(sender_address);
+    // A transaction that does not pass `__validate__`, sent right after the account deployment.
+    let tx = create_account_tx_for_validate_test(
+        &mut nonce_manager,
+        FaultyAccountTxCreatorArgs {
+            tx_type: TransactionType::InvokeFunction,
+            scenario: INVALID,
+            sender_address,
+            max_fee: Fee(MAX_FEE),
+            ..Default::default()
+        },
+    );
+    let result = validator.perform_validations(tx, deploy_account_tx_hash);
+
+    assert_eq!(result.is_ok(), should_skip_validate);
+}
+
+#[rstest]
+fn test_deploy_account_is_executed(block_context: BlockContext) {
+    let faulty_account = FeatureContract::FaultyAccount(CairoVersion::Cairo0);
+    let mut state = test_state(&block_context, BALANCE, &[(faulty_account, 0)]);
+
+    let tx = create_account_tx_for_validate_test(
+        &mut NonceManager::default(),
+        FaultyAccountTxCreatorArgs {
+            tx_type: TransactionType::DeployAccount,
+            scenario: VALID,
+            class_hash: faulty_account.get_class_hash(),
+            max_fee: Fee(MAX_FEE),
+            ..Default::default()
+        },
+    );
+    let AccountTransaction::DeployAccount(deploy_account_tx) = &tx else {
+        panic!("Expected a deploy account transaction.")
+    };
+    let deployed_account_address = deploy_account_tx.contract_address;
+    fund_account(&block_context, deployed_account_address, BALANCE, &mut state);
+
+    let mut validator = StatefulValidator::create(state, block_context, Nonce(StarkFelt::ONE));
+    validator.perform_validations(tx, None).unwrap();
+
+    // The constructor has run, and the account is deployed.
+    assert_eq!(
+        validator.state.get_class_hash_at(deployed_account_address).unwrap(),
+        faulty_account.get_class_hash()
+    );
+    assert_eq!(
+        validator.state.get_nonce_at(deployed_account_address).unwrap(),
+        Nonce(StarkFelt::ONE)
+    );
+}
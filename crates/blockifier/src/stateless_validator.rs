@@ -0,0 +1,151 @@
+use starknet_api::transaction::{Fee, ResourceBounds};
+use thiserror::Error;
+
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::errors::{
+    ContractClassLimitError, TransactionExecutionError, TransactionFeeError,
+    TransactionVersionError,
+};
+use crate::transaction::objects::AccountTransactionContext;
+use crate::versioned_constants::{ContractClassLimits, TransactionVersionRanges};
+
+#[cfg(test)]
+#[path = "stateless_validator_test.rs"]
+pub mod test;
+
+#[derive(Debug, Error)]
+pub enum StatelessValidatorError {
+    #[error(transparent)]
+    ContractClassLimitError(#[from] ContractClassLimitError),
+    #[error("Calldata length ({calldata_length}) exceeds the maximum ({max_calldata_length}).")]
+    CalldataTooLong { calldata_length: usize, max_calldata_length: usize },
+    #[error(
+        "Max L1 gas amount ({max_amount}) times max L1 gas price ({max_price_per_unit}) overflows."
+    )]
+    L1GasBoundsOverflow { max_amount: u64, max_price_per_unit: u128 },
+    #[error("Signature length ({signature_length}) exceeds the maximum ({max_signature_length}).")]
+    SignatureTooLong { signature_length: usize, max_signature_length: usize },
+    #[error(transparent)]
+    TransactionExecutionError(#[from] TransactionExecutionError),
+    #[error(transparent)]
+    TransactionFeeError(#[from] TransactionFeeError),
+    #[error(transparent)]
+    TransactionVersionError(#[from] TransactionVersionError),
+    #[error("Expected a positive max fee.")]
+    ZeroMaxFee,
+    #[error("Expected positive L1 gas bounds; got: {l1_gas_bounds:?}.")]
+    ZeroL1GasBounds { l1_gas_bounds: ResourceBounds },
+}
+
+pub type StatelessValidatorResult<T> = Result<T, StatelessValidatorError>;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StatelessValidatorConfig {
+    /// If true, transactions must commit to paying a fee (non-zero max fee, or non-zero L1 gas
+    /// bounds for transactions of version 3 and above).
+    pub validate_non_zero_fee: bool,
+    pub max_calldata_length: usize,
+    pub max_signature_length: usize,
+    pub class_limits: ContractClassLimits,
+    pub tx_versions: TransactionVersionRanges,
+}
+
+impl Default for StatelessValidatorConfig {
+    fn default() -> Self {
+        Self {
+            validate_non_zero_fee: true,
+            max_calldata_length: 4000,
+            max_signature_length: 4000,
+            class_limits: ContractClassLimits::default(),
+            tx_versions: TransactionVersionRanges::default(),
+        }
+    }
+}
+
+/// Performs the transaction checks that do not require access to the state (e.g., a gateway
+/// rejecting malformed transactions before they reach the mempool).
+#[derive(Clone, Debug, Default)]
+pub struct StatelessValidator {
+    pub config: StatelessValidatorConfig,
+}
+
+impl StatelessValidator {
+    pub fn new(config: StatelessValidatorConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn validate(&self, tx: &AccountTransaction) -> StatelessValidatorResult<()> {
+        let account_tx_context = tx.get_account_tx_context();
+
+        tx.verify_tx_version(account_tx_context.version(), &self.config.tx_versions)?;
+        self.validate_fee_bounds(&account_tx_context)?;
+        self.validate_tx_size(tx, &account_tx_context)?;
+
+        if let AccountTransaction::Declare(declare_tx) = tx {
+            self.config.class_limits.verify_compiled_class(&declare_tx.contract_class())?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_fee_bounds(
+        &self,
+        account_tx_context: &AccountTransactionContext,
+    ) -> StatelessValidatorResult<()> {
+        match account_tx_context {
+            AccountTransactionContext::Current(context) => {
+                let l1_gas_bounds = context.l1_resource_bounds()?;
+                let ResourceBounds { max_amount, max_price_per_unit } = l1_gas_bounds;
+
+                if self.config.validate_non_zero_fee && (max_amount == 0 || max_price_per_unit == 0)
+                {
+                    return Err(StatelessValidatorError::ZeroL1GasBounds { l1_gas_bounds });
+                }
+                // The maximal fee the account commits to pay must be representable.
+                if u128::from(max_amount).checked_mul(max_price_per_unit).is_none() {
+                    return Err(StatelessValidatorError::L1GasBoundsOverflow {
+                        max_amount,
+                        max_price_per_unit,
+                    });
+                }
+            }
+            AccountTransactionContext::Deprecated(context) => {
+                if self.config.validate_non_zero_fee && context.max_fee == Fee(0) {
+                    return Err(StatelessValidatorError::ZeroMaxFee);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_tx_size(
+        &self,
+        tx: &AccountTransaction,
+        account_tx_context: &AccountTransactionContext,
+    ) -> StatelessValidatorResult<()> {
+        let calldata_length = match tx {
+            AccountTransaction::Declare(_) => 0,
+            AccountTransaction::DeployAccount(tx) => tx.constructor_calldata().0.len(),
+            AccountTransaction::Invoke(tx) => tx.calldata().0.len(),
+        };
+        let max_calldata_length = self.config.max_calldata_length;
+        if calldata_length > max_calldata_length {
+            return Err(StatelessValidatorError::CalldataTooLong {
+                calldata_length,
+                max_calldata_length,
+            });
+        }
+
+        let signature_length = account_tx_context.signature().0.len();
+        let max_signature_length = self.config.max_signature_length;
+        if signature_length > max_signature_length {
+            return Err(StatelessValidatorError::SignatureTooLong {
+                signature_length,
+                max_signature_length,
+            });
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+use assert_matches::assert_matches;
+use rstest::rstest;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::{Calldata, Fee, TransactionSignature, TransactionVersion};
+
+use super::{StatelessValidator, StatelessValidatorConfig, StatelessValidatorError};
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::declare::declare_tx;
+use crate::test_utils::invoke::InvokeTxArgs;
+use crate::test_utils::{CairoVersion, MAX_FEE};
+use crate::transaction::errors::{ContractClassLimitError, TransactionVersionError};
+use crate::transaction::test_utils::{account_invoke_tx, l1_resource_bounds};
+use crate::versioned_constants::{TransactionVersionRanges, VersionRange};
+use crate::{declare_tx_args, invoke_tx_args};
+
+fn valid_invoke_args(version: TransactionVersion) -> InvokeTxArgs {
+    invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        resource_bounds: l1_resource_bounds(1000, 1),
+        version,
+    }
+}
+
+#[rstest]
+fn test_valid_invoke(
+    #[values(TransactionVersion::ONE, TransactionVersion::THREE)] version: TransactionVersion,
+) {
+    let tx = account_invoke_tx(valid_invoke_args(version));
+    StatelessValidator::default().validate(&tx).unwrap();
+}
+
+#[test]
+fn test_tx_version_cutoff() {
+    // Reject invoke transactions of version 0.
+    let validator = StatelessValidator::new(StatelessValidatorConfig {
+        tx_versions: TransactionVersionRanges {
+            invoke: VersionRange { min: 1, max: 3 },
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    let v0_tx = account_invoke_tx(valid_invoke_args(TransactionVersion::ZERO));
+
+    assert_matches!(
+        validator.validate(&v0_tx).unwrap_err(),
+        StatelessValidatorError::TransactionVersionError(
+            TransactionVersionError::UnsupportedVersion { version: TransactionVersion::ZERO, allowed_versions, .. }
+        ) if allowed_versions == vec![TransactionVersion::ONE, TransactionVersion::THREE]
+    );
+    validator.validate(&account_invoke_tx(valid_invoke_args(TransactionVersion::ONE))).unwrap();
+}
+
+#[test]
+fn test_tx_size_limits() {
+    let validator = StatelessValidator::new(StatelessValidatorConfig {
+        max_calldata_length: 2,
+        max_signature_length: 2,
+        ..Default::default()
+    });
+    let long_calldata_tx = account_invoke_tx(InvokeTxArgs {
+        calldata: Calldata(vec![StarkFelt::ONE; 3].into()),
+        ..valid_invoke_args(TransactionVersion::ONE)
+    });
+    let long_signature_tx = account_invoke_tx(InvokeTxArgs {
+        signature: TransactionSignature(vec![StarkFelt::ONE; 3]),
+        ..valid_invoke_args(TransactionVersion::ONE)
+    });
+
+    assert_matches!(
+        validator.validate(&long_calldata_tx).unwrap_err(),
+        StatelessValidatorError::CalldataTooLong { calldata_length: 3, max_calldata_length: 2 }
+    );
+    assert_matches!(
+        validator.validate(&long_signature_tx).unwrap_err(),
+        StatelessValidatorError::SignatureTooLong { signature_length: 3, max_signature_length: 2 }
+    );
+}
+
+#[rstest]
+fn test_zero_fee(#[values(true, false)] validate_non_zero_fee: bool) {
+    let validator = StatelessValidator::new(StatelessValidatorConfig {
+        validate_non_zero_fee,
+        ..Default::default()
+    });
+    let deprecated_tx = account_invoke_tx(InvokeTxArgs {
+        max_fee: Fee(0),
+        ..valid_invoke_args(TransactionVersion::ONE)
+    });
+    let current_tx = account_invoke_tx(InvokeTxArgs {
+        resource_bounds: l1_resource_bounds(0, 1),
+        ..valid_invoke_args(TransactionVersion::THREE)
+    });
+
+    let deprecated_result = validator.validate(&deprecated_tx);
+    let current_result = validator.validate(&current_tx);
+    if validate_non_zero_fee {
+        assert_matches!(deprecated_result.unwrap_err(), StatelessValidatorError::ZeroMaxFee);
+        assert_matches!(
+            current_result.unwrap_err(),
+            StatelessValidatorError::ZeroL1GasBounds { .. }
+        );
+    } else {
+        deprecated_result.unwrap();
+        current_result.unwrap();
+    }
+}
+
+#[test]
+fn test_l1_gas_bounds_overflow() {
+    let tx = account_invoke_tx(InvokeTxArgs {
+        resource_bounds: l1_resource_bounds(u64::MAX, u128::MAX),
+        ..valid_invoke_args(TransactionVersion::THREE)
+    });
+    assert_matches!(
+        StatelessValidator::default().validate(&tx).unwrap_err(),
+        StatelessValidatorError::L1GasBoundsOverflow { max_amount: u64::MAX, .. }
+    );
+}
+
+#[test]
+fn test_declared_class_size_limit() {
+    let contract_class = FeatureContract::TestContract(CairoVersion::Cairo0).get_class();
+    let bytecode_length = contract_class.bytecode_length();
+    let tx = declare_tx(declare_tx_args! { max_fee: Fee(MAX_FEE) }, contract_class);
+
+    let mut config = StatelessValidatorConfig::default();
+    config.class_limits.max_bytecode_length = bytecode_length;
+    StatelessValidator::new(config.clone()).validate(&tx).unwrap();
+
+    config.class_limits.max_bytecode_length = bytecode_length - 1;
+    assert_matches!(
+        StatelessValidator::new(config).validate(&tx).unwrap_err(),
+        StatelessValidatorError::ContractClassLimitError(
+            ContractClassLimitError::BytecodeTooLong { .. }
+        )
+    );
+}
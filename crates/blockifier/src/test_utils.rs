@@ -9,6 +9,7 @@ pub mod prices;
 pub mod struct_impls;
 
 use std::collections::HashMap;
+use std::fmt::Write;
 use std::fs;
 use std::path::PathBuf;
 
@@ -30,7 +31,7 @@ use crate::abi::constants::{self};
 use crate::execution::contract_class::{ContractClass, ContractClassV0};
 use crate::execution::entry_point::{CallEntryPoint, CallType};
 use crate::execution::execution_utils::felt_to_stark_felt;
-use crate::utils::const_max;
+use crate::utils::{const_max, usize_to_felt};
 
 // TODO(Dori, 1/2/2024): Remove these constants once all tests use the `contracts` and
 //   `initial_test_state` modules for testing.
@@ -119,6 +120,8 @@ pub const BALANCE: u128 = 10 * const_max(MAX_FEE, MAX_RESOURCE_COMMITMENT);
 
 pub const DEFAULT_ETH_L1_GAS_PRICE: u128 = 100 * u128::pow(10, 9); // Given in units of Wei.
 pub const DEFAULT_STRK_L1_GAS_PRICE: u128 = 100 * u128::pow(10, 9); // Given in units of STRK.
+pub const DEFAULT_ETH_L2_GAS_PRICE: u128 = u128::pow(10, 6); // Given in units of Wei.
+pub const DEFAULT_STRK_L2_GAS_PRICE: u128 = u128::pow(10, 6); // Given in units of STRK.
 
 // The block number of the BlockContext being used for testing.
 pub const CURRENT_BLOCK_NUMBER: u64 = 2000;
@@ -350,8 +353,7 @@ pub fn create_calldata(
     entry_point_name: &str,
     entry_point_args: &[StarkFelt],
 ) -> Calldata {
-    let n_args = u128::try_from(entry_point_args.len()).expect("Calldata too big");
-    let n_args = StarkFelt::from(n_args);
+    let n_args = usize_to_felt(entry_point_args.len());
 
     let mut calldata = vec![
         *contract_address.0.key(),              // Contract address.
@@ -362,3 +364,11 @@ pub fn create_calldata(
 
     Calldata(calldata.into())
 }
+
+/// Returns the hex encoding of the given bytes (e.g., of a hash), without a `0x` prefix.
+pub fn to_hex_string(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        write!(hex, "{byte:02x}").expect("Writing to a string cannot fail.");
+        hex
+    })
+}
@@ -22,6 +22,7 @@ const LEGACY_CONTRACT_BASE: u32 = 5 * CLASS_HASH_BASE;
 const SECURITY_TEST_CONTRACT_BASE: u32 = 6 * CLASS_HASH_BASE;
 const TEST_CONTRACT_BASE: u32 = 7 * CLASS_HASH_BASE;
 const ERC20_CONTRACT_BASE: u32 = 8 * CLASS_HASH_BASE;
+const UNIVERSAL_DEPLOYER_BASE: u32 = 9 * CLASS_HASH_BASE;
 
 // Contract names.
 const ACCOUNT_LONG_VALIDATE_NAME: &str = "account_with_long_validate";
@@ -31,6 +32,7 @@ const FAULTY_ACCOUNT_NAME: &str = "account_faulty";
 const LEGACY_CONTRACT_NAME: &str = "legacy_test_contract";
 const SECURITY_TEST_CONTRACT_NAME: &str = "security_tests_contract";
 const TEST_CONTRACT_NAME: &str = "test_contract";
+const UNIVERSAL_DEPLOYER_NAME: &str = "universal_deployer";
 
 // ERC20 contract is in a unique location.
 const ERC20_CONTRACT_PATH: &str =
@@ -48,6 +50,7 @@ pub enum FeatureContract {
     LegacyTestContract,
     SecurityTests,
     TestContract(CairoVersion),
+    UniversalDeployer,
 }
 
 impl FeatureContract {
@@ -58,7 +61,7 @@ impl FeatureContract {
             | Self::Empty(version)
             | Self::FaultyAccount(version)
             | Self::TestContract(version) => *version,
-            Self::SecurityTests | Self::ERC20 => CairoVersion::Cairo0,
+            Self::SecurityTests | Self::ERC20 | Self::UniversalDeployer => CairoVersion::Cairo0,
             Self::LegacyTestContract => CairoVersion::Cairo1,
         }
     }
@@ -82,6 +85,7 @@ impl FeatureContract {
                 Self::LegacyTestContract => LEGACY_CONTRACT_BASE,
                 Self::SecurityTests => SECURITY_TEST_CONTRACT_BASE,
                 Self::TestContract(_) => TEST_CONTRACT_BASE,
+                Self::UniversalDeployer => UNIVERSAL_DEPLOYER_BASE,
             }
     }
 
@@ -95,6 +99,7 @@ impl FeatureContract {
             Self::LegacyTestContract => LEGACY_CONTRACT_NAME,
             Self::SecurityTests => SECURITY_TEST_CONTRACT_NAME,
             Self::TestContract(_) => TEST_CONTRACT_NAME,
+            Self::UniversalDeployer => UNIVERSAL_DEPLOYER_NAME,
             // ERC20 is a special case - not in the feature_contracts directory.
             Self::ERC20 => return ERC20_CONTRACT_PATH.into(),
         };
@@ -119,7 +124,10 @@ impl FeatureContract {
             | Self::Empty(v)
             | Self::FaultyAccount(v)
             | Self::TestContract(v) => *v = version,
-            Self::ERC20 | Self::LegacyTestContract | Self::SecurityTests => {
+            Self::ERC20
+            | Self::LegacyTestContract
+            | Self::SecurityTests
+            | Self::UniversalDeployer => {
                 panic!("{self:?} contract has no configurable version.")
             }
         }
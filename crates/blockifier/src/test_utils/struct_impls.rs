@@ -1,10 +1,6 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use cairo_vm::vm::runners::builtin_runner::{
-    BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME, OUTPUT_BUILTIN_NAME,
-    POSEIDON_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME, SIGNATURE_BUILTIN_NAME,
-};
 use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::core::{ChainId, ContractAddress, PatriciaKey};
 use starknet_api::hash::StarkHash;
@@ -12,10 +8,9 @@ use starknet_api::{contract_address, patricia_key};
 
 use super::{
     CHAIN_ID_NAME, CURRENT_BLOCK_NUMBER, CURRENT_BLOCK_TIMESTAMP, DEFAULT_ETH_L1_GAS_PRICE,
-    DEFAULT_STRK_L1_GAS_PRICE, TEST_ERC20_CONTRACT_ADDRESS, TEST_ERC20_CONTRACT_ADDRESS2,
-    TEST_SEQUENCER_ADDRESS,
+    DEFAULT_ETH_L2_GAS_PRICE, DEFAULT_STRK_L1_GAS_PRICE, DEFAULT_STRK_L2_GAS_PRICE,
+    TEST_ERC20_CONTRACT_ADDRESS, TEST_ERC20_CONTRACT_ADDRESS2, TEST_SEQUENCER_ADDRESS,
 };
-use crate::abi::constants;
 use crate::abi::constants::{MAX_STEPS_PER_TX, MAX_VALIDATE_STEPS_PER_TX};
 use crate::block_context::{BlockContext, FeeTokenAddresses, GasPrices};
 use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
@@ -23,9 +18,11 @@ use crate::execution::contract_class::{ContractClassV0, ContractClassV1};
 use crate::execution::entry_point::{
     CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult, ExecutionResources,
 };
+use crate::fee::vm_resource_costs::{Ratio, VmResourceFeeCosts, VmResourceKey};
 use crate::state::state_api::State;
 use crate::test_utils::get_raw_contract_class;
 use crate::transaction::objects::{AccountTransactionContext, DeprecatedAccountTransactionContext};
+use crate::versioned_constants::VersionedConstants;
 
 impl CallEntryPoint {
     /// Executes the call directly, without account context. Limits the number of steps by resource
@@ -99,24 +96,41 @@ impl BlockContext {
             gas_prices: GasPrices {
                 eth_l1_gas_price: DEFAULT_ETH_L1_GAS_PRICE,
                 strk_l1_gas_price: DEFAULT_STRK_L1_GAS_PRICE,
+                eth_l2_gas_price: DEFAULT_ETH_L2_GAS_PRICE,
+                strk_l2_gas_price: DEFAULT_STRK_L2_GAS_PRICE,
             },
+            concurrency_mode: false,
+            charge_tips: false,
             invoke_tx_max_n_steps: MAX_STEPS_PER_TX as u32,
             validate_max_n_steps: MAX_VALIDATE_STEPS_PER_TX as u32,
             max_recursion_depth: 50,
+            max_nonce_gap: 0,
+            deprecated_hint_aliases: Arc::default(),
+            unknown_hint_policy: None,
+            retain_sierra_classes: false,
+            determinism_audit: false,
+            verify_retdata_segments: false,
+            collect_program_outputs: false,
+            versioned_constants: VersionedConstants::default(),
+            hint_extension: None,
+            custom_syscalls: Arc::default(),
+            fee_transfer_delegate: None,
+            fee_model: None,
+            metrics_sink: None,
         }
     }
 
     pub fn create_for_account_testing() -> BlockContext {
-        let vm_resource_fee_cost = Arc::new(HashMap::from([
-            (constants::N_STEPS_RESOURCE.to_string(), 1_f64),
-            (HASH_BUILTIN_NAME.to_string(), 1_f64),
-            (RANGE_CHECK_BUILTIN_NAME.to_string(), 1_f64),
-            (SIGNATURE_BUILTIN_NAME.to_string(), 1_f64),
-            (BITWISE_BUILTIN_NAME.to_string(), 1_f64),
-            (POSEIDON_BUILTIN_NAME.to_string(), 1_f64),
-            (OUTPUT_BUILTIN_NAME.to_string(), 1_f64),
-            (EC_OP_BUILTIN_NAME.to_string(), 1_f64),
-        ]));
+        let vm_resource_fee_cost = Arc::new(VmResourceFeeCosts::from(HashMap::from([
+            (VmResourceKey::NSteps, Ratio::from(1)),
+            (VmResourceKey::Pedersen, Ratio::from(1)),
+            (VmResourceKey::RangeCheck, Ratio::from(1)),
+            (VmResourceKey::Ecdsa, Ratio::from(1)),
+            (VmResourceKey::Bitwise, Ratio::from(1)),
+            (VmResourceKey::Poseidon, Ratio::from(1)),
+            (VmResourceKey::Output, Ratio::from(1)),
+            (VmResourceKey::EcOp, Ratio::from(1)),
+        ])));
         BlockContext { vm_resource_fee_cost, ..BlockContext::create_for_testing() }
     }
 }
@@ -3,7 +3,7 @@ use starknet_api::calldata;
 use starknet_api::core::{ContractAddress, EntryPointSelector};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkFelt;
-use starknet_api::transaction::{Calldata, Fee, ResourceBounds, TransactionVersion};
+use starknet_api::transaction::{Calldata, Fee, ResourceBounds, Tip, TransactionVersion};
 
 use crate::abi::abi_utils::selector_from_name;
 use crate::abi::constants as abi_constants;
@@ -15,26 +15,30 @@ use crate::execution::entry_point::{
 };
 use crate::fee::actual_cost::{ActualCost, ActualCostBuilder};
 use crate::fee::fee_checks::{FeeCheckReportFields, PostExecutionReport};
-use crate::fee::fee_utils::{get_fee_by_l1_gas_usage, verify_can_pay_committed_bounds};
-use crate::fee::gas_usage::estimate_minimal_l1_gas;
+use crate::fee::fee_transfer_delegate::DelegatedFeeTransfer;
+use crate::fee::fee_utils::verify_can_pay_committed_bounds;
+use crate::fee::gas_usage::estimate_minimal_gas_vector;
+use crate::fee::sequencer_fee::{is_self_fee_transfer, SequencerBalanceMask};
 use crate::retdata;
 use crate::state::cached_state::{CachedState, TransactionalState};
 use crate::state::state_api::{State, StateReader};
 use crate::transaction::constants;
 use crate::transaction::errors::{
     TransactionExecutionError, TransactionFeeError, TransactionPreValidationError,
+    TransactionVersionError,
 };
 use crate::transaction::objects::{
-    AccountTransactionContext, HasRelatedFeeType, TransactionExecutionInfo,
-    TransactionExecutionResult, TransactionPreValidationResult,
+    AccountTransactionContext, HasRelatedFeeType, RevertReport, TransactionExecutionInfo,
+    TransactionExecutionResult, TransactionPhase, TransactionPreValidationResult,
 };
-use crate::transaction::transaction_execution::Transaction;
 use crate::transaction::transaction_types::TransactionType;
 use crate::transaction::transaction_utils::update_remaining_gas;
 use crate::transaction::transactions::{
     DeclareTransaction, DeployAccountTransaction, Executable, ExecutableTransaction,
     InvokeTransaction, ValidatableTransaction,
 };
+use crate::utils::{checked_felt_sub, felt_to_u64};
+use crate::versioned_constants::TransactionVersionRanges;
 
 #[cfg(test)]
 #[path = "account_transactions_test.rs"]
@@ -49,7 +53,7 @@ mod flavors_test;
 mod post_execution_test;
 
 /// Represents a paid Starknet transaction.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum AccountTransaction {
     Declare(DeclareTransaction),
     DeployAccount(DeployAccountTransaction),
@@ -118,8 +122,14 @@ impl AccountTransaction {
         }
     }
 
-    fn verify_tx_version(&self, version: TransactionVersion) -> TransactionExecutionResult<()> {
-        let allowed_versions: Vec<TransactionVersion> = match self {
+    /// Verifies that the transaction version exists for the transaction type, and is within the
+    /// configured range.
+    pub(crate) fn verify_tx_version(
+        &self,
+        version: TransactionVersion,
+        tx_versions: &TransactionVersionRanges,
+    ) -> Result<(), TransactionVersionError> {
+        let mut allowed_versions: Vec<TransactionVersion> = match self {
             // Support `Declare` of version 0 in order to allow bootstrapping of a new system.
             Self::Declare(_) => {
                 vec![
@@ -136,10 +146,13 @@ impl AccountTransaction {
                 vec![TransactionVersion::ZERO, TransactionVersion::ONE, TransactionVersion::THREE]
             }
         };
+        let tx_type = self.tx_type();
+        let version_range = tx_versions.get(tx_type);
+        allowed_versions.retain(|allowed_version| version_range.contains(*allowed_version));
         if allowed_versions.contains(&version) {
             Ok(())
         } else {
-            Err(TransactionExecutionError::InvalidVersion { version, allowed_versions })
+            Err(TransactionVersionError::UnsupportedVersion { tx_type, version, allowed_versions })
         }
     }
 
@@ -153,7 +166,12 @@ impl AccountTransaction {
         charge_fee: bool,
         strict_nonce_check: bool,
     ) -> TransactionPreValidationResult<()> {
-        Self::handle_nonce(state, account_tx_context, strict_nonce_check)?;
+        Self::handle_nonce(
+            state,
+            account_tx_context,
+            strict_nonce_check,
+            block_context.max_nonce_gap,
+        )?;
 
         if charge_fee && account_tx_context.enforce_fee()? {
             self.check_fee_bounds(account_tx_context, block_context)?;
@@ -169,7 +187,8 @@ impl AccountTransaction {
         account_tx_context: &AccountTransactionContext,
         block_context: &BlockContext,
     ) -> TransactionPreValidationResult<()> {
-        let minimal_l1_gas_amount = estimate_minimal_l1_gas(block_context, self)?;
+        let minimal_gas = estimate_minimal_gas_vector(block_context, self)?;
+        let minimal_l1_gas_amount = minimal_gas.l1_gas;
 
         match account_tx_context {
             AccountTransactionContext::Current(context) => {
@@ -181,26 +200,53 @@ impl AccountTransaction {
                 if (max_l1_gas_amount as u128) < minimal_l1_gas_amount {
                     return Err(TransactionFeeError::MaxL1GasAmountTooLow {
                         max_l1_gas_amount,
-                        minimal_l1_gas_amount: (minimal_l1_gas_amount as u64),
+                        // Only reported; saturating does not affect the check.
+                        minimal_l1_gas_amount: u64::try_from(minimal_l1_gas_amount)
+                            .unwrap_or(u64::MAX),
                     })?;
                 }
 
-                let actual_l1_gas_price =
-                    block_context.gas_prices.get_by_fee_type(&account_tx_context.fee_type());
+                let actual_l1_gas_price = account_tx_context.l1_gas_price(block_context);
                 if max_l1_gas_price < actual_l1_gas_price {
                     return Err(TransactionFeeError::MaxL1GasPriceTooLow {
                         max_l1_gas_price,
                         actual_l1_gas_price,
                     })?;
                 }
+
+                if block_context.versioned_constants.resource_gas_mode.charges_l2_gas() {
+                    let ResourceBounds {
+                        max_amount: max_l2_gas_amount,
+                        max_price_per_unit: max_l2_gas_price,
+                    } = context.l2_resource_bounds();
+
+                    if (max_l2_gas_amount as u128) < minimal_gas.l2_gas {
+                        return Err(TransactionFeeError::MaxL2GasAmountTooLow {
+                            max_l2_gas_amount,
+                            minimal_l2_gas_amount: u64::try_from(minimal_gas.l2_gas)
+                                .unwrap_or(u64::MAX),
+                        })?;
+                    }
+
+                    let actual_l2_gas_price = block_context
+                        .gas_prices
+                        .get_l2_gas_price_by_fee_type(&account_tx_context.fee_type());
+                    if max_l2_gas_price < actual_l2_gas_price {
+                        return Err(TransactionFeeError::MaxL2GasPriceTooLow {
+                            max_l2_gas_price,
+                            actual_l2_gas_price,
+                        })?;
+                    }
+                }
             }
             AccountTransactionContext::Deprecated(context) => {
                 let max_fee = context.max_fee;
-                let min_fee = get_fee_by_l1_gas_usage(
+                let min_fee = block_context.fee_model().calculate_fee(
                     block_context,
-                    minimal_l1_gas_amount,
+                    &minimal_gas,
                     &account_tx_context.fee_type(),
-                );
+                    Tip::default(),
+                )?;
                 if max_fee < min_fee {
                     return Err(TransactionFeeError::MaxFeeTooLow { min_fee, max_fee })?;
                 }
@@ -209,10 +255,14 @@ impl AccountTransaction {
         Ok(())
     }
 
+    /// Verifies the transaction nonce against the account nonce, and increments the latter.
+    /// In strict mode, the transaction nonce may be ahead of the account nonce by at most
+    /// `max_nonce_gap`; otherwise, it may be arbitrarily ahead.
     fn handle_nonce(
         state: &mut dyn State,
         account_tx_context: &AccountTransactionContext,
         strict: bool,
+        max_nonce_gap: u64,
     ) -> TransactionPreValidationResult<()> {
         if account_tx_context.is_v0() {
             return Ok(());
@@ -222,7 +272,9 @@ impl AccountTransaction {
         let account_nonce = state.get_nonce_at(address)?;
         let incoming_tx_nonce = account_tx_context.nonce();
         let valid_nonce = if strict {
-            account_nonce == incoming_tx_nonce
+            checked_felt_sub(incoming_tx_nonce.0, account_nonce.0)
+                .and_then(|nonce_gap| felt_to_u64(nonce_gap).ok())
+                .is_some_and(|nonce_gap| nonce_gap <= max_nonce_gap)
         } else {
             account_nonce <= incoming_tx_nonce
         };
@@ -261,24 +313,52 @@ impl AccountTransaction {
         }
     }
 
+    /// Charges the actual fee; returns the fee transfer call info, or the transfer left to the
+    /// embedder if fees are charged through a fee transfer delegate.
     fn handle_fee(
         &self,
         state: &mut dyn State,
         block_context: &BlockContext,
         actual_fee: Fee,
         charge_fee: bool,
-    ) -> TransactionExecutionResult<Option<CallInfo>> {
-        if !charge_fee || actual_fee == Fee(0) {
-            // Fee charging is not enforced in some transaction simulations and tests.
-            return Ok(None);
+    ) -> TransactionExecutionResult<(Option<CallInfo>, Option<DelegatedFeeTransfer>)> {
+        let account_tx_context = self.get_account_tx_context();
+        if !charge_fee || !account_tx_context.enforce_fee()? || actual_fee == Fee(0) {
+            // Fee charging is disabled in some transaction simulations and tests, and is not
+            // enforced for transactions with a zero max fee (e.g., on free-transaction devnets).
+            return Ok((None, None));
         }
 
-        // Charge fee.
-        let account_tx_context = self.get_account_tx_context();
-        let fee_transfer_call_info =
-            Self::execute_fee_transfer(state, block_context, account_tx_context, actual_fee)?;
+        if block_context.fee_transfer_delegate.is_some() {
+            let delegated_fee_transfer = DelegatedFeeTransfer {
+                sender_address: account_tx_context.sender_address(),
+                sequencer_address: block_context.sequencer_address,
+                fee_type: account_tx_context.fee_type(),
+                amount: actual_fee,
+            };
+            return Ok((None, Some(delegated_fee_transfer)));
+        }
 
-        Ok(Some(fee_transfer_call_info))
+        // Charge fee. A fee paid by the sequencer to itself is transferred as usual (the OS runs
+        // the transfer as well); it leaves the balance unchanged.
+        let fee_transfer_call_info = if block_context.concurrency_mode
+            && !is_self_fee_transfer(block_context, account_tx_context.sender_address())
+        {
+            // The sequencer is credited later on; see `DeferredSequencerFees`.
+            let fee_token_address = block_context.fee_token_address(&account_tx_context.fee_type());
+            let mut masked_state =
+                SequencerBalanceMask::new(state, block_context, fee_token_address);
+            Self::execute_fee_transfer(
+                &mut masked_state,
+                block_context,
+                account_tx_context,
+                actual_fee,
+            )?
+        } else {
+            Self::execute_fee_transfer(state, block_context, account_tx_context, actual_fee)?
+        };
+
+        Ok((Some(fee_transfer_call_info), None))
     }
 
     fn execute_fee_transfer(
@@ -493,9 +573,15 @@ impl AccountTransaction {
                         Ok(ValidateExecuteCallInfo::new_reverted(
                             validate_call_info,
                             post_execution_error.to_string(),
+                            RevertReport {
+                                phase: TransactionPhase::PostExecution,
+                                n_steps: execution_steps_consumed,
+                                failing_contract_address: None,
+                            },
                             ActualCost {
                                 actual_fee: post_execution_report.recommended_fee(),
                                 actual_resources: revert_cost.actual_resources,
+                                actual_gas: revert_cost.actual_gas,
                             },
                         ))
                     }
@@ -523,9 +609,20 @@ impl AccountTransaction {
                 Ok(ValidateExecuteCallInfo::new_reverted(
                     validate_call_info,
                     execution_context.error_trace(),
+                    RevertReport {
+                        phase: TransactionPhase::Execution,
+                        n_steps: execution_steps_consumed,
+                        // The error stack is built as the error propagates; the deepest frame is
+                        // pushed first.
+                        failing_contract_address: execution_context
+                            .error_stack
+                            .first()
+                            .map(|(contract_address, _)| *contract_address),
+                    },
                     ActualCost {
                         actual_fee: post_execution_report.recommended_fee(),
                         actual_resources: revert_cost.actual_resources,
+                        actual_gas: revert_cost.actual_gas,
                     },
                 ))
             }
@@ -592,7 +689,22 @@ impl<S: StateReader> ExecutableTransaction<S> for AccountTransaction {
     ) -> TransactionExecutionResult<TransactionExecutionInfo> {
         let account_tx_context = self.get_account_tx_context();
 
-        self.verify_tx_version(account_tx_context.version())?;
+        self.verify_tx_version(
+            account_tx_context.version(),
+            &block_context.versioned_constants.tx_versions,
+        )?;
+        if let Self::Declare(tx) = &self {
+            block_context
+                .versioned_constants
+                .class_limits
+                .verify_compiled_class(&tx.contract_class())?;
+        }
+
+        // A transaction executed ahead of the account nonce (see `BlockContext::max_nonce_gap`)
+        // assumes the preceding transactions; its result is speculative.
+        let is_speculative = !account_tx_context.is_v0()
+            && state.get_nonce_at(account_tx_context.sender_address())?
+                != account_tx_context.nonce();
 
         // Nonce and fee check should be done before running user code.
         let strict_nonce_check = true;
@@ -605,24 +717,39 @@ impl<S: StateReader> ExecutableTransaction<S> for AccountTransaction {
         )?;
 
         // Run validation and execution.
-        let mut remaining_gas = Transaction::initial_gas();
+        let mut remaining_gas = block_context.versioned_constants.tx_initial_gas;
         let ValidateExecuteCallInfo {
             validate_call_info,
             execute_call_info,
             revert_error,
-            final_cost: ActualCost { actual_fee: final_fee, actual_resources: final_resources },
+            revert_report,
+            final_cost:
+                ActualCost {
+                    actual_fee: final_fee,
+                    actual_resources: final_resources,
+                    actual_gas: final_gas,
+                },
         } = self.run_or_revert(state, &mut remaining_gas, block_context, validate, charge_fee)?;
 
-        let fee_transfer_call_info =
+        let (fee_transfer_call_info, delegated_fee_transfer) =
             self.handle_fee(state, block_context, final_fee, charge_fee)?;
 
         let tx_execution_info = TransactionExecutionInfo {
             validate_call_info,
             execute_call_info,
             fee_transfer_call_info,
+            delegated_fee_transfer,
             actual_fee: final_fee,
             actual_resources: final_resources,
+            actual_gas: final_gas,
             revert_error,
+            is_speculative,
+            l1_handler_fee: None,
+            refund: Fee(account_tx_context
+                .saturating_committed_fee(block_context)
+                .0
+                .saturating_sub(final_fee.0)),
+            revert_report,
         };
         Ok(tx_execution_info)
     }
@@ -633,6 +760,7 @@ struct ValidateExecuteCallInfo {
     validate_call_info: Option<CallInfo>,
     execute_call_info: Option<CallInfo>,
     revert_error: Option<String>,
+    revert_report: Option<RevertReport>,
     final_cost: ActualCost,
 }
 
@@ -642,18 +770,26 @@ impl ValidateExecuteCallInfo {
         execute_call_info: Option<CallInfo>,
         final_cost: ActualCost,
     ) -> Self {
-        Self { validate_call_info, execute_call_info, revert_error: None, final_cost }
+        Self {
+            validate_call_info,
+            execute_call_info,
+            revert_error: None,
+            revert_report: None,
+            final_cost,
+        }
     }
 
     pub fn new_reverted(
         validate_call_info: Option<CallInfo>,
         revert_error: String,
+        revert_report: RevertReport,
         final_cost: ActualCost,
     ) -> Self {
         Self {
             validate_call_info,
             execute_call_info: None,
             revert_error: Some(revert_error),
+            revert_report: Some(revert_report),
             final_cost,
         }
     }
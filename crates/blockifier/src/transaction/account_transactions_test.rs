@@ -6,7 +6,7 @@ use cairo_vm::vm::runners::cairo_runner::ResourceTracker;
 use pretty_assertions::assert_eq;
 use rstest::rstest;
 use starknet_api::core::{
-    calculate_contract_address, ClassHash, ContractAddress, Nonce, PatriciaKey,
+    calculate_contract_address, ClassHash, CompiledClassHash, ContractAddress, Nonce, PatriciaKey,
 };
 use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::state::StorageKey;
@@ -41,7 +41,7 @@ use crate::test_utils::{
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::constants::TRANSFER_ENTRY_POINT_NAME;
 use crate::transaction::errors::TransactionExecutionError;
-use crate::transaction::objects::{FeeType, HasRelatedFeeType};
+use crate::transaction::objects::{FeeType, HasRelatedFeeType, TransactionPhase};
 use crate::transaction::test_utils::{
     account_invoke_tx, block_context, create_account_tx_for_validate_test, create_test_init_data,
     deploy_and_fund_account, l1_resource_bounds, max_fee, max_resource_bounds, run_invoke_tx,
@@ -103,7 +103,16 @@ fn test_enforce_fee_false_works(block_context: BlockContext, #[case] version: Tr
     )
     .unwrap();
     assert!(!tx_execution_info.is_reverted());
-    assert_eq!(tx_execution_info.actual_fee, Fee(0));
+
+    // The actual fee is reported, but not charged.
+    assert!(tx_execution_info.actual_fee > Fee(0));
+    assert!(tx_execution_info.fee_transfer_call_info.is_none());
+    let fee_type = if version == TransactionVersion::THREE { FeeType::Strk } else { FeeType::Eth };
+    let fee_token_address = block_context.fee_token_address(&fee_type);
+    assert_eq!(
+        state.get_fee_token_balance(account_address, fee_token_address).unwrap(),
+        (stark_felt!(BALANCE), stark_felt!(0_u8))
+    );
 }
 
 // TODO(Dori, 15/9/2023): Convert version variance to attribute macro.
@@ -330,7 +339,8 @@ fn test_max_fee_limit_validate(
     });
     let estimated_min_l1_gas = estimate_minimal_l1_gas(&block_context, &account_tx).unwrap();
     let estimated_min_fee =
-        get_fee_by_l1_gas_usage(&block_context, estimated_min_l1_gas, &account_tx.fee_type());
+        get_fee_by_l1_gas_usage(&block_context, estimated_min_l1_gas, &account_tx.fee_type())
+            .unwrap();
 
     let error = run_invoke_tx(
         &mut state,
@@ -430,6 +440,7 @@ fn test_recursion_depth_exceeded(
 fn test_revert_invoke(
     block_context: BlockContext,
     max_fee: Fee,
+    max_resource_bounds: ResourceBoundsMapping,
     #[case] transaction_version: TransactionVersion,
     #[case] fee_type: FeeType,
 ) {
@@ -447,6 +458,7 @@ fn test_revert_invoke(
         &block_context,
         invoke_tx_args! {
             max_fee,
+            resource_bounds: max_resource_bounds,
             sender_address: account_address,
             calldata: create_calldata(
                 test_contract_address,
@@ -536,7 +548,8 @@ fn test_fail_declare(block_context: BlockContext, max_fee: Fee) {
     let contract_class = ContractClass::V1(ContractClassV1::default());
     let next_nonce = nonce_manager.next(account_address);
 
-    // Cannot fail executing a declare tx unless it's V2 or above, and already declared.
+    // Cannot fail executing a declare tx unless it's V2 or above, and already declared with a
+    // different compiled class hash.
     let declare_tx = DeclareTransactionV2 {
         max_fee,
         class_hash,
@@ -544,7 +557,7 @@ fn test_fail_declare(block_context: BlockContext, max_fee: Fee) {
         ..Default::default()
     };
     state.set_contract_class(class_hash, contract_class.clone()).unwrap();
-    state.set_compiled_class_hash(class_hash, declare_tx.compiled_class_hash).unwrap();
+    state.set_compiled_class_hash(class_hash, CompiledClassHash(stark_felt!(1_u8))).unwrap();
     let declare_account_tx = AccountTransaction::Declare(
         DeclareTransaction::new(
             starknet_api::transaction::DeclareTransaction::V2(DeclareTransactionV2 {
@@ -902,6 +915,7 @@ fn test_insufficient_max_fee_reverts(
     )
     .unwrap();
     assert!(!tx_execution_info1.is_reverted());
+    assert_eq!(tx_execution_info1.revert_report, None);
     let actual_fee_depth1 = tx_execution_info1.actual_fee;
 
     // Invoke the `recurse` function with depth of 2 and the actual fee of depth 1 as max_fee.
@@ -921,6 +935,9 @@ fn test_insufficient_max_fee_reverts(
     assert!(tx_execution_info2.is_reverted());
     assert!(tx_execution_info2.actual_fee == actual_fee_depth1);
     assert!(tx_execution_info2.revert_error.unwrap().starts_with("Insufficient max fee"));
+    let post_execution_revert_report = tx_execution_info2.revert_report.unwrap();
+    assert_eq!(post_execution_revert_report.phase, TransactionPhase::PostExecution);
+    assert_eq!(post_execution_revert_report.failing_contract_address, None);
 
     // Invoke the `recurse` function with depth of 824 and the actual fee of depth 1 as max_fee.
     // This call should fail due to no remaining steps (execution steps based on max_fee are bounded
@@ -938,9 +955,15 @@ fn test_insufficient_max_fee_reverts(
     .unwrap();
     assert!(tx_execution_info3.is_reverted());
     assert!(tx_execution_info3.actual_fee == actual_fee_depth1);
-    assert!(
-        tx_execution_info3.revert_error.unwrap().contains("RunResources has no remaining steps.")
-    );
+    assert!(tx_execution_info3
+        .revert_error
+        .unwrap()
+        .contains("RunResources has no remaining steps."));
+    let revert_report = tx_execution_info3.revert_report.unwrap();
+    assert_eq!(revert_report.phase, TransactionPhase::Execution);
+    // Running out of steps consumes more than the full (shallower) execution above.
+    assert!(revert_report.n_steps > post_execution_revert_report.n_steps);
+    assert_eq!(revert_report.failing_contract_address, Some(contract_address));
 }
 
 #[rstest]
@@ -1,6 +1,6 @@
 use starknet_api::core::{ClassHash, ContractAddress, Nonce};
 use starknet_api::hash::StarkFelt;
-use starknet_api::transaction::{Fee, TransactionVersion};
+use starknet_api::transaction::{Fee, ResourceBounds, TransactionVersion};
 use starknet_api::StarknetApiError;
 use thiserror::Error;
 
@@ -8,6 +8,9 @@ use crate::execution::call_info::Retdata;
 use crate::execution::errors::EntryPointExecutionError;
 use crate::fee::fee_checks::FeeCheckError;
 use crate::state::errors::StateError;
+use crate::transaction::objects::TransactionPhase;
+use crate::transaction::transaction_types::TransactionType;
+use crate::utils::{format_address, format_class_hash, format_felt};
 
 #[derive(Debug, Error)]
 pub enum TransactionFeeError {
@@ -15,8 +18,24 @@ pub enum TransactionFeeError {
     CairoResourcesNotContainedInFeeCosts,
     #[error(transparent)]
     ExecuteFeeTransferError(#[from] EntryPointExecutionError),
+    #[error("Fee transfer delegate failed: {0}")]
+    FeeTransferDelegateError(String),
     #[error("Actual fee ({actual_fee:?}) exceeded max fee ({max_fee:?}).")]
     FeeTransferError { max_fee: Fee, actual_fee: Fee },
+    #[error("Fee overflow: gas usage ({gas_usage:?}) times gas price ({gas_price:?}).")]
+    FeeOverflow { gas_usage: u128, gas_price: u128 },
+    #[error("Fee overflow: L1 gas fee ({l1_gas_fee:?}) plus L2 gas fee ({l2_gas_fee:?}).")]
+    FeeSumOverflow { l1_gas_fee: Fee, l2_gas_fee: Fee },
+    #[error(
+        "L1 gas bounds ({l1_bounds:?}) and L2 gas bounds ({l2_bounds:?}) exceed balance \
+         (Uint256({balance_low:?}, {balance_high:?}))."
+    )]
+    GasBoundsExceedBalance {
+        l1_bounds: ResourceBounds,
+        l2_bounds: ResourceBounds,
+        balance_low: StarkFelt,
+        balance_high: StarkFelt,
+    },
     #[error("Actual fee ({actual_fee:?}) exceeded paid fee on L1 ({paid_fee:?}).")]
     InsufficientL1Fee { paid_fee: Fee, actual_fee: Fee },
     #[error(
@@ -43,8 +62,23 @@ pub enum TransactionFeeError {
          {minimal_l1_gas_amount:?}."
     )]
     MaxL1GasAmountTooLow { max_l1_gas_amount: u64, minimal_l1_gas_amount: u64 },
+    #[error(
+        "Max L2 gas price ({max_l2_gas_price:?}) is lower than the actual gas price: \
+         {actual_l2_gas_price:?}."
+    )]
+    MaxL2GasPriceTooLow { max_l2_gas_price: u128, actual_l2_gas_price: u128 },
+    #[error(
+        "Max L2 gas amount ({max_l2_gas_amount:?}) is lower than the minimal gas amount: \
+         {minimal_l2_gas_amount:?}."
+    )]
+    MaxL2GasAmountTooLow { max_l2_gas_amount: u64, minimal_l2_gas_amount: u64 },
     #[error("Missing L1 gas bounds in resource bounds.")]
     MissingL1GasBounds,
+    #[error(
+        "The fee transfer in fee token {} does not match the sequencer balance reads.",
+        format_address(fee_token_address)
+    )]
+    SequencerBalanceReadMismatch { fee_token_address: ContractAddress },
     #[error(transparent)]
     StateError(#[from] StateError),
 }
@@ -56,9 +90,14 @@ pub enum TransactionExecutionError {
          version {cairo_version:?}."
     )]
     ContractClassVersionMismatch { declare_version: TransactionVersion, cairo_version: u64 },
+    #[error(transparent)]
+    ContractClassLimitError(#[from] ContractClassLimitError),
     #[error("Contract constructor execution has failed: {0}")]
     ContractConstructorExecutionFailed(#[source] EntryPointExecutionError),
-    #[error("Class with hash {class_hash:?} is already declared.")]
+    #[error(
+        "Class with hash {} is already declared with a different compiled class hash.",
+        format_class_hash(.class_hash)
+    )]
     DeclareTransactionError { class_hash: ClassHash },
     #[error("Transaction execution has failed: {0}")]
     ExecutionError(#[source] EntryPointExecutionError),
@@ -71,11 +110,10 @@ pub enum TransactionExecutionError {
     InvalidOrder { object: String, order: usize, max_order: usize },
     #[error("The `validate` entry point should return `VALID`. Got {actual:?}.")]
     InvalidValidateReturnData { actual: Retdata },
-    #[error(
-        "Transaction version {version:?} is not supported. Supported versions: \
-         {allowed_versions:?}."
-    )]
-    InvalidVersion { version: TransactionVersion, allowed_versions: Vec<TransactionVersion> },
+    #[error(transparent)]
+    L1HandlerTransactionError(#[from] L1HandlerTransactionError),
+    #[error("Two executions of the transaction on the same state differ in their {mismatch}.")]
+    NondeterministicExecution { mismatch: &'static str },
     #[error(transparent)]
     StarknetApiError(#[from] StarknetApiError),
     #[error(transparent)]
@@ -83,6 +121,8 @@ pub enum TransactionExecutionError {
     #[error(transparent)]
     TransactionFeeError(#[from] TransactionFeeError),
     #[error(transparent)]
+    TransactionVersionError(#[from] TransactionVersionError),
+    #[error(transparent)]
     TransactionPreValidationError(#[from] TransactionPreValidationError),
     #[error("Unexpected holes in the {object} order. No object with the order: {order}.")]
     UnexpectedHoles { object: String, order: usize },
@@ -90,11 +130,52 @@ pub enum TransactionExecutionError {
     ValidateTransactionError(#[source] EntryPointExecutionError),
 }
 
+impl TransactionExecutionError {
+    /// Returns the phase of the transaction that failed; [None] for errors that are not tied to a
+    /// phase (e.g., invalid transactions and state errors).
+    pub fn phase(&self) -> Option<TransactionPhase> {
+        match self {
+            Self::TransactionPreValidationError(_) => Some(TransactionPhase::PreValidation),
+            Self::ValidateTransactionError(_) | Self::InvalidValidateReturnData { .. } => {
+                Some(TransactionPhase::Validation)
+            }
+            Self::ExecutionError(_) | Self::ContractConstructorExecutionFailed(_) => {
+                Some(TransactionPhase::Execution)
+            }
+            Self::FeeCheckError(_) => Some(TransactionPhase::PostExecution),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TransactionVersionError {
+    #[error(
+        "{tx_type:?} transaction version {version:?} is not supported. Supported versions: \
+         {allowed_versions:?}."
+    )]
+    UnsupportedVersion {
+        tx_type: TransactionType,
+        version: TransactionVersion,
+        allowed_versions: Vec<TransactionVersion>,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum L1HandlerTransactionError {
+    #[error("L1 handler transaction calldata must start with the L1 sender address.")]
+    MissingL1Sender,
+    #[error("L1 handler transaction version must be 0; got {version:?}.")]
+    UnsupportedVersion { version: TransactionVersion },
+}
+
 #[derive(Debug, Error)]
 pub enum TransactionPreValidationError {
     #[error(
-        "Invalid transaction nonce of contract at address {address:?}. Account nonce: \
-         {account_nonce:?}; got: {incoming_tx_nonce:?}."
+        "Invalid transaction nonce of contract at address {}. Account nonce: {}; got: {}.",
+        format_address(.address),
+        format_felt(&.account_nonce.0),
+        format_felt(&.incoming_tx_nonce.0)
     )]
     InvalidNonce { address: ContractAddress, account_nonce: Nonce, incoming_tx_nonce: Nonce },
     #[error(transparent)]
@@ -103,8 +184,31 @@ pub enum TransactionPreValidationError {
     TransactionFeeError(#[from] TransactionFeeError),
 }
 
+#[derive(Debug, Error)]
+pub enum ContractClassLimitError {
+    #[error("ABI length ({abi_length}) exceeds the maximum ({max_abi_length}).")]
+    AbiTooLong { abi_length: usize, max_abi_length: usize },
+    #[error(
+        "Contract class artifact size ({artifact_size}) exceeds the maximum ({max_artifact_size})."
+    )]
+    ArtifactTooLarge { artifact_size: usize, max_artifact_size: usize },
+    #[error("Bytecode length ({bytecode_length}) exceeds the maximum ({max_bytecode_length}).")]
+    BytecodeTooLong { bytecode_length: usize, max_bytecode_length: usize },
+    #[error(
+        "Sierra program length ({sierra_program_length}) exceeds the maximum \
+         ({max_sierra_program_length})."
+    )]
+    SierraProgramTooLong { sierra_program_length: usize, max_sierra_program_length: usize },
+    #[error("Number of entry points ({n_entry_points}) exceeds the maximum ({max_entry_points}).")]
+    TooManyEntryPoints { n_entry_points: usize, max_entry_points: usize },
+}
+
 #[derive(Debug, Error)]
 pub enum ParseError {
     #[error("Unsupported transaction type: {0}")]
     UnknownTransactionType(String),
+    #[error("Unsupported VM resource: {0}")]
+    UnknownVmResource(String),
+    #[error("Invalid VM resource fee cost: {0}")]
+    InvalidVmResourceCost(String),
 }
@@ -24,7 +24,7 @@ use crate::test_utils::{
 use crate::transaction::errors::{
     TransactionExecutionError, TransactionFeeError, TransactionPreValidationError,
 };
-use crate::transaction::objects::{FeeType, TransactionExecutionInfo};
+use crate::transaction::objects::{FeeType, TransactionExecutionInfo, TransactionPhase};
 use crate::transaction::test_utils::{account_invoke_tx, l1_resource_bounds, INVALID};
 use crate::transaction::transactions::ExecutableTransaction;
 const VALIDATE_GAS_OVERHEAD: u64 = 21;
@@ -86,7 +86,8 @@ fn gas_and_fee(base_gas: u64, validate_mode: bool, fee_type: &FeeType) -> (u64,
     let gas = base_gas + if validate_mode { VALIDATE_GAS_OVERHEAD } else { 0 };
     (
         gas,
-        get_fee_by_l1_gas_usage(&BlockContext::create_for_account_testing(), gas as u128, fee_type),
+        get_fee_by_l1_gas_usage(&BlockContext::create_for_account_testing(), gas as u128, fee_type)
+            .unwrap(),
     )
 }
 
@@ -167,8 +168,10 @@ fn test_simulate_validate_charge_fee_pre_validate(
         invoke_tx_args! {nonce: invalid_nonce, ..pre_validation_base_args.clone()},
     )
     .execute(&mut state, &block_context, charge_fee, validate);
+    let error = result.unwrap_err();
+    assert_eq!(error.phase(), Some(TransactionPhase::PreValidation));
     assert_matches!(
-        result.unwrap_err(),
+        error,
         TransactionExecutionError::TransactionPreValidationError(
             TransactionPreValidationError::InvalidNonce {
                 address, account_nonce: expected_nonce, incoming_tx_nonce
@@ -471,7 +474,7 @@ fn test_simulate_validate_charge_fee_mid_execution(
     // lower when `validate` is true, but this is not reflected in the actual gas usage.
     let block_limit_gas = low_step_block_context.invoke_tx_max_n_steps as u64 + 1720;
     let block_limit_fee =
-        get_fee_by_l1_gas_usage(&block_context, block_limit_gas as u128, &fee_type);
+        get_fee_by_l1_gas_usage(&block_context, block_limit_gas as u128, &fee_type).unwrap();
     let tx_execution_info = account_invoke_tx(invoke_tx_args! {
         max_fee: huge_fee,
         resource_bounds: l1_resource_bounds(huge_gas_limit, gas_price),
@@ -1,8 +1,12 @@
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign};
 
 use cairo_felt::Felt252;
 use itertools::concat;
 use num_traits::Pow;
+use serde::{Deserialize, Serialize};
 use starknet_api::core::{ClassHash, ContractAddress, Nonce};
 use starknet_api::data_availability::DataAvailabilityMode;
 use starknet_api::transaction::{
@@ -11,10 +15,15 @@ use starknet_api::transaction::{
 };
 use strum_macros::EnumIter;
 
+use crate::abi::abi_registry::AbiRegistry;
 use crate::block_context::BlockContext;
-use crate::execution::call_info::CallInfo;
+use crate::execution::call_info::{merge_syscall_counters, CallInfo};
+use crate::execution::deprecated_syscalls::hint_processor::SyscallCounter;
 use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
-use crate::fee::fee_utils::calculate_tx_fee;
+use crate::fee::fee_transfer_delegate::DelegatedFeeTransfer;
+use crate::fee::fee_utils::{
+    calculate_fee_by_gas_price, calculate_tx_fee, calculate_tx_fee_with_tip,
+};
 use crate::state::cached_state::StorageEntry;
 use crate::transaction::constants;
 use crate::transaction::errors::{
@@ -36,7 +45,7 @@ macro_rules! implement_getters {
     };
 }
 
-#[derive(Clone, Copy, Hash, EnumIter, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Hash, EnumIter, Eq, PartialEq, Serialize)]
 pub enum FeeType {
     Strk,
     Eth,
@@ -80,15 +89,85 @@ impl AccountTransactionContext {
         TransactionVersion(felt_to_stark_felt(&query_version))
     }
 
+    /// Returns whether the transaction commits to paying a fee, i.e., has a non-zero max fee (or
+    /// non-zero L1 gas bounds). Balance checks and the fee transfer are skipped for transactions
+    /// that do not, but their actual fee is still reported.
     pub fn enforce_fee(&self) -> TransactionFeeResult<bool> {
         match self {
             AccountTransactionContext::Current(context) => {
                 let l1_bounds = context.l1_resource_bounds()?;
-                Ok(l1_bounds.max_amount as u128 * l1_bounds.max_price_per_unit > 0)
+                Ok(l1_bounds.max_amount > 0 && l1_bounds.max_price_per_unit > 0)
             }
             AccountTransactionContext::Deprecated(context) => Ok(context.max_fee != Fee(0)),
         }
     }
+
+    /// Returns the fee the transaction commits to paying at most: the max fee, or the gas bounds
+    /// at their max price (regardless of the current gas price). The L2 gas bounds are only
+    /// committed to if L2 gas is charged.
+    pub fn committed_fee(&self, block_context: &BlockContext) -> TransactionFeeResult<Fee> {
+        match self {
+            AccountTransactionContext::Current(context) => {
+                let l1_bounds = context.l1_resource_bounds()?;
+                let l1_gas_fee = calculate_fee_by_gas_price(
+                    l1_bounds.max_amount.into(),
+                    l1_bounds.max_price_per_unit,
+                )?;
+                if !block_context.versioned_constants.resource_gas_mode.charges_l2_gas() {
+                    return Ok(l1_gas_fee);
+                }
+
+                let l2_bounds = context.l2_resource_bounds();
+                let l2_gas_fee = calculate_fee_by_gas_price(
+                    l2_bounds.max_amount.into(),
+                    l2_bounds.max_price_per_unit,
+                )?;
+                l1_gas_fee
+                    .0
+                    .checked_add(l2_gas_fee.0)
+                    .map(Fee)
+                    .ok_or(TransactionFeeError::FeeSumOverflow { l1_gas_fee, l2_gas_fee })
+            }
+            AccountTransactionContext::Deprecated(context) => Ok(context.max_fee),
+        }
+    }
+
+    /// Returns the committed fee (see `committed_fee`), saturated at `u128::MAX` on overflow;
+    /// missing L1 gas bounds commit to no fee. Not fallible, as the committed fee is only verified
+    /// if the fee is charged.
+    pub fn saturating_committed_fee(&self, block_context: &BlockContext) -> Fee {
+        match self {
+            AccountTransactionContext::Current(context) => {
+                let bounds_fee = |bounds: ResourceBounds| {
+                    u128::from(bounds.max_amount).saturating_mul(bounds.max_price_per_unit)
+                };
+                let l1_gas_fee = bounds_fee(context.l1_resource_bounds().unwrap_or_default());
+                if !block_context.versioned_constants.resource_gas_mode.charges_l2_gas() {
+                    return Fee(l1_gas_fee);
+                }
+
+                Fee(l1_gas_fee.saturating_add(bounds_fee(context.l2_resource_bounds())))
+            }
+            AccountTransactionContext::Deprecated(context) => context.max_fee,
+        }
+    }
+
+    /// Returns the tip paid per unit of L1 gas, on top of the L1 gas price; zero for pre-V3
+    /// transactions, and if tips are not charged (see `BlockContext::charge_tips`).
+    pub fn charged_tip(&self, block_context: &BlockContext) -> Tip {
+        match self {
+            AccountTransactionContext::Current(context) if block_context.charge_tips => context.tip,
+            _ => Tip::default(),
+        }
+    }
+
+    /// Returns the price paid per unit of L1 gas, including the charged tip.
+    pub fn l1_gas_price(&self, block_context: &BlockContext) -> u128 {
+        block_context
+            .gas_prices
+            .get_by_fee_type(&self.fee_type())
+            .saturating_add(self.charged_tip(block_context).0.into())
+    }
 }
 
 impl HasRelatedFeeType for AccountTransactionContext {
@@ -99,6 +178,15 @@ impl HasRelatedFeeType for AccountTransactionContext {
     fn is_l1_handler(&self) -> bool {
         false
     }
+
+    fn calculate_tx_fee(
+        &self,
+        resources: &ResourcesMapping,
+        block_context: &BlockContext,
+    ) -> TransactionExecutionResult<Fee> {
+        let tip = self.charged_tip(block_context);
+        Ok(calculate_tx_fee_with_tip(resources, block_context, &self.fee_type(), tip)?)
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -120,6 +208,11 @@ impl CurrentAccountTransactionContext {
             None => Err(TransactionFeeError::MissingL1GasBounds),
         }
     }
+
+    /// Fetch the L2 resource bounds; missing bounds allow no L2 gas.
+    pub fn l2_resource_bounds(&self) -> ResourceBounds {
+        self.resource_bounds.0.get(&Resource::L2Gas).copied().unwrap_or_default()
+    }
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -138,8 +231,33 @@ pub struct CommonAccountFields {
     pub only_query: bool,
 }
 
+/// A phase of the execution of a transaction.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum TransactionPhase {
+    /// The nonce, fee bounds and balance checks, before running any user code.
+    PreValidation,
+    /// The `__validate__` family of entry points.
+    Validation,
+    /// The `__execute__` entry point, or the constructor of a deployed account.
+    Execution,
+    /// The fee checks against the actual cost of the transaction.
+    PostExecution,
+}
+
+/// Describes the failure of a reverted transaction, so that operators can spot griefing patterns
+/// (e.g., transactions repeatedly running many steps before failing in the same contract).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RevertReport {
+    pub phase: TransactionPhase,
+    /// The execution steps consumed until the failure (excluding validation).
+    pub n_steps: usize,
+    /// The contract of the deepest failing call frame; [None] if the failure is not in a call
+    /// (e.g., a post-execution fee check).
+    pub failing_contract_address: Option<ContractAddress>,
+}
+
 /// Contains the information gathered by the execution of a transaction.
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct TransactionExecutionInfo {
     /// Transaction validation call info; [None] for `L1Handler`.
     pub validate_call_info: Option<CallInfo>,
@@ -147,15 +265,32 @@ pub struct TransactionExecutionInfo {
     pub execute_call_info: Option<CallInfo>,
     /// Fee transfer call info; [None] for `L1Handler`.
     pub fee_transfer_call_info: Option<CallInfo>,
+    /// The fee transfer to be applied by the embedder on commit; [None] unless fees are charged
+    /// through `BlockContext::fee_transfer_delegate`.
+    pub delegated_fee_transfer: Option<DelegatedFeeTransfer>,
     /// The actual fee that was charged (in Wei).
     pub actual_fee: Fee,
     /// Actual execution resources the transaction is charged for,
     /// including L1 gas and additional OS resources estimation.
     pub actual_resources: ResourcesMapping,
+    /// The gas consumed by the transaction, per gas kind.
+    pub actual_gas: GasVector,
     /// Error string for reverted transactions; [None] if transaction execution was successful.
     // TODO(Dori, 1/8/2023): If the `Eq` and `PartialEq` traits are removed, or implemented on all
     //   internal structs in this enum, this field should be `Option<TransactionExecutionError>`.
     pub revert_error: Option<String>,
+    /// True if the transaction nonce was ahead of the account nonce (see
+    /// `BlockContext::max_nonce_gap`); the result then assumes the execution of the preceding
+    /// transactions, and must not be committed to a block.
+    pub is_speculative: bool,
+    /// The fee paid on L1, and the fee consumed; [None] for account transactions.
+    pub l1_handler_fee: Option<L1HandlerFee>,
+    /// The part of the committed fee (see `AccountTransactionContext::saturating_committed_fee`)
+    /// that was not charged; zero for `L1Handler`.
+    pub refund: Fee,
+    /// Where and how far a reverted transaction ran; [None] if transaction execution was
+    /// successful.
+    pub revert_report: Option<RevertReport>,
 }
 
 impl TransactionExecutionInfo {
@@ -180,16 +315,130 @@ impl TransactionExecutionInfo {
         )
     }
 
+    /// Returns the number of invocations of each syscall during this transaction execution.
+    pub fn summarize_syscalls(&self) -> SyscallCounter {
+        let mut syscall_counter = SyscallCounter::new();
+        for call_info in self.non_optional_call_infos() {
+            merge_syscall_counters(&mut syscall_counter, &call_info.summarize_syscalls());
+        }
+
+        syscall_counter
+    }
+
     pub fn is_reverted(&self) -> bool {
         self.revert_error.is_some()
     }
+
+    /// Renders the call trees of the transaction stages (see `CallInfo::fmt_tree`), followed by
+    /// the charged fee and the revert error, if any.
+    pub fn fmt_tree(
+        &self,
+        f: &mut dyn fmt::Write,
+        abi_registry: Option<&AbiRegistry>,
+    ) -> fmt::Result {
+        let mut lines = Vec::new();
+        for (stage, call_info) in [
+            ("validate", &self.validate_call_info),
+            ("execute", &self.execute_call_info),
+            ("fee transfer", &self.fee_transfer_call_info),
+        ] {
+            if let Some(call_info) = call_info {
+                lines.push(format!("{stage}:"));
+                call_info.push_tree_lines(&mut lines, abi_registry, 1);
+            }
+        }
+        lines.push(format!("actual fee: {}", self.actual_fee.0));
+        if let Some(revert_error) = &self.revert_error {
+            let revert_error = match abi_registry {
+                Some(registry) => registry.annotate(revert_error),
+                None => revert_error.clone(),
+            };
+            lines.push(format!("reverted: {revert_error}"));
+        }
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl fmt::Display for TransactionExecutionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_tree(f, None)
+    }
+}
+
+/// The fee bookkeeping of an L1 handler transaction, which is paid on L1 rather than charged on L2.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct L1HandlerFee {
+    pub paid_fee_on_l1: Fee,
+    /// The fee of the resources consumed by the transaction.
+    pub consumed_fee: Fee,
+}
+
+impl L1HandlerFee {
+    /// Returns the part of the paid fee that was not consumed (e.g., to be refunded); [None] if the
+    /// paid fee does not cover the consumed fee.
+    pub fn leftover_fee(&self) -> Option<Fee> {
+        self.paid_fee_on_l1.0.checked_sub(self.consumed_fee.0).map(Fee)
+    }
+
+    pub fn is_sufficient(&self) -> bool {
+        self.leftover_fee().is_some()
+    }
+}
+
+/// The gas consumed by a transaction, broken down by the kind of gas.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GasVector {
+    pub l1_gas: u128,
+    /// Data availability gas; zero as long as state diffs are posted as calldata, in which case
+    /// their cost is included in `l1_gas`.
+    pub l1_data_gas: u128,
+    /// Zero as long as Cairo resources are converted to L1 gas.
+    pub l2_gas: u128,
+}
+
+impl Add for GasVector {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            l1_gas: self.l1_gas + other.l1_gas,
+            l1_data_gas: self.l1_data_gas + other.l1_data_gas,
+            l2_gas: self.l2_gas + other.l2_gas,
+        }
+    }
+}
+
+impl Sum for GasVector {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
 }
 
 /// A mapping from a transaction execution resource to its actual usage.
 #[cfg_attr(test, derive(Clone))]
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct ResourcesMapping(pub HashMap<String, usize>);
 
+/// Adds the resources by name; e.g., to accumulate the actual resources of the transactions of a
+/// block.
+impl AddAssign<&ResourcesMapping> for ResourcesMapping {
+    fn add_assign(&mut self, other: &ResourcesMapping) {
+        for (resource, amount) in &other.0 {
+            *self.0.entry(resource.clone()).or_default() += amount;
+        }
+    }
+}
+
+impl<'a> Sum<&'a ResourcesMapping> for ResourcesMapping {
+    fn sum<I: Iterator<Item = &'a ResourcesMapping>>(iter: I) -> Self {
+        iter.fold(Self::default(), |mut total, resources| {
+            total += resources;
+            total
+        })
+    }
+}
+
 impl ResourcesMapping {
     #[cfg(test)]
     pub fn n_steps(&self) -> usize {
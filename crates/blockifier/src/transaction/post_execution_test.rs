@@ -17,7 +17,7 @@ use crate::test_utils::initial_test_state::test_state;
 use crate::test_utils::{create_calldata, CairoVersion, BALANCE, MAX_L1_GAS_PRICE};
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::errors::TransactionExecutionError;
-use crate::transaction::objects::{FeeType, HasRelatedFeeType};
+use crate::transaction::objects::{FeeType, HasRelatedFeeType, TransactionPhase};
 use crate::transaction::test_utils::{
     account_invoke_tx, block_context, l1_resource_bounds, max_fee, max_resource_bounds,
     run_invoke_tx, TestInitData,
@@ -176,6 +176,9 @@ fn test_revert_on_overdraft(
     // Verify the execution was reverted (including nonce bump) with the correct error.
     assert!(execution_info.is_reverted());
     assert!(execution_info.revert_error.unwrap().starts_with("Insufficient fee token balance"));
+    // The balance is re-checked after execution, rather than failing on the fee transfer.
+    assert_eq!(execution_info.revert_report.unwrap().phase, TransactionPhase::PostExecution);
+    assert_eq!(execution_info.actual_fee, transfer_tx_fee);
     assert_eq!(state.get_nonce_at(account_address).unwrap(), nonce_manager.next(account_address));
 
     // Verify the storage key/value were not updated in the last tx.
@@ -0,0 +1,119 @@
+use starknet_api::transaction::Fee;
+
+use crate::block_context::BlockContext;
+use crate::state::cached_state::{CachedState, CommitmentStateDiff};
+use crate::state::state_api::StateReader;
+use crate::transaction::objects::{
+    FeeType, GasVector, HasRelatedFeeType, TransactionExecutionInfo, TransactionExecutionResult,
+};
+use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transactions::ExecutableTransaction;
+
+#[cfg(test)]
+#[path = "simulation_test.rs"]
+pub mod test;
+
+/// Flags controlling the simulation of transactions, as in the `starknet_simulateTransactions`
+/// RPC method.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SimulationFlags {
+    pub skip_validate: bool,
+    pub skip_fee_charge: bool,
+    /// Overrides the initial gas of the transactions (`VersionedConstants::tx_initial_gas`); e.g.,
+    /// fee estimation may supply the maximal gas.
+    pub initial_gas: Option<u64>,
+}
+
+/// The fee a simulated transaction would be charged.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeEstimate {
+    pub gas_consumed: GasVector,
+    /// The price per unit of L1 gas, including the charged tip (see
+    /// `AccountTransactionContext::charged_tip`).
+    pub gas_price: u128,
+    pub overall_fee: Fee,
+    pub unit: FeeType,
+}
+
+/// The execution trace and the fee estimate of a simulated transaction.
+#[derive(Debug)]
+pub struct SimulatedTransaction {
+    pub execution_info: TransactionExecutionInfo,
+    pub fee_estimate: FeeEstimate,
+}
+
+#[derive(Debug)]
+pub struct SimulationOutput {
+    pub simulated_transactions: Vec<SimulatedTransaction>,
+    /// The cumulative state diff of all simulated transactions.
+    pub state_diff: CommitmentStateDiff,
+}
+
+/// Executes the given transactions in order, against a transactional fork of the given state;
+/// each transaction observes the effects of the ones preceding it. The given state is left
+/// unchanged.
+/// Fails on the first transaction that fails to execute (reverted transactions do not fail the
+/// simulation).
+pub fn simulate_transactions<S: StateReader>(
+    state: &mut CachedState<S>,
+    block_context: &BlockContext,
+    txs: Vec<Transaction>,
+    flags: SimulationFlags,
+) -> TransactionExecutionResult<SimulationOutput> {
+    let gas_bounded_block_context;
+    let block_context = match flags.initial_gas {
+        Some(initial_gas) => {
+            let mut versioned_constants = block_context.versioned_constants.clone();
+            versioned_constants.tx_initial_gas = initial_gas;
+            gas_bounded_block_context =
+                BlockContext { versioned_constants, ..block_context.clone() };
+            &gas_bounded_block_context
+        }
+        None => block_context,
+    };
+
+    let mut simulation_state = CachedState::create_transactional(state);
+    let mut simulated_transactions = Vec::with_capacity(txs.len());
+    for tx in txs {
+        let (fee_type, gas_price) = match &tx {
+            Transaction::AccountTransaction(account_tx) => (
+                account_tx.fee_type(),
+                account_tx.get_account_tx_context().l1_gas_price(block_context),
+            ),
+            Transaction::L1HandlerTransaction(l1_handler_tx) => {
+                let fee_type = l1_handler_tx.fee_type();
+                (fee_type, block_context.gas_prices.get_by_fee_type(&fee_type))
+            }
+        };
+        let execution_result = tx.execute(
+            &mut simulation_state,
+            block_context,
+            !flags.skip_fee_charge,
+            !flags.skip_validate,
+        );
+        let execution_info = match execution_result {
+            Ok(execution_info) => execution_info,
+            Err(error) => {
+                simulation_state.abort();
+                return Err(error);
+            }
+        };
+
+        let fee_estimate = FeeEstimate {
+            gas_consumed: execution_info.actual_gas,
+            gas_price,
+            // L1 handler transactions are paid for on L1.
+            overall_fee: match &execution_info.l1_handler_fee {
+                Some(l1_handler_fee) => l1_handler_fee.consumed_fee,
+                None => execution_info.actual_fee,
+            },
+            unit: fee_type,
+        };
+        simulated_transactions.push(SimulatedTransaction { execution_info, fee_estimate });
+    }
+
+    let state_diff = simulation_state.to_state_diff();
+    simulation_state.abort();
+
+    Ok(SimulationOutput { simulated_transactions, state_diff })
+}
@@ -0,0 +1,110 @@
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::core::Nonce;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::{Fee, Tip, TransactionVersion};
+
+use crate::block_context::BlockContext;
+use crate::invoke_tx_args;
+use crate::state::state_api::StateReader;
+use crate::test_utils::{
+    create_calldata, CairoVersion, NonceManager, MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE,
+};
+use crate::transaction::objects::FeeType;
+use crate::transaction::simulation::{simulate_transactions, SimulationFlags};
+use crate::transaction::test_utils::{
+    account_invoke_tx, block_context, create_test_init_data, l1_resource_bounds, max_fee,
+    TestInitData,
+};
+use crate::transaction::transaction_execution::Transaction;
+
+#[rstest]
+fn test_simulate_transactions(
+    block_context: BlockContext,
+    max_fee: Fee,
+    #[values(true, false)] skip_fee_charge: bool,
+) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context, CairoVersion::Cairo0);
+    let invoke_tx = |nonce_manager: &mut NonceManager| {
+        Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+            max_fee,
+            sender_address: account_address,
+            calldata: create_calldata(contract_address, "return_result", &[stark_felt!(2_u8)]),
+            nonce: nonce_manager.next(account_address),
+        }))
+    };
+    // The second transaction is only valid after the first one's nonce increment.
+    let txs = vec![invoke_tx(&mut nonce_manager), invoke_tx(&mut nonce_manager)];
+
+    let flags = SimulationFlags { skip_fee_charge, ..Default::default() };
+    let output = simulate_transactions(&mut state, &block_context, txs, flags).unwrap();
+
+    assert_eq!(output.simulated_transactions.len(), 2);
+    for simulated_tx in &output.simulated_transactions {
+        let execution_info = &simulated_tx.execution_info;
+        assert!(!execution_info.is_reverted());
+        assert_eq!(execution_info.fee_transfer_call_info.is_none(), skip_fee_charge);
+
+        let fee_estimate = &simulated_tx.fee_estimate;
+        assert_eq!(fee_estimate.unit, FeeType::Eth);
+        assert_eq!(fee_estimate.gas_price, block_context.gas_prices.eth_l1_gas_price);
+        assert_eq!(fee_estimate.gas_consumed, execution_info.actual_gas);
+        assert_eq!(fee_estimate.overall_fee, execution_info.actual_fee);
+        assert!(fee_estimate.overall_fee > Fee(0));
+    }
+    assert_eq!(output.state_diff.address_to_nonce[&account_address], Nonce(stark_felt!(2_u8)));
+
+    // The simulated transactions do not affect the given state.
+    assert_eq!(state.get_nonce_at(account_address).unwrap(), Nonce::default());
+}
+
+#[rstest]
+fn test_simulate_with_initial_gas(block_context: BlockContext, max_fee: Fee) {
+    let TestInitData { mut state, account_address, contract_address, .. } =
+        create_test_init_data(&block_context, CairoVersion::Cairo1);
+    let tx = Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+        max_fee,
+        sender_address: account_address,
+        calldata: create_calldata(contract_address, "test_storage_read_write",
+            &[stark_felt!(1_u8), stark_felt!(2_u8)],
+        ),
+    }));
+
+    let initial_gas = block_context.versioned_constants.tx_initial_gas / 2;
+    let flags = SimulationFlags {
+        skip_validate: true,
+        initial_gas: Some(initial_gas),
+        ..Default::default()
+    };
+    let output = simulate_transactions(&mut state, &block_context, vec![tx], flags).unwrap();
+
+    let execution_info = &output.simulated_transactions[0].execution_info;
+    assert_eq!(execution_info.execute_call_info.as_ref().unwrap().call.initial_gas, initial_gas);
+}
+
+#[rstest]
+fn test_simulate_with_tip(#[values(false, true)] charge_tips: bool) {
+    let block_context = &BlockContext { charge_tips, ..BlockContext::create_for_account_testing() };
+    let TestInitData { mut state, account_address, contract_address, .. } =
+        create_test_init_data(block_context, CairoVersion::Cairo0);
+    let tip = Tip(7);
+    let tx = Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+        resource_bounds: l1_resource_bounds(MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE + 100),
+        version: TransactionVersion::THREE,
+        tip,
+        sender_address: account_address,
+        calldata: create_calldata(contract_address, "return_result", &[stark_felt!(2_u8)]),
+    }));
+
+    let output =
+        simulate_transactions(&mut state, block_context, vec![tx], SimulationFlags::default())
+            .unwrap();
+
+    // The reported gas price includes the tip, if charged.
+    let fee_estimate = &output.simulated_transactions[0].fee_estimate;
+    let charged_tip = if charge_tips { u128::from(tip.0) } else { 0 };
+    assert_eq!(fee_estimate.unit, FeeType::Strk);
+    assert_eq!(fee_estimate.gas_price, block_context.gas_prices.strk_l1_gas_price + charged_tip);
+}
@@ -10,24 +10,34 @@ use crate::state::cached_state::TransactionalState;
 use crate::state::state_api::StateReader;
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::errors::TransactionFeeError;
-use crate::transaction::objects::{TransactionExecutionInfo, TransactionExecutionResult};
+use crate::transaction::objects::{
+    L1HandlerFee, TransactionExecutionInfo, TransactionExecutionResult,
+};
+use crate::transaction::transaction_types::TransactionType;
 use crate::transaction::transactions::{
     DeclareTransaction, DeployAccountTransaction, Executable, ExecutableTransaction,
     InvokeTransaction, L1HandlerTransaction,
 };
 
-#[derive(Debug, derive_more::From)]
+#[derive(Clone, Debug, derive_more::From)]
 pub enum Transaction {
     AccountTransaction(AccountTransaction),
     L1HandlerTransaction(L1HandlerTransaction),
 }
 
 impl Transaction {
-    /// Returns the initial gas of the transaction to run with.
+    /// Returns the default initial gas of transactions; see `VersionedConstants::tx_initial_gas`.
     pub fn initial_gas() -> u64 {
         abi_constants::INITIAL_GAS_COST - abi_constants::TRANSACTION_GAS_COST
     }
 
+    pub fn tx_type(&self) -> TransactionType {
+        match self {
+            Self::AccountTransaction(account_tx) => account_tx.tx_type(),
+            Self::L1HandlerTransaction(_) => TransactionType::L1Handler,
+        }
+    }
+
     pub fn from_api(
         tx: StarknetApiTransaction,
         tx_hash: TransactionHash,
@@ -96,17 +106,18 @@ impl<S: StateReader> ExecutableTransaction<S> for L1HandlerTransaction {
         _charge_fee: bool,
         _validate: bool,
     ) -> TransactionExecutionResult<TransactionExecutionInfo> {
+        self.verify()?;
         let tx_context = self.get_account_tx_context();
 
         let mut execution_resources = ExecutionResources::default();
         let mut context = EntryPointExecutionContext::new_invoke(block_context, &tx_context, true)?;
-        let mut remaining_gas = Transaction::initial_gas();
+        let mut remaining_gas = block_context.versioned_constants.tx_initial_gas;
         let execute_call_info =
             self.run_execute(state, &mut execution_resources, &mut context, &mut remaining_gas)?;
         // The calldata includes the "from" field, which is not a part of the payload.
         let l1_handler_payload_size = self.tx.calldata.0.len() - 1;
 
-        let ActualCost { actual_fee, actual_resources } =
+        let ActualCost { actual_fee, actual_resources, actual_gas } =
             ActualCost::builder_for_l1_handler(block_context, tx_context, l1_handler_payload_size)
                 .with_execute_call_info(&execute_call_info)
                 .try_add_state_changes(state)?
@@ -123,9 +134,18 @@ impl<S: StateReader> ExecutableTransaction<S> for L1HandlerTransaction {
             validate_call_info: None,
             execute_call_info,
             fee_transfer_call_info: None,
+            delegated_fee_transfer: None,
             actual_fee: Fee::default(),
             actual_resources,
+            actual_gas,
             revert_error: None,
+            is_speculative: false,
+            l1_handler_fee: Some(L1HandlerFee {
+                paid_fee_on_l1: paid_fee,
+                consumed_fee: actual_fee,
+            }),
+            refund: Fee(0),
+            revert_report: None,
         })
     }
 }
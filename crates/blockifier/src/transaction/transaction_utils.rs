@@ -36,6 +36,8 @@ pub fn calculate_l1_gas_usage<'a>(
 /// Calculates the total resources needed to include the transaction in a Starknet block as
 /// most-recent (recent w.r.t. application on the given state).
 /// I.e., Cairo VM execution resources.
+/// Memory holes (unused memory cells, e.g., of builtin segments) are charged as steps, as they are
+/// filled by the prover.
 pub fn calculate_tx_resources(
     execution_resources: &ExecutionResources,
     l1_gas_usage: usize,
@@ -56,6 +58,7 @@ pub fn calculate_tx_resources(
 
     let mut tx_resources = HashMap::from([
         (constants::GAS_USAGE.to_string(), l1_gas_usage),
+        // Memory holes are charged as steps.
         (constants::N_STEPS_RESOURCE.to_string(), n_steps + total_vm_usage.n_memory_holes),
     ]);
     tx_resources.extend(total_vm_usage.builtin_instance_counter);
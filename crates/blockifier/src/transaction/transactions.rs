@@ -2,12 +2,13 @@ use std::sync::Arc;
 
 use starknet_api::core::{ClassHash, ContractAddress, Nonce};
 use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::state::ContractClass as SierraContractClass;
 use starknet_api::transaction::{
     AccountDeploymentData, Calldata, ContractAddressSalt, DeclareTransactionV2,
     DeclareTransactionV3, Fee, TransactionHash, TransactionSignature, TransactionVersion,
 };
 
-use crate::abi::abi_utils::selector_from_name;
+use crate::abi::abi_utils::{keccak256_of_felts, selector_from_name};
 use crate::block_context::BlockContext;
 use crate::execution::call_info::CallInfo;
 use crate::execution::contract_class::ContractClass;
@@ -15,22 +16,55 @@ use crate::execution::entry_point::{
     CallEntryPoint, CallType, ConstructorContext, EntryPointExecutionContext, ExecutionResources,
 };
 use crate::execution::execution_utils::execute_deployment;
-use crate::state::cached_state::{CachedState, TransactionalState};
+use crate::metrics::report_execution;
+use crate::state::cached_state::{CachedState, CommitmentStateDiff, TransactionalState};
 use crate::state::errors::StateError;
 use crate::state::state_api::{State, StateReader};
 use crate::transaction::constants;
-use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::errors::{L1HandlerTransactionError, TransactionExecutionError};
 use crate::transaction::objects::{
     AccountTransactionContext, CommonAccountFields, CurrentAccountTransactionContext,
     DeprecatedAccountTransactionContext, HasRelatedFeeType, TransactionExecutionInfo,
     TransactionExecutionResult,
 };
 use crate::transaction::transaction_utils::{update_remaining_gas, verify_contract_class_version};
+use crate::utils::usize_to_felt;
 
 #[cfg(test)]
 #[path = "transactions_test.rs"]
 mod test;
 
+/// Verifies that two executions of a transaction on the same state have the same result and state
+/// diff; errors are compared by their messages.
+fn verify_deterministic_execution(
+    (first_result, first_state_diff): (
+        &TransactionExecutionResult<TransactionExecutionInfo>,
+        &CommitmentStateDiff,
+    ),
+    (second_result, second_state_diff): (
+        &TransactionExecutionResult<TransactionExecutionInfo>,
+        &CommitmentStateDiff,
+    ),
+) -> TransactionExecutionResult<()> {
+    let mismatch = match (first_result, second_result) {
+        (Ok(first_info), Ok(second_info)) if first_info != second_info => "execution info",
+        (Err(first_error), Err(second_error))
+            if first_error.to_string() != second_error.to_string() =>
+        {
+            "error"
+        }
+        (Ok(_), Err(_)) | (Err(_), Ok(_)) => "outcome",
+        _ if first_state_diff != second_state_diff => "state diff",
+        _ => return Ok(()),
+    };
+
+    log::error!(
+        "Nondeterministic execution; first execution: {first_result:?}, {first_state_diff:?}; \
+         second execution: {second_result:?}, {second_state_diff:?}."
+    );
+    Err(TransactionExecutionError::NondeterministicExecution { mismatch })
+}
+
 macro_rules! implement_inner_tx_getter_calls {
     ($(($field:ident, $field_type:ty)),*) => {
         $(pub fn $field(&self) -> $field_type {
@@ -39,9 +73,16 @@ macro_rules! implement_inner_tx_getter_calls {
     };
 }
 
-pub trait ExecutableTransaction<S: StateReader>: Sized {
+/// Transactions are `Clone`, so that `execute` can run a copy of the transaction for the
+/// determinism audit.
+pub trait ExecutableTransaction<S: StateReader>: Clone + Sized {
     /// Executes the transaction in a transactional manner
     /// (if it fails, given state does not modify).
+    ///
+    /// Under `BlockContext::determinism_audit`, a copy of the transaction is first executed on an
+    /// isolated transactional state (see `CachedState::create_isolated_transactional`), which is
+    /// discarded; the execution extensions of the block context (e.g., hint extensions and custom
+    /// syscalls) are thus invoked on both executions.
     fn execute(
         self,
         state: &mut CachedState<S>,
@@ -50,18 +91,50 @@ pub trait ExecutableTransaction<S: StateReader>: Sized {
         validate: bool,
     ) -> TransactionExecutionResult<TransactionExecutionInfo> {
         log::debug!("Executing Transaction...");
+        let audit_execution = if block_context.determinism_audit {
+            let mut audit_state = CachedState::create_isolated_transactional(state);
+            let audit_result =
+                self.clone().execute_raw(&mut audit_state, block_context, charge_fee, validate);
+            Some((audit_result, audit_state.to_state_diff()))
+        } else {
+            None
+        };
+
         let mut transactional_state = CachedState::create_transactional(state);
-        let execution_result =
+        let mut execution_result =
             self.execute_raw(&mut transactional_state, block_context, charge_fee, validate);
+        if let Some((audit_result, audit_state_diff)) = audit_execution {
+            if let Err(error) = verify_deterministic_execution(
+                (&audit_result, &audit_state_diff),
+                (&execution_result, &transactional_state.to_state_diff()),
+            ) {
+                execution_result = Err(error);
+            }
+        }
+        if let Some(metrics_sink) = &block_context.metrics_sink {
+            report_execution(metrics_sink.as_ref(), &execution_result);
+        }
 
         match execution_result {
             Ok(value) => {
                 transactional_state.commit();
                 log::debug!("Transaction execution complete and committed.");
+                if let Some(revert_report) = &value.revert_report {
+                    log::info!(
+                        "Transaction reverted in the {:?} phase after {} steps; deepest failing \
+                         contract: {:?}.",
+                        revert_report.phase,
+                        revert_report.n_steps,
+                        revert_report.failing_contract_address
+                    );
+                }
                 Ok(value)
             }
             Err(error) => {
                 log::debug!("Transaction execution failed with: {error}");
+                if let Some(phase) = error.phase() {
+                    log::info!("Transaction rejected in the {phase:?} phase.");
+                }
                 transactional_state.abort();
                 Err(error)
             }
@@ -102,11 +175,20 @@ pub trait ValidatableTransaction {
     ) -> TransactionExecutionResult<Option<CallInfo>>;
 }
 
-#[derive(Debug)]
+/// A declare transaction; its behavior depends on its version:
+/// * V0: declares a Cairo 0 class. Has no nonce (its hash does not cover one, and the transaction
+///   info reports a zero nonce), and is neither validated nor affects the sender nonce.
+/// * V1: like V0, but has a nonce, and is validated by the sender account.
+/// * V2: declares a Cairo 1 class, along with its compiled class hash, which is also hashed.
+/// * V3: like V2, but pays with resource bounds (and a tip) rather than a max fee; its hash also
+///   covers the data availability modes, paymaster data and account deployment data.
+#[derive(Clone, Debug)]
 pub struct DeclareTransaction {
     tx: starknet_api::transaction::DeclareTransaction,
     tx_hash: TransactionHash,
     contract_class: ContractClass,
+    // The class the compiled class was compiled from, if given; see `with_sierra_class`.
+    sierra_class: Option<SierraContractClass>,
     // Indicates the presence of the only_query bit in the version.
     only_query: bool,
 }
@@ -120,7 +202,7 @@ impl DeclareTransaction {
     ) -> TransactionExecutionResult<Self> {
         let declare_version = declare_tx.version();
         let contract_class = verify_contract_class_version(contract_class, declare_version)?;
-        Ok(Self { tx: declare_tx, tx_hash, contract_class, only_query })
+        Ok(Self { tx: declare_tx, tx_hash, contract_class, sierra_class: None, only_query })
     }
 
     pub fn new(
@@ -139,6 +221,13 @@ impl DeclareTransaction {
         Self::create(declare_tx, tx_hash, contract_class, true)
     }
 
+    /// Attaches the Sierra class of the declared (Cairo 1) class, to be stored with the compiled
+    /// class if `BlockContext::retain_sierra_classes` is set. The Sierra class is not verified
+    /// against the class hash.
+    pub fn with_sierra_class(self, sierra_class: SierraContractClass) -> Self {
+        Self { sierra_class: Some(sierra_class), ..self }
+    }
+
     implement_inner_tx_getter_calls!((class_hash, ClassHash));
 
     pub fn tx(&self) -> &starknet_api::transaction::DeclareTransaction {
@@ -155,11 +244,16 @@ impl DeclareTransaction {
 
     pub fn get_account_tx_context(&self) -> AccountTransactionContext {
         // TODO(Nir, 01/11/2023): Consider to move this (from all get_account_tx_context methods).
+        let nonce = match &self.tx {
+            // Declare V0 transactions have no nonce.
+            starknet_api::transaction::DeclareTransaction::V0(_) => Nonce::default(),
+            _ => self.tx.nonce(),
+        };
         let common_fields = CommonAccountFields {
             transaction_hash: self.tx_hash(),
             version: self.tx.version(),
             signature: self.tx.signature(),
-            nonce: self.tx.nonce(),
+            nonce,
             sender_address: self.tx.sender_address(),
             only_query: self.only_query,
         };
@@ -195,6 +289,18 @@ impl DeclareTransaction {
     pub fn only_query(&self) -> bool {
         self.only_query
     }
+
+    /// Returns the class to store in the state on declaration.
+    fn class_to_store(&self, block_context: &BlockContext) -> ContractClass {
+        match (&self.contract_class, &self.sierra_class) {
+            (ContractClass::V1(class), Some(sierra_class))
+                if block_context.retain_sierra_classes =>
+            {
+                class.with_sierra_class(sierra_class.clone()).into()
+            }
+            _ => self.contract_class.clone(),
+        }
+    }
 }
 
 impl<S: State> Executable<S> for DeclareTransaction {
@@ -202,16 +308,26 @@ impl<S: State> Executable<S> for DeclareTransaction {
         &self,
         state: &mut S,
         _resources: &mut ExecutionResources,
-        _context: &mut EntryPointExecutionContext,
+        context: &mut EntryPointExecutionContext,
         _remaining_gas: &mut u64,
     ) -> TransactionExecutionResult<Option<CallInfo>> {
         let class_hash = self.class_hash();
 
+        // Re-declaring a class is a no-op for the class store: the transaction is still validated
+        // and charged for, but the declared class is kept as is.
+        let is_declared = match state.get_compiled_contract_class(class_hash) {
+            Ok(_) => true,
+            Err(StateError::UndeclaredClassHash(_)) => false,
+            Err(error) => Err(error)?,
+        };
+
         match &self.tx {
-            // No class commitment, so no need to check if the class is already declared.
+            // No class commitment; Cairo 0 classes are stored under their hash only.
             starknet_api::transaction::DeclareTransaction::V0(_)
             | starknet_api::transaction::DeclareTransaction::V1(_) => {
-                state.set_contract_class(class_hash, self.contract_class.clone())?;
+                if !is_declared {
+                    state.set_contract_class(class_hash, self.contract_class.clone())?;
+                }
                 Ok(None)
             }
             starknet_api::transaction::DeclareTransaction::V2(DeclareTransactionV2 {
@@ -222,20 +338,18 @@ impl<S: State> Executable<S> for DeclareTransaction {
                 compiled_class_hash,
                 ..
             }) => {
-                match state.get_compiled_contract_class(class_hash) {
-                    Err(StateError::UndeclaredClassHash(_)) => {
-                        // Class is undeclared; declare it.
-                        state.set_contract_class(class_hash, self.contract_class.clone())?;
-                        state.set_compiled_class_hash(class_hash, *compiled_class_hash)?;
-                        Ok(None)
-                    }
-                    Err(error) => Err(error)?,
-                    Ok(_) => {
-                        // Class is already declared, cannot redeclare
-                        // (i.e., make sure the leaf is uninitialized).
-                        Err(TransactionExecutionError::DeclareTransactionError { class_hash })
-                    }
+                if !is_declared {
+                    state.set_contract_class(
+                        class_hash,
+                        self.class_to_store(&context.block_context),
+                    )?;
+                    state.set_compiled_class_hash(class_hash, *compiled_class_hash)?;
+                } else if state.get_compiled_class_hash(class_hash)? != *compiled_class_hash {
+                    // The class commitment must not change (i.e., the leaf is either
+                    // uninitialized or already holds this compiled class hash).
+                    return Err(TransactionExecutionError::DeclareTransactionError { class_hash });
                 }
+                Ok(None)
             }
         }
     }
@@ -445,7 +559,7 @@ impl<S: State> Executable<S> for InvokeTransaction {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct L1HandlerTransaction {
     pub tx: starknet_api::transaction::L1HandlerTransaction,
     pub tx_hash: TransactionHash,
@@ -453,6 +567,39 @@ pub struct L1HandlerTransaction {
 }
 
 impl L1HandlerTransaction {
+    /// Returns the hash of the L1-to-L2 message that invoked this transaction, as computed by the
+    /// Starknet core contract on L1 upon sending:
+    /// `keccak256(from_address, to_address, nonce, selector, payload_size, payload)`.
+    pub fn message_hash(&self) -> Result<[u8; 32], L1HandlerTransactionError> {
+        // The calldata consists of the L1 sender address, followed by the message payload.
+        let (from_address, payload) =
+            self.tx.calldata.0.split_first().ok_or(L1HandlerTransactionError::MissingL1Sender)?;
+        let header = [
+            *from_address,
+            *self.tx.contract_address.0.key(),
+            self.tx.nonce.0,
+            self.tx.entry_point_selector.0,
+            usize_to_felt(payload.len()),
+        ];
+
+        Ok(keccak256_of_felts(header.into_iter().chain(payload.iter().copied())))
+    }
+
+    /// Checks the protocol rules of L1 handler transactions, which differ from those of account
+    /// transactions. The nonce is assigned by the Starknet core contract on L1, and is not checked
+    /// against (nor does it advance) the nonce of the contract; there is no signature to validate,
+    /// and the fee is paid on L1, so nothing is transferred on L2.
+    pub fn verify(&self) -> Result<(), L1HandlerTransactionError> {
+        if self.tx.version != TransactionVersion::ZERO {
+            return Err(L1HandlerTransactionError::UnsupportedVersion { version: self.tx.version });
+        }
+        if self.tx.calldata.0.is_empty() {
+            return Err(L1HandlerTransactionError::MissingL1Sender);
+        }
+
+        Ok(())
+    }
+
     pub fn get_account_tx_context(&self) -> AccountTransactionContext {
         AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext {
             common_fields: CommonAccountFields {
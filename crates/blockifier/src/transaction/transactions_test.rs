@@ -9,13 +9,17 @@ use itertools::concat;
 use num_traits::Pow;
 use pretty_assertions::assert_eq;
 use rstest::rstest;
-use starknet_api::core::{ChainId, ClassHash, ContractAddress, EthAddress, Nonce, PatriciaKey};
+use starknet_api::core::{
+    calculate_contract_address, ChainId, ClassHash, ContractAddress, EntryPointSelector,
+    EthAddress, Nonce, PatriciaKey,
+};
 use starknet_api::deprecated_contract_class::EntryPointType;
-use starknet_api::hash::{StarkFelt, StarkHash};
-use starknet_api::state::StorageKey;
+use starknet_api::hash::{pedersen_hash, StarkFelt, StarkHash};
+use starknet_api::state::{ContractClass as SierraContractClass, StorageKey};
 use starknet_api::transaction::{
-    Calldata, EventContent, EventData, EventKey, Fee, L2ToL1Payload, TransactionHash,
-    TransactionSignature, TransactionVersion,
+    Calldata, ContractAddressSalt, EventContent, EventData, EventKey, Fee, L2ToL1Payload, Resource,
+    ResourceBounds, ResourceBoundsMapping, Tip, TransactionHash, TransactionSignature,
+    TransactionVersion,
 };
 use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
 use strum::IntoEnumIterator;
@@ -30,12 +34,16 @@ use crate::block_context::BlockContext;
 use crate::execution::call_info::{
     CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message, Retdata,
 };
-use crate::execution::entry_point::{CallEntryPoint, CallType};
+use crate::execution::contract_class::ContractClass;
+use crate::execution::deprecated_syscalls::hint_processor::SyscallCounter;
+use crate::execution::deprecated_syscalls::DeprecatedSyscallSelector;
+use crate::execution::entry_point::{CallEntryPoint, CallType, ExecutionResources};
 use crate::execution::errors::{EntryPointExecutionError, VirtualMachineExecutionError};
 use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
-use crate::fee::fee_utils::calculate_tx_fee;
+use crate::fee::fee_utils::{calculate_tx_fee, calculate_tx_gas_vector};
 use crate::fee::gas_usage::{
-    calculate_tx_gas_usage, estimate_minimal_l1_gas, get_onchain_data_cost,
+    calculate_tx_gas_usage, estimate_minimal_gas_vector, estimate_minimal_l1_gas,
+    get_onchain_data_cost,
 };
 use crate::state::cached_state::{CachedState, StateChangesCount};
 use crate::state::errors::StateError;
@@ -49,27 +57,33 @@ use crate::test_utils::initial_test_state::test_state;
 use crate::test_utils::invoke::{invoke_tx, InvokeTxArgs};
 use crate::test_utils::prices::Prices;
 use crate::test_utils::{
-    create_calldata, test_erc20_sequencer_balance_key, CairoVersion, NonceManager, SaltManager,
-    BALANCE, CHAIN_ID_NAME, CURRENT_BLOCK_NUMBER, CURRENT_BLOCK_TIMESTAMP, MAX_FEE,
+    create_calldata, test_erc20_sequencer_balance_key, to_hex_string, CairoVersion, NonceManager,
+    SaltManager, BALANCE, CHAIN_ID_NAME, CURRENT_BLOCK_NUMBER, CURRENT_BLOCK_TIMESTAMP, MAX_FEE,
     MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE, TEST_CLASS_HASH, TEST_CONTRACT_ADDRESS,
     TEST_SEQUENCER_ADDRESS,
 };
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::constants;
 use crate::transaction::errors::{
-    TransactionExecutionError, TransactionFeeError, TransactionPreValidationError,
+    ContractClassLimitError, L1HandlerTransactionError, TransactionExecutionError,
+    TransactionFeeError, TransactionPreValidationError,
 };
 use crate::transaction::objects::{
-    AccountTransactionContext, FeeType, HasRelatedFeeType, ResourcesMapping,
-    TransactionExecutionInfo,
+    AccountTransactionContext, FeeType, GasVector, HasRelatedFeeType, L1HandlerFee,
+    ResourcesMapping, TransactionExecutionInfo,
 };
 use crate::transaction::test_utils::{
-    account_invoke_tx, create_account_tx_for_validate_test, l1_resource_bounds,
-    FaultyAccountTxCreatorArgs, CALL_CONTRACT, GET_BLOCK_HASH, INVALID, VALID,
+    account_invoke_tx, block_context, create_account_tx_for_validate_test, create_test_init_data,
+    l1_resource_bounds, run_invoke_tx, FaultyAccountTxCreatorArgs, TestInitData, CALL_CONTRACT,
+    GET_BLOCK_HASH, INVALID, VALID,
 };
 use crate::transaction::transaction_execution::Transaction;
 use crate::transaction::transaction_types::TransactionType;
-use crate::transaction::transactions::{ExecutableTransaction, L1HandlerTransaction};
+use crate::transaction::transactions::{
+    verify_deterministic_execution, ExecutableTransaction, L1HandlerTransaction,
+};
+use crate::utils::format_address;
+use crate::versioned_constants::ResourceGasMode;
 use crate::{
     check_transaction_execution_error_for_custom_hint,
     check_transaction_execution_error_for_invalid_scenario, declare_tx_args,
@@ -83,6 +97,8 @@ struct ExpectedResultTestInvokeTx {
     validate_gas_consumed: u64,
     execute_gas_consumed: u64,
     inner_call_initial_gas: u64,
+    execute_syscall_counter: SyscallCounter,
+    l1_gas: u128,
 }
 
 fn expected_validate_call_info(
@@ -220,12 +236,28 @@ fn expected_fee_transfer_call_info(
             stark_felt!(0_u8),
             stark_felt!(0_u8),
         ],
+        storage_read_keys: vec![
+            sender_balance_key_low,
+            sender_balance_key_high,
+            sender_balance_key_low,
+            sender_balance_key_high,
+            sequencer_balance_key_low,
+            sequencer_balance_key_high,
+            sequencer_balance_key_low,
+            sequencer_balance_key_high,
+        ],
         accessed_storage_keys: HashSet::from_iter(vec![
             sender_balance_key_low,
             sender_balance_key_high,
             sequencer_balance_key_low,
             sequencer_balance_key_high,
         ]),
+        syscall_counter: HashMap::from([
+            (DeprecatedSyscallSelector::GetCallerAddress, 1),
+            (DeprecatedSyscallSelector::EmitEvent, 1),
+            (DeprecatedSyscallSelector::StorageRead, 4),
+            (DeprecatedSyscallSelector::StorageWrite, 4),
+        ]),
         ..Default::default()
     })
 }
@@ -290,6 +322,19 @@ fn default_invoke_tx_args(
     }
 }
 
+/// Returns the arguments of an invoke transaction, sent by the account of `test_init_data`, which
+/// writes `value` to storage address 1 of its test contract, and returns it.
+fn storage_read_write_invoke_tx_args(test_init_data: &TestInitData, value: u8) -> InvokeTxArgs {
+    invoke_tx_args! {
+        calldata: create_calldata(
+            test_init_data.contract_address,
+            "test_storage_read_write",
+            &[stark_felt!(1_u8), stark_felt!(value)],
+        ),
+        ..default_invoke_tx_args(test_init_data.account_address, test_init_data.contract_address)
+    }
+}
+
 #[test_case(
     ExpectedResultTestInvokeTx{
         range_check: 102,
@@ -302,6 +347,8 @@ fn default_invoke_tx_args(
         validate_gas_consumed: 0,
         execute_gas_consumed: 0,
         inner_call_initial_gas: abi_constants::INITIAL_GAS_COST,
+        execute_syscall_counter: HashMap::from([(DeprecatedSyscallSelector::CallContract, 1)]),
+        l1_gas: 6116,
     },
     CairoVersion::Cairo0;
     "With Cairo0 account")]
@@ -318,6 +365,11 @@ fn default_invoke_tx_args(
             // arguments.
         execute_gas_consumed: 103660,
         inner_call_initial_gas: 9999681980,
+        execute_syscall_counter: HashMap::from([
+            (DeprecatedSyscallSelector::CallContract, 1),
+            (DeprecatedSyscallSelector::GetExecutionInfo, 1),
+        ]),
+        l1_gas: 6569,
     },
     CairoVersion::Cairo1;
     "With Cairo1 account")]
@@ -342,6 +394,7 @@ fn test_invoke_tx(
 
     let account_tx = AccountTransaction::Invoke(invoke_tx);
     let fee_type = &account_tx.fee_type();
+    let committed_fee = account_tx.get_account_tx_context().committed_fee(block_context).unwrap();
     let actual_execution_info = account_tx.execute(state, block_context, true, true).unwrap();
 
     // Build expected validate call info.
@@ -377,7 +430,7 @@ fn test_invoke_tx(
     let expected_execute_call_info = Some(CallInfo {
         call: expected_execute_call,
         execution: CallExecution {
-            retdata: Retdata(expected_return_result_retdata.0.clone()),
+            retdata: expected_return_result_retdata.clone(),
             gas_consumed: expected_arguments.execute_gas_consumed,
             ..Default::default()
         },
@@ -392,6 +445,7 @@ fn test_invoke_tx(
             },
             ..Default::default()
         }],
+        syscall_counter: expected_arguments.execute_syscall_counter,
         ..Default::default()
     });
 
@@ -410,7 +464,9 @@ fn test_invoke_tx(
         validate_call_info: expected_validate_call_info,
         execute_call_info: expected_execute_call_info,
         fee_transfer_call_info: expected_fee_transfer_call_info,
+        delegated_fee_transfer: None,
         actual_fee: expected_actual_fee,
+        actual_gas: GasVector { l1_gas: expected_arguments.l1_gas, ..Default::default() },
         actual_resources: ResourcesMapping(HashMap::from([
             (
                 abi_constants::GAS_USAGE.to_string(),
@@ -425,11 +481,20 @@ fn test_invoke_tx(
             (abi_constants::N_STEPS_RESOURCE.to_string(), expected_arguments.n_steps),
         ])),
         revert_error: None,
+        is_speculative: false,
+        l1_handler_fee: None,
+        refund: Fee(committed_fee.0 - expected_actual_fee.0),
+        revert_report: None,
     };
 
     // Test execution info result.
     assert_eq!(actual_execution_info, expected_execution_info);
 
+    // The transaction syscall summary covers all stages, including the fee transfer.
+    let syscall_summary = actual_execution_info.summarize_syscalls();
+    assert_eq!(syscall_summary[&DeprecatedSyscallSelector::CallContract], 1);
+    assert_eq!(syscall_summary[&DeprecatedSyscallSelector::StorageWrite], 4);
+
     // Test nonce update.
     let nonce_from_state = state.get_nonce_at(sender_address).unwrap();
     assert_eq!(nonce_from_state, Nonce(stark_felt!(1_u8)));
@@ -695,6 +760,234 @@ fn test_state_get_fee_token_balance(
     assert_eq!(high, mint_high);
 }
 
+#[rstest]
+fn test_accumulated_usage(block_context: BlockContext) {
+    let mut test_init_data = create_test_init_data(&block_context, CairoVersion::Cairo0);
+    let tx_execution_infos: Vec<TransactionExecutionInfo> = (0..2_u8)
+        .map(|value| {
+            let invoke_tx_args = invoke_tx_args! {
+                nonce: test_init_data.nonce_manager.next(test_init_data.account_address),
+                ..storage_read_write_invoke_tx_args(&test_init_data, value)
+            };
+            run_invoke_tx(&mut test_init_data.state, &block_context, invoke_tx_args).unwrap()
+        })
+        .collect();
+
+    let total_gas: GasVector = tx_execution_infos.iter().map(|info| info.actual_gas).sum();
+    assert_eq!(
+        total_gas.l1_gas,
+        tx_execution_infos[0].actual_gas.l1_gas + tx_execution_infos[1].actual_gas.l1_gas
+    );
+    let total_resources: ResourcesMapping =
+        tx_execution_infos.iter().map(|info| &info.actual_resources).sum();
+    assert_eq!(
+        total_resources.n_steps(),
+        tx_execution_infos[0].actual_resources.n_steps()
+            + tx_execution_infos[1].actual_resources.n_steps()
+    );
+
+    let execution_resources = ExecutionResources {
+        vm_resources: VmExecutionResources { n_steps: 10, ..Default::default() },
+        syscall_counter: HashMap::from([(DeprecatedSyscallSelector::StorageRead, 1)]),
+    };
+    let total_execution_resources: ExecutionResources =
+        [&execution_resources, &execution_resources].into_iter().sum();
+    assert_eq!(
+        total_execution_resources,
+        ExecutionResources {
+            vm_resources: VmExecutionResources { n_steps: 20, ..Default::default() },
+            syscall_counter: HashMap::from([(DeprecatedSyscallSelector::StorageRead, 2)]),
+        }
+    );
+
+    // Summing nothing yields no usage.
+    assert_eq!(std::iter::empty::<GasVector>().sum::<GasVector>(), GasVector::default());
+    assert_eq!(
+        std::iter::empty::<&ResourcesMapping>().sum::<ResourcesMapping>(),
+        ResourcesMapping::default()
+    );
+    assert_eq!(
+        std::iter::empty::<&ExecutionResources>().sum::<ExecutionResources>(),
+        ExecutionResources::default()
+    );
+}
+
+#[rstest]
+fn test_retdata_segment_verification(
+    block_context: BlockContext,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let mut test_init_data = create_test_init_data(&block_context, cairo_version);
+    let invoke_args = storage_read_write_invoke_tx_args(&test_init_data, 7);
+    let reverting_invoke_args = invoke_tx_args! {
+        calldata: create_calldata(
+            test_init_data.contract_address,
+            "write_and_revert",
+            &[stark_felt!(9_u8), stark_felt!(99_u8)],
+        ),
+        nonce: Nonce(StarkFelt::ONE),
+        ..invoke_args.clone()
+    };
+    let run_invoke_txs = |state: &mut CachedState<DictStateReader>,
+                          block_context: &BlockContext| {
+        [invoke_args.clone(), reverting_invoke_args.clone()]
+            .map(|invoke_args| run_invoke_tx(state, block_context, invoke_args).unwrap())
+    };
+
+    // Well-formed return data passes verification, and verification does not affect the outcome
+    // of calls that fail. Malformed return data is covered by `test_verify_retdata_segment`.
+    let tx_execution_infos = run_invoke_txs(&mut test_init_data.state, &block_context);
+    let verifying_block_context =
+        BlockContext { verify_retdata_segments: true, ..block_context.clone() };
+    let TestInitData { state: mut verified_state, .. } =
+        create_test_init_data(&verifying_block_context, cairo_version);
+    let verified_tx_execution_infos = run_invoke_txs(&mut verified_state, &verifying_block_context);
+    assert_eq!(verified_tx_execution_infos, tx_execution_infos);
+    assert!(!verified_tx_execution_infos[0].is_reverted());
+    assert!(verified_tx_execution_infos[1].is_reverted());
+}
+
+#[rstest]
+fn test_collect_program_outputs(
+    block_context: BlockContext,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let mut test_init_data = create_test_init_data(&block_context, cairo_version);
+    let invoke_args = storage_read_write_invoke_tx_args(&test_init_data, 7);
+    let tx_execution_info =
+        run_invoke_tx(&mut test_init_data.state, &block_context, invoke_args.clone()).unwrap();
+
+    // None of the programs use the output builtin, so collecting outputs does not affect the
+    // execution; the output builtin segment itself is covered by `test_read_program_output`.
+    let collecting_block_context = BlockContext { collect_program_outputs: true, ..block_context };
+    let TestInitData { state: mut collecting_state, .. } =
+        create_test_init_data(&collecting_block_context, cairo_version);
+    let collected_tx_execution_info =
+        run_invoke_tx(&mut collecting_state, &collecting_block_context, invoke_args).unwrap();
+    assert_eq!(collected_tx_execution_info, tx_execution_info);
+    let execute_call_info = &collected_tx_execution_info.execute_call_info.unwrap();
+    assert!(execute_call_info
+        .into_iter()
+        .all(|call_info| call_info.execution.program_output.is_empty()));
+    assert_eq!(execute_call_info.inner_calls[0].execution.retdata, retdata![stark_felt!(7_u8)]);
+}
+
+#[rstest]
+fn test_determinism_audit(block_context: BlockContext) {
+    let mut test_init_data = create_test_init_data(&block_context, CairoVersion::Cairo1);
+    let invoke_args = storage_read_write_invoke_tx_args(&test_init_data, 2);
+
+    // An audited execution is committed once, as an unaudited one.
+    let state = &mut test_init_data.state;
+    let tx_execution_info = run_invoke_tx(state, &block_context, invoke_args.clone()).unwrap();
+    let audit_block_context = BlockContext { determinism_audit: true, ..block_context.clone() };
+    let TestInitData { state: mut audited_state, .. } =
+        create_test_init_data(&audit_block_context, CairoVersion::Cairo1);
+    let audited_tx_execution_info =
+        run_invoke_tx(&mut audited_state, &audit_block_context, invoke_args).unwrap();
+    assert_eq!(audited_tx_execution_info, tx_execution_info);
+    assert_eq!(audited_state.to_state_diff(), state.to_state_diff());
+
+    // Differing executions are detected.
+    let state_diff = state.to_state_diff();
+    let empty_state_diff = CachedState::from(DictStateReader::default()).to_state_diff();
+    let execution_result = Ok(TransactionExecutionInfo::default());
+    assert_matches!(
+        verify_deterministic_execution(
+            (&execution_result, &state_diff),
+            (&execution_result, &empty_state_diff),
+        ),
+        Err(TransactionExecutionError::NondeterministicExecution { mismatch: "state diff" })
+    );
+    assert_matches!(
+        verify_deterministic_execution(
+            (&execution_result, &state_diff),
+            (
+                &Err(TransactionExecutionError::UnexpectedHoles {
+                    object: "event".into(),
+                    order: 0
+                }),
+                &state_diff
+            ),
+        ),
+        Err(TransactionExecutionError::NondeterministicExecution { mismatch: "outcome" })
+    );
+}
+
+#[rstest]
+#[case(TransactionVersion::ONE, FeeType::Eth, MAX_FEE)]
+#[case(TransactionVersion::THREE, FeeType::Strk, u128::from(MAX_L1_GAS_AMOUNT) * MAX_L1_GAS_PRICE)]
+fn test_refund(
+    block_context: BlockContext,
+    #[case] tx_version: TransactionVersion,
+    #[case] fee_type: FeeType,
+    #[case] committed_fee: u128,
+) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context, CairoVersion::Cairo0);
+    let fee_token_address = block_context.fee_token_address(&fee_type);
+    let invoke_tx_args = invoke_tx_args! {
+        resource_bounds: l1_resource_bounds(MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE),
+        version: tx_version,
+        ..default_invoke_tx_args(account_address, contract_address)
+    };
+
+    // The refund is the uncharged part of the committed fee, for successful and reverted
+    // transactions alike; only the actual fee is transferred.
+    let mut expected_balance = BALANCE;
+    for (calldata, is_reverted) in [
+        (create_calldata(contract_address, "return_result", &[stark_felt!(2_u8)]), false),
+        (
+            create_calldata(
+                contract_address,
+                "write_and_revert",
+                &[stark_felt!(9_u8), stark_felt!(99_u8)],
+            ),
+            true,
+        ),
+    ] {
+        let tx_execution_info = run_invoke_tx(
+            &mut state,
+            &block_context,
+            invoke_tx_args! {
+                calldata,
+                nonce: nonce_manager.next(account_address),
+                ..invoke_tx_args.clone()
+            },
+        )
+        .unwrap();
+        assert_eq!(tx_execution_info.is_reverted(), is_reverted);
+        let actual_fee = tx_execution_info.actual_fee;
+        assert!(actual_fee.0 > 0);
+        assert_eq!(tx_execution_info.refund, Fee(committed_fee - actual_fee.0));
+
+        let fee_transfer_call_info = tx_execution_info.fee_transfer_call_info.unwrap();
+        assert_eq!(fee_transfer_call_info.call.calldata.0[1], stark_felt!(actual_fee.0));
+        expected_balance -= actual_fee.0;
+        let (balance_low, balance_high) =
+            state.get_fee_token_balance(account_address, fee_token_address).unwrap();
+        assert_eq!((balance_low, balance_high), (stark_felt!(expected_balance), stark_felt!(0_u8)));
+    }
+}
+
+#[rstest]
+fn test_refund_of_overflowing_bounds_without_fee_charge(block_context: BlockContext) {
+    let TestInitData { mut state, account_address, contract_address, .. } =
+        create_test_init_data(&block_context, CairoVersion::Cairo0);
+
+    // The committed fee overflows; as the fee is not charged, the transaction is executed and the
+    // refund saturates.
+    let tx_execution_info = account_invoke_tx(invoke_tx_args! {
+        resource_bounds: l1_resource_bounds(u64::MAX, u128::MAX),
+        version: TransactionVersion::THREE,
+        ..default_invoke_tx_args(account_address, contract_address)
+    })
+    .execute(&mut state, &block_context, false, true)
+    .unwrap();
+    assert!(!tx_execution_info.is_reverted());
+    assert_eq!(tx_execution_info.refund, Fee(u128::MAX - tx_execution_info.actual_fee.0));
+}
+
 fn assert_failure_if_resource_bounds_exceed_balance(
     state: &mut CachedState<DictStateReader>,
     block_context: &BlockContext,
@@ -854,6 +1147,162 @@ fn test_insufficient_resource_bounds(account_cairo_version: CairoVersion) {
     );
 }
 
+#[test]
+fn test_l2_gas_resource_bounds() {
+    let mut block_context = BlockContext::create_for_account_testing();
+    block_context.versioned_constants.resource_gas_mode =
+        ResourceGasMode::SeparateL2Gas { l2_gas_per_l1_gas: 40_000 };
+    let block_context = &block_context;
+    let TestInitData { mut state, account_address, contract_address, .. } =
+        create_test_init_data(block_context, CairoVersion::Cairo0);
+    let state = &mut state;
+    let valid_invoke_tx_args = default_invoke_tx_args(account_address, contract_address);
+    let actual_strk_l2_gas_price = block_context.gas_prices.strk_l2_gas_price;
+    // Failing transactions do not increment the nonce; reverted transactions do.
+    let invoke_tx = |nonce: u8, max_l2_gas_amount: u64, max_l2_gas_price: u128| {
+        let resource_bounds = ResourceBoundsMapping::try_from(vec![
+            (
+                Resource::L1Gas,
+                ResourceBounds {
+                    max_amount: MAX_L1_GAS_AMOUNT,
+                    max_price_per_unit: MAX_L1_GAS_PRICE,
+                },
+            ),
+            (
+                Resource::L2Gas,
+                ResourceBounds {
+                    max_amount: max_l2_gas_amount,
+                    max_price_per_unit: max_l2_gas_price,
+                },
+            ),
+        ])
+        .unwrap();
+        account_invoke_tx(invoke_tx_args! {
+            resource_bounds,
+            version: TransactionVersion::THREE,
+            nonce: Nonce(StarkFelt::from(nonce)),
+            ..valid_invoke_tx_args.clone()
+        })
+    };
+
+    // The Cairo resources of the transaction are charged as L2 gas.
+    let minimal_l2_gas =
+        estimate_minimal_gas_vector(block_context, &invoke_tx(0, 0, 0)).unwrap().l2_gas as u64;
+    assert!(minimal_l2_gas > 0);
+
+    // Max L2 gas amount too low.
+    let execution_error = invoke_tx(0, minimal_l2_gas - 1, actual_strk_l2_gas_price)
+        .execute(state, block_context, true, true)
+        .unwrap_err();
+    assert_matches!(
+        execution_error,
+        TransactionExecutionError::TransactionPreValidationError(
+            TransactionPreValidationError::TransactionFeeError(
+                TransactionFeeError::MaxL2GasAmountTooLow {
+                    max_l2_gas_amount, minimal_l2_gas_amount }))
+        if max_l2_gas_amount == minimal_l2_gas - 1 && minimal_l2_gas_amount == minimal_l2_gas
+    );
+
+    // Max L2 gas price too low.
+    let execution_error = invoke_tx(0, minimal_l2_gas, actual_strk_l2_gas_price - 1)
+        .execute(state, block_context, true, true)
+        .unwrap_err();
+    assert_matches!(
+        execution_error,
+        TransactionExecutionError::TransactionPreValidationError(
+            TransactionPreValidationError::TransactionFeeError(
+                TransactionFeeError::MaxL2GasPriceTooLow { max_l2_gas_price, actual_l2_gas_price }))
+        if max_l2_gas_price == actual_strk_l2_gas_price - 1
+            && actual_l2_gas_price == actual_strk_l2_gas_price
+    );
+
+    // The committed fee includes the L2 gas bounds.
+    let execution_error = invoke_tx(0, u64::MAX, actual_strk_l2_gas_price)
+        .execute(state, block_context, true, true)
+        .unwrap_err();
+    assert_matches!(
+        execution_error,
+        TransactionExecutionError::TransactionPreValidationError(
+            TransactionPreValidationError::TransactionFeeError(
+                TransactionFeeError::GasBoundsExceedBalance { .. }
+            )
+        )
+    );
+
+    // The L2 gas bounds cover the minimal gas, but not the execution; the transaction is
+    // reverted, and is charged at most its bounds.
+    let tx = invoke_tx(0, minimal_l2_gas, actual_strk_l2_gas_price);
+    let committed_fee = tx.get_account_tx_context().committed_fee(block_context).unwrap();
+    let tx_execution_info = tx.execute(state, block_context, true, true).unwrap();
+    assert!(tx_execution_info.is_reverted());
+    assert!(tx_execution_info.actual_fee <= committed_fee);
+
+    // Sufficient L2 gas bounds.
+    let tx = invoke_tx(1, 100 * minimal_l2_gas, actual_strk_l2_gas_price);
+    let tx_execution_info = tx.execute(state, block_context, true, true).unwrap();
+    assert!(!tx_execution_info.is_reverted());
+    assert!(tx_execution_info.actual_gas.l2_gas > minimal_l2_gas.into());
+
+    // When all resources collapse to L1 gas, no L2 gas is charged, and its bounds are ignored.
+    let l1_gas_block_context = &BlockContext::create_for_account_testing();
+    let tx = invoke_tx(2, 0, 0);
+    assert_eq!(estimate_minimal_gas_vector(l1_gas_block_context, &tx).unwrap().l2_gas, 0);
+    let tx_execution_info = tx.execute(state, l1_gas_block_context, true, true).unwrap();
+    assert!(!tx_execution_info.is_reverted());
+    assert_eq!(tx_execution_info.actual_gas.l2_gas, 0);
+}
+
+#[rstest]
+fn test_tip(#[values(false, true)] charge_tips: bool) {
+    let block_context = &BlockContext { charge_tips, ..BlockContext::create_for_account_testing() };
+    let TestInitData { mut state, account_address, contract_address, .. } =
+        create_test_init_data(block_context, CairoVersion::Cairo0);
+    let state = &mut state;
+    let tip = Tip(7);
+    let invoke_tx_args = invoke_tx_args! {
+        version: TransactionVersion::THREE,
+        tip,
+        ..default_invoke_tx_args(account_address, contract_address)
+    };
+
+    // A charged tip is added to the L1 gas price, which the max price must cover.
+    let tx_execution_result = account_invoke_tx(invoke_tx_args! {
+        resource_bounds: l1_resource_bounds(MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE),
+        ..invoke_tx_args.clone()
+    })
+    .execute(state, block_context, true, true);
+    if charge_tips {
+        assert_matches!(
+            tx_execution_result.unwrap_err(),
+            TransactionExecutionError::TransactionPreValidationError(
+                TransactionPreValidationError::TransactionFeeError(
+                    TransactionFeeError::MaxL1GasPriceTooLow { max_l1_gas_price, actual_l1_gas_price }
+                )
+            ) if max_l1_gas_price == MAX_L1_GAS_PRICE
+                && actual_l1_gas_price == MAX_L1_GAS_PRICE + u128::from(tip.0)
+        );
+    } else {
+        assert!(!tx_execution_result.unwrap().is_reverted());
+    }
+
+    let tx_execution_info = account_invoke_tx(invoke_tx_args! {
+        resource_bounds: l1_resource_bounds(MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE + 100),
+        nonce: state.get_nonce_at(account_address).unwrap(),
+        ..invoke_tx_args
+    })
+    .execute(state, block_context, true, true)
+    .unwrap();
+    let actual_resources = &tx_execution_info.actual_resources;
+    let fee_without_tip =
+        calculate_tx_fee(actual_resources, block_context, &FeeType::Strk).unwrap();
+    let expected_tip_fee = if charge_tips {
+        calculate_tx_gas_vector(actual_resources, block_context).unwrap().l1_gas * u128::from(tip.0)
+    } else {
+        0
+    };
+    assert_eq!(tx_execution_info.actual_fee, Fee(fee_without_tip.0 + expected_tip_fee));
+}
+
 #[test_case(CairoVersion::Cairo0; "With Cairo0 account")]
 #[test_case(CairoVersion::Cairo1; "With Cairo1 account")]
 fn test_actual_fee_gt_resource_bounds(account_cairo_version: CairoVersion) {
@@ -958,6 +1407,51 @@ fn test_invalid_nonce(account_cairo_version: CairoVersion) {
     );
 }
 
+#[rstest]
+fn test_nonce_gap_mode(#[values(0, 1, 2, 3)] nonce_offset: u8) {
+    let max_nonce_gap = 2;
+    let block_context =
+        &BlockContext { max_nonce_gap, ..BlockContext::create_for_account_testing() };
+    let TestInitData { mut state, account_address: sender_address, contract_address, .. } =
+        create_test_init_data(block_context, CairoVersion::Cairo0);
+    let incoming_tx_nonce = Nonce(stark_felt!(nonce_offset));
+    let invoke_tx = |block_context: &BlockContext, state: &mut CachedState<DictStateReader>| {
+        account_invoke_tx(invoke_tx_args! {
+            nonce: incoming_tx_nonce,
+            ..default_invoke_tx_args(sender_address, contract_address)
+        })
+        .execute(state, block_context, true, true)
+    };
+
+    // Without the mode, any nonce ahead of the account nonce is rejected (before any state change).
+    if nonce_offset > 0 {
+        let strict_block_context = &BlockContext::create_for_account_testing();
+        assert_matches!(
+            invoke_tx(strict_block_context, &mut state).unwrap_err(),
+            TransactionExecutionError::TransactionPreValidationError(
+                TransactionPreValidationError::InvalidNonce { .. }
+            )
+        );
+    }
+
+    let result = invoke_tx(block_context, &mut state);
+
+    if u64::from(nonce_offset) > max_nonce_gap {
+        assert_matches!(
+            result.unwrap_err(),
+            TransactionExecutionError::TransactionPreValidationError(
+                TransactionPreValidationError::InvalidNonce { account_nonce, .. }
+            ) if account_nonce == Nonce::default()
+        );
+        assert_eq!(state.get_nonce_at(sender_address).unwrap(), Nonce::default());
+        return;
+    }
+    let execution_info = result.unwrap();
+    assert!(execution_info.revert_error.is_none());
+    assert_eq!(execution_info.is_speculative, nonce_offset > 0);
+    assert_eq!(state.get_nonce_at(sender_address).unwrap(), Nonce(StarkFelt::ONE));
+}
+
 /// Returns the expected number of range checks in a declare transaction.
 fn declare_expected_range_check_builtin(
     version: TransactionVersion,
@@ -1069,6 +1563,7 @@ fn test_declare_tx(
         undeclared_class_hash == class_hash
     );
     let fee_type = &account_tx.fee_type();
+    let committed_fee = account_tx.get_account_tx_context().committed_fee(block_context).unwrap();
     let actual_execution_info = account_tx.execute(state, block_context, true, true).unwrap();
 
     // Build expected validate call info.
@@ -1095,7 +1590,10 @@ fn test_declare_tx(
         validate_call_info: expected_validate_call_info,
         execute_call_info: None,
         fee_transfer_call_info: expected_fee_transfer_call_info,
+        delegated_fee_transfer: None,
         actual_fee: expected_actual_fee,
+        actual_gas: calculate_tx_gas_vector(&actual_execution_info.actual_resources, block_context)
+            .unwrap(),
         revert_error: None,
         actual_resources: ResourcesMapping(HashMap::from([
             (abi_constants::GAS_USAGE.to_string(), declare_expected_l1_gas_usage(tx_version)),
@@ -1109,6 +1607,10 @@ fn test_declare_tx(
                 declare_n_steps(tx_version, account_cairo_version),
             ),
         ])),
+        is_speculative: false,
+        l1_handler_fee: None,
+        refund: Fee(committed_fee.0 - expected_actual_fee.0),
+        revert_report: None,
     };
 
     // Test execution info result.
@@ -1136,6 +1638,153 @@ fn test_declare_tx(
     assert_eq!(contract_class_from_state, contract_class);
 }
 
+/// Tests that re-declaring a class is valid and charged for, but keeps the declared class.
+#[rstest]
+#[case(TransactionVersion::ONE, CairoVersion::Cairo0)]
+#[case(TransactionVersion::TWO, CairoVersion::Cairo1)]
+#[case(TransactionVersion::THREE, CairoVersion::Cairo1)]
+fn test_redeclare_tx(#[case] tx_version: TransactionVersion, #[case] cairo_version: CairoVersion) {
+    let block_context = &BlockContext::create_for_account_testing();
+    let empty_contract = FeatureContract::Empty(cairo_version);
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let state = &mut test_state(block_context, BALANCE, &[(account, 1)]);
+    let class_hash = empty_contract.get_class_hash();
+    let sender_address = account.get_instance_address(0);
+
+    let mut run_declare = |nonce: u8, contract_class: ContractClass| {
+        declare_tx(
+            declare_tx_args! {
+                max_fee: Fee(MAX_FEE),
+                sender_address,
+                version: tx_version,
+                resource_bounds: l1_resource_bounds(MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE),
+                class_hash,
+                nonce: Nonce(stark_felt!(nonce)),
+            },
+            contract_class,
+        )
+        .execute(state, block_context, true, true)
+        .unwrap()
+    };
+    run_declare(0, empty_contract.get_class());
+
+    // Re-declare the class hash with another class; the declared class is kept.
+    let tx_execution_info =
+        run_declare(1, FeatureContract::TestContract(cairo_version).get_class());
+    assert!(!tx_execution_info.is_reverted());
+    assert!(tx_execution_info.actual_fee > Fee(0));
+    assert_eq!(state.get_nonce_at(sender_address).unwrap(), Nonce(stark_felt!(2_u8)));
+    assert_eq!(state.get_compiled_contract_class(class_hash).unwrap(), empty_contract.get_class());
+}
+
+/// Tests the transaction info reported by declare transactions of each version.
+#[rstest]
+#[case(TransactionVersion::ZERO, CairoVersion::Cairo0)]
+#[case(TransactionVersion::ONE, CairoVersion::Cairo0)]
+#[case(TransactionVersion::TWO, CairoVersion::Cairo1)]
+#[case(TransactionVersion::THREE, CairoVersion::Cairo1)]
+fn test_declare_tx_context(
+    #[case] tx_version: TransactionVersion,
+    #[case] empty_contract_version: CairoVersion,
+) {
+    let empty_contract = FeatureContract::Empty(empty_contract_version);
+    let resource_bounds = l1_resource_bounds(MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE);
+    let account_tx = declare_tx(
+        declare_tx_args! {
+            max_fee: Fee(MAX_FEE),
+            version: tx_version,
+            resource_bounds: resource_bounds.clone(),
+            nonce: Nonce(stark_felt!(5_u8)),
+            class_hash: empty_contract.get_class_hash(),
+        },
+        empty_contract.get_class(),
+    );
+
+    let account_tx_context = account_tx.get_account_tx_context();
+    assert_eq!(account_tx_context.version(), tx_version);
+    // V0 transactions have no nonce.
+    let expected_nonce = if tx_version == TransactionVersion::ZERO { 0_u8 } else { 5_u8 };
+    assert_eq!(account_tx_context.nonce(), Nonce(stark_felt!(expected_nonce)));
+    if tx_version == TransactionVersion::THREE {
+        assert_matches!(
+            account_tx_context,
+            AccountTransactionContext::Current(context)
+            if context.resource_bounds == resource_bounds
+        );
+    } else {
+        assert_matches!(
+            account_tx_context,
+            AccountTransactionContext::Deprecated(context) if context.max_fee == Fee(MAX_FEE)
+        );
+    }
+}
+
+#[rstest]
+fn test_declare_tx_exceeding_class_limits(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let contract_class = test_contract.get_class();
+    let mut block_context = BlockContext::create_for_account_testing();
+    block_context.versioned_constants.class_limits.max_bytecode_length =
+        contract_class.bytecode_length() - 1;
+    let state = &mut test_state(&block_context, BALANCE, &[(account, 1)]);
+    let tx_version = match cairo_version {
+        CairoVersion::Cairo0 => TransactionVersion::ONE,
+        CairoVersion::Cairo1 => TransactionVersion::TWO,
+    };
+
+    let account_tx = declare_tx(
+        declare_tx_args! {
+            max_fee: Fee(MAX_FEE),
+            sender_address: account.get_instance_address(0),
+            version: tx_version,
+            class_hash: test_contract.get_class_hash(),
+        },
+        contract_class,
+    );
+
+    // The transaction is rejected before any fee is charged.
+    assert_matches!(
+        account_tx.execute(state, &block_context, true, true).unwrap_err(),
+        TransactionExecutionError::ContractClassLimitError(
+            ContractClassLimitError::BytecodeTooLong { .. }
+        )
+    );
+}
+
+#[rstest]
+fn test_declare_tx_sierra_class_retention(#[values(false, true)] retain_sierra_classes: bool) {
+    let block_context =
+        &BlockContext { retain_sierra_classes, ..BlockContext::create_for_account_testing() };
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let empty_contract = FeatureContract::Empty(CairoVersion::Cairo1);
+    let state = &mut test_state(block_context, BALANCE, &[(account, 1)]);
+    let class_hash = empty_contract.get_class_hash();
+    let sierra_class =
+        SierraContractClass { abi: "[]".to_string(), ..SierraContractClass::default() };
+
+    let AccountTransaction::Declare(declare_tx) = declare_tx(
+        declare_tx_args! {
+            max_fee: Fee(MAX_FEE),
+            sender_address: account.get_instance_address(0),
+            version: TransactionVersion::TWO,
+            class_hash,
+        },
+        empty_contract.get_class(),
+    ) else {
+        panic!("Expected a declare transaction.");
+    };
+    let account_tx =
+        AccountTransaction::Declare(declare_tx.with_sierra_class(sierra_class.clone()));
+    account_tx.execute(state, block_context, true, true).unwrap();
+
+    let contract_class_from_state = state.get_compiled_contract_class(class_hash).unwrap();
+    let expected_sierra_class = if retain_sierra_classes { Some(&sierra_class) } else { None };
+    assert_eq!(contract_class_from_state.sierra_class(), expected_sierra_class);
+}
+
 #[rstest]
 #[case(83, 3893, CairoVersion::Cairo0)]
 #[case(85, 3949, CairoVersion::Cairo1)]
@@ -1176,6 +1825,7 @@ fn test_deploy_account_tx(
 
     let account_tx = AccountTransaction::DeployAccount(deploy_account);
     let fee_type = &account_tx.fee_type();
+    let committed_fee = account_tx.get_account_tx_context().committed_fee(block_context).unwrap();
     let actual_execution_info = account_tx.execute(state, block_context, true, true).unwrap();
 
     // Build expected validate call info.
@@ -1220,7 +1870,10 @@ fn test_deploy_account_tx(
         validate_call_info: expected_validate_call_info,
         execute_call_info: expected_execute_call_info,
         fee_transfer_call_info: expected_fee_transfer_call_info,
+        delegated_fee_transfer: None,
         actual_fee: expected_actual_fee,
+        actual_gas: calculate_tx_gas_vector(&actual_execution_info.actual_resources, block_context)
+            .unwrap(),
         revert_error: None,
         actual_resources: ResourcesMapping(HashMap::from([
             (
@@ -1236,6 +1889,10 @@ fn test_deploy_account_tx(
             (RANGE_CHECK_BUILTIN_NAME.to_string(), expected_range_check_builtin),
             (abi_constants::N_STEPS_RESOURCE.to_string(), expected_n_steps_resource),
         ])),
+        is_speculative: false,
+        l1_handler_fee: None,
+        refund: Fee(committed_fee.0 - expected_actual_fee.0),
+        revert_report: None,
     };
 
     // Test execution info result.
@@ -1271,8 +1928,8 @@ fn test_deploy_account_tx(
     assert_matches!(
         error,
         TransactionExecutionError::ContractConstructorExecutionFailed(
-            EntryPointExecutionError::StateError(StateError::UnavailableContractAddress(_))
-        )
+            EntryPointExecutionError::StateError(StateError::UnavailableContractAddress(address))
+        ) if address == deployed_account_address
     );
 }
 
@@ -1307,6 +1964,41 @@ fn test_fail_deploy_account_undeclared_class_hash() {
     );
 }
 
+#[rstest]
+fn test_fail_deploy_account_calldata_without_constructor() {
+    let block_context = &BlockContext::create_for_account_testing();
+    // The Cairo 1 dummy account has no constructor.
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let state = &mut test_state(block_context, BALANCE, &[(account, 0)]);
+    let deploy_account = deploy_account_tx(
+        deploy_account_tx_args! {
+            max_fee: Fee(MAX_FEE),
+            class_hash: account.get_class_hash(),
+            constructor_calldata: calldata![stark_felt!(1_u8)],
+        },
+        &mut NonceManager::default(),
+    );
+
+    // Fund account, so as not to fail pre-validation.
+    state
+        .set_storage_at(
+            block_context.fee_token_address(&FeeType::Eth),
+            get_fee_token_var_address(deploy_account.contract_address),
+            stark_felt!(BALANCE),
+        )
+        .unwrap();
+
+    let account_tx = AccountTransaction::DeployAccount(deploy_account);
+    let error = account_tx.execute(state, block_context, true, true).unwrap_err();
+    assert_matches!(
+        error,
+        TransactionExecutionError::ContractConstructorExecutionFailed(
+            EntryPointExecutionError::InvalidExecutionInput { input_descriptor, .. }
+        )
+        if input_descriptor == "constructor_calldata"
+    );
+}
+
 // TODO(Arni, 1/1/2024): Consider converting this test to use V3 txs.
 #[rstest]
 #[case::validate(TransactionType::InvokeFunction, false)]
@@ -1363,10 +2055,18 @@ fn test_validate_accounts_tx(
             ..default_args
         },
     );
+    let account_address = match &account_tx {
+        AccountTransaction::DeployAccount(deploy_account_tx) => deploy_account_tx.contract_address,
+        _ => sender_address,
+    };
     let error = account_tx.execute(state, block_context, true, true).unwrap_err();
     check_transaction_execution_error_for_custom_hint!(
         &error,
-        "Unauthorized syscall call_contract in execution mode Validate.",
+        &format!(
+            "Unauthorized call from contract {} to contract 0x1991 in execution mode Validate; \
+             validation may only call the validated account.",
+            format_address(&account_address)
+        ),
         validate_constructor,
     );
 
@@ -1618,7 +2318,7 @@ fn test_l1_handler() {
             initial_gas: Transaction::initial_gas(),
         },
         execution: CallExecution {
-            retdata: Retdata(vec![value]),
+            retdata: retdata![value],
             gas_consumed: 19650,
             ..Default::default()
         },
@@ -1628,6 +2328,7 @@ fn test_l1_handler() {
             builtin_instance_counter: HashMap::from([(RANGE_CHECK_BUILTIN_NAME.to_string(), 5)]),
         },
         accessed_storage_keys: HashSet::from_iter(vec![accessed_storage_key]),
+        syscall_counter: HashMap::from([(DeprecatedSyscallSelector::StorageWrite, 1)]),
         ..Default::default()
     };
 
@@ -1644,9 +2345,18 @@ fn test_l1_handler() {
         validate_call_info: None,
         execute_call_info: Some(expected_call_info),
         fee_transfer_call_info: None,
+        delegated_fee_transfer: None,
         actual_fee: Fee(0),
         actual_resources: expected_resource_mapping,
+        actual_gas: GasVector { l1_gas: 19065, ..Default::default() },
         revert_error: None,
+        is_speculative: false,
+        l1_handler_fee: Some(L1HandlerFee {
+            paid_fee_on_l1: Fee(1),
+            consumed_fee: Fee(1906500000000000),
+        }),
+        refund: Fee(0),
+        revert_report: None,
     };
 
     // Check the actual returned execution info.
@@ -1673,6 +2383,67 @@ fn test_l1_handler() {
             TransactionFeeError::InsufficientL1Fee { paid_fee, actual_fee, })
             if paid_fee == Fee(0) && actual_fee == Fee(1741300000000000)
     );
+
+    // The fee paid on L1 is not yet checked against the consumed fee, but the leftover is exposed.
+    assert_eq!(actual_execution_info.l1_handler_fee.unwrap().leftover_fee(), None);
+    let paid_fee = Fee(1741300000000000 + 10);
+    let tx_execution_info =
+        l1_handler_tx(&calldata, paid_fee).execute(state, block_context, true, true).unwrap();
+    let l1_handler_fee = tx_execution_info.l1_handler_fee.unwrap();
+    assert!(l1_handler_fee.is_sufficient());
+    assert_eq!(l1_handler_fee.leftover_fee(), Some(Fee(10)));
+}
+
+#[test]
+fn test_l1_handler_rules() {
+    let state = &mut create_test_state();
+    let block_context = &BlockContext::create_for_account_testing();
+    let calldata = calldata![stark_felt!(0x123_u16), stark_felt!(0x876_u16), stark_felt!(0x44_u8)];
+
+    // The nonce is set on L1; it is neither checked against nor advances the contract nonce.
+    let mut tx = l1_handler_tx(&calldata, Fee(1));
+    tx.tx.nonce = Nonce(stark_felt!(7_u8));
+    tx.execute(state, block_context, true, true).unwrap();
+    assert_eq!(
+        state.get_nonce_at(contract_address!(TEST_CONTRACT_ADDRESS)).unwrap(),
+        Nonce::default()
+    );
+
+    let mut tx = l1_handler_tx(&calldata, Fee(1));
+    tx.tx.version = TransactionVersion::ONE;
+    assert_matches!(
+        tx.execute(state, block_context, true, true).unwrap_err(),
+        TransactionExecutionError::L1HandlerTransactionError(
+            L1HandlerTransactionError::UnsupportedVersion { version }
+        ) if version == TransactionVersion::ONE
+    );
+
+    let tx = l1_handler_tx(&calldata![], Fee(1));
+    assert_matches!(
+        tx.execute(state, block_context, true, true).unwrap_err(),
+        TransactionExecutionError::L1HandlerTransactionError(
+            L1HandlerTransactionError::MissingL1Sender
+        )
+    );
+}
+
+#[test]
+fn test_l1_handler_message_hash() {
+    let from_address = stark_felt!(0x123_u16);
+    let mut tx = l1_handler_tx(
+        &calldata![from_address, stark_felt!(0x876_u16), stark_felt!(0x44_u8)],
+        Fee(1),
+    );
+    tx.tx.nonce = Nonce(stark_felt!(7_u8));
+    tx.tx.entry_point_selector = EntryPointSelector(stark_felt!(0x89_u8));
+
+    assert_eq!(
+        to_hex_string(&tx.message_hash().unwrap()),
+        "332cfc805762d665fcff4ec448981958de7e4e28981554e42ac2108697e26ddf"
+    );
+
+    let tx = l1_handler_tx(&calldata![], Fee(1));
+    assert_matches!(tx.message_hash(), Err(L1HandlerTransactionError::MissingL1Sender));
 }
 
 #[test]
@@ -1695,10 +2466,87 @@ fn test_execute_tx_with_invalid_transaction_version() {
     });
 
     let execution_info = account_tx.execute(state, block_context, true, true).unwrap();
-    assert!(
-        execution_info
-            .revert_error
-            .unwrap()
-            .contains(format!("ASSERT_EQ instruction failed: {} != 1.", invalid_version).as_str())
+    assert!(execution_info
+        .revert_error
+        .unwrap()
+        .contains(format!("ASSERT_EQ instruction failed: {} != 1.", invalid_version).as_str()));
+}
+
+#[rstest]
+fn test_deploy_through_universal_deployer(
+    #[values(true, false)] unique: bool,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let universal_deployer = FeatureContract::UniversalDeployer;
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let block_context = &BlockContext::create_for_account_testing();
+    // The test contract is only declared.
+    let state = &mut test_state(
+        block_context,
+        BALANCE,
+        &[(account, 1), (universal_deployer, 1), (test_contract, 0)],
+    );
+    let account_address = account.get_instance_address(0);
+    let deployer_address = universal_deployer.get_instance_address(0);
+
+    let class_hash = test_contract.get_class_hash();
+    let salt = stark_felt!(7_u8);
+    let constructor_calldata = calldata![stark_felt!(1_u8), stark_felt!(2_u8)];
+    let deploy_calldata = [
+        vec![class_hash.0, salt, StarkFelt::from(u8::from(unique)), stark_felt!(2_u8)],
+        constructor_calldata.0.to_vec(),
+    ]
+    .concat();
+    let account_tx = account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address: account_address,
+        calldata: create_calldata(deployer_address, "deployContract", &deploy_calldata),
+    });
+    let execution_info = account_tx.execute(state, block_context, true, true).unwrap();
+    assert!(!execution_info.is_reverted());
+
+    // A unique deployment binds the address to the caller, through the salt and the deployer.
+    let contract_address = if unique {
+        calculate_contract_address(
+            ContractAddressSalt(pedersen_hash(account_address.0.key(), &salt)),
+            class_hash,
+            &constructor_calldata,
+            deployer_address,
+        )
+    } else {
+        calculate_contract_address(
+            ContractAddressSalt(salt),
+            class_hash,
+            &constructor_calldata,
+            ContractAddress::default(),
+        )
+    }
+    .unwrap();
+    assert_eq!(state.get_class_hash_at(contract_address).unwrap(), class_hash);
+
+    let deploy_call_info = &execution_info.execute_call_info.unwrap().inner_calls[0];
+    assert_eq!(deploy_call_info.execution.retdata, retdata![*contract_address.0.key()]);
+    assert_eq!(deploy_call_info.inner_calls[0].call.caller_address, deployer_address);
+    let event_data = [
+        vec![
+            *contract_address.0.key(),
+            *account_address.0.key(),
+            StarkFelt::from(u8::from(unique)),
+        ],
+        deploy_calldata[..1].to_vec(),
+        deploy_calldata[3..].to_vec(),
+        vec![salt],
+    ]
+    .concat();
+    assert_eq!(
+        deploy_call_info.execution.events,
+        vec![OrderedEvent {
+            order: 0,
+            event: EventContent {
+                keys: vec![EventKey(selector_from_name("ContractDeployed").0)],
+                data: EventData(event_data),
+            },
+        }]
     );
 }
@@ -0,0 +1,134 @@
+//! The `starknet_api` types used in the public API of the crate, re-exported so that embedders
+//! need not depend on the exact version of `starknet_api` (or of the felt crates) this crate uses.
+//!
+//! Embedders holding felts of another crate convert them through `Felt`; e.g.,
+//! `ClassHash::from(Felt::from(field_element))`, or
+//! `field_elements.into_iter().map(Felt::from).collect::<Calldata>()`.
+
+use cairo_felt::Felt252;
+pub use starknet_api;
+pub use starknet_api::block::{BlockHash, BlockNumber, BlockTimestamp};
+pub use starknet_api::core::{
+    ChainId, ClassHash, CompiledClassHash, ContractAddress, EntryPointSelector, EthAddress, Nonce,
+    PatriciaKey,
+};
+pub use starknet_api::hash::{StarkFelt, StarkHash};
+pub use starknet_api::state::StorageKey;
+pub use starknet_api::transaction::{
+    Calldata, ContractAddressSalt, Fee, TransactionHash, TransactionSignature, TransactionVersion,
+};
+use starknet_api::StarknetApiError;
+use starknet_crypto::FieldElement;
+
+use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
+
+#[cfg(test)]
+#[path = "types_test.rs"]
+pub mod test;
+
+/// A felt, convertible from and into the felt types of the common felt crates, and into the
+/// felt-based types of `starknet_api`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Felt(pub StarkFelt);
+
+impl From<StarkFelt> for Felt {
+    fn from(felt: StarkFelt) -> Self {
+        Self(felt)
+    }
+}
+
+impl From<Felt> for StarkFelt {
+    fn from(felt: Felt) -> Self {
+        felt.0
+    }
+}
+
+impl From<Felt252> for Felt {
+    fn from(felt: Felt252) -> Self {
+        Self(felt_to_stark_felt(&felt))
+    }
+}
+
+impl From<Felt> for Felt252 {
+    fn from(felt: Felt) -> Self {
+        stark_felt_to_felt(felt.0)
+    }
+}
+
+impl From<FieldElement> for Felt {
+    fn from(felt: FieldElement) -> Self {
+        Self(StarkFelt::from(felt))
+    }
+}
+
+impl From<Felt> for FieldElement {
+    fn from(felt: Felt) -> Self {
+        FieldElement::from(felt.0)
+    }
+}
+
+impl From<u128> for Felt {
+    fn from(value: u128) -> Self {
+        Self(StarkFelt::from(value))
+    }
+}
+
+impl From<Felt> for ClassHash {
+    fn from(felt: Felt) -> Self {
+        ClassHash(felt.0)
+    }
+}
+
+impl From<Felt> for CompiledClassHash {
+    fn from(felt: Felt) -> Self {
+        CompiledClassHash(felt.0)
+    }
+}
+
+impl From<Felt> for EntryPointSelector {
+    fn from(felt: Felt) -> Self {
+        EntryPointSelector(felt.0)
+    }
+}
+
+impl From<Felt> for Nonce {
+    fn from(felt: Felt) -> Self {
+        Nonce(felt.0)
+    }
+}
+
+impl From<Felt> for TransactionHash {
+    fn from(felt: Felt) -> Self {
+        TransactionHash(felt.0)
+    }
+}
+
+impl TryFrom<Felt> for PatriciaKey {
+    type Error = StarknetApiError;
+
+    fn try_from(felt: Felt) -> Result<Self, Self::Error> {
+        PatriciaKey::try_from(felt.0)
+    }
+}
+
+impl TryFrom<Felt> for ContractAddress {
+    type Error = StarknetApiError;
+
+    fn try_from(felt: Felt) -> Result<Self, Self::Error> {
+        Ok(ContractAddress(PatriciaKey::try_from(felt)?))
+    }
+}
+
+impl TryFrom<Felt> for StorageKey {
+    type Error = StarknetApiError;
+
+    fn try_from(felt: Felt) -> Result<Self, Self::Error> {
+        Ok(StorageKey(PatriciaKey::try_from(felt)?))
+    }
+}
+
+impl FromIterator<Felt> for Calldata {
+    fn from_iter<I: IntoIterator<Item = Felt>>(felts: I) -> Self {
+        Calldata(felts.into_iter().map(StarkFelt::from).collect::<Vec<_>>().into())
+    }
+}
@@ -0,0 +1,48 @@
+use assert_matches::assert_matches;
+use cairo_felt::Felt252;
+use starknet_api::{calldata, stark_felt};
+use starknet_crypto::FieldElement;
+
+use super::*;
+
+#[test]
+fn test_felt_conversions() {
+    let stark_felt = stark_felt!("0x1234");
+    let felt = Felt(stark_felt);
+
+    // Round trips through the felt crates.
+    assert_eq!(Felt::from(Felt252::from(felt)), felt);
+    assert_eq!(Felt::from(FieldElement::from(felt)), felt);
+    assert_eq!(Felt::from(Felt252::from(0x1234_u16)), felt);
+    assert_eq!(Felt::from(FieldElement::from(0x1234_u16)), felt);
+    assert_eq!(Felt::from(0x1234_u128), felt);
+    assert_eq!(StarkFelt::from(felt), stark_felt);
+
+    assert_eq!(ClassHash::from(felt), ClassHash(stark_felt));
+    assert_eq!(CompiledClassHash::from(felt), CompiledClassHash(stark_felt));
+    assert_eq!(EntryPointSelector::from(felt), EntryPointSelector(stark_felt));
+    assert_eq!(Nonce::from(felt), Nonce(stark_felt));
+    assert_eq!(TransactionHash::from(felt), TransactionHash(stark_felt));
+    assert_eq!(
+        ContractAddress::try_from(felt).unwrap(),
+        ContractAddress(PatriciaKey::try_from(stark_felt).unwrap())
+    );
+    assert_eq!(
+        StorageKey::try_from(felt).unwrap(),
+        StorageKey(PatriciaKey::try_from(stark_felt).unwrap())
+    );
+
+    // Addresses are bounded.
+    let out_of_range = Felt(StarkFelt::from(FieldElement::MAX));
+    assert_matches!(
+        ContractAddress::try_from(out_of_range),
+        Err(StarknetApiError::OutOfRange { .. })
+    );
+}
+
+#[test]
+fn test_calldata_from_felts() {
+    let calldata: Calldata =
+        [FieldElement::ONE, FieldElement::TWO].into_iter().map(Felt::from).collect();
+    assert_eq!(calldata, calldata![stark_felt!(1_u8), stark_felt!(2_u8)]);
+}
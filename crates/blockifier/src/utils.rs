@@ -1,4 +1,18 @@
 use std::collections::HashMap;
+use std::io::{self, Read, Write};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{ToPrimitive, Zero};
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector};
+use starknet_api::hash::StarkFelt;
+use starknet_api::StarknetApiError;
+use starknet_crypto::FieldElement;
+
+use crate::fee::rounding::Rounding;
 
 #[cfg(test)]
 #[path = "utils_test.rs"]
@@ -19,3 +33,231 @@ where
 pub const fn const_max(a: u128, b: u128) -> u128 {
     [a, b][(a < b) as usize]
 }
+
+// Fixed-point arithmetic.
+// Integer-only (rather than floating point), so that fee computations are bit-identical on all
+// targets.
+
+/// Returns `value * numerator / denominator`, rounded as given, without intermediate overflow;
+/// returns `None` if the denominator is zero, or if the result does not fit in a `u128`.
+pub fn checked_mul_div(
+    value: u128,
+    numerator: u128,
+    denominator: u128,
+    rounding: Rounding,
+) -> Option<u128> {
+    if denominator == 0 {
+        return None;
+    }
+
+    let product = BigUint::from(value) * numerator;
+    let (quotient, remainder) = product.div_rem(&BigUint::from(denominator));
+    let quotient = match rounding {
+        Rounding::Ceil if !remainder.is_zero() => quotient + 1_u8,
+        _ => quotient,
+    };
+    quotient.to_u128()
+}
+
+/// Like `checked_mul_div`, with the result saturating at `u128::MAX`; still `None` if the
+/// denominator is zero.
+pub fn saturating_mul_div(
+    value: u128,
+    numerator: u128,
+    denominator: u128,
+    rounding: Rounding,
+) -> Option<u128> {
+    if denominator == 0 {
+        return None;
+    }
+
+    Some(checked_mul_div(value, numerator, denominator, rounding).unwrap_or(u128::MAX))
+}
+
+// Felt arithmetic.
+
+/// Field addition (modulo the Stark prime).
+pub fn felt_add(lhs: StarkFelt, rhs: StarkFelt) -> StarkFelt {
+    StarkFelt::from(FieldElement::from(lhs) + FieldElement::from(rhs))
+}
+
+/// Field subtraction (modulo the Stark prime).
+pub fn felt_sub(lhs: StarkFelt, rhs: StarkFelt) -> StarkFelt {
+    StarkFelt::from(FieldElement::from(lhs) - FieldElement::from(rhs))
+}
+
+/// Field multiplication (modulo the Stark prime).
+pub fn felt_mul(lhs: StarkFelt, rhs: StarkFelt) -> StarkFelt {
+    StarkFelt::from(FieldElement::from(lhs) * FieldElement::from(rhs))
+}
+
+/// Integer addition of felts; returns `None` if the sum is not smaller than the Stark prime.
+pub fn checked_felt_add(lhs: StarkFelt, rhs: StarkFelt) -> Option<StarkFelt> {
+    biguint_to_felt(felt_to_biguint(lhs) + felt_to_biguint(rhs)).ok()
+}
+
+/// Integer subtraction of felts; returns `None` if the difference is negative.
+pub fn checked_felt_sub(lhs: StarkFelt, rhs: StarkFelt) -> Option<StarkFelt> {
+    if lhs < rhs {
+        return None;
+    }
+    Some(felt_sub(lhs, rhs))
+}
+
+// Felt conversions.
+
+pub fn felt_to_biguint(felt: StarkFelt) -> BigUint {
+    BigUint::from_bytes_be(felt.bytes())
+}
+
+/// Converts a non-negative integer to a felt; fails if it is not smaller than the Stark prime.
+pub fn biguint_to_felt(value: BigUint) -> Result<StarkFelt, StarknetApiError> {
+    let out_of_range = || StarknetApiError::OutOfRange { string: format!("{value:#x}") };
+    let value_bytes = value.to_bytes_be();
+    let mut bytes = [0_u8; 32];
+    let first_byte_index = bytes.len().checked_sub(value_bytes.len()).ok_or_else(out_of_range)?;
+    bytes[first_byte_index..].copy_from_slice(&value_bytes);
+
+    let field_element = FieldElement::from_bytes_be(&bytes).map_err(|_| out_of_range())?;
+    Ok(StarkFelt::from(field_element))
+}
+
+/// Converts a felt to a `usize`; fails if the value does not fit.
+pub fn felt_to_usize(felt: StarkFelt) -> Result<usize, StarknetApiError> {
+    usize::try_from(felt)
+}
+
+/// Converts a felt to a `u64`; fails if the value does not fit.
+pub fn felt_to_u64(felt: StarkFelt) -> Result<u64, StarknetApiError> {
+    u64::try_from(felt)
+}
+
+/// Converts a felt to a `u128`; fails if the value does not fit.
+pub fn felt_to_u128(felt: StarkFelt) -> Result<u128, StarknetApiError> {
+    let (rest, u128_bytes) = felt.bytes().split_at(16);
+    if rest != [0_u8; 16] {
+        return Err(StarknetApiError::OutOfRange { string: felt.to_string() });
+    }
+
+    Ok(u128::from_be_bytes(u128_bytes.try_into().expect("Expected a slice of 16 bytes.")))
+}
+
+pub fn usize_to_felt(value: usize) -> StarkFelt {
+    // A `usize` is at most 64 bits wide on all supported targets.
+    StarkFelt::from(u64::try_from(value).expect("Failed to convert usize to u64."))
+}
+
+/// Converts a u256 value, given as its (low, high) 128-bit felts, to a `u128`; fails if either
+/// part is out of range, or if the value does not fit.
+pub fn u128_from_felts(low: StarkFelt, high: StarkFelt) -> Result<u128, StarknetApiError> {
+    if high != StarkFelt::ZERO {
+        return Err(StarknetApiError::OutOfRange { string: format!("({low}, {high})") });
+    }
+
+    felt_to_u128(low)
+}
+
+// Compression.
+// Cairo 0 programs are served by the gateway base64-encoded and gzipped.
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes in standard (padded) base64.
+pub fn encode_base64(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let triple = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let sextet = (triple >> (18 - 6 * i)) & 0x3f;
+                encoded.push(char::from(BASE64_ALPHABET[sextet as usize]));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Decodes standard base64; the padding is optional, but if present, must complete the final
+/// quantum. Returns `None` on invalid input, including encodings whose unused trailing bits are
+/// not zero (which are not canonical).
+pub fn decode_base64(encoded: &str) -> Option<Vec<u8>> {
+    let unpadded = encoded.trim_end_matches('=');
+    let n_padding_symbols = encoded.len() - unpadded.len();
+    if n_padding_symbols > 0 && (n_padding_symbols > 2 || encoded.len() % 4 != 0) {
+        return None;
+    }
+
+    let unpadded = unpadded.as_bytes();
+    let mut decoded = Vec::with_capacity(unpadded.len() * 3 / 4);
+    for chunk in unpadded.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+
+        let mut triple = 0_u32;
+        for (i, byte) in chunk.iter().enumerate() {
+            let sextet = BASE64_ALPHABET.iter().position(|symbol| symbol == byte)?;
+            triple |= (sextet as u32) << (18 - 6 * i);
+        }
+        let n_decoded_bytes = chunk.len() - 1;
+        let unused_bits_mask = (1_u32 << (24 - 8 * n_decoded_bytes)) - 1;
+        if triple & unused_bits_mask != 0 {
+            return None;
+        }
+        decoded.extend_from_slice(&triple.to_be_bytes()[1..=n_decoded_bytes]);
+    }
+
+    Some(decoded)
+}
+
+pub fn gzip(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+pub fn gunzip(compressed_data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    GzDecoder::new(compressed_data).read_to_end(&mut data)?;
+    Ok(data)
+}
+
+// Formatting.
+
+/// Formats a felt (e.g., a selector or a class hash) as 0x-prefixed hex, without leading zeros;
+/// used by errors and traces, rather than the `Debug` format of the wrapping types.
+pub fn format_felt(felt: &StarkFelt) -> String {
+    format!("{:#x}", FieldElement::from(*felt))
+}
+
+/// Like `format_felt`, with the digits in between the leading and trailing four elided, if long.
+pub fn format_felt_short(felt: &StarkFelt) -> String {
+    let hex = format_felt(felt);
+    let digits = &hex[2..];
+    if digits.len() <= 10 {
+        return hex;
+    }
+
+    format!("0x{}…{}", &digits[..4], &digits[digits.len() - 4..])
+}
+
+pub fn format_felts(felts: &[StarkFelt]) -> String {
+    format!("[{}]", felts.iter().map(format_felt).collect::<Vec<_>>().join(", "))
+}
+
+pub fn format_address(address: &ContractAddress) -> String {
+    format_felt(address.0.key())
+}
+
+pub fn format_class_hash(class_hash: &ClassHash) -> String {
+    format_felt(&class_hash.0)
+}
+
+pub fn format_selector(selector: &EntryPointSelector) -> String {
+    format_felt(&selector.0)
+}
@@ -1,8 +1,19 @@
 use std::collections::HashMap;
 
+use assert_matches::assert_matches;
+use num_bigint::BigUint;
 use pretty_assertions::assert_eq;
+use starknet_api::core::{ContractAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::{contract_address, patricia_key, stark_felt, StarknetApiError};
 
-use crate::utils::subtract_mappings;
+use crate::fee::rounding::Rounding;
+use crate::utils::{
+    biguint_to_felt, checked_felt_add, checked_felt_sub, checked_mul_div, decode_base64,
+    encode_base64, felt_add, felt_mul, felt_sub, felt_to_biguint, felt_to_u128, felt_to_u64,
+    felt_to_usize, format_address, format_felt, format_felt_short, format_felts, gunzip, gzip,
+    saturating_mul_div, subtract_mappings, u128_from_felts, usize_to_felt,
+};
 
 #[test]
 fn test_subtract_mappings() {
@@ -18,3 +29,121 @@ fn test_subtract_mappings() {
     let expected = HashMap::from([("red", 1), ("blue", 3)]);
     assert_eq!(expected, subtract_mappings(&map1, &map2));
 }
+
+#[test]
+fn test_mul_div() {
+    assert_eq!(checked_mul_div(10, 3, 4, Rounding::Floor), Some(7));
+    assert_eq!(checked_mul_div(10, 3, 4, Rounding::Ceil), Some(8));
+    assert_eq!(checked_mul_div(10, 4, 5, Rounding::Ceil), Some(8));
+    // No intermediate overflow.
+    assert_eq!(checked_mul_div(u128::MAX, u128::MAX, u128::MAX, Rounding::Ceil), Some(u128::MAX));
+    assert_eq!(checked_mul_div(u128::MAX, 3, 4, Rounding::Floor), Some(u128::MAX / 4 * 3 + 2));
+
+    assert_eq!(checked_mul_div(u128::MAX, 2, 1, Rounding::Floor), None);
+    assert_eq!(saturating_mul_div(u128::MAX, 2, 1, Rounding::Floor), Some(u128::MAX));
+    for rounding in [Rounding::Floor, Rounding::Ceil] {
+        assert_eq!(checked_mul_div(1, 1, 0, rounding), None);
+        assert_eq!(saturating_mul_div(1, 1, 0, rounding), None);
+    }
+}
+
+#[test]
+fn test_felt_conversions() {
+    assert_eq!(felt_to_usize(usize_to_felt(usize::MAX)).unwrap(), usize::MAX);
+    assert_matches!(
+        felt_to_usize(stark_felt!("0x10000000000000000")),
+        Err(StarknetApiError::OutOfRange { .. })
+    );
+
+    assert_eq!(u128_from_felts(stark_felt!(u128::MAX), StarkFelt::ZERO).unwrap(), u128::MAX);
+    assert_matches!(
+        u128_from_felts(StarkFelt::ZERO, StarkFelt::ONE),
+        Err(StarknetApiError::OutOfRange { .. })
+    );
+    assert_matches!(
+        u128_from_felts(stark_felt!("0x100000000000000000000000000000000"), StarkFelt::ZERO),
+        Err(StarknetApiError::OutOfRange { .. })
+    );
+}
+
+#[test]
+fn test_felt_arithmetic() {
+    let (two, three) = (stark_felt!(2_u8), stark_felt!(3_u8));
+    // The largest felt, P - 1.
+    let max_felt = felt_sub(StarkFelt::ZERO, StarkFelt::ONE);
+
+    assert_eq!(felt_add(two, three), stark_felt!(5_u8));
+    assert_eq!(felt_add(max_felt, two), StarkFelt::ONE);
+    assert_eq!(felt_sub(three, two), StarkFelt::ONE);
+    assert_eq!(felt_mul(two, three), stark_felt!(6_u8));
+    assert_eq!(felt_mul(max_felt, max_felt), StarkFelt::ONE);
+
+    assert_eq!(checked_felt_add(two, three), Some(stark_felt!(5_u8)));
+    assert_eq!(checked_felt_add(max_felt, StarkFelt::ONE), None);
+    assert_eq!(checked_felt_sub(three, two), Some(StarkFelt::ONE));
+    assert_eq!(checked_felt_sub(two, three), None);
+}
+
+#[test]
+fn test_felt_biguint_conversions() {
+    let max_felt = felt_sub(StarkFelt::ZERO, StarkFelt::ONE);
+    let max_felt_as_biguint = felt_to_biguint(max_felt);
+
+    assert_eq!(biguint_to_felt(max_felt_as_biguint.clone()).unwrap(), max_felt);
+    assert_matches!(
+        biguint_to_felt(max_felt_as_biguint + 1_u8),
+        Err(StarknetApiError::OutOfRange { .. })
+    );
+    assert_matches!(
+        biguint_to_felt(BigUint::from(1_u8) << 256),
+        Err(StarknetApiError::OutOfRange { .. })
+    );
+
+    assert_eq!(felt_to_u64(stark_felt!(u64::MAX)).unwrap(), u64::MAX);
+    assert_matches!(felt_to_u64(stark_felt!(u128::MAX)), Err(StarknetApiError::OutOfRange { .. }));
+    assert_eq!(felt_to_u128(stark_felt!(u128::MAX)).unwrap(), u128::MAX);
+}
+
+#[test]
+fn test_felt_formatting() {
+    let selector = stark_felt!("0x15d40a3d6ca2ac30f4031e42be28da9b056fef9bb7357ac5e85627ee876e5ad");
+    assert_eq!(
+        format_felt(&selector),
+        "0x15d40a3d6ca2ac30f4031e42be28da9b056fef9bb7357ac5e85627ee876e5ad"
+    );
+    assert_eq!(format_felt_short(&selector), "0x15d4…e5ad");
+    assert_eq!(format_felt(&StarkFelt::ZERO), "0x0");
+    assert_eq!(format_felt_short(&stark_felt!(0x1234_u16)), "0x1234");
+    assert_eq!(format_felts(&[stark_felt!(1_u8), stark_felt!(17_u8)]), "[0x1, 0x11]");
+    assert_eq!(format_address(&contract_address!("0x0100")), "0x100");
+}
+
+#[test]
+fn test_base64() {
+    for (data, encoded) in
+        [("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"), ("foob", "Zm9vYg==")]
+    {
+        assert_eq!(encode_base64(data.as_bytes()), encoded);
+        assert_eq!(decode_base64(encoded).unwrap(), data.as_bytes());
+    }
+    // Padding is optional.
+    assert_eq!(decode_base64("Zm8").unwrap(), b"fo");
+    assert_eq!(decode_base64("Zm9v!"), None);
+    assert_eq!(decode_base64("Zm9vY"), None);
+    // Padding must complete the final quantum.
+    for encoded in ["Zm8==", "Zg=", "Zm9v=", "Zm9v====", "="] {
+        assert_eq!(decode_base64(encoded), None, "{encoded}");
+    }
+    // The unused trailing bits must be zero.
+    assert_eq!(decode_base64("Zm9="), None);
+    assert_eq!(decode_base64("Zh=="), None);
+}
+
+#[test]
+fn test_gzip() {
+    let data = b"A program, repeated. A program, repeated. A program, repeated.";
+    let compressed_data = gzip(data).unwrap();
+    assert!(compressed_data.len() < data.len());
+    assert_eq!(gunzip(&compressed_data).unwrap(), data);
+    assert!(gunzip(data).is_err());
+}
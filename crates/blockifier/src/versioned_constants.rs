@@ -0,0 +1,453 @@
+#[cfg(feature = "fs")]
+use std::fs;
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::TransactionVersion;
+use strum_macros::EnumIter;
+use thiserror::Error;
+
+use crate::abi::constants;
+use crate::execution::contract_class::ContractClass;
+use crate::execution::deprecated_syscalls::DeprecatedSyscallSelector;
+use crate::transaction::errors::ContractClassLimitError;
+use crate::transaction::transaction_types::TransactionType;
+
+#[cfg(test)]
+#[path = "versioned_constants_test.rs"]
+pub mod test;
+
+/// The Starknet protocol versions whose behavior is known to this crate.
+#[derive(
+    Clone, Copy, Debug, Deserialize, EnumIter, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub enum StarknetVersion {
+    V0_12_3,
+    V0_13_0,
+    V0_13_1,
+    V0_13_2,
+    V0_13_3,
+    V0_13_4,
+}
+
+impl StarknetVersion {
+    /// The latest version charging all resources as L1 gas; the separate L2 gas of
+    /// `V0_13_4` must be opted into explicitly.
+    pub const LATEST: Self = Self::V0_13_3;
+}
+
+/// Network-wide parameters that may change between Starknet versions (or be tuned by appchains),
+/// e.g., the gas cost of each syscall.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct VersionedConstants {
+    pub gas_costs: SyscallGasCosts,
+    /// The gas that transactions (and read-only calls) start with; bounds the gas consumed by
+    /// Cairo 1 entry points.
+    #[serde(default = "default_tx_initial_gas")]
+    pub tx_initial_gas: u64,
+    pub class_limits: ContractClassLimits,
+    #[serde(default)]
+    pub resource_gas_mode: ResourceGasMode,
+    #[serde(default)]
+    pub tx_versions: TransactionVersionRanges,
+    /// Syscalls that fail when invoked, by both Cairo 0 and Cairo 1 contracts.
+    #[serde(default)]
+    pub unavailable_syscalls: Vec<DeprecatedSyscallSelector>,
+    #[serde(default)]
+    pub validate_rounding: ValidateRounding,
+}
+
+impl Default for VersionedConstants {
+    fn default() -> Self {
+        Self {
+            gas_costs: SyscallGasCosts::default(),
+            tx_initial_gas: default_tx_initial_gas(),
+            class_limits: ContractClassLimits::default(),
+            resource_gas_mode: ResourceGasMode::default(),
+            tx_versions: TransactionVersionRanges::default(),
+            unavailable_syscalls: Vec::new(),
+            validate_rounding: ValidateRounding::default(),
+        }
+    }
+}
+
+fn default_tx_initial_gas() -> u64 {
+    constants::INITIAL_GAS_COST - constants::TRANSACTION_GAS_COST
+}
+
+impl VersionedConstants {
+    /// Returns the constants of the given Starknet version. Only the behavioral switches differ
+    /// between versions; the rest of the constants are the defaults of this crate.
+    pub fn get(version: StarknetVersion) -> Self {
+        let resource_gas_mode = match version {
+            StarknetVersion::V0_13_4 => {
+                // Steps cost 100 L2 gas, and 0.0025 L1 gas before Starknet 0.13.4.
+                ResourceGasMode::SeparateL2Gas { l2_gas_per_l1_gas: 40_000 }
+            }
+            _ => ResourceGasMode::AllResourcesAsL1Gas,
+        };
+
+        let tx_versions = match version {
+            // Transactions of version 3 were introduced in Starknet 0.13.0.
+            StarknetVersion::V0_12_3 => TransactionVersionRanges {
+                declare: VersionRange { min: 0, max: 2 },
+                deploy_account: VersionRange { min: 1, max: 1 },
+                invoke: VersionRange { min: 0, max: 1 },
+            },
+            _ => TransactionVersionRanges::default(),
+        };
+
+        let unavailable_syscalls = match version {
+            // The secp256r1 syscalls were introduced in Starknet 0.13.0.
+            StarknetVersion::V0_12_3 => vec![
+                DeprecatedSyscallSelector::Secp256r1Add,
+                DeprecatedSyscallSelector::Secp256r1GetPointFromX,
+                DeprecatedSyscallSelector::Secp256r1GetXy,
+                DeprecatedSyscallSelector::Secp256r1Mul,
+                DeprecatedSyscallSelector::Secp256r1New,
+            ],
+            _ => Vec::new(),
+        };
+
+        let validate_rounding = match version {
+            // The block info was rounded during validation starting from Starknet 0.13.1.
+            StarknetVersion::V0_12_3 | StarknetVersion::V0_13_0 => {
+                ValidateRounding { block_number: 1, timestamp: 1 }
+            }
+            _ => ValidateRounding::default(),
+        };
+
+        Self {
+            resource_gas_mode,
+            tx_versions,
+            unavailable_syscalls,
+            validate_rounding,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_syscall_available(&self, selector: &DeprecatedSyscallSelector) -> bool {
+        !self.unavailable_syscalls.contains(selector)
+    }
+
+    pub fn try_from_json_string(raw_json: &str) -> VersionedConstantsResult<Self> {
+        let versioned_constants: Self = serde_json::from_str(raw_json)?;
+        versioned_constants.validate()?;
+        Ok(versioned_constants)
+    }
+
+    /// Verifies that every syscall costs at least the base syscall gas cost, which is pre-charged
+    /// by the compiled code and refunded before the syscall is charged.
+    pub fn validate(&self) -> VersionedConstantsResult<()> {
+        match self
+            .gas_costs
+            .syscall_costs()
+            .into_iter()
+            .find(|(_, gas_cost)| *gas_cost < constants::SYSCALL_BASE_GAS_COST)
+        {
+            Some((syscall, gas_cost)) => {
+                Err(VersionedConstantsError::SyscallGasCostTooLow { syscall, gas_cost })
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "fs")]
+impl TryFrom<&Path> for VersionedConstants {
+    type Error = VersionedConstantsError;
+
+    fn try_from(path: &Path) -> VersionedConstantsResult<Self> {
+        Self::try_from_json_string(&fs::read_to_string(path)?)
+    }
+}
+
+/// How the Cairo resources of a transaction (steps and builtins) are charged.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceGasMode {
+    /// All resources collapse to L1 gas; used by Starknet versions before 0.13.4.
+    #[default]
+    AllResourcesAsL1Gas,
+    /// Cairo resources are charged as L2 gas: their L1 gas weight (see
+    /// `BlockContext::vm_resource_fee_cost`) is scaled by `l2_gas_per_l1_gas`. The remaining
+    /// resources (e.g., messages and state diffs) are still charged as L1 gas.
+    SeparateL2Gas { l2_gas_per_l1_gas: u128 },
+}
+
+impl ResourceGasMode {
+    /// Returns whether L2 gas is charged, in which case it is bounded by the L2 gas resource
+    /// bounds of V3 transactions.
+    pub fn charges_l2_gas(&self) -> bool {
+        matches!(self, Self::SeparateL2Gas { .. })
+    }
+}
+
+/// An inclusive range of transaction versions.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct VersionRange {
+    pub min: u8,
+    pub max: u8,
+}
+
+impl VersionRange {
+    pub fn contains(&self, version: TransactionVersion) -> bool {
+        let [min, max] =
+            [self.min, self.max].map(|bound| TransactionVersion(StarkFelt::from(bound)));
+        min <= version && version <= max
+    }
+}
+
+/// The transaction versions accepted per account transaction type (e.g., to reject `Invoke`
+/// transactions of version 0 after a cutoff), on top of the versions each type supports.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TransactionVersionRanges {
+    pub declare: VersionRange,
+    pub deploy_account: VersionRange,
+    pub invoke: VersionRange,
+}
+
+impl TransactionVersionRanges {
+    pub fn get(&self, tx_type: TransactionType) -> VersionRange {
+        match tx_type {
+            TransactionType::Declare => self.declare,
+            TransactionType::DeployAccount => self.deploy_account,
+            TransactionType::InvokeFunction => self.invoke,
+            TransactionType::L1Handler => VersionRange { min: 0, max: 0 },
+        }
+    }
+}
+
+impl Default for TransactionVersionRanges {
+    fn default() -> Self {
+        Self {
+            declare: VersionRange { min: 0, max: 3 },
+            deploy_account: VersionRange { min: 1, max: 3 },
+            invoke: VersionRange { min: 0, max: 3 },
+        }
+    }
+}
+
+/// The granularity of the block info exposed to `validate` entry points: the block number and
+/// timestamp are rounded down to multiples of these values, so that a validation does not depend
+/// on the exact block the transaction is included in. A value of 1 disables the rounding.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ValidateRounding {
+    pub block_number: u64,
+    pub timestamp: u64,
+}
+
+impl ValidateRounding {
+    pub fn round_block_number(&self, block_number: u64) -> u64 {
+        round_down(block_number, self.block_number)
+    }
+
+    pub fn round_timestamp(&self, timestamp: u64) -> u64 {
+        round_down(timestamp, self.timestamp)
+    }
+}
+
+impl Default for ValidateRounding {
+    fn default() -> Self {
+        Self {
+            block_number: constants::VALIDATE_BLOCK_NUMBER_ROUNDING,
+            timestamp: constants::VALIDATE_TIMESTAMP_ROUNDING,
+        }
+    }
+}
+
+fn round_down(value: u64, granularity: u64) -> u64 {
+    // A zero granularity is treated as no rounding.
+    match granularity {
+        0 => value,
+        _ => value / granularity * granularity,
+    }
+}
+
+/// The amount of gas charged by each Cairo 1.0 syscall, including the base syscall cost.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SyscallGasCosts {
+    pub call_contract: u64,
+    pub deploy: u64,
+    pub emit_event: u64,
+    pub get_block_hash: u64,
+    pub get_execution_info: u64,
+    pub keccak: u64,
+    /// Charged per keccak round, on top of the `keccak` syscall cost.
+    pub keccak_round_cost: u64,
+    pub library_call: u64,
+    pub replace_class: u64,
+    pub secp256k1_add: u64,
+    pub secp256k1_get_point_from_x: u64,
+    pub secp256k1_get_xy: u64,
+    pub secp256k1_mul: u64,
+    pub secp256k1_new: u64,
+    pub secp256r1_add: u64,
+    pub secp256r1_get_point_from_x: u64,
+    pub secp256r1_get_xy: u64,
+    pub secp256r1_mul: u64,
+    pub secp256r1_new: u64,
+    pub send_message_to_l1: u64,
+    pub storage_read: u64,
+    pub storage_write: u64,
+}
+
+impl Default for SyscallGasCosts {
+    fn default() -> Self {
+        Self {
+            call_contract: constants::CALL_CONTRACT_GAS_COST,
+            deploy: constants::DEPLOY_GAS_COST,
+            emit_event: constants::EMIT_EVENT_GAS_COST,
+            get_block_hash: constants::GET_BLOCK_HASH_GAS_COST,
+            get_execution_info: constants::GET_EXECUTION_INFO_GAS_COST,
+            keccak: constants::KECCAK_GAS_COST,
+            keccak_round_cost: constants::KECCAK_ROUND_COST_GAS_COST,
+            library_call: constants::LIBRARY_CALL_GAS_COST,
+            replace_class: constants::REPLACE_CLASS_GAS_COST,
+            secp256k1_add: constants::SECP256K1_ADD_GAS_COST,
+            secp256k1_get_point_from_x: constants::SECP256K1_GET_POINT_FROM_X_GAS_COST,
+            secp256k1_get_xy: constants::SECP256K1_GET_XY_GAS_COST,
+            secp256k1_mul: constants::SECP256K1_MUL_GAS_COST,
+            secp256k1_new: constants::SECP256K1_NEW_GAS_COST,
+            secp256r1_add: constants::SECP256R1_ADD_GAS_COST,
+            secp256r1_get_point_from_x: constants::SECP256R1_GET_POINT_FROM_X_GAS_COST,
+            secp256r1_get_xy: constants::SECP256R1_GET_XY_GAS_COST,
+            secp256r1_mul: constants::SECP256R1_MUL_GAS_COST,
+            secp256r1_new: constants::SECP256R1_NEW_GAS_COST,
+            send_message_to_l1: constants::SEND_MESSAGE_TO_L1_GAS_COST,
+            storage_read: constants::STORAGE_READ_GAS_COST,
+            storage_write: constants::STORAGE_WRITE_GAS_COST,
+        }
+    }
+}
+
+impl SyscallGasCosts {
+    /// Returns the cost of each syscall by its name; excludes the per-round keccak cost, which is
+    /// charged on top of a syscall cost.
+    pub fn syscall_costs(&self) -> [(&'static str, u64); 21] {
+        [
+            ("call_contract", self.call_contract),
+            ("deploy", self.deploy),
+            ("emit_event", self.emit_event),
+            ("get_block_hash", self.get_block_hash),
+            ("get_execution_info", self.get_execution_info),
+            ("keccak", self.keccak),
+            ("library_call", self.library_call),
+            ("replace_class", self.replace_class),
+            ("secp256k1_add", self.secp256k1_add),
+            ("secp256k1_get_point_from_x", self.secp256k1_get_point_from_x),
+            ("secp256k1_get_xy", self.secp256k1_get_xy),
+            ("secp256k1_mul", self.secp256k1_mul),
+            ("secp256k1_new", self.secp256k1_new),
+            ("secp256r1_add", self.secp256r1_add),
+            ("secp256r1_get_point_from_x", self.secp256r1_get_point_from_x),
+            ("secp256r1_get_xy", self.secp256r1_get_xy),
+            ("secp256r1_mul", self.secp256r1_mul),
+            ("secp256r1_new", self.secp256r1_new),
+            ("send_message_to_l1", self.send_message_to_l1),
+            ("storage_read", self.storage_read),
+            ("storage_write", self.storage_write),
+        ]
+    }
+}
+
+/// Size limits enforced on declared contract classes.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ContractClassLimits {
+    /// Measured in felts.
+    pub max_sierra_program_length: usize,
+    /// Measured in felts; applies to the compiled (CASM or Cairo 0) program.
+    pub max_bytecode_length: usize,
+    pub max_entry_points: usize,
+    /// Measured in bytes.
+    pub max_abi_length: usize,
+    /// Measured in bytes; applies to the serialized class, as submitted.
+    pub max_artifact_size: usize,
+}
+
+impl ContractClassLimits {
+    /// Verifies the limits applicable to the compiled class, which is all that a declare
+    /// transaction carries.
+    pub fn verify_compiled_class(
+        &self,
+        contract_class: &ContractClass,
+    ) -> Result<(), ContractClassLimitError> {
+        let bytecode_length = contract_class.bytecode_length();
+        if bytecode_length > self.max_bytecode_length {
+            return Err(ContractClassLimitError::BytecodeTooLong {
+                bytecode_length,
+                max_bytecode_length: self.max_bytecode_length,
+            });
+        }
+
+        let n_entry_points = contract_class.n_entry_points();
+        if n_entry_points > self.max_entry_points {
+            return Err(ContractClassLimitError::TooManyEntryPoints {
+                n_entry_points,
+                max_entry_points: self.max_entry_points,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Verifies the limits applicable to the class as submitted by the user, for flows that hold
+    /// the Sierra program and the raw artifact (e.g., a gateway).
+    pub fn verify_raw_class(
+        &self,
+        sierra_program_length: usize,
+        abi_length: usize,
+        artifact_size: usize,
+    ) -> Result<(), ContractClassLimitError> {
+        if sierra_program_length > self.max_sierra_program_length {
+            return Err(ContractClassLimitError::SierraProgramTooLong {
+                sierra_program_length,
+                max_sierra_program_length: self.max_sierra_program_length,
+            });
+        }
+
+        if abi_length > self.max_abi_length {
+            return Err(ContractClassLimitError::AbiTooLong {
+                abi_length,
+                max_abi_length: self.max_abi_length,
+            });
+        }
+
+        if artifact_size > self.max_artifact_size {
+            return Err(ContractClassLimitError::ArtifactTooLarge {
+                artifact_size,
+                max_artifact_size: self.max_artifact_size,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for ContractClassLimits {
+    fn default() -> Self {
+        Self {
+            max_sierra_program_length: constants::MAX_SIERRA_PROGRAM_LENGTH,
+            max_bytecode_length: constants::MAX_BYTECODE_LENGTH,
+            max_entry_points: constants::MAX_ENTRY_POINTS,
+            max_abi_length: constants::MAX_ABI_LENGTH,
+            max_artifact_size: constants::MAX_ARTIFACT_SIZE,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum VersionedConstantsError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("Failed to parse JSON: {0}.")]
+    JsonParseError(#[from] serde_json::Error),
+    #[error(
+        "The gas cost of syscall {syscall} ({gas_cost}) is lower than the base syscall gas cost."
+    )]
+    SyscallGasCostTooLow { syscall: &'static str, gas_cost: u64 },
+}
+
+pub type VersionedConstantsResult<T> = Result<T, VersionedConstantsError>;
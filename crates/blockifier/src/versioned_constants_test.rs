@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+use assert_matches::assert_matches;
+use cairo_vm::types::relocatable::Relocatable;
+use pretty_assertions::assert_eq;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::{Calldata, TransactionVersion};
+use starknet_api::{calldata, stark_felt};
+use strum::IntoEnumIterator;
+
+use super::*;
+use crate::abi::abi_utils::selector_from_name;
+use crate::block_context::BlockContext;
+use crate::execution::entry_point::{
+    CallEntryPoint, EntryPointExecutionContext, ExecutionResources,
+};
+use crate::execution::execution_utils::ReadOnlySegments;
+use crate::execution::syscalls::hint_processor::{SyscallExecutionError, SyscallHintProcessor};
+use crate::invoke_tx_args;
+use crate::test_utils::cached_state::create_test_state;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{trivial_external_entry_point, CairoVersion, BALANCE};
+use crate::transaction::errors::{
+    ContractClassLimitError, TransactionExecutionError, TransactionVersionError,
+};
+use crate::transaction::objects::{AccountTransactionContext, DeprecatedAccountTransactionContext};
+use crate::transaction::test_utils::account_invoke_tx;
+use crate::transaction::transaction_types::TransactionType;
+use crate::transaction::transactions::ExecutableTransaction;
+
+#[test]
+fn test_json_round_trip() {
+    let versioned_constants = VersionedConstants::default();
+    let raw_json = serde_json::to_string(&versioned_constants).unwrap();
+    assert_eq!(VersionedConstants::try_from_json_string(&raw_json).unwrap(), versioned_constants);
+}
+
+#[test]
+fn test_missing_gas_cost_fails_to_parse() {
+    let mut json_value = serde_json::to_value(VersionedConstants::default()).unwrap();
+    json_value["gas_costs"].as_object_mut().unwrap().remove("storage_read");
+    assert!(matches!(
+        VersionedConstants::try_from_json_string(&json_value.to_string()),
+        Err(VersionedConstantsError::JsonParseError(_))
+    ));
+}
+
+#[test]
+fn test_syscall_gas_cost_below_base_cost_fails_to_load() {
+    let mut json_value = serde_json::to_value(VersionedConstants::default()).unwrap();
+    json_value["gas_costs"]["emit_event"] = (constants::SYSCALL_BASE_GAS_COST - 1).into();
+    assert_matches!(
+        VersionedConstants::try_from_json_string(&json_value.to_string()),
+        Err(VersionedConstantsError::SyscallGasCostTooLow { syscall: "emit_event", .. })
+    );
+
+    // The keccak round cost is charged on top of the keccak syscall cost, and is not bounded.
+    json_value["gas_costs"]["emit_event"] = constants::SYSCALL_BASE_GAS_COST.into();
+    json_value["gas_costs"]["keccak_round_cost"] = 0.into();
+    assert!(VersionedConstants::try_from_json_string(&json_value.to_string()).is_ok());
+}
+
+#[test]
+fn test_custom_syscall_gas_costs() {
+    let execute_with_constants = |versioned_constants: VersionedConstants| {
+        let mut state = create_test_state();
+        let block_context =
+            BlockContext { versioned_constants, ..BlockContext::create_for_testing() };
+        let mut context = EntryPointExecutionContext::new_invoke(
+            &block_context,
+            &AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext::default()),
+            true,
+        )
+        .unwrap();
+        let entry_point_call = CallEntryPoint {
+            calldata: calldata![stark_felt!(1234_u16), stark_felt!(18_u8)],
+            entry_point_selector: selector_from_name("test_storage_read_write"),
+            ..trivial_external_entry_point()
+        };
+        entry_point_call
+            .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+            .unwrap()
+            .execution
+            .gas_consumed
+    };
+
+    let default_constants = VersionedConstants::default();
+    let extra_cost = 1000;
+    let mut custom_constants = default_constants.clone();
+    custom_constants.gas_costs.storage_read += extra_cost;
+    custom_constants.gas_costs.storage_write += 2 * extra_cost;
+
+    // The tested entry point performs a single storage write followed by a single storage read.
+    assert_eq!(
+        execute_with_constants(custom_constants),
+        execute_with_constants(default_constants) + 3 * extra_cost
+    );
+}
+
+#[test]
+fn test_compiled_class_limits() {
+    let contract_class = FeatureContract::TestContract(CairoVersion::Cairo1).get_class();
+    let bytecode_length = contract_class.bytecode_length();
+    let n_entry_points = contract_class.n_entry_points();
+    let limits = ContractClassLimits {
+        max_bytecode_length: bytecode_length,
+        max_entry_points: n_entry_points,
+        ..Default::default()
+    };
+    limits.verify_compiled_class(&contract_class).unwrap();
+
+    let bytecode_limits =
+        ContractClassLimits { max_bytecode_length: bytecode_length - 1, ..limits };
+    assert_matches!(
+        bytecode_limits.verify_compiled_class(&contract_class),
+        Err(ContractClassLimitError::BytecodeTooLong { .. })
+    );
+
+    let entry_point_limits = ContractClassLimits { max_entry_points: n_entry_points - 1, ..limits };
+    assert_matches!(
+        entry_point_limits.verify_compiled_class(&contract_class),
+        Err(ContractClassLimitError::TooManyEntryPoints { .. })
+    );
+}
+
+#[test]
+fn test_raw_class_limits() {
+    let limits = ContractClassLimits {
+        max_sierra_program_length: 10,
+        max_abi_length: 10,
+        max_artifact_size: 10,
+        ..Default::default()
+    };
+    limits.verify_raw_class(10, 10, 10).unwrap();
+    assert_matches!(
+        limits.verify_raw_class(11, 10, 10),
+        Err(ContractClassLimitError::SierraProgramTooLong { sierra_program_length: 11, .. })
+    );
+    assert_matches!(
+        limits.verify_raw_class(10, 11, 10),
+        Err(ContractClassLimitError::AbiTooLong { abi_length: 11, .. })
+    );
+    assert_matches!(
+        limits.verify_raw_class(10, 10, 11),
+        Err(ContractClassLimitError::ArtifactTooLarge { artifact_size: 11, .. })
+    );
+}
+
+#[test]
+fn test_constants_by_version() {
+    for version in StarknetVersion::iter() {
+        let versioned_constants = VersionedConstants::get(version);
+        let raw_json = serde_json::to_string(&versioned_constants).unwrap();
+        assert_eq!(
+            VersionedConstants::try_from_json_string(&raw_json).unwrap(),
+            versioned_constants
+        );
+        // Transactions of version 3 were introduced in Starknet 0.13.0.
+        let v3_invoke_allowed =
+            versioned_constants.tx_versions.invoke.contains(TransactionVersion::THREE);
+        assert_eq!(v3_invoke_allowed, version >= StarknetVersion::V0_13_0);
+        // The secp256r1 syscalls were introduced in Starknet 0.13.0.
+        let secp256r1_available =
+            versioned_constants.is_syscall_available(&DeprecatedSyscallSelector::Secp256r1New);
+        assert_eq!(secp256r1_available, version >= StarknetVersion::V0_13_0);
+    }
+
+    assert_eq!(
+        VersionedConstants::get(StarknetVersion::LATEST).resource_gas_mode,
+        ResourceGasMode::AllResourcesAsL1Gas
+    );
+    assert_matches!(
+        VersionedConstants::get(StarknetVersion::V0_13_4).resource_gas_mode,
+        ResourceGasMode::SeparateL2Gas { .. }
+    );
+}
+
+#[test]
+fn test_validate_rounding() {
+    let rounding = ValidateRounding::default();
+    assert_eq!(rounding.round_block_number(2099), 2000);
+    assert_eq!(rounding.round_timestamp(7199), 3600);
+    assert_eq!(rounding.round_timestamp(7200), 7200);
+
+    // No rounding before Starknet 0.13.1.
+    let rounding = VersionedConstants::get(StarknetVersion::V0_13_0).validate_rounding;
+    assert_eq!(rounding.round_block_number(2099), 2099);
+    assert_eq!(rounding.round_timestamp(7199), 7199);
+    assert_eq!(
+        VersionedConstants::get(StarknetVersion::V0_13_1).validate_rounding,
+        ValidateRounding::default()
+    );
+}
+
+#[test]
+fn test_unavailable_syscall() {
+    let mut state = create_test_state();
+    let versioned_constants = VersionedConstants {
+        unavailable_syscalls: vec![DeprecatedSyscallSelector::StorageRead],
+        ..VersionedConstants::default()
+    };
+    let block_context = BlockContext { versioned_constants, ..BlockContext::create_for_testing() };
+    let mut context = EntryPointExecutionContext::new_invoke(
+        &block_context,
+        &AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext::default()),
+        true,
+    )
+    .unwrap();
+    let mut resources = ExecutionResources::default();
+    let hints = HashMap::new();
+    let syscall_handler = SyscallHintProcessor::new(
+        &mut state,
+        &mut resources,
+        &mut context,
+        Relocatable::from((2, 0)),
+        trivial_external_entry_point(),
+        &hints,
+        ReadOnlySegments::default(),
+    );
+
+    assert_matches!(
+        syscall_handler.verify_syscall_allowed(DeprecatedSyscallSelector::StorageRead),
+        Err(SyscallExecutionError::SyscallUnavailable { syscall_name })
+        if syscall_name == "storage_read"
+    );
+    syscall_handler.verify_syscall_allowed(DeprecatedSyscallSelector::StorageWrite).unwrap();
+}
+
+#[test]
+fn test_v3_transactions_by_version() {
+    let block_context = BlockContext {
+        versioned_constants: VersionedConstants::get(StarknetVersion::V0_12_3),
+        ..BlockContext::create_for_account_testing()
+    };
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let state = &mut test_state(&block_context, BALANCE, &[(account, 1)]);
+    let invoke_tx = account_invoke_tx(invoke_tx_args! {
+        sender_address: account.get_instance_address(0),
+        version: TransactionVersion::THREE,
+    });
+
+    assert_matches!(
+        invoke_tx.execute(state, &block_context, true, true).unwrap_err(),
+        TransactionExecutionError::TransactionVersionError(
+            TransactionVersionError::UnsupportedVersion {
+                tx_type: TransactionType::InvokeFunction,
+                version: TransactionVersion::THREE,
+                allowed_versions,
+            }
+        ) if allowed_versions == vec![TransactionVersion::ZERO, TransactionVersion::ONE]
+    );
+}
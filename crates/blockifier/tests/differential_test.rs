@@ -0,0 +1,210 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::{env, fs};
+
+use blockifier::execution::call_info::{MessageToL1, Retdata};
+use blockifier::invoke_tx_args;
+use blockifier::state::cached_state::CommitmentStateDiff;
+use blockifier::test_utils::{create_calldata, CairoVersion};
+use blockifier::transaction::objects::TransactionExecutionInfo;
+use blockifier::transaction::test_utils::{
+    block_context, create_test_init_data, max_fee, run_invoke_tx, TestInitData,
+};
+use serde::{Deserialize, Serialize};
+use starknet_api::core::ContractAddress;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::{Calldata, EventContent, Fee};
+
+const FIXTURES_DIR: &str = "tests/differential_fixtures";
+const REFERENCE_COMMAND_ENV_VAR: &str = "DIFFERENTIAL_REFERENCE_COMMAND";
+const FIX_ENV_VAR: &str = "FIX_DIFFERENTIAL_FIXTURES";
+
+// Compares the execution of a corpus of transactions with the one of the Python implementation
+// (`cairo-lang`), to catch semantic drift between the two.
+//
+// By default, the expected executions are read from the recorded fixtures in `FIXTURES_DIR`.
+// To compare with a live reference, set `REFERENCE_COMMAND_ENV_VAR` to a shell command that reads
+// a `TransactionInput` (JSON) from its stdin, executes it over the test state using `cairo-lang`
+// (see `requirements.txt`), and writes the resulting `RecordedExecution` (JSON) to its stdout.
+// To re-record the fixtures, also set `FIX_ENV_VAR=1`; without a reference command, the fixtures
+// are recorded from this implementation - do so only to re-baseline after an intended change.
+//
+// Run with `cargo test --features differential_testing --test differential_test`.
+
+/// A transaction of the corpus: an invocation of a test contract function, through an account
+/// without validations, over the state of `create_test_init_data`.
+#[derive(Serialize)]
+struct TransactionInput {
+    name: String,
+    cairo_version: String,
+    sender_address: ContractAddress,
+    contract_address: ContractAddress,
+    entry_point_name: &'static str,
+    calldata: Calldata,
+    max_fee: Fee,
+}
+
+/// The observable effects of a transaction, in a form shared by both implementations.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct RecordedExecution {
+    retdata: Retdata,
+    /// Events and messages, in the order of the call tree traversal.
+    events: Vec<EventContent>,
+    l2_to_l1_messages: Vec<MessageToL1>,
+    reverted: bool,
+    actual_fee: Fee,
+    state_diff: CommitmentStateDiff,
+}
+
+impl RecordedExecution {
+    fn new(tx_execution_info: &TransactionExecutionInfo, state_diff: CommitmentStateDiff) -> Self {
+        let call_infos: Vec<_> = tx_execution_info.execute_call_info.iter().flatten().collect();
+        Self {
+            retdata: tx_execution_info
+                .execute_call_info
+                .as_ref()
+                .map(|call_info| call_info.execution.retdata.clone())
+                .unwrap_or_default(),
+            events: call_infos
+                .iter()
+                .flat_map(|call_info| &call_info.execution.events)
+                .map(|ordered_event| ordered_event.event.clone())
+                .collect(),
+            l2_to_l1_messages: call_infos
+                .iter()
+                .flat_map(|call_info| &call_info.execution.l2_to_l1_messages)
+                .map(|ordered_message| MessageToL1 {
+                    to_address: ordered_message.message.to_address,
+                    payload: ordered_message.message.payload.clone(),
+                })
+                .collect(),
+            reverted: tx_execution_info.is_reverted(),
+            actual_fee: tx_execution_info.actual_fee,
+            state_diff,
+        }
+    }
+
+    /// Returns the names of the fields in which `self` and `other` differ.
+    fn diff(&self, other: &Self) -> Vec<&'static str> {
+        [
+            ("retdata", self.retdata == other.retdata),
+            ("events", self.events == other.events),
+            ("l2_to_l1_messages", self.l2_to_l1_messages == other.l2_to_l1_messages),
+            ("reverted", self.reverted == other.reverted),
+            ("actual_fee", self.actual_fee == other.actual_fee),
+            ("state_diff", self.state_diff == other.state_diff),
+        ]
+        .into_iter()
+        .filter_map(|(field, equal)| (!equal).then_some(field))
+        .collect()
+    }
+}
+
+/// Returns the corpus, as (name, entry point name, entry point arguments) triplets; each is run
+/// over both Cairo versions of the test contract.
+fn corpus() -> Vec<(&'static str, &'static str, Vec<StarkFelt>)> {
+    vec![
+        (
+            "storage_read_write",
+            "test_storage_read_write",
+            vec![stark_felt!(15_u8), stark_felt!(7_u8)],
+        ),
+        (
+            "advance_counter",
+            "advance_counter",
+            vec![stark_felt!(0_u8), stark_felt!(3_u8), stark_felt!(4_u8)],
+        ),
+        ("send_message", "send_message", vec![stark_felt!(0x1234_u16)]),
+        ("recurse", "recurse", vec![stark_felt!(5_u8)]),
+        ("recursive_fail", "recursive_fail", vec![stark_felt!(3_u8)]),
+        ("nonexistent_entry_point", "nonexistent_entry_point", vec![]),
+    ]
+}
+
+fn execute(cairo_version: CairoVersion, input: &TransactionInput) -> RecordedExecution {
+    let block_context = block_context();
+    let TestInitData { mut state, mut nonce_manager, .. } =
+        create_test_init_data(&block_context, cairo_version);
+    let tx_execution_info = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            max_fee: input.max_fee,
+            sender_address: input.sender_address,
+            calldata: input.calldata.clone(),
+            nonce: nonce_manager.next(input.sender_address),
+        },
+    )
+    .unwrap();
+
+    RecordedExecution::new(&tx_execution_info, state.to_state_diff())
+}
+
+fn execute_reference(command: &str, input: &TransactionInput) -> RecordedExecution {
+    let mut child = Command::new("sh")
+        .args(["-c", command])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let stdin = child.stdin.as_mut().unwrap();
+    stdin.write_all(serde_json::to_string(input).unwrap().as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "The reference command failed on `{}`.", input.name);
+
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn test_differential() {
+    let reference_command = env::var(REFERENCE_COMMAND_ENV_VAR).ok();
+    let fix = env::var(FIX_ENV_VAR).is_ok();
+
+    let mut drifts = Vec::new();
+    for cairo_version in [CairoVersion::Cairo0, CairoVersion::Cairo1] {
+        let TestInitData { account_address, contract_address, .. } =
+            create_test_init_data(&block_context(), cairo_version);
+        for (name, entry_point_name, entry_point_args) in corpus() {
+            let input = TransactionInput {
+                name: format!("{name}_{}", format!("{cairo_version:?}").to_lowercase()),
+                cairo_version: format!("{cairo_version:?}"),
+                sender_address: account_address,
+                contract_address,
+                entry_point_name,
+                calldata: create_calldata(contract_address, entry_point_name, &entry_point_args),
+                max_fee: max_fee(),
+            };
+            let actual = execute(cairo_version, &input);
+            let fixture_path = format!("{FIXTURES_DIR}/{}.json", input.name);
+
+            if fix {
+                let recorded = match &reference_command {
+                    Some(command) => execute_reference(command, &input),
+                    None => actual,
+                };
+                fs::write(&fixture_path, serde_json::to_string_pretty(&recorded).unwrap() + "\n")
+                    .unwrap();
+                continue;
+            }
+
+            let expected = match &reference_command {
+                Some(command) => execute_reference(command, &input),
+                None => serde_json::from_str(&fs::read_to_string(&fixture_path).unwrap_or_else(
+                    |error| panic!("Failed to read fixture '{fixture_path}': {error}."),
+                ))
+                .unwrap(),
+            };
+            let drifted_fields = actual.diff(&expected);
+            if !drifted_fields.is_empty() {
+                drifts.push(format!("{}: {drifted_fields:?}", input.name));
+            }
+        }
+    }
+
+    assert!(
+        drifts.is_empty(),
+        "Execution drifted from the reference in the following transactions (and fields):\n{}",
+        drifts.join("\n")
+    );
+}
@@ -1,4 +1,7 @@
+use blockifier::bouncer::BouncerError;
+use blockifier::execution::errors::ContractClassError;
 use blockifier::state::errors::StateError;
+use blockifier::stateful_validator::StatefulValidatorError;
 use blockifier::transaction::errors::{
     ParseError, TransactionExecutionError, TransactionPreValidationError,
 };
@@ -59,6 +62,8 @@ macro_rules! native_blockifier_errors {
 }
 
 native_blockifier_errors!(
+    (BouncerError, BouncerError, PyBouncerError),
+    (ContractClassError, ContractClassError, PyContractClassError),
     (NativeBlockifierInputError, NativeBlockifierInputError, PyNativeBlockifierInputError),
     (ProgramError, ProgramError, PyProgramError),
     (Pyo3Error, PyErr, PyPyo3Error),
@@ -70,6 +75,17 @@ native_blockifier_errors!(
     (TransactionPreValidationError, TransactionPreValidationError, PyTransactionPreValidationError)
 );
 
+// Keeps the Python error types of the underlying errors.
+impl From<StatefulValidatorError> for NativeBlockifierError {
+    fn from(error: StatefulValidatorError) -> Self {
+        match error {
+            StatefulValidatorError::StateError(error) => error.into(),
+            StatefulValidatorError::TransactionExecutionError(error) => error.into(),
+            StatefulValidatorError::TransactionPreValidationError(error) => error.into(),
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum NativeBlockifierInputError {
     #[error(transparent)]
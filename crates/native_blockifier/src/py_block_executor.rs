@@ -2,13 +2,15 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use blockifier::block_context::{BlockContext, FeeTokenAddresses, GasPrices};
+use blockifier::fee::vm_resource_costs::VmResourceFeeCosts;
 use blockifier::state::cached_state::GlobalContractCache;
+use blockifier::versioned_constants::VersionedConstants;
 use pyo3::prelude::*;
 use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::core::{ChainId, ContractAddress};
 use starknet_api::hash::StarkFelt;
 
-use crate::errors::NativeBlockifierResult;
+use crate::errors::{NativeBlockifierError, NativeBlockifierInputError, NativeBlockifierResult};
 use crate::py_state_diff::{PyBlockInfo, PyStateDiff};
 use crate::py_transaction_execution_info::{PyBouncerInfo, PyTransactionExecutionInfo};
 use crate::py_utils::{int_to_chain_id, py_attr, PyFelt};
@@ -90,12 +92,17 @@ impl PyBlockExecutor {
         self.tx_executor().execute(tx, raw_contract_class, charge_fee)
     }
 
-    pub fn finalize(&mut self, is_pending_block: bool) -> PyStateDiff {
+    /// Returns the state diff of the block, and the fees collected per fee token address (to be
+    /// cross-checked against, or used instead of, the per-transaction fee transfers).
+    pub fn finalize(
+        &mut self,
+        is_pending_block: bool,
+    ) -> NativeBlockifierResult<(PyStateDiff, HashMap<PyFelt, u128>)> {
         log::debug!("Finalizing execution...");
-        let finalized_state = self.tx_executor().finalize(is_pending_block);
+        let finalized_state = self.tx_executor().finalize(is_pending_block)?;
         log::debug!("Finalized execution.");
 
-        finalized_state
+        Ok(finalized_state)
     }
 
     #[pyo3(signature = (old_block_number_and_hash))]
@@ -234,7 +241,7 @@ pub struct PyGeneralConfig {
     pub starknet_os_config: PyOsConfig,
     pub min_strk_l1_gas_price: u128,
     pub max_strk_l1_gas_price: u128,
-    pub cairo_resource_fee_weights: Arc<HashMap<String, f64>>,
+    pub cairo_resource_fee_weights: Arc<VmResourceFeeCosts>,
     pub invoke_tx_max_n_steps: u32,
     pub validate_max_n_steps: u32,
 }
@@ -244,6 +251,9 @@ impl FromPyObject<'_> for PyGeneralConfig {
         let starknet_os_config: PyOsConfig = py_attr(general_config, "starknet_os_config")?;
         let cairo_resource_fee_weights: HashMap<String, f64> =
             py_attr(general_config, "cairo_resource_fee_weights")?;
+        let cairo_resource_fee_weights = VmResourceFeeCosts::try_from(cairo_resource_fee_weights)
+            .map_err(NativeBlockifierInputError::from)
+            .map_err(NativeBlockifierError::from)?;
         let cairo_resource_fee_weights = Arc::new(cairo_resource_fee_weights);
         let min_strk_l1_gas_price: u128 = py_attr(general_config, "min_strk_l1_gas_price")?;
         let max_strk_l1_gas_price: u128 = py_attr(general_config, "max_strk_l1_gas_price")?;
@@ -303,10 +313,28 @@ pub fn into_block_context(
         gas_prices: GasPrices {
             eth_l1_gas_price: block_info.eth_l1_gas_price,
             strk_l1_gas_price: block_info.strk_l1_gas_price,
+            // L2 gas is not charged by the default versioned constants.
+            eth_l2_gas_price: 0,
+            strk_l2_gas_price: 0,
         },
+        concurrency_mode: false,
+        charge_tips: false,
         invoke_tx_max_n_steps: general_config.invoke_tx_max_n_steps,
         validate_max_n_steps: general_config.validate_max_n_steps,
         max_recursion_depth,
+        max_nonce_gap: 0,
+        deprecated_hint_aliases: Arc::default(),
+        unknown_hint_policy: None,
+        retain_sierra_classes: false,
+        determinism_audit: false,
+        verify_retdata_segments: false,
+        collect_program_outputs: false,
+        versioned_constants: VersionedConstants::default(),
+        hint_extension: None,
+        custom_syscalls: Arc::default(),
+        fee_transfer_delegate: None,
+        fee_model: None,
+        metrics_sink: None,
     };
 
     Ok(block_context)
@@ -1,7 +1,7 @@
 use std::collections::{HashMap, HashSet};
 
 use blockifier::execution::call_info::{CallInfo, OrderedEvent, OrderedL2ToL1Message};
-use blockifier::transaction::objects::TransactionExecutionInfo;
+use blockifier::transaction::objects::{GasVector, TransactionExecutionInfo};
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
 use pyo3::prelude::*;
 
@@ -19,9 +19,18 @@ pub struct PyTransactionExecutionInfo {
     #[pyo3(get)]
     pub actual_fee: u128,
     #[pyo3(get)]
+    pub refund: u128,
+    #[pyo3(get)]
     pub actual_resources: HashMap<String, usize>,
     #[pyo3(get)]
+    pub actual_gas: PyGasVector,
+    #[pyo3(get)]
     pub revert_error: Option<String>,
+    #[pyo3(get)]
+    pub is_speculative: bool,
+    #[pyo3(get)]
+    // The fee consumed by an L1 handler transaction, out of the fee paid on L1.
+    pub l1_handler_consumed_fee: Option<u128>,
 }
 
 impl From<TransactionExecutionInfo> for PyTransactionExecutionInfo {
@@ -32,8 +41,33 @@ impl From<TransactionExecutionInfo> for PyTransactionExecutionInfo {
             execute_call_info: info.execute_call_info.map(PyCallInfo::from),
             fee_transfer_call_info: info.fee_transfer_call_info.map(PyCallInfo::from),
             actual_fee: info.actual_fee.0,
+            refund: info.refund.0,
             actual_resources: info.actual_resources.0,
+            actual_gas: PyGasVector::from(info.actual_gas),
             revert_error: info.revert_error,
+            is_speculative: info.is_speculative,
+            l1_handler_consumed_fee: info.l1_handler_fee.map(|fee| fee.consumed_fee.0),
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Clone, Default)]
+pub struct PyGasVector {
+    #[pyo3(get)]
+    pub l1_gas: u128,
+    #[pyo3(get)]
+    pub l1_data_gas: u128,
+    #[pyo3(get)]
+    pub l2_gas: u128,
+}
+
+impl From<GasVector> for PyGasVector {
+    fn from(gas_vector: GasVector) -> Self {
+        Self {
+            l1_gas: gas_vector.l1_gas,
+            l1_data_gas: gas_vector.l1_data_gas,
+            l2_gas: gas_vector.l2_gas,
         }
     }
 }
@@ -70,6 +104,8 @@ pub struct PyCallInfo {
     pub events: Vec<PyOrderedEvent>,
     #[pyo3(get)]
     pub l2_to_l1_messages: Vec<PyOrderedL2ToL1Message>,
+    #[pyo3(get)]
+    pub program_output: Vec<PyFelt>,
 
     // Internal calls invoked by this call.
     #[pyo3(get)]
@@ -80,6 +116,8 @@ pub struct PyCallInfo {
     pub storage_read_values: Vec<PyFelt>,
     #[pyo3(get)]
     pub accessed_storage_keys: HashSet<PyFelt>,
+    #[pyo3(get)]
+    pub syscall_counter: HashMap<String, usize>,
 
     // Deprecated fields; maintained for backward compatibility to Python.
     #[pyo3(get)]
@@ -104,6 +142,7 @@ impl From<CallInfo> for PyCallInfo {
             execution_resources: PyVmExecutionResources::from(call_info.vm_resources),
             events: to_py_vec(execution.events, PyOrderedEvent::from),
             l2_to_l1_messages: to_py_vec(execution.l2_to_l1_messages, PyOrderedL2ToL1Message::from),
+            program_output: to_py_vec(execution.program_output, PyFelt),
             internal_calls: to_py_vec(call_info.inner_calls, PyCallInfo::from),
             storage_read_values: to_py_vec(call_info.storage_read_values, PyFelt),
             accessed_storage_keys: call_info
@@ -111,6 +150,11 @@ impl From<CallInfo> for PyCallInfo {
                 .into_iter()
                 .map(|storage_key| PyFelt(*storage_key.0.key()))
                 .collect(),
+            syscall_counter: call_info
+                .syscall_counter
+                .into_iter()
+                .map(|(selector, count)| (format!("{selector:?}"), count))
+                .collect(),
             call_type: call.call_type as u8,
             code_address: call.code_address.map(PyFelt::from),
         }
@@ -197,4 +241,9 @@ pub struct PyBouncerInfo {
     pub kzg_resources: PyKzgResources,
     #[pyo3(get)]
     pub additional_os_resources: PyVmExecutionResources,
+    #[pyo3(get)]
+    // The number of classes declared and contracts deployed, which are capped per block.
+    pub n_declared_classes: usize,
+    #[pyo3(get)]
+    pub n_deployed_contracts: usize,
 }
@@ -2,14 +2,18 @@ use std::collections::{HashMap, HashSet};
 
 use blockifier::block_context::BlockContext;
 use blockifier::block_execution::pre_process_block;
+use blockifier::bouncer::{Bouncer, BouncerWeights};
 use blockifier::execution::call_info::CallInfo;
 use blockifier::execution::entry_point::ExecutionResources;
 use blockifier::fee::actual_cost::ActualCost;
+use blockifier::fee::sequencer_fee::{CollectedFees, DeferredSequencerFees};
+use blockifier::metrics::report_execution;
 use blockifier::state::cached_state::{
     CachedState, GlobalContractCache, StagedTransactionalState, StorageEntry, TransactionalState,
 };
 use blockifier::state::state_api::{State, StateReader};
 use blockifier::transaction::account_transaction::AccountTransaction;
+use blockifier::transaction::errors::TransactionExecutionError;
 use blockifier::transaction::transaction_execution::Transaction;
 use blockifier::transaction::transactions::{ExecutableTransaction, ValidatableTransaction};
 use cairo_vm::vm::runners::builtin_runner::HASH_BUILTIN_NAME;
@@ -34,6 +38,20 @@ pub struct TransactionExecutor<S: StateReader> {
     pub executed_class_hashes: HashSet<ClassHash>,
     pub visited_storage_entries: HashSet<StorageEntry>,
 
+    // The fees of the committed transactions, and of the staged one.
+    pub collected_fees: CollectedFees,
+    pub staged_for_commit_fees: CollectedFees,
+
+    // The fees not yet credited to the sequencer, in concurrency mode; see
+    // `DeferredSequencerFees`. The staged fees include those of the staged transaction.
+    pub deferred_sequencer_fees: DeferredSequencerFees,
+    pub staged_for_commit_deferred_sequencer_fees: Option<DeferredSequencerFees>,
+
+    // Rejects transactions that exceed the block caps; unbounded unless configured. The staged
+    // bouncer includes the weights of the staged transaction.
+    pub bouncer: Bouncer,
+    pub staged_for_commit_bouncer: Option<Bouncer>,
+
     // State-related fields.
     pub state: CachedState<S>,
 
@@ -56,6 +74,12 @@ impl<S: StateReader> TransactionExecutor<S> {
             block_context: into_block_context(general_config, block_info, max_recursion_depth)?,
             executed_class_hashes: HashSet::<ClassHash>::new(),
             visited_storage_entries: HashSet::<StorageEntry>::new(),
+            collected_fees: CollectedFees::default(),
+            staged_for_commit_fees: CollectedFees::default(),
+            deferred_sequencer_fees: DeferredSequencerFees::default(),
+            staged_for_commit_deferred_sequencer_fees: None,
+            bouncer: Bouncer::default(),
+            staged_for_commit_bouncer: None,
             state: CachedState::new(state_reader, global_contract_cache),
             staged_for_commit_state: None,
         };
@@ -74,22 +98,51 @@ impl<S: StateReader> TransactionExecutor<S> {
         charge_fee: bool,
     ) -> NativeBlockifierResult<(PyTransactionExecutionInfo, PyBouncerInfo)> {
         let tx: Transaction = py_tx(tx, raw_contract_class)?;
+        let tx_type = tx.tx_type();
 
         let mut tx_executed_class_hashes = HashSet::<ClassHash>::new();
         let mut tx_visited_storage_entries = HashSet::<StorageEntry>::new();
         let mut transactional_state = CachedState::create_transactional(&mut self.state);
         let validate = true;
 
+        // Updated on a copy, which is only kept if the transaction is committed.
+        let mut tx_deferred_sequencer_fees = self.deferred_sequencer_fees.clone();
+        let concurrency_mode = self.block_context.concurrency_mode;
+        if concurrency_mode {
+            if let Transaction::AccountTransaction(account_tx) = &tx {
+                tx_deferred_sequencer_fees.prepare_for_sender(
+                    &mut transactional_state,
+                    &self.block_context,
+                    account_tx.get_account_tx_context().sender_address(),
+                )?;
+            }
+        }
+
         let tx_execution_result = tx
             .execute_raw(&mut transactional_state, &self.block_context, charge_fee, validate)
-            .map_err(NativeBlockifierError::from);
-        match tx_execution_result {
+            .and_then(|mut tx_execution_info| {
+                if concurrency_mode {
+                    tx_deferred_sequencer_fees
+                        .add(&mut transactional_state, &self.block_context, &mut tx_execution_info)
+                        .map_err(TransactionExecutionError::from)?;
+                }
+                Ok(tx_execution_info)
+            });
+        if let Some(metrics_sink) = &self.block_context.metrics_sink {
+            report_execution(metrics_sink.as_ref(), &tx_execution_result);
+        }
+        match tx_execution_result.map_err(NativeBlockifierError::from) {
             Ok(tx_execution_info) => {
                 // TODO(Elin, 01/06/2024): consider traversing the calls to collect data once.
                 tx_executed_class_hashes.extend(tx_execution_info.get_executed_class_hashes());
                 tx_visited_storage_entries.extend(tx_execution_info.get_visited_storage_entries());
+                let mut tx_collected_fees = CollectedFees::default();
+                tx_collected_fees.add(&tx_execution_info);
 
                 // TODO(Elin, 01/06/2024): consider moving Bouncer logic to a function.
+                let bouncer_weights = BouncerWeights::new(tx_type, &tx_execution_info);
+                let mut tx_bouncer = self.bouncer.clone();
+                tx_bouncer.try_update(&bouncer_weights)?;
                 let py_tx_execution_info = PyTransactionExecutionInfo::from(tx_execution_info);
                 let mut additional_os_resources = get_casm_hash_calculation_resources(
                     &mut transactional_state,
@@ -104,11 +157,16 @@ impl<S: StateReader> TransactionExecutor<S> {
                     message_segment_length: 0,
                     kzg_resources: PyKzgResources { state_diff_size: 0 },
                     additional_os_resources: PyVmExecutionResources::from(additional_os_resources),
+                    n_declared_classes: bouncer_weights.n_declared_classes,
+                    n_deployed_contracts: bouncer_weights.n_deployed_contracts,
                 };
 
                 self.staged_for_commit_state = Some(
                     transactional_state.stage(tx_executed_class_hashes, tx_visited_storage_entries),
                 );
+                self.staged_for_commit_fees = tx_collected_fees;
+                self.staged_for_commit_deferred_sequencer_fees = Some(tx_deferred_sequencer_fees);
+                self.staged_for_commit_bouncer = Some(tx_bouncer);
                 Ok((py_tx_execution_info, py_bouncer_info))
             }
             Err(error) => {
@@ -151,16 +209,33 @@ impl<S: StateReader> TransactionExecutor<S> {
         Ok((validate_call_info, actual_cost))
     }
 
-    /// Returns the state diff resulting in executing transactions.
-    pub fn finalize(&mut self, is_pending_block: bool) -> PyStateDiff {
+    /// Returns the state diff resulting in executing transactions, along with the total fees
+    /// collected, per fee token address; in concurrency mode, the deferred fees are credited to
+    /// the sequencer first.
+    pub fn finalize(
+        &mut self,
+        is_pending_block: bool,
+    ) -> NativeBlockifierResult<(PyStateDiff, HashMap<PyFelt, u128>)> {
+        self.deferred_sequencer_fees.credit_sequencer(&mut self.state, &self.block_context)?;
+
         // Do not cache classes that were declared during a pending block.
         // They will be redeclared, and should not be cached since the content of this block is
         // transient.
         if !is_pending_block {
             self.state.move_classes_to_global_cache();
         }
+        if let Some(metrics_sink) = &self.block_context.metrics_sink {
+            self.state.report_cache_metrics(metrics_sink.as_ref());
+            self.bouncer.report(metrics_sink.as_ref());
+        }
 
-        PyStateDiff::from(self.state.to_state_diff())
+        let collected_fees = self
+            .collected_fees
+            .fees()
+            .iter()
+            .map(|(&fee_token_address, fee)| (PyFelt::from(fee_token_address), fee.0))
+            .collect();
+        Ok((PyStateDiff::from(self.state.to_state_diff()), collected_fees))
     }
 
     // Block pre-processing; see `block_execution::pre_process_block` documentation.
@@ -182,20 +257,34 @@ impl<S: StateReader> TransactionExecutor<S> {
         };
 
         let child_cache = finalized_transactional_state.cache;
+        self.state.absorb_class_reads(finalized_transactional_state.state_reads);
+        self.state.absorb_visited_pcs(finalized_transactional_state.visited_pcs);
         self.state.update_cache(child_cache);
         self.state.update_contract_class_caches(
             finalized_transactional_state.class_hash_to_class,
+            finalized_transactional_state.declared_class_hashes,
             finalized_transactional_state.global_class_hash_to_class,
         );
 
         self.executed_class_hashes.extend(&finalized_transactional_state.tx_executed_class_hashes);
         self.visited_storage_entries
             .extend(&finalized_transactional_state.tx_visited_storage_entries);
+        self.collected_fees.extend(std::mem::take(&mut self.staged_for_commit_fees));
+        if let Some(deferred_sequencer_fees) = self.staged_for_commit_deferred_sequencer_fees.take()
+        {
+            self.deferred_sequencer_fees = deferred_sequencer_fees;
+        }
+        if let Some(bouncer) = self.staged_for_commit_bouncer.take() {
+            self.bouncer = bouncer;
+        }
 
         self.staged_for_commit_state = None
     }
 
     pub fn abort(&mut self) {
+        self.staged_for_commit_fees = CollectedFees::default();
+        self.staged_for_commit_deferred_sequencer_fees = None;
+        self.staged_for_commit_bouncer = None;
         self.staged_for_commit_state = None
     }
 }